@@ -0,0 +1,131 @@
+//! An [`actix-web`](https://docs.rs/actix-web) extractor for versioned JSON request bodies.
+//!
+//! [`Versioned<T>`] deserializes the body as the version named by the `X-Schema-Version` header
+//! (which can be renamed by adding a [`VersionHeader`] to the app data), or, when the header is
+//! missing, as the version-tagged encoding of `T` (e.g. with `#[obake(serde(tag = "version"))]`).
+//! Either way, the handler receives the latest version, as with [`crate::axum::Versioned`].
+//!
+//! ```
+//! # use serde::Deserialize;
+//! use actix_web::{web, App};
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(derive(Deserialize), serde(tag = "version"))]
+//! #[derive(Deserialize)]
+//! struct Signup {
+//!     #[obake(cfg("0.1.0"))]
+//!     name: String,
+//!     #[obake(cfg(">=0.2"))]
+//!     display_name: String,
+//! }
+//!
+//! impl From<Signup!["0.1.0"]> for Signup!["0.2.0"] {
+//!     fn from(from: Signup!["0.1.0"]) -> Self {
+//!         Self { display_name: from.name }
+//!     }
+//! }
+//!
+//! async fn signup(obake::actix::Versioned(signup): obake::actix::Versioned<Signup>) -> String {
+//!     signup.display_name
+//! }
+//!
+//! let app = App::new().route("/signup", web::post().to(signup));
+//! ```
+
+use core::future::Future;
+use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+use std::boxed::Box;
+
+use actix_web::dev::Payload;
+use actix_web::http::header::{HeaderName, CONTENT_TYPE};
+use actix_web::http::StatusCode;
+use actix_web::web::Bytes;
+use actix_web::{FromRequest, HttpRequest, HttpResponse, ResponseError};
+use serde::de::DeserializeOwned;
+
+use crate::extract;
+use crate::{AnyVersion, DeserializeVersion};
+
+pub use crate::extract::DEFAULT_VERSION_HEADER;
+
+/// Renames the header read by [`Versioned`], when added to an app as data (e.g. with
+/// `.app_data(VersionHeader(HeaderName::from_static("x-api-version")))`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionHeader(pub HeaderName);
+
+/// Extracts a JSON request body in any version of `T`, upgrading it to the latest version.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Versioned<T>(pub T);
+
+impl<T> Deref for Versioned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Versioned<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> FromRequest for Versioned<T>
+where
+    T: for<'de> DeserializeVersion<'de> + 'static,
+    AnyVersion<T>: DeserializeOwned,
+{
+    type Error = VersionRejection;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, VersionRejection>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let header = req.app_data::<VersionHeader>().map_or_else(
+            || HeaderName::from_static(DEFAULT_VERSION_HEADER),
+            |header| header.0.clone(),
+        );
+        let header = req
+            .headers()
+            .get(&header)
+            .map(|value| value.to_str().unwrap_or_default());
+        let version = match extract::header_version::<T, _>(header) {
+            Ok(version) => version,
+            Err(rejection) => return Box::pin(core::future::ready(Err(rejection))),
+        };
+
+        let body = Bytes::from_request(req, payload);
+        Box::pin(async move {
+            let body = body.await.map_err(VersionRejection::Body)?;
+            extract::decode_body(version, &body).map(Self)
+        })
+    }
+}
+
+/// The error returned by [`Versioned`].
+///
+/// Unsupported versions and invalid bodies are rejected with `400 Bad Request` and a JSON body
+/// like `{"error": "...", "supported_versions": ["0.1.0", "0.2.0"]}`.
+pub type VersionRejection = extract::VersionRejection<actix_web::Error>;
+
+impl std::error::Error for VersionRejection {}
+
+impl ResponseError for VersionRejection {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::UnsupportedVersion { .. } | Self::InvalidBody { .. } => StatusCode::BAD_REQUEST,
+            Self::Body(err) => err.as_response_error().status_code(),
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            Self::Body(err) => err.error_response(),
+            rejection => HttpResponse::build(self.status_code())
+                .insert_header((CONTENT_TYPE, "application/json"))
+                .body(rejection.response_body()),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! An [`axum`](https://docs.rs/axum) extractor for versioned JSON request bodies.
+//!
+//! [`Versioned<T>`] deserializes the body as the version named by the `X-Schema-Version` header
+//! (which can be renamed by adding a [`VersionHeader`] extension to the router), or, when the
+//! header is missing, as the version-tagged encoding of `T` (e.g. with
+//! `#[obake(serde(tag = "version"))]`). Either way, the handler receives the latest version.
+//!
+//! ```
+//! # use serde::Deserialize;
+//! use axum::routing::post;
+//! use axum::Router;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(derive(Deserialize), serde(tag = "version"))]
+//! #[derive(Deserialize)]
+//! struct Signup {
+//!     #[obake(cfg("0.1.0"))]
+//!     name: String,
+//!     #[obake(cfg(">=0.2"))]
+//!     display_name: String,
+//! }
+//!
+//! impl From<Signup!["0.1.0"]> for Signup!["0.2.0"] {
+//!     fn from(from: Signup!["0.1.0"]) -> Self {
+//!         Self { display_name: from.name }
+//!     }
+//! }
+//!
+//! async fn signup(obake::axum::Versioned(signup): obake::axum::Versioned<Signup>) -> String {
+//!     signup.display_name
+//! }
+//!
+//! let app: Router = Router::new().route("/signup", post(signup));
+//! ```
+
+use core::ops::{Deref, DerefMut};
+
+use ::axum::body::Bytes;
+use ::axum::extract::rejection::BytesRejection;
+use ::axum::extract::{FromRequest, Request};
+use ::axum::http::header::{HeaderName, CONTENT_TYPE};
+use ::axum::http::StatusCode;
+use ::axum::response::{IntoResponse, Response};
+use serde::de::DeserializeOwned;
+
+use crate::extract;
+use crate::{AnyVersion, DeserializeVersion};
+
+pub use crate::extract::DEFAULT_VERSION_HEADER;
+
+/// Renames the header read by [`Versioned`], when added to a router as an extension (e.g. with
+/// `.layer(Extension(VersionHeader(HeaderName::from_static("x-api-version"))))`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionHeader(pub HeaderName);
+
+/// Extracts a JSON request body in any version of `T`, upgrading it to the latest version.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Versioned<T>(pub T);
+
+impl<T> Deref for Versioned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Versioned<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T, S> FromRequest<S> for Versioned<T>
+where
+    T: for<'de> DeserializeVersion<'de>,
+    AnyVersion<T>: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = VersionRejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let header = req.extensions().get::<VersionHeader>().map_or_else(
+            || HeaderName::from_static(DEFAULT_VERSION_HEADER),
+            |header| header.0.clone(),
+        );
+        let header = req
+            .headers()
+            .get(&header)
+            .map(|value| value.to_str().unwrap_or_default());
+        let version = extract::header_version::<T, _>(header)?;
+
+        let body = Bytes::from_request(req, state)
+            .await
+            .map_err(VersionRejection::Body)?;
+        extract::decode_body(version, &body).map(Self)
+    }
+}
+
+/// The rejection returned by [`Versioned`].
+///
+/// Unsupported versions and invalid bodies are rejected with `400 Bad Request` and a JSON body
+/// like `{"error": "...", "supported_versions": ["0.1.0", "0.2.0"]}`.
+pub type VersionRejection = extract::VersionRejection<BytesRejection>;
+
+impl std::error::Error for VersionRejection {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Body(rejection) => Some(rejection),
+            _ => None,
+        }
+    }
+}
+
+impl IntoResponse for VersionRejection {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Body(rejection) => rejection.into_response(),
+            rejection => (
+                StatusCode::BAD_REQUEST,
+                [(CONTENT_TYPE, "application/json")],
+                rejection.response_body(),
+            )
+                .into_response(),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Best-effort deserialization of documents written by a newer version than this one knows.
+//!
+//! Deserializing through the generated `enum` fails outright for a document tagged with a version
+//! it doesn't know, such as one written by a newer release of the same application. [`from_value`]
+//! instead reads such documents as the latest known version, ignoring any fields it doesn't
+//! declare, and reports that it did so with a [`NewerThanSupported`] warning. Documents tagged
+//! with a known version are upgraded as usual.
+//!
+//! ```
+//! # use serde::Deserialize;
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(trivial_migrations)]
+//! #[obake(derive(Deserialize), serde(tag = "version"))]
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     workers: u32,
+//! }
+//!
+//! let json = serde_json::json!({ "version": "0.4.0", "workers": 8, "threads": 2 });
+//! let loaded = obake::best_effort::from_value::<Config>(json, "version").unwrap();
+//! assert_eq!(loaded.value.workers, 8);
+//!
+//! let warning = loaded.warning.unwrap();
+//! assert_eq!((warning.found.as_str(), warning.latest), ("0.4.0", "0.2.0"));
+//! ```
+
+use std::fmt;
+use std::string::{String, ToString};
+
+use semver::Version;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{AnyVersion, Versioned};
+
+/// The warning reported when a document is tagged with a newer version than the latest known
+/// version, and so was read as the latest version instead.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NewerThanSupported {
+    /// The version the document is tagged with.
+    pub found: String,
+    /// The latest known version, which the document was read as.
+    pub latest: &'static str,
+}
+
+impl fmt::Display for NewerThanSupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "version {} is newer than the latest supported version ({}), so fields added since \
+             may have been ignored",
+            self.found, self.latest
+        )
+    }
+}
+
+/// A document read as the latest version, along with a warning if it was tagged with a newer
+/// version.
+#[derive(Clone, Debug)]
+pub struct Loaded<T> {
+    /// The latest version of the document.
+    pub value: T,
+    /// Set if the document was tagged with a newer version than the latest known version.
+    pub warning: Option<NewerThanSupported>,
+}
+
+/// Reads an object tagged with its version in the field `tag` (as with
+/// `#[obake(serde(tag = "version"))]`), upgrading it to the latest version of `T`.
+///
+/// If the tag is a valid semantic version newer than the latest version of `T`, the object is
+/// read as the latest version instead, ignoring fields it doesn't declare (unless it denies
+/// unknown fields), and [`Loaded::warning`] is set.
+///
+/// # Errors
+///
+/// Returns an error if the object doesn't match the shape of the version it is tagged with (or,
+/// for newer versions, of the latest version).
+pub fn from_value<T>(mut value: Value, tag: &str) -> Result<Loaded<T>, serde_json::Error>
+where
+    T: Versioned + DeserializeOwned,
+    AnyVersion<T>: DeserializeOwned,
+{
+    let latest = T::VERSIONS.last().copied().unwrap_or_default();
+    let newer = value
+        .get(tag)
+        .and_then(Value::as_str)
+        .filter(|found| is_newer(found, latest))
+        .map(ToString::to_string);
+
+    let Some(found) = newer else {
+        let versioned = serde_json::from_value::<AnyVersion<T>>(value)?;
+        return Ok(Loaded {
+            value: versioned.into(),
+            warning: None,
+        });
+    };
+
+    if let Some(object) = value.as_object_mut() {
+        object.remove(tag);
+    }
+    Ok(Loaded {
+        value: serde_json::from_value(value)?,
+        warning: Some(NewerThanSupported { found, latest }),
+    })
+}
+
+/// Reads a JSON document with [`from_value`].
+///
+/// # Errors
+///
+/// Returns an error if the document isn't valid JSON, or see [`from_value`].
+pub fn from_str<T>(json: &str, tag: &str) -> Result<Loaded<T>, serde_json::Error>
+where
+    T: Versioned + DeserializeOwned,
+    AnyVersion<T>: DeserializeOwned,
+{
+    from_value(serde_json::from_str(json)?, tag)
+}
+
+fn is_newer(found: &str, latest: &str) -> bool {
+    match (Version::parse(found), Version::parse(latest)) {
+        (Ok(found), Ok(latest)) => found > latest,
+        _ => false,
+    }
+}
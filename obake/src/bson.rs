@@ -0,0 +1,57 @@
+//! Helpers for storing versioned data-structures in [BSON](https://bsonspec.org/) documents.
+//!
+//! Items declared with `#[obake(bson)]` record their version in a document field (by default,
+//! [`DEFAULT_VERSION_FIELD`]), following the schema-versioning pattern commonly used with
+//! [MongoDB](https://www.mongodb.com/). The field can be renamed with `#[obake(bson(field = "..."))]`.
+//!
+//! Note that the version field is implemented as an internally tagged `serde` representation, so
+//! each version must serialize as a map (i.e. be a `struct` with named fields).
+
+use bson::Document;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::Versioned;
+
+/// The document field used to store the version of a data-structure when `#[obake(bson)]` is
+/// given no explicit field name.
+pub const DEFAULT_VERSION_FIELD: &str = "_schema_version";
+
+/// Automatically implemented for versioned data-structures declared with `#[obake(bson)]`.
+pub trait BsonVersioned: Versioned {
+    /// The name of the document field holding the version.
+    const VERSION_FIELD: &'static str;
+}
+
+/// Serializes a value to a document tagged with its version.
+///
+/// # Errors
+///
+/// Returns an error if the value cannot be represented as a BSON document.
+pub fn to_document<T>(value: T) -> Result<Document, bson::ser::Error>
+where
+    T: BsonVersioned,
+    T::Versioned: Serialize,
+{
+    bson::to_document(&T::Versioned::from(value))
+}
+
+/// Deserializes a version-tagged document, upgrading it to the latest version.
+///
+/// # Errors
+///
+/// Returns an error if the version field is missing or unrecognised, or if the document doesn't
+/// match the shape of the version it is tagged with.
+pub fn from_document<T>(document: Document) -> Result<T, bson::de::Error>
+where
+    T: BsonVersioned,
+    T::Versioned: DeserializeOwned,
+{
+    bson::from_document::<T::Versioned>(document).map(Into::into)
+}
+
+/// Reads the version a document is tagged with, without deserializing the rest of it.
+#[must_use]
+pub fn version_of<T: BsonVersioned>(document: &Document) -> Option<&str> {
+    document.get_str(T::VERSION_FIELD).ok()
+}
@@ -0,0 +1,521 @@
+//! Build-script support for generating C headers for every version with
+//! [`cbindgen`](https://docs.rs/cbindgen).
+//!
+//! `cbindgen` reads source files rather than expanded code, so it never sees the versions generated
+//! by `#[obake::versioned]`. [`expand_file`] rewrites a source file, replacing every versioned
+//! `struct` or `enum` with a plain item for each version (e.g. `Foo_v0_1_0`) and an alias for the
+//! latest version, as generated by the macro. The result can be written to `OUT_DIR` and handed to
+//! `cbindgen` in place of the original, so headers declare every version:
+//!
+//! ```no_run
+//! // build.rs
+//! let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("ffi.rs");
+//! obake::cbindgen::write_expanded("src/ffi.rs", &out).unwrap();
+//! // cbindgen::Builder::new().with_src(&out).generate().unwrap().write_to_file("include/ffi.h");
+//! ```
+//!
+//! Only the declarations of versions are generated: migrations and other impls are left out, and
+//! only the helpers declaring versions (`version(...)`, `versions(...)`, `versions_from = "..."`
+//! and `use_versions(...)`, naming an `obake::version_set!` in the same file), `cfg(...)`,
+//! `inherit` and `renamed_from(...)` affect the output. Versions and requirements are parsed just
+//! as by `#[obake::versioned]`. Versions gated behind a feature are marked
+//! `#[cfg(feature = "...")]`, which `cbindgen` can translate with its `[defines]` configuration.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::format;
+use std::io;
+use std::path::Path;
+use std::string::{String, ToString};
+use std::vec;
+use std::vec::Vec;
+
+use obake_syntax::{parse_version, VersionsHelper};
+use obake_syntax::{
+    version_suffix, CfgPredicate, InheritMapping, Version, VersionAttr, VersionSet,
+};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::parse::{ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, token, Attribute, Fields, Ident, Item, Token, Type, Visibility};
+
+/// The error returned when a source file can't be expanded.
+#[derive(Debug)]
+pub enum ExpandError {
+    /// The source file couldn't be read, or the expansion couldn't be written.
+    Io(io::Error),
+    /// The source file isn't valid Rust, or declares versions which can't be expanded.
+    Syntax(syn::Error),
+}
+
+impl fmt::Display for ExpandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Syntax(err) => write!(f, "failed to expand versioned items: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ExpandError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Syntax(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for ExpandError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<syn::Error> for ExpandError {
+    fn from(err: syn::Error) -> Self {
+        Self::Syntax(err)
+    }
+}
+
+/// Expands every versioned item in the Rust source `source` (including those in inline modules),
+/// leaving other items as they are.
+///
+/// # Errors
+///
+/// Returns an error if `source` isn't valid Rust, or if a versioned item declares an invalid
+/// version or requirement.
+pub fn expand_str(source: &str) -> Result<String, ExpandError> {
+    let mut file = syn::parse_file(source)?;
+    let mut sets = VersionSets::new();
+    collect_version_sets(&file.items, &mut sets)?;
+    file.items = expand_items(file.items, &sets)?;
+    Ok(file.into_token_stream().to_string())
+}
+
+/// Expands the Rust source file at `path` with [`expand_str`].
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read, or see [`expand_str`].
+pub fn expand_file(path: impl AsRef<Path>) -> Result<String, ExpandError> {
+    expand_str(&std::fs::read_to_string(path)?)
+}
+
+/// Expands the Rust source file at `src` with [`expand_str`], writing the expansion to `out`.
+///
+/// # Errors
+///
+/// Returns an error if `out` can't be written, or see [`expand_file`].
+pub fn write_expanded(src: impl AsRef<Path>, out: impl AsRef<Path>) -> Result<(), ExpandError> {
+    std::fs::write(out, expand_file(src)?)?;
+    Ok(())
+}
+
+/// The versions of each set declared with `obake::version_set!`, by name.
+type VersionSets = BTreeMap<String, Vec<VersionAttr>>;
+
+/// Collects the sets declared in `items` (including those in inline modules). Sets are only
+/// looked up by name, so `#[obake(use_versions(...))]` can name a set declared anywhere in the
+/// file.
+fn collect_version_sets(items: &[Item], sets: &mut VersionSets) -> syn::Result<()> {
+    for item in items {
+        match item {
+            Item::Macro(item)
+                if item
+                    .mac
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "version_set") =>
+            {
+                let set = item.mac.parse_body::<VersionSet>()?;
+                let versions = set
+                    .versions
+                    .iter()
+                    .map(|version| parse_version.parse2(version.to_token_stream()))
+                    .collect::<syn::Result<_>>()?;
+                sets.insert(set.ident.to_string(), versions);
+            }
+            Item::Mod(item) => {
+                if let Some((_, items)) = &item.content {
+                    collect_version_sets(items, sets)?;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn expand_items(items: Vec<Item>, sets: &VersionSets) -> syn::Result<Vec<Item>> {
+    let mut expanded = Vec::new();
+    for item in items {
+        match item {
+            Item::Struct(item) if item.attrs.iter().any(is_versioned) => {
+                let attrs = ItemAttrs::parse(&item.attrs, sets)?;
+                let fields = item.fields.iter().map(|field| &field.attrs[..]);
+                check_declared(&item.ident, &attrs.versions, fields)?;
+                for decl in &attrs.versions {
+                    let mut version = item.clone();
+                    version.ident = attrs.version_ident(&item.ident, &decl.version);
+                    version.attrs = ItemAttrs::version_attrs(&item.attrs, decl);
+                    version.fields = expand_fields(&item.fields, &decl.version)?;
+                    expanded.push(Item::Struct(version));
+                }
+                expanded.extend(attrs.aliases(&item.vis, &item.ident)?);
+            }
+            Item::Enum(item) if item.attrs.iter().any(is_versioned) => {
+                let attrs = ItemAttrs::parse(&item.attrs, sets)?;
+                let variants = item.variants.iter().flat_map(|variant| {
+                    let fields = variant.fields.iter().map(|field| &field.attrs[..]);
+                    core::iter::once(&variant.attrs[..]).chain(fields)
+                });
+                check_declared(&item.ident, &attrs.versions, variants)?;
+                for decl in &attrs.versions {
+                    let mut version = item.clone();
+                    version.ident = attrs.version_ident(&item.ident, &decl.version);
+                    version.attrs = ItemAttrs::version_attrs(&item.attrs, decl);
+                    version.variants = Punctuated::new();
+                    for variant in &item.variants {
+                        if !enabled_in(&variant.attrs, &decl.version)? {
+                            continue;
+                        }
+                        let mut variant = variant.clone();
+                        variant.attrs = strip_attrs(&variant.attrs);
+                        variant.fields = expand_fields(&variant.fields, &decl.version)?;
+                        version.variants.push(variant);
+                    }
+                    expanded.push(Item::Enum(version));
+                }
+                expanded.extend(attrs.aliases(&item.vis, &item.ident)?);
+            }
+            Item::Mod(mut item) => {
+                if let Some((brace, items)) = item.content.take() {
+                    item.content = Some((brace, expand_items(items, sets)?));
+                }
+                expanded.push(Item::Mod(item));
+            }
+            item => expanded.push(item),
+        }
+    }
+
+    Ok(expanded)
+}
+
+fn is_versioned(attr: &Attribute) -> bool {
+    let segments: Vec<_> = attr
+        .path
+        .segments
+        .iter()
+        .map(|s| s.ident.to_string())
+        .collect();
+    segments == ["versioned"] || segments == ["obake", "versioned"]
+}
+
+/// A helper of an `#[obake(...)]` attribute.
+enum Helper {
+    /// Versions declared with `version(...)`, `versions(...)` or `versions_from = "..."`, parsed
+    /// as by `#[obake::versioned]`.
+    Versions(Vec<VersionAttr>),
+    /// Any other helper, as its identifier and its arguments (which are empty if there are none).
+    Other(Ident, TokenStream),
+}
+
+/// The helpers of every `#[obake(...)]` attribute in `attrs`.
+fn helpers(attrs: &[Attribute]) -> syn::Result<Vec<Helper>> {
+    let mut helpers = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("obake")) {
+        let parser = |input: ParseStream| {
+            // A trailing `feature = "..."` only gates a version declared in the same attribute
+            let mut attr_helpers = Vec::new();
+            while !input.is_empty() {
+                if VersionsHelper::peek(input) {
+                    match input.parse()? {
+                        VersionsHelper::Version(version) => {
+                            attr_helpers.push(Helper::Versions(vec![version]));
+                        }
+                        VersionsHelper::Versions(versions)
+                        | VersionsHelper::VersionsFrom { versions, .. }
+                        | VersionsHelper::VersionSet { versions, .. } => {
+                            attr_helpers.push(Helper::Versions(versions));
+                        }
+                        VersionsHelper::Feature { span, feature } => {
+                            let previous = match attr_helpers.last_mut() {
+                                Some(Helper::Versions(versions)) => versions.last_mut(),
+                                _ => None,
+                            };
+                            VersionsHelper::gate(previous, span, feature)?;
+                        }
+                    }
+                } else {
+                    let ident = input.call(Ident::parse_any)?;
+                    let args = if input.peek(token::Paren) {
+                        let content;
+                        parenthesized!(content in input);
+                        content.parse()?
+                    } else if input.peek(Token![=]) {
+                        input.parse::<Token![=]>()?;
+                        let mut args = TokenStream::new();
+                        while !input.is_empty() && !input.peek(Token![,]) {
+                            args.extend(core::iter::once(input.parse::<TokenTree>()?));
+                        }
+                        args
+                    } else {
+                        TokenStream::new()
+                    };
+                    attr_helpers.push(Helper::Other(ident, args));
+                }
+
+                if input.is_empty() {
+                    break;
+                }
+                input.parse::<Token![,]>()?;
+            }
+
+            Ok(attr_helpers)
+        };
+        helpers.extend(attr.parse_args_with(parser)?);
+    }
+
+    Ok(helpers)
+}
+
+/// The arguments of each helper named `name` in `helpers`.
+fn args_of<'a>(helpers: &'a [Helper], name: &'a str) -> impl Iterator<Item = &'a TokenStream> {
+    helpers.iter().filter_map(move |helper| match helper {
+        Helper::Other(ident, args) if ident == name => Some(args),
+        _ => None,
+    })
+}
+
+fn version_ident(ident: &Ident, version: &Version) -> Ident {
+    format_ident!("{}_v{}", ident, version_suffix(version))
+}
+
+/// Whether an item with `attrs` is declared in `version`, following `#[obake(cfg(...))]`.
+fn enabled_in(attrs: &[Attribute], version: &Version) -> syn::Result<bool> {
+    let helpers = helpers(attrs)?;
+    let reqs = args_of(&helpers, "cfg")
+        .map(|args| syn::parse2::<CfgPredicate>(args.clone()))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(reqs.is_empty() || reqs.iter().any(|req| req.matches(version)))
+}
+
+/// Checks that every version listed by a `cfg(in(...))` in `attrs` (those of the fields or
+/// variants of `ident`) is one of `versions`, as in `#[obake::versioned]`.
+fn check_declared<'a>(
+    ident: &Ident,
+    versions: &[VersionAttr],
+    attrs: impl Iterator<Item = &'a [Attribute]>,
+) -> syn::Result<()> {
+    for attrs in attrs {
+        let helpers = helpers(attrs)?;
+        for args in args_of(&helpers, "cfg") {
+            let req = syn::parse2::<CfgPredicate>(args.clone())?;
+            if let Some(undeclared) = req.undeclared_version(versions) {
+                return Err(syn::Error::new(
+                    undeclared.span,
+                    format!("`{ident}` has no version {}", undeclared.version),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The item-level helpers which affect the declarations of versions.
+struct ItemAttrs {
+    versions: Vec<VersionAttr>,
+    renamed_froms: Vec<(Ident, Version)>,
+}
+
+impl ItemAttrs {
+    fn parse(attrs: &[Attribute], sets: &VersionSets) -> syn::Result<Self> {
+        let helpers = helpers(attrs)?;
+        let mut versions = Vec::new();
+        for helper in &helpers {
+            if let Helper::Versions(declared) = helper {
+                versions.extend(declared.iter().cloned());
+            }
+        }
+
+        for args in args_of(&helpers, "use_versions") {
+            let path = syn::parse2::<syn::Path>(args.clone())?;
+            let name = path.segments.last().unwrap().ident.to_string();
+            let set = sets.get(&name).ok_or_else(|| {
+                syn::Error::new_spanned(
+                    &path,
+                    format!("`{name}` isn't declared with `obake::version_set!` in this file"),
+                )
+            })?;
+            versions.extend(set.iter().cloned());
+        }
+
+        let mut renamed_froms = Vec::new();
+        for args in args_of(&helpers, "renamed_from") {
+            let parser = |input: ParseStream| {
+                let name = input.parse::<syn::LitStr>()?.parse::<Ident>()?;
+                input.parse::<Token![,]>()?;
+                let key = input.parse::<Ident>()?;
+                if key != "until" {
+                    return Err(syn::Error::new(key.span(), "expected `until`"));
+                }
+                input.parse::<Token![=]>()?;
+                Ok((name, parse_version(input)?.version))
+            };
+            renamed_froms.push(parser.parse2(args.clone())?);
+        }
+
+        if versions.is_empty() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "expected at least one `#[obake(version(...))]` attribute",
+            ));
+        }
+        versions.sort();
+
+        Ok(Self {
+            versions,
+            renamed_froms,
+        })
+    }
+
+    /// The identifier of `version`, named after the earliest `#[obake(renamed_from(...))]` still
+    /// in effect in that version, if any.
+    fn version_ident(&self, ident: &Ident, version: &Version) -> Ident {
+        let ident = self
+            .renamed_froms
+            .iter()
+            .filter(|(_, until)| version < until)
+            .min_by(|a, b| a.1.cmp(&b.1))
+            .map_or(ident, |(renamed, _)| renamed);
+        version_ident(ident, version)
+    }
+
+    /// The attributes of the declaration of `decl`, without those only understood by
+    /// `#[obake::versioned]`.
+    fn version_attrs(attrs: &[Attribute], decl: &VersionAttr) -> Vec<Attribute> {
+        let mut attrs = strip_attrs(attrs);
+        attrs.push(syn::parse_quote!(#[allow(non_camel_case_types)]));
+        if let Some(feature) = &decl.feature {
+            attrs.push(syn::parse_quote!(#[cfg(feature = #feature)]));
+        }
+        attrs
+    }
+
+    /// Aliases named after the current identifier for versions declared with an old identifier,
+    /// and the alias of the latest version.
+    fn aliases(&self, vis: &Visibility, ident: &Ident) -> syn::Result<Vec<Item>> {
+        let mut aliases = Vec::new();
+        for decl in &self.versions {
+            let renamed = self.version_ident(ident, &decl.version);
+            let alias = version_ident(ident, &decl.version);
+            if renamed != alias {
+                let cfg = decl.expand_cfg();
+                aliases.push(syn::parse2(quote! {
+                    #cfg
+                    #[allow(non_camel_case_types)]
+                    #vis type #alias = #renamed;
+                })?);
+            }
+        }
+
+        // A feature-gated latest version is only the latest when its feature is enabled
+        let latest = self.versions.last().unwrap();
+        let current = self.version_ident(ident, &latest.version);
+        match (&latest.feature, self.versions.len()) {
+            (Some(feature), len) if len > 1 => {
+                let previous = self.version_ident(ident, &self.versions[len - 2].version);
+                aliases.push(syn::parse2(quote! {
+                    #[cfg(feature = #feature)]
+                    #vis type #ident = #current;
+                })?);
+                aliases.push(syn::parse2(quote! {
+                    #[cfg(not(feature = #feature))]
+                    #vis type #ident = #previous;
+                })?);
+            }
+            _ => {
+                let cfg = latest.expand_cfg();
+                aliases.push(syn::parse2(quote! {
+                    #cfg
+                    #vis type #ident = #current;
+                })?);
+            }
+        }
+
+        Ok(aliases)
+    }
+}
+
+fn strip_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| !attr.path.is_ident("obake") && !is_versioned(attr))
+        .cloned()
+        .collect()
+}
+
+/// The fields of `fields` declared in `version`, with `#[obake(inherit)]` fields referring to the
+/// matching version of their type.
+fn expand_fields(fields: &Fields, version: &Version) -> syn::Result<Fields> {
+    let mut fields = fields.clone();
+    let punctuated = match &mut fields {
+        Fields::Named(fields) => &mut fields.named,
+        Fields::Unnamed(fields) => &mut fields.unnamed,
+        Fields::Unit => return Ok(fields),
+    };
+
+    let mut expanded = Punctuated::new();
+    for mut field in core::mem::take(punctuated) {
+        if !enabled_in(&field.attrs, version)? {
+            continue;
+        }
+
+        let helpers = helpers(&field.attrs)?;
+        let mut inherits = false;
+        let mut mappings = Vec::new();
+        for args in args_of(&helpers, "inherit") {
+            inherits = true;
+            if !args.is_empty() {
+                mappings.push(syn::parse2::<InheritMapping>(args.clone())?);
+            }
+        }
+
+        if inherits {
+            // The mapping for the latest outer version at or before this one applies, as in
+            // `#[obake::versioned]`
+            mappings.sort_by(|a, b| a.outer.cmp(&b.outer));
+            let inner = mappings
+                .iter()
+                .rev()
+                .find(|mapping| mapping.outer <= *version)
+                .map_or(version, |mapping| &mapping.inner);
+            match &mut field.ty {
+                Type::Path(ty) if !ty.path.segments.is_empty() => {
+                    let last = ty.path.segments.last_mut().unwrap();
+                    last.ident = version_ident(&last.ident, inner);
+                }
+                ty => {
+                    return Err(syn::Error::new_spanned(
+                        ty,
+                        "`#[obake(inherit)]` can only be applied to fields with \
+                         `#[obake::versioned]` types",
+                    ))
+                }
+            }
+        }
+
+        field.attrs = strip_attrs(&field.attrs);
+        expanded.push(field);
+    }
+    *punctuated = expanded;
+
+    Ok(fields)
+}
@@ -0,0 +1,88 @@
+//! Layered configuration with [`config`](https://docs.rs/config).
+//!
+//! Deserializing a [`Config`] straight into the generated `enum` buffers it through `serde`'s
+//! internally tagged representation, which loses `config`'s coercion of values (so `PORT=8080`
+//! from the environment is no longer accepted as a number). [`ConfigExt::try_deserialize_versioned`]
+//! instead reads the version from the field named by `#[obake(serde(tag = "..."))]`, then
+//! deserializes the rest of the configuration directly as that version and upgrades it to the
+//! latest version.
+//!
+//! ```
+//! # use serde::Deserialize;
+//! use config::{Config, File, FileFormat};
+//! use obake::config::ConfigExt;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(derive(Deserialize), serde(tag = "version"))]
+//! #[derive(Deserialize)]
+//! struct Server {
+//!     host: String,
+//!     #[obake(cfg("0.1.0"))]
+//!     port: u16,
+//!     #[obake(cfg(">=0.2"))]
+//!     ports: Vec<u16>,
+//! }
+//!
+//! impl From<Server!["0.1.0"]> for Server!["0.2.0"] {
+//!     fn from(from: Server!["0.1.0"]) -> Self {
+//!         Self { host: from.host, ports: vec![from.port] }
+//!     }
+//! }
+//!
+//! let config = Config::builder()
+//!     .set_default("host", "localhost")
+//!     .unwrap()
+//!     .add_source(File::from_str(r#"{ "version": "0.1.0", "port": "8080" }"#, FileFormat::Json))
+//!     .build()
+//!     .unwrap();
+//! let server: Server = config.try_deserialize_versioned().unwrap();
+//! assert_eq!(server.host, "localhost");
+//! assert_eq!(server.ports, [8080]);
+//! ```
+
+use std::format;
+use std::string::String;
+
+use config::{Config, ConfigError, Map, Value, ValueKind};
+
+use crate::DeserializeVersion;
+
+/// Deserializing versioned configuration from a [`Config`].
+pub trait ConfigExt {
+    /// Deserializes the configuration as the version named by the tag of `T` (its
+    /// [`DeserializeVersion::TAG`]), upgrading it to the latest version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `T` isn't declared with `#[obake(serde(tag = "..."))]`, if the tag is
+    /// missing or names a version which isn't one of
+    /// [`Versioned::VERSIONS`](crate::Versioned::VERSIONS), or if the configuration can't be
+    /// deserialized as that version.
+    fn try_deserialize_versioned<T>(self) -> Result<T, ConfigError>
+    where
+        T: for<'de> DeserializeVersion<'de>;
+}
+
+impl ConfigExt for Config {
+    fn try_deserialize_versioned<T>(self) -> Result<T, ConfigError>
+    where
+        T: for<'de> DeserializeVersion<'de>,
+    {
+        let tag = T::TAG.ok_or_else(|| {
+            ConfigError::Message(format!(
+                "`{}` must be declared with `#[obake(serde(tag = \"...\"))]` to be deserialized \
+                 from a `Config`",
+                T::NAME
+            ))
+        })?;
+        let version = self.get_string(tag)?;
+        let mut table = self.try_deserialize::<Map<String, Value>>()?;
+        table.remove(tag);
+
+        let versioned =
+            T::deserialize_version(&version, Value::new(None, ValueKind::Table(table)))?;
+        Ok(versioned.into())
+    }
+}
@@ -0,0 +1,378 @@
+//! A small self-describing file format for versioned data-structures, such as save files.
+//!
+//! Each file starts with a header recording the version of the payload, so [`load`] can decode
+//! files written by any older version of an application and upgrade them to the latest version.
+//! Payloads are encoded with `bincode`, optionally compressed (with the `deflate` or `zstd`
+//! features), and checked against a CRC-32 of the stored bytes. The layout is:
+//!
+//! | Bytes | Contents                                              |
+//! |-------|-------------------------------------------------------|
+//! | 4     | Magic bytes ([`DEFAULT_MAGIC`] unless configured)     |
+//! | 1     | Format version ([`FORMAT_VERSION`])                   |
+//! | 1     | [`Compression`]                                       |
+//! | 1     | Length of the version tag                             |
+//! | n     | Version tag (e.g. `0.2.0`), in UTF-8                  |
+//! | 4     | CRC-32 of the stored payload, little-endian           |
+//! | 8     | Length of the stored payload, little-endian           |
+//! | n     | Payload                                               |
+//!
+//! Items must be declared with `#[obake(serde(...))]` so that each version implements
+//! [`DeserializeVersion`].
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Save {
+//!     level: u32,
+//!     #[obake(cfg(">=0.2"))]
+//!     checkpoint: Option<u32>,
+//! }
+//!
+//! impl From<Save!["0.1.0"]> for Save!["0.2.0"] {
+//!     fn from(from: Save!["0.1.0"]) -> Self {
+//!         Self { level: from.level, checkpoint: None }
+//!     }
+//! }
+//!
+//! let mut file = Vec::new();
+//! obake::container::save(&Save { level: 3, checkpoint: Some(1) }, &mut file).unwrap();
+//!
+//! let save: Save = obake::container::load(&file[..]).unwrap();
+//! assert_eq!(save, Save { level: 3, checkpoint: Some(1) });
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::string::String;
+use std::vec;
+use std::vec::Vec;
+
+use bincode::Options;
+use serde::Serialize;
+
+use crate::{DeserializeVersion, Versioned};
+
+/// The magic bytes used by [`Container::default`].
+pub const DEFAULT_MAGIC: [u8; 4] = *b"OBK\0";
+
+/// The version of the container format written by this version of `obake`.
+pub const FORMAT_VERSION: u8 = 1;
+
+/// How a payload is compressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Compression {
+    /// Stored as-is.
+    #[default]
+    None,
+    /// Compressed with DEFLATE (requires the `deflate` feature).
+    #[cfg(feature = "deflate")]
+    Deflate,
+    /// Compressed with Zstandard (requires the `zstd` feature).
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    fn tag(self) -> u8 {
+        match self {
+            Self::None => 0,
+            #[cfg(feature = "deflate")]
+            Self::Deflate => 1,
+            #[cfg(feature = "zstd")]
+            Self::Zstd => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Self::None),
+            #[cfg(feature = "deflate")]
+            1 => Some(Self::Deflate),
+            #[cfg(feature = "zstd")]
+            2 => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    // Only Zstandard compression can fail
+    #[cfg_attr(not(feature = "zstd"), allow(clippy::unnecessary_wraps))]
+    fn compress(self, payload: Vec<u8>) -> Result<Vec<u8>, Error> {
+        match self {
+            Self::None => Ok(payload),
+            #[cfg(feature = "deflate")]
+            Self::Deflate => Ok(miniz_oxide::deflate::compress_to_vec(&payload, 6)),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => zstd::encode_all(&payload[..], 0).map_err(Error::Io),
+        }
+    }
+
+    #[cfg_attr(
+        not(any(feature = "deflate", feature = "zstd")),
+        allow(clippy::unnecessary_wraps)
+    )]
+    fn decompress(self, stored: &[u8]) -> Result<Cow<'_, [u8]>, Error> {
+        match self {
+            Self::None => Ok(Cow::Borrowed(stored)),
+            #[cfg(feature = "deflate")]
+            Self::Deflate => miniz_oxide::inflate::decompress_to_vec(stored)
+                .map(Cow::Owned)
+                .map_err(|err| Error::Decompression(std::format!("{err}"))),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => zstd::decode_all(stored)
+                .map(Cow::Owned)
+                .map_err(|err| Error::Decompression(std::format!("{err}"))),
+        }
+    }
+}
+
+/// The error returned when a container can't be saved or loaded.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reading or writing failed.
+    Io(io::Error),
+    /// The file doesn't start with the expected magic bytes.
+    BadMagic([u8; 4]),
+    /// The file was written in a newer version of the container format.
+    UnsupportedFormat(u8),
+    /// The file was compressed with an unknown (or disabled) compression scheme.
+    UnsupportedCompression(u8),
+    /// The payload doesn't match its checksum.
+    Checksum {
+        /// The checksum recorded in the header.
+        expected: u32,
+        /// The checksum of the stored payload.
+        actual: u32,
+    },
+    /// The version tag isn't valid UTF-8.
+    InvalidVersionTag,
+    /// The payload couldn't be decompressed.
+    Decompression(String),
+    /// The payload couldn't be encoded, or decoded as its version (including when the version
+    /// isn't one of [`Versioned::VERSIONS`]).
+    Encoding(bincode::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read or write container: {err}"),
+            Self::BadMagic(magic) => write!(f, "unrecognised magic bytes {magic:02x?}"),
+            Self::UnsupportedFormat(format) => {
+                write!(f, "unsupported container format version {format}")
+            }
+            Self::UnsupportedCompression(tag) => write!(f, "unsupported compression scheme {tag}"),
+            Self::Checksum { expected, actual } => write!(
+                f,
+                "checksum mismatch (expected {expected:#010x}, found {actual:#010x})"
+            ),
+            Self::InvalidVersionTag => write!(f, "version tag isn't valid UTF-8"),
+            Self::Decompression(err) => write!(f, "failed to decompress payload: {err}"),
+            Self::Encoding(err) => write!(f, "failed to encode or decode payload: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Encoding(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<bincode::Error> for Error {
+    fn from(err: bincode::Error) -> Self {
+        Self::Encoding(err)
+    }
+}
+
+/// The configuration of a container: its magic bytes and how payloads are compressed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Container {
+    magic: [u8; 4],
+    compression: Compression,
+}
+
+impl Default for Container {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAGIC)
+    }
+}
+
+impl Container {
+    /// Creates a container identified by `magic`, storing payloads uncompressed.
+    #[must_use]
+    pub const fn new(magic: [u8; 4]) -> Self {
+        Self {
+            magic,
+            compression: Compression::None,
+        }
+    }
+
+    /// Compresses saved payloads with `compression`. Any supported compression scheme is
+    /// accepted when loading.
+    #[must_use]
+    pub const fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Writes `value` to `writer`, tagged with the latest version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value can't be encoded or compressed, or if writing fails.
+    pub fn save<T, W>(&self, value: &T, mut writer: W) -> Result<(), Error>
+    where
+        T: Versioned + Serialize,
+        W: Write,
+    {
+        let version = T::VERSIONS.last().map_or("", |version| version);
+        let payload = self.compression.compress(options().serialize(value)?)?;
+
+        let mut header = Vec::with_capacity(19 + version.len());
+        header.extend_from_slice(&self.magic);
+        header.push(FORMAT_VERSION);
+        header.push(self.compression.tag());
+        // Versions are far shorter than 256 bytes
+        #[allow(clippy::cast_possible_truncation)]
+        header.push(version.len() as u8);
+        header.extend_from_slice(version.as_bytes());
+        header.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+        header.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+
+        writer.write_all(&header)?;
+        writer.write_all(&payload)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a value of any version of `T` from `reader`, upgrading it to the latest version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, if the header is invalid, if the payload doesn't match
+    /// its checksum, or if it can't be decoded as the version it is tagged with.
+    pub fn load<T, R>(&self, reader: R) -> Result<T, Error>
+    where
+        T: for<'de> DeserializeVersion<'de>,
+        R: Read,
+    {
+        let (version, payload) = self.read(reader)?;
+        let mut deserializer = bincode::Deserializer::from_slice(&payload, options());
+        let versioned = T::deserialize_version(&version, &mut deserializer)?;
+        Ok(versioned.into())
+    }
+
+    /// Reads only the version tag from the header of a container, without reading (or checking)
+    /// the payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails, or if the header is invalid.
+    pub fn read_version<R: Read>(&self, mut reader: R) -> Result<String, Error> {
+        self.read_header(&mut reader).map(|(version, _)| version)
+    }
+
+    /// Reads the version tag and decompressed payload of a container.
+    fn read<R: Read>(self, mut reader: R) -> Result<(String, Vec<u8>), Error> {
+        let (version, compression) = self.read_header(&mut reader)?;
+
+        let mut crc = [0; 4];
+        reader.read_exact(&mut crc)?;
+        let expected = u32::from_le_bytes(crc);
+        let mut len = [0; 8];
+        reader.read_exact(&mut len)?;
+        let mut stored = Vec::new();
+        reader
+            .take(u64::from_le_bytes(len))
+            .read_to_end(&mut stored)?;
+        if stored.len() as u64 != u64::from_le_bytes(len) {
+            return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()));
+        }
+
+        let actual = crc32fast::hash(&stored);
+        if actual != expected {
+            return Err(Error::Checksum { expected, actual });
+        }
+
+        let payload = compression.decompress(&stored)?.into_owned();
+        Ok((version, payload))
+    }
+
+    /// Reads the header of a container, up to and including the version tag.
+    fn read_header<R: Read>(self, reader: &mut R) -> Result<(String, Compression), Error> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != self.magic {
+            return Err(Error::BadMagic(magic));
+        }
+
+        let format = read_u8(reader)?;
+        if format != FORMAT_VERSION {
+            return Err(Error::UnsupportedFormat(format));
+        }
+
+        let compression = read_u8(reader)?;
+        let compression =
+            Compression::from_tag(compression).ok_or(Error::UnsupportedCompression(compression))?;
+
+        let mut version = vec![0; usize::from(read_u8(reader)?)];
+        reader.read_exact(&mut version)?;
+        let version = String::from_utf8(version).map_err(|_| Error::InvalidVersionTag)?;
+        Ok((version, compression))
+    }
+}
+
+fn options() -> impl Options {
+    bincode::DefaultOptions::new().with_fixint_encoding()
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut byte = [0];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+/// Writes `value` to `writer` in a [`Container::default`], tagged with the latest version.
+///
+/// # Errors
+///
+/// Returns an error if the value can't be encoded, or if writing fails.
+pub fn save<T, W>(value: &T, writer: W) -> Result<(), Error>
+where
+    T: Versioned + Serialize,
+    W: Write,
+{
+    Container::default().save(value, writer)
+}
+
+/// Reads a value of any version of `T` from a [`Container::default`], upgrading it to the
+/// latest version.
+///
+/// # Errors
+///
+/// Returns an error if reading fails, if the header is invalid, if the payload doesn't match its
+/// checksum, or if it can't be decoded as the version it is tagged with.
+pub fn load<T, R>(reader: R) -> Result<T, Error>
+where
+    T: for<'de> DeserializeVersion<'de>,
+    R: Read,
+{
+    Container::default().load(reader)
+}
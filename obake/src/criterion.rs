@@ -0,0 +1,123 @@
+//! Benchmarks of migrations with [`criterion`](https://docs.rs/criterion).
+//!
+//! Items declared with `#[obake(generate_benches)]` get a `{name}_benches` module (e.g.
+//! `settings_benches` for `Settings`) with a `migrations` function, which benchmarks
+//! deserializing a batch of JSON payloads in every version and upgrading them to the latest
+//! version. Every version must implement `arbitrary::Arbitrary` (e.g. with
+//! `#[obake(arbitrary)]`), `Serialize` and `Deserialize`, and the payloads are generated from a
+//! fixed seed, so measurements are comparable between runs (and regressions in the cost of a
+//! migration show up in CI). `#[obake(generate_benches(feature = "bench"))]` gates the module
+//! behind a feature of the declaring crate.
+//!
+//! ```no_run
+//! # use serde::{Deserialize, Serialize};
+//! use criterion::criterion_group;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(arbitrary, generate_benches)]
+//! #[derive(Serialize, Deserialize)]
+//! pub struct Settings {
+//!     #[obake(cfg("0.1.0"))]
+//!     pub timeout_secs: u32,
+//!     #[obake(cfg(">=0.2"))]
+//!     pub timeout_ms: u64,
+//! }
+//!
+//! impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+//!     fn from(from: Settings!["0.1.0"]) -> Self {
+//!         Self { timeout_ms: u64::from(from.timeout_secs) * 1000 }
+//!     }
+//! }
+//!
+//! // In a bench target (declared with `harness = false`), along with `criterion_main!(benches)`
+//! criterion_group!(benches, settings_benches::migrations);
+//! # fn main() {}
+//! ```
+
+use core::convert::TryFrom;
+use core::hint::black_box;
+use std::format;
+use std::vec::Vec;
+
+use ::criterion::measurement::WallTime;
+use ::criterion::{BenchmarkGroup, BenchmarkId, Criterion, Throughput};
+use arbitrary::{Arbitrary, Unstructured};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::Versioned;
+
+/// The number of payloads deserialized and upgraded in each iteration of a benchmark.
+const SAMPLES: usize = 64;
+
+/// The number of pseudo-random bytes the payloads are generated from.
+const ENTROPY: usize = 64 * 1024;
+
+/// The group of benchmarks for every version of `T`, named `{name}/upgrade`.
+#[doc(hidden)]
+pub fn group<T: Versioned>(c: &mut Criterion) -> BenchmarkGroup<'_, WallTime> {
+    c.benchmark_group(format!("{}/upgrade", T::NAME))
+}
+
+/// Benchmarks deserializing payloads in version `version` (of type `V`) and upgrading them with
+/// `upgrade`.
+#[doc(hidden)]
+pub fn bench_version<V, T>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    version: &str,
+    upgrade: impl Fn(V) -> T,
+) where
+    V: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    let payloads = payloads::<V>();
+    if payloads.is_empty() {
+        return;
+    }
+
+    group.throughput(Throughput::Elements(
+        u64::try_from(payloads.len()).unwrap_or(u64::MAX),
+    ));
+    group.bench_function(BenchmarkId::from_parameter(version), |b| {
+        b.iter(|| {
+            for payload in &payloads {
+                if let Ok(from) = serde_json::from_slice::<V>(black_box(payload)) {
+                    black_box(upgrade(from));
+                }
+            }
+        });
+    });
+}
+
+/// Generates up to [`SAMPLES`] JSON payloads of `V` from a fixed seed, skipping any which don't
+/// survive a round trip through JSON (e.g. with non-finite floats).
+fn payloads<V>() -> Vec<Vec<u8>>
+where
+    V: for<'a> Arbitrary<'a> + Serialize + DeserializeOwned,
+{
+    // xorshift64, as the payloads only need to be varied and reproducible
+    let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+    let bytes: Vec<u8> = (0..ENTROPY)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state.to_le_bytes()[0]
+        })
+        .collect();
+
+    let mut u = Unstructured::new(&bytes);
+    let mut payloads = Vec::with_capacity(SAMPLES);
+    while payloads.len() < SAMPLES && !u.is_empty() {
+        let Ok(value) = V::arbitrary(&mut u) else {
+            break;
+        };
+        if let Ok(payload) = serde_json::to_vec(&value) {
+            if serde_json::from_slice::<V>(&payload).is_ok() {
+                payloads.push(payload);
+            }
+        }
+    }
+    payloads
+}
@@ -0,0 +1,195 @@
+//! Upgrading raw JSON without the typed versions.
+//!
+//! Items declared with `#[obake(dynamic)]` implement [`Dynamic`], describing how the serialized
+//! fields of each version differ from those of the previous version: fields which are renamed
+//! (e.g. with `#[obake(serde_cfg(">=0.2", rename = "..."))]`), removed, or added (with their
+//! `Default` value, if they have one). [`upgrade`] applies those changes to a
+//! [`serde_json::Value`], so tools operating on raw JSON (e.g. data fixes) don't have to duplicate
+//! what the migrations know about each version.
+//!
+//! Only the structure of the payload is changed: the values of fields kept between versions are
+//! passed on as they are, and fields computed by a typed migration (e.g. with
+//! `#[obake(computed = ...)]`) have to already be present.
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! use serde_json::json;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(dynamic)]
+//! #[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+//! #[derive(Serialize, Deserialize)]
+//! struct User {
+//!     #[obake(serde_cfg("0.1.0", rename = "login"))]
+//!     name: String,
+//!     #[obake(cfg("0.1.0"))]
+//!     legacy_id: u32,
+//!     #[obake(cfg(">=0.2"))]
+//!     admin: bool,
+//! }
+//! # impl From<User!["0.1.0"]> for User!["0.2.0"] {
+//! #     fn from(from: User!["0.1.0"]) -> Self {
+//! #         Self { name: from.name, admin: false }
+//! #     }
+//! # }
+//!
+//! let user = json!({ "version": "0.1.0", "login": "ferris", "legacy_id": 7 });
+//! let user = obake::dynamic::upgrade_to_latest::<User>(user).unwrap();
+//! assert_eq!(user, json!({ "version": "0.2.0", "name": "ferris", "admin": false }));
+//! ```
+
+use std::format;
+use std::string::String;
+
+use serde_json::{Map, Value};
+
+use crate::{Error, UnknownVersionError, Versioned};
+
+/// A versioned data-structure whose serialized fields can be upgraded without deserializing them,
+/// as generated by `#[obake(dynamic)]`.
+pub trait Dynamic: Versioned {
+    /// The field naming the version of payloads (as set with `#[obake(serde(tag = "..."))]`), if
+    /// they are internally tagged.
+    const TAG: Option<&'static str>;
+
+    /// How the serialized fields of each version differ from those of the previous version, in
+    /// order.
+    const STEPS: &'static [Step];
+}
+
+/// The changes to the serialized fields between two consecutive versions.
+#[derive(Clone, Copy, Debug)]
+pub struct Step {
+    /// The version being upgraded from.
+    pub from: &'static str,
+    /// The version being upgraded to.
+    pub to: &'static str,
+    /// The changes to the serialized fields, in order.
+    pub changes: &'static [Change],
+}
+
+/// A change to the serialized fields of a versioned data-structure.
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub enum Change {
+    /// A field is serialized under a new name.
+    Rename {
+        /// The name the field was serialized under.
+        from: &'static str,
+        /// The name the field is serialized under.
+        to: &'static str,
+    },
+    /// A field is no longer serialized.
+    Remove {
+        /// The name the field was serialized under.
+        field: &'static str,
+    },
+    /// A field is newly serialized.
+    Add {
+        /// The name the field is serialized under.
+        field: &'static str,
+        /// The value of the field when it's missing, which is its `Default` value (if it has one
+        /// and isn't computed by a typed migration).
+        default: fn() -> Option<Value>,
+    },
+}
+
+impl Change {
+    fn apply<T: Versioned>(
+        &self,
+        step: &Step,
+        object: &mut Map<String, Value>,
+    ) -> Result<(), Error> {
+        match *self {
+            Change::Rename { from, to } => {
+                if let Some(value) = object.remove(from) {
+                    object.insert(to.into(), value);
+                }
+            }
+            Change::Remove { field } => {
+                object.remove(field);
+            }
+            Change::Add { field, default } => {
+                if !object.contains_key(field) {
+                    let value = default().ok_or_else(|| {
+                        Error::migration::<T>(
+                            step.from,
+                            format!(
+                                "`{field}` is new in version {}, but has no default value",
+                                step.to
+                            ),
+                        )
+                    })?;
+                    object.insert(field.into(), value);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Upgrades `value`, serialized as version `from` of `T`, to version `to`, updating its version
+/// tag (if `T` has one and `value` names its version).
+///
+/// # Errors
+///
+/// Returns an error if either version isn't one of [`Versioned::VERSIONS`], if `to` is older than
+/// `from`, if `value` isn't an object, or if a field added along the way is missing and has no
+/// default value.
+pub fn upgrade<T: Dynamic>(value: Value, from: &str, to: &str) -> Result<Value, Error> {
+    let from = UnknownVersionError::check::<T>(from)?;
+    let to = UnknownVersionError::check::<T>(to)?;
+    let position = |version| T::VERSIONS.iter().position(|found| *found == version);
+    if position(to) < position(from) {
+        return Err(Error::migration::<T>(
+            from,
+            format!("can't upgrade to version {to}, which is older"),
+        ));
+    }
+
+    let Value::Object(mut object) = value else {
+        return Err(Error::parse::<T>("expected an object"));
+    };
+    let steps = T::STEPS
+        .iter()
+        .skip_while(|step| step.from != from)
+        .take_while(|step| step.from != to);
+    for step in steps {
+        for change in step.changes {
+            change.apply::<T>(step, &mut object)?;
+        }
+    }
+
+    if let Some(tag) = T::TAG {
+        if let Some(version) = object.get_mut(tag) {
+            *version = Value::String(to.into());
+        }
+    }
+    Ok(Value::Object(object))
+}
+
+/// Upgrades `value` to the latest version of `T`, reading the version it is serialized as from
+/// its version tag.
+///
+/// # Errors
+///
+/// Returns an error if `T` isn't declared with `#[obake(serde(tag = "..."))]`, if `value` doesn't
+/// name its version, or if it can't be upgraded as with [`upgrade`].
+pub fn upgrade_to_latest<T: Dynamic>(value: Value) -> Result<Value, Error> {
+    let tag = T::TAG.ok_or_else(|| {
+        Error::parse::<T>(format!(
+            "`{}` must be declared with `#[obake(serde(tag = \"...\"))]` to read the version of \
+             payloads",
+            T::NAME
+        ))
+    })?;
+    let from = value
+        .get(tag)
+        .and_then(Value::as_str)
+        .map(String::from)
+        .ok_or_else(|| Error::parse::<T>("payload doesn't name a version"))?;
+    let latest = T::VERSIONS.last().copied().unwrap_or_default();
+    upgrade::<T>(value, &from, latest)
+}
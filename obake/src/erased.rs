@@ -0,0 +1,140 @@
+//! Serializing versioned values of different types through trait objects, with
+//! [`erased-serde`](https://docs.rs/erased-serde).
+//!
+//! [`AnyVersionedSerialize`] is object-safe, so values of any number of versioned types can be
+//! kept together (e.g. as `Vec<Box<dyn AnyVersionedSerialize>>`) and serialized uniformly, along
+//! with the name and version of each. It's implemented by the latest version of every versioned
+//! data-structure deriving `Serialize`, and by [`Any`], holding any version of one. [`Envelope`]
+//! serializes a value along with its name and version.
+//!
+//! ```
+//! # use serde::Serialize;
+//! use obake::erased::{AnyVersionedSerialize, Envelope};
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[derive(Serialize)]
+//! struct User {
+//!     name: String,
+//!     #[obake(cfg(">=0.2"))]
+//!     admin: bool,
+//! }
+//! # impl From<User!["0.1.0"]> for User!["0.2.0"] {
+//! #     fn from(from: User!["0.1.0"]) -> Self {
+//! #         Self { name: from.name, admin: false }
+//! #     }
+//! # }
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[derive(Serialize)]
+//! struct Team {
+//!     members: u32,
+//! }
+//!
+//! let registry: Vec<Box<dyn AnyVersionedSerialize>> = vec![
+//!     Box::new(User { name: "ferris".to_owned(), admin: true }),
+//!     Box::new(Team { members: 3 }),
+//! ];
+//! let exported: Vec<_> = registry.iter().map(|value| Envelope(&**value)).collect();
+//! assert_eq!(
+//!     serde_json::to_value(&exported).unwrap(),
+//!     serde_json::json!([
+//!         { "type": "User", "version": "0.2.0", "value": { "name": "ferris", "admin": true } },
+//!         { "type": "Team", "version": "0.1.0", "value": { "members": 3 } },
+//!     ]),
+//! );
+//! ```
+
+use core::fmt;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::{AnyVersion, Versioned};
+
+/// A versioned value which can be serialized through a trait object, whatever its type.
+pub trait AnyVersionedSerialize: erased_serde::Serialize {
+    /// The name of the versioned data-structure, as with [`Versioned::NAME`].
+    fn type_name(&self) -> &'static str;
+
+    /// The version of the value, one of [`Versioned::VERSIONS`].
+    fn version(&self) -> &'static str;
+}
+
+erased_serde::serialize_trait_object!(AnyVersionedSerialize);
+
+impl<T> AnyVersionedSerialize for T
+where
+    T: Versioned + Serialize,
+{
+    fn type_name(&self) -> &'static str {
+        T::NAME
+    }
+
+    fn version(&self) -> &'static str {
+        T::VERSIONS.last().copied().unwrap_or_default()
+    }
+}
+
+/// Any version of `T`, serialized as the generated `enum` (e.g. with its version tag, given
+/// `#[obake(serde(tag = "..."))]`), rather than as the latest version.
+pub struct Any<T: Versioned>(pub AnyVersion<T>);
+
+impl<T> AnyVersionedSerialize for Any<T>
+where
+    T: Versioned,
+    AnyVersion<T>: Serialize,
+{
+    fn type_name(&self) -> &'static str {
+        T::NAME
+    }
+
+    fn version(&self) -> &'static str {
+        T::version_of(&self.0)
+    }
+}
+
+impl<T> Serialize for Any<T>
+where
+    T: Versioned,
+    AnyVersion<T>: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<T> fmt::Debug for Any<T>
+where
+    T: Versioned,
+    AnyVersion<T>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Any").field(&self.0).finish()
+    }
+}
+
+/// Serializes a value as a `struct` with its `type` (the name of the versioned data-structure),
+/// its `version`, and the `value` itself.
+#[derive(Clone, Copy)]
+pub struct Envelope<'a>(pub &'a dyn AnyVersionedSerialize);
+
+impl Serialize for Envelope<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut envelope = serializer.serialize_struct("Envelope", 3)?;
+        envelope.serialize_field("type", self.0.type_name())?;
+        envelope.serialize_field("version", self.0.version())?;
+        envelope.serialize_field("value", self.0)?;
+        envelope.end()
+    }
+}
+
+impl fmt::Debug for Envelope<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Envelope")
+            .field("type", &self.0.type_name())
+            .field("version", &self.0.version())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,255 @@
+//! Errors shared by integrations built on versioned data-structures.
+
+use core::fmt;
+use std::boxed::Box;
+use std::string::String;
+
+use crate::Versioned;
+
+/// A boxed error from another crate (e.g. a deserializer or a validation function).
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The error returned when a payload names a version which isn't one of
+/// [`Versioned::VERSIONS`], listing the supported versions.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnknownVersionError {
+    /// The name of the versioned data-structure.
+    pub type_name: &'static str,
+    /// The version the payload named.
+    pub found: String,
+    /// Every supported version, from oldest to latest.
+    pub supported: &'static [&'static str],
+}
+
+impl UnknownVersionError {
+    /// The error for an unknown version `found` of `T`.
+    pub fn new<T: Versioned>(found: impl Into<String>) -> Self {
+        Self {
+            type_name: T::NAME,
+            found: found.into(),
+            supported: T::VERSIONS,
+        }
+    }
+
+    /// Finds `found` in [`Versioned::VERSIONS`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `found` isn't a version of `T`.
+    pub fn check<T: Versioned>(found: &str) -> Result<&'static str, Self> {
+        T::VERSIONS
+            .iter()
+            .find(|version| **version == found)
+            .copied()
+            .ok_or_else(|| Self::new::<T>(found))
+    }
+}
+
+impl fmt::Display for UnknownVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unsupported version {} of `{}` (expected one of {})",
+            self.found,
+            self.type_name,
+            self.supported.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for UnknownVersionError {}
+
+/// Where in its input a payload failed to parse.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Location {
+    /// The input which was being parsed.
+    pub input: String,
+    /// The byte offset into `input` at which parsing failed.
+    pub offset: usize,
+}
+
+/// The error returned by operations on versioned data-structures, for integrations to report
+/// rather than each defining their own.
+///
+/// With the `miette` feature, this is also a [`miette::Diagnostic`], labelling the point at which
+/// a payload failed to parse and listing the supported versions of unknown versions.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A payload couldn't be parsed.
+    Parse {
+        /// The name of the versioned data-structure.
+        type_name: &'static str,
+        /// Where parsing failed, if known.
+        location: Option<Location>,
+        /// The parser's error.
+        source: BoxError,
+    },
+    /// A payload named a version which isn't supported.
+    UnknownVersion(UnknownVersionError),
+    /// A value couldn't be migrated from an older version.
+    Migration {
+        /// The name of the versioned data-structure.
+        type_name: &'static str,
+        /// The version being migrated from.
+        from: &'static str,
+        /// Why the migration failed.
+        source: BoxError,
+    },
+    /// The upgraded value was rejected by validation.
+    Validation {
+        /// The name of the versioned data-structure.
+        type_name: &'static str,
+        /// Why the value was rejected.
+        source: BoxError,
+    },
+}
+
+impl Error {
+    /// A payload of `T` couldn't be parsed.
+    pub fn parse<T: Versioned>(source: impl Into<BoxError>) -> Self {
+        Self::Parse {
+            type_name: T::NAME,
+            location: None,
+            source: source.into(),
+        }
+    }
+
+    /// A JSON document `input` of `T` couldn't be parsed, recording where parsing failed.
+    #[cfg(feature = "json")]
+    #[must_use]
+    pub fn parse_json<T: Versioned>(input: &str, source: serde_json::Error) -> Self {
+        // Lines and columns are counted from 1, and are 0 for errors without a position
+        let offset = (source.line() > 0).then(|| {
+            let line_start: usize = input
+                .split_inclusive('\n')
+                .take(source.line() - 1)
+                .map(str::len)
+                .sum();
+            (line_start + source.column().saturating_sub(1)).min(input.len())
+        });
+
+        Self::Parse {
+            type_name: T::NAME,
+            location: offset.map(|offset| Location {
+                input: String::from(input),
+                offset,
+            }),
+            source: source.into(),
+        }
+    }
+
+    /// A value of `T` couldn't be migrated from version `from`.
+    pub fn migration<T: Versioned>(from: &'static str, source: impl Into<BoxError>) -> Self {
+        Self::Migration {
+            type_name: T::NAME,
+            from,
+            source: source.into(),
+        }
+    }
+
+    /// The latest version of `T` was rejected by validation.
+    pub fn validation<T: Versioned>(source: impl Into<BoxError>) -> Self {
+        Self::Validation {
+            type_name: T::NAME,
+            source: source.into(),
+        }
+    }
+
+    /// The name of the versioned data-structure the error concerns.
+    #[must_use]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Parse { type_name, .. }
+            | Self::Migration { type_name, .. }
+            | Self::Validation { type_name, .. } => type_name,
+            Self::UnknownVersion(err) => err.type_name,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse {
+                type_name, source, ..
+            } => write!(f, "invalid `{type_name}`: {source}"),
+            Self::UnknownVersion(err) => write!(f, "{err}"),
+            Self::Migration {
+                type_name,
+                from,
+                source,
+            } => write!(
+                f,
+                "failed to migrate `{type_name}` from version {from}: {source}"
+            ),
+            Self::Validation { type_name, source } => {
+                write!(f, "`{type_name}` failed validation: {source}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse { source, .. }
+            | Self::Migration { source, .. }
+            | Self::Validation { source, .. } => Some(&**source),
+            Self::UnknownVersion(err) => Some(err),
+        }
+    }
+}
+
+impl From<UnknownVersionError> for Error {
+    fn from(err: UnknownVersionError) -> Self {
+        Self::UnknownVersion(err)
+    }
+}
+
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        let code = match self {
+            Self::Parse { .. } => "obake::parse",
+            Self::UnknownVersion(_) => "obake::unknown_version",
+            Self::Migration { .. } => "obake::migration",
+            Self::Validation { .. } => "obake::validation",
+        };
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        match self {
+            Self::UnknownVersion(err) => Some(Box::new(std::format!(
+                "supported versions are {}",
+                err.supported.join(", ")
+            ))),
+            _ => None,
+        }
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        match self {
+            Self::Parse {
+                location: Some(location),
+                ..
+            } => Some(&location.input),
+            _ => None,
+        }
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        match self {
+            Self::Parse {
+                location: Some(location),
+                source,
+                ..
+            } => Some(Box::new(core::iter::once(miette::LabeledSpan::at_offset(
+                location.offset,
+                std::string::ToString::to_string(source),
+            )))),
+            _ => None,
+        }
+    }
+}
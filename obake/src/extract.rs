@@ -0,0 +1,118 @@
+//! The parts of the versioned request body extractors shared by every web framework: reading the
+//! version header, decoding the body, and the rejection returned when either fails.
+
+use core::fmt;
+use std::string::{String, ToString};
+
+use serde::de::DeserializeOwned;
+
+use crate::{AnyVersion, DeserializeVersion, UnknownVersionError, Versioned};
+
+/// The header naming the version of a request body when no `VersionHeader` is configured.
+pub const DEFAULT_VERSION_HEADER: &str = "x-schema-version";
+
+/// The rejection returned by a versioned request body extractor, where `B` is the framework's
+/// error for a body which couldn't be read.
+#[derive(Debug)]
+pub enum VersionRejection<B> {
+    /// The version header named a version which isn't one of [`crate::Versioned::VERSIONS`].
+    UnsupportedVersion {
+        /// The name of the versioned data-structure.
+        name: &'static str,
+        /// The requested version.
+        version: String,
+        /// The supported versions.
+        supported: &'static [&'static str],
+    },
+    /// The body couldn't be deserialized as the requested version (or any version, if it wasn't
+    /// named by a header).
+    InvalidBody {
+        /// The name of the versioned data-structure.
+        name: &'static str,
+        /// The deserialization error.
+        error: String,
+        /// The supported versions.
+        supported: &'static [&'static str],
+    },
+    /// The body couldn't be read.
+    Body(B),
+}
+
+impl<B> VersionRejection<B> {
+    /// The JSON body of the `400 Bad Request` response to an unsupported version or an invalid
+    /// body. Bodies which couldn't be read are responded to as the framework would.
+    pub(crate) fn response_body(&self) -> String
+    where
+        B: fmt::Display,
+    {
+        let supported = match self {
+            Self::UnsupportedVersion { supported, .. } | Self::InvalidBody { supported, .. } => {
+                supported
+            }
+            Self::Body(_) => &[][..],
+        };
+
+        let body = serde_json::json!({
+            "error": self.to_string(),
+            "supported_versions": supported,
+        });
+        body.to_string()
+    }
+}
+
+impl<B: fmt::Display> fmt::Display for VersionRejection<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion { name, version, .. } => {
+                write!(f, "unsupported version {version} of `{name}`")
+            }
+            Self::InvalidBody { name, error, .. } => write!(f, "invalid `{name}`: {error}"),
+            Self::Body(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+/// Checks the value of the version header, if the request has one, is a version of `T`.
+pub(crate) fn header_version<T: Versioned, B>(
+    header: Option<&str>,
+) -> Result<Option<&'static str>, VersionRejection<B>> {
+    header
+        .map(|value| {
+            UnknownVersionError::check::<T>(value).map_err(|err| {
+                VersionRejection::UnsupportedVersion {
+                    name: err.type_name,
+                    version: err.found,
+                    supported: err.supported,
+                }
+            })
+        })
+        .transpose()
+}
+
+/// Deserializes a JSON body as `version` (or as the versioned encoding, without a version
+/// header), upgrading it to the latest version.
+pub(crate) fn decode_body<T, B>(
+    version: Option<&'static str>,
+    body: &[u8],
+) -> Result<T, VersionRejection<B>>
+where
+    T: for<'de> DeserializeVersion<'de>,
+    AnyVersion<T>: DeserializeOwned,
+{
+    let invalid = |err: serde_json::Error| VersionRejection::InvalidBody {
+        name: T::NAME,
+        error: err.to_string(),
+        supported: T::VERSIONS,
+    };
+    let versioned = match version {
+        Some(version) => {
+            let mut deserializer = serde_json::Deserializer::from_slice(body);
+            let versioned = T::deserialize_version(version, &mut deserializer).map_err(invalid)?;
+            deserializer.end().map_err(invalid)?;
+            versioned
+        }
+        None => serde_json::from_slice::<AnyVersion<T>>(body).map_err(invalid)?,
+    };
+
+    Ok(versioned.into())
+}
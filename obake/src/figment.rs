@@ -0,0 +1,71 @@
+//! Layered configuration with [`figment`](https://docs.rs/figment).
+//!
+//! [`extract`] reads the version of a configuration from a top-level key of a [`Figment`]
+//! (merged from any number of providers, such as defaults, a file and the environment),
+//! deserializes the configuration as that version, and upgrades it to the latest version. Items
+//! must implement [`DeserializeVersion`] (e.g. by being declared with
+//! `#[obake(serde(tag = "version"))]`), and the version key isn't passed on to the version being
+//! deserialized.
+//!
+//! ```
+//! # use serde::Deserialize;
+//! use figment::providers::{Format, Json, Serialized};
+//! use figment::Figment;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(derive(Deserialize), serde(tag = "version"))]
+//! #[derive(Deserialize)]
+//! struct Server {
+//!     host: String,
+//!     #[obake(cfg("0.1.0"))]
+//!     port: u16,
+//!     #[obake(cfg(">=0.2"))]
+//!     ports: Vec<u16>,
+//! }
+//!
+//! impl From<Server!["0.1.0"]> for Server!["0.2.0"] {
+//!     fn from(from: Server!["0.1.0"]) -> Self {
+//!         Self { host: from.host, ports: vec![from.port] }
+//!     }
+//! }
+//!
+//! let figment = Figment::new()
+//!     .merge(Serialized::default("host", "localhost"))
+//!     .merge(Json::string(r#"{ "version": "0.1.0", "port": 8080 }"#));
+//! let server: Server = obake::figment::extract(&figment, "version").unwrap();
+//! assert_eq!(server.host, "localhost");
+//! assert_eq!(server.ports, [8080]);
+//! ```
+
+use std::string::String;
+
+use figment::value::Value;
+use figment::{Error, Figment};
+
+use crate::DeserializeVersion;
+
+/// Extracts the latest version of `T` from `figment`, reading the version the configuration is
+/// written in from the top-level `key`.
+///
+/// # Errors
+///
+/// Returns an error if `key` is missing or isn't a string, if it names a version which isn't
+/// one of [`Versioned::VERSIONS`](crate::Versioned::VERSIONS), or if the configuration can't be
+/// deserialized as that version.
+// Returns `figment`'s own error, as `Figment::extract` does, so it composes with other extractors
+#[allow(clippy::result_large_err)]
+pub fn extract<T>(figment: &Figment, key: &str) -> Result<T, Error>
+where
+    T: for<'de> DeserializeVersion<'de>,
+{
+    let version = figment.extract_inner::<String>(key)?;
+    let mut value = figment.extract::<Value>()?;
+    if let Value::Dict(_, dict) = &mut value {
+        dict.remove(key);
+    }
+
+    let versioned = T::deserialize_version(&version, &value)?;
+    Ok(versioned.into())
+}
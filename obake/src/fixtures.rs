@@ -0,0 +1,40 @@
+//! Sample payloads for every version of a versioned data-structure.
+//!
+//! Keeping serialized payloads from every released version around is the simplest way of making
+//! sure old data stays readable. Data-structures declared with `#[obake(fixtures)]` get a
+//! test-only `write_fixtures(dir)` associated function, which serializes the `Default` value of
+//! every version (through the generated `enum`) into `dir` using [`write`].
+
+use std::format;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Serialize;
+
+/// Serializes `value` into `dir`, creating `dir` if it doesn't already exist.
+///
+/// The payload is written as pretty-printed JSON to `{name}-{version}.json` and, with the
+/// `bincode` feature, as `bincode` to `{name}-{version}.bin`.
+///
+/// # Errors
+///
+/// Returns an error if `value` can't be serialized or a file can't be written.
+pub fn write<T>(dir: &Path, name: &str, version: &str, value: &T) -> io::Result<()>
+where
+    T: Serialize + ?Sized,
+{
+    fs::create_dir_all(dir)?;
+
+    let json = serde_json::to_vec_pretty(value)?;
+    fs::write(dir.join(format!("{name}-{version}.json")), json)?;
+
+    #[cfg(feature = "bincode")]
+    {
+        let bytes = bincode::serialize(value)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(dir.join(format!("{name}-{version}.bin")), bytes)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+//! Rewriting version-tagged files in place at the latest version.
+//!
+//! [`migrate_file`] reads a file as any version of `T` through the generated `enum`, upgrades it,
+//! and writes it back tagged with the latest version, while [`migrate_dir`] does the same for
+//! every file matching a glob pattern, reporting the version each file was migrated from along
+//! with any failures. Files should be tagged with their version (e.g. with
+//! `#[obake(serde(tag = "version"))]`).
+//!
+//! ```no_run
+//! # use serde::{Deserialize, Serialize};
+//! use obake::fs::Format;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(trivial_migrations)]
+//! #[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+//! #[derive(Serialize, Deserialize)]
+//! struct Save {
+//!     level: u32,
+//! }
+//!
+//! let report = obake::fs::migrate_dir::<Save>("saves/*.json", Format::Json).unwrap();
+//! for file in &report.files {
+//!     match &file.result {
+//!         Ok(from) => println!("{}: migrated from {from}", file.path.display()),
+//!         Err(err) => eprintln!("{}: {err}", file.path.display()),
+//!     }
+//! }
+//! ```
+
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::vec::Vec;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{AnyVersion, Versioned};
+
+/// The encoding of migrated files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Format {
+    /// Compact JSON.
+    Json,
+    /// Pretty-printed JSON.
+    JsonPretty,
+}
+
+/// The error returned when a file can't be migrated.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Reading or writing the file failed.
+    Io(io::Error),
+    /// The file couldn't be deserialized as any version (or its upgraded contents couldn't be
+    /// serialized).
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to migrate file: {err}"),
+            Self::Json(err) => write!(f, "invalid file: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Json(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+/// The outcome of migrating a single file.
+#[derive(Debug)]
+pub struct FileReport {
+    /// The path of the file.
+    pub path: PathBuf,
+    /// The version the file was migrated from, or why it couldn't be migrated.
+    pub result: Result<&'static str, Error>,
+}
+
+/// A summary of a migrated directory.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Every file matching the pattern, in the order they were migrated.
+    pub files: Vec<FileReport>,
+}
+
+impl Report {
+    /// The files which couldn't be migrated.
+    pub fn failures(&self) -> impl Iterator<Item = &FileReport> {
+        self.files.iter().filter(|file| file.result.is_err())
+    }
+
+    /// Whether every file was migrated.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
+
+/// Rewrites the file at `path` at the latest version of `T`, returning the version it was
+/// written in. Files already at the latest version are left untouched.
+///
+/// The upgraded file is written next to the original and then renamed over it, so the original
+/// isn't lost if writing fails partway through.
+///
+/// # Errors
+///
+/// Returns an error if reading or writing the file fails, or if it can't be deserialized as any
+/// version.
+pub fn migrate_file<T>(path: impl AsRef<Path>, format: Format) -> Result<&'static str, Error>
+where
+    T: Versioned,
+    AnyVersion<T>: Serialize + DeserializeOwned,
+{
+    let path = path.as_ref();
+    let versioned = serde_json::from_slice::<AnyVersion<T>>(&std::fs::read(path)?)?;
+    let from = T::version_of(&versioned);
+    if T::VERSIONS.last() == Some(&from) {
+        return Ok(from);
+    }
+
+    let latest = AnyVersion::<T>::from(versioned.into());
+    let mut encoded = match format {
+        Format::Json => serde_json::to_vec(&latest)?,
+        Format::JsonPretty => serde_json::to_vec_pretty(&latest)?,
+    };
+    encoded.push(b'\n');
+
+    let mut temporary = OsString::from(".");
+    temporary.push(path.file_name().unwrap_or_default());
+    temporary.push(".obake-tmp");
+    let temporary = path.with_file_name(temporary);
+    std::fs::write(&temporary, encoded)?;
+    if let Err(err) = std::fs::rename(&temporary, path) {
+        let _ = std::fs::remove_file(&temporary);
+        return Err(err.into());
+    }
+
+    Ok(from)
+}
+
+/// Rewrites every file matching the glob `pattern` (e.g. `saves/**/*.json`) at the latest
+/// version of `T` with [`migrate_file`], carrying on past files which can't be migrated.
+///
+/// # Errors
+///
+/// Returns an error if `pattern` isn't a valid glob pattern.
+pub fn migrate_dir<T>(pattern: &str, format: Format) -> Result<Report, glob::PatternError>
+where
+    T: Versioned,
+    AnyVersion<T>: Serialize + DeserializeOwned,
+{
+    let mut report = Report::default();
+    for entry in glob::glob(pattern)? {
+        let file = match entry {
+            Ok(path) if path.is_dir() => continue,
+            Ok(path) => FileReport {
+                result: migrate_file::<T>(&path, format),
+                path,
+            },
+            Err(err) => FileReport {
+                path: err.path().to_path_buf(),
+                result: Err(Error::Io(err.into())),
+            },
+        };
+        report.files.push(file);
+    }
+    Ok(report)
+}
@@ -0,0 +1,72 @@
+//! Fuzzing the decoding of old payloads through the full migration chain.
+//!
+//! Items declared with `#[obake(fuzz)]` get a `fuzz_decode_upgrade(bytes)` associated function,
+//! which decodes `bytes` as the generated `enum` (so it must implement `Deserialize`, e.g. with
+//! `#[obake(derive(Deserialize), serde(tag = "version"))]`) and upgrades whatever version it
+//! decodes to the latest version. Payloads are decoded as JSON by default, or with another
+//! [`Format`] given as in `#[obake(fuzz(format = obake::fuzz::Bincode))]`. This makes a complete
+//! `cargo fuzz` target:
+//!
+//! ```ignore
+//! #![no_main]
+//!
+//! libfuzzer_sys::fuzz_target!(|bytes: &[u8]| my_crate::Settings::fuzz_decode_upgrade(bytes));
+//! ```
+
+use serde::de::DeserializeOwned;
+
+use crate::Versioned;
+
+/// A serialization format payloads can be decoded from while fuzzing.
+pub trait Format {
+    /// Decodes `bytes` as a `T`, returning `None` if they aren't a valid payload.
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Option<T>;
+}
+
+/// Decodes payloads as JSON (requires the `json` feature).
+#[cfg(feature = "json")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl Format for Json {
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+        serde_json::from_slice(bytes).ok()
+    }
+}
+
+/// Decodes payloads as `bincode` (encoded as with `bincode::serialize`), limited to allocating as
+/// many bytes as there are in the payload (requires the `bincode` feature).
+#[cfg(feature = "bincode")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Bincode;
+
+#[cfg(feature = "bincode")]
+impl Format for Bincode {
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+        use bincode::Options;
+        use core::convert::TryFrom;
+
+        // Length prefixes in hostile payloads would otherwise make for huge allocations
+        let limit = u64::try_from(bytes.len()).unwrap_or(u64::MAX);
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .with_limit(limit)
+            .deserialize(bytes)
+            .ok()
+    }
+}
+
+/// Decodes `bytes` as any version of `T` with `F`, upgrading it to the latest version if it
+/// decodes.
+#[doc(hidden)]
+pub fn decode_upgrade<T, F>(bytes: &[u8])
+where
+    T: Versioned,
+    T::Versioned: DeserializeOwned,
+    F: Format,
+{
+    if let Some(versioned) = F::decode::<T::Versioned>(bytes) {
+        drop(versioned.into());
+    }
+}
@@ -0,0 +1,52 @@
+//! Per-version validation with the [`garde`](https://docs.rs/garde) crate.
+//!
+//! Valid ranges often differ between versions of a format, so `garde` rules can be scoped to
+//! versions with `#[obake(garde_cfg("<0.2", range(max = 8)))]`, which applies
+//! `#[garde(range(max = 8))]` to a field in the matching versions only. Items declared with
+//! `#[obake(garde)]` get a `deserialize_validated` constructor for every version and for the
+//! generated `enum`, which checks the deserialized version against its own rules, so every version
+//! must derive `garde::Validate` (with a `Default` context). Failures are reported as an
+//! [`Error::Validation`](crate::Error::Validation) with an [`Invalid`] source.
+//!
+//! ```
+//! # use serde::Deserialize;
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(trivial_migrations, garde)]
+//! #[obake(derive(Deserialize), serde(tag = "version"))]
+//! #[derive(Deserialize, garde::Validate)]
+//! struct Pool {
+//!     #[obake(garde_cfg("<0.2", range(max = 8)))]
+//!     #[obake(garde_cfg(">=0.2", range(max = 64)))]
+//!     workers: u32,
+//! }
+//!
+//! let load = |json| {
+//!     VersionedPool::deserialize_validated(&mut serde_json::Deserializer::from_str(json))
+//! };
+//! assert!(load(r#"{ "version": "0.2.0", "workers": 32 }"#).is_ok());
+//! assert!(load(r#"{ "version": "0.1.0", "workers": 32 }"#).is_err());
+//! ```
+
+use std::fmt;
+
+use garde::Report;
+
+/// The source of an [`Error::Validation`](crate::Error::Validation) returned by a generated
+/// `deserialize_validated` constructor, recording which version failed validation.
+#[derive(Debug)]
+pub struct Invalid {
+    /// The version which failed validation.
+    pub version: &'static str,
+    /// Why the version failed validation.
+    pub report: Report,
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "version {} is invalid: {}", self.version, self.report)
+    }
+}
+
+impl std::error::Error for Invalid {}
@@ -0,0 +1,43 @@
+//! Codecs for storing versioned data-structures in [LMDB](http://www.lmdb.tech/doc/) databases
+//! with [`heed`](https://docs.rs/heed/).
+//!
+//! [`VersionedCodec`] wraps a codec for the versioned encoding of a data-structure (e.g.
+//! `heed::types::SerdeJson<AnyVersion<Foo>>`). Any version can be written, and records written
+//! by older binaries are upgraded to the latest version as they are read, so caches don't need a
+//! separate migration pass.
+
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use heed_traits::{BoxedError, BytesDecode, BytesEncode};
+
+use crate::{AnyVersion, Versioned};
+
+/// A codec which upgrades records to the latest version of `T` on read, where records are
+/// encoded by the codec `C`.
+pub struct VersionedCodec<T, C>(PhantomData<fn() -> (T, C)>);
+
+impl<'a, T, C> BytesEncode<'a> for VersionedCodec<T, C>
+where
+    T: Versioned,
+    AnyVersion<T>: 'a,
+    C: BytesEncode<'a, EItem = AnyVersion<T>>,
+{
+    type EItem = AnyVersion<T>;
+
+    fn bytes_encode(item: &'a Self::EItem) -> Result<Cow<'a, [u8]>, BoxedError> {
+        C::bytes_encode(item)
+    }
+}
+
+impl<'a, T, C> BytesDecode<'a> for VersionedCodec<T, C>
+where
+    T: Versioned + 'a,
+    C: BytesDecode<'a, DItem = AnyVersion<T>>,
+{
+    type DItem = T;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, BoxedError> {
+        C::bytes_decode(bytes).map(Into::into)
+    }
+}
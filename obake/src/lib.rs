@@ -14,11 +14,687 @@
 #![deny(clippy::all, clippy::pedantic)]
 #![deny(missing_docs, unused_imports)]
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "actix")]
+pub mod actix;
+#[cfg(feature = "axum")]
+pub mod axum;
+#[cfg(feature = "best_effort")]
+pub mod best_effort;
+#[cfg(feature = "bson")]
+pub mod bson;
+#[cfg(feature = "cbindgen")]
+pub mod cbindgen;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "container")]
+pub mod container;
+#[cfg(feature = "criterion")]
+pub mod criterion;
+#[cfg(feature = "dynamic")]
+pub mod dynamic;
+#[cfg(feature = "erased")]
+pub mod erased;
+#[cfg(feature = "std")]
+mod error;
+#[cfg(any(feature = "actix", feature = "axum"))]
+mod extract;
+#[cfg(feature = "figment")]
+pub mod figment;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+#[cfg(feature = "fs")]
+pub mod fs;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+#[cfg(feature = "garde")]
+pub mod garde;
+#[cfg(feature = "heed")]
+pub mod heed;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod migrated;
+#[cfg(feature = "negotiate")]
+pub mod negotiate;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "std")]
+mod peek;
+#[cfg(feature = "pyo3")]
+pub mod pyo3;
+#[cfg(feature = "schema_registry")]
+pub mod schema_registry;
+#[cfg(feature = "sniff")]
+pub mod sniff;
+#[cfg(feature = "stream")]
+pub mod stream;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tonic")]
+pub mod tonic;
+#[cfg(feature = "tracing")]
+pub mod tracing;
+#[cfg(feature = "typetag")]
+pub mod typetag;
+#[cfg(feature = "upcast")]
+pub mod upcast;
+#[cfg(feature = "validator")]
+pub mod validator;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "watch")]
+pub mod watch;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "criterion")]
+    pub use ::criterion;
+    #[cfg(feature = "pyo3")]
+    pub use ::pyo3;
+    #[cfg(feature = "arbitrary")]
+    pub use arbitrary;
+    #[cfg(feature = "graphql")]
+    pub use async_graphql;
+    #[cfg(feature = "proptest")]
+    pub use proptest;
+    #[cfg(feature = "tonic")]
+    pub use prost;
+    #[cfg(feature = "serde")]
+    pub use serde;
+    #[cfg(feature = "json")]
+    pub use serde_json;
+    #[cfg(feature = "wasm")]
+    pub use wasm_bindgen;
+    #[cfg(feature = "zeroize")]
+    pub use zeroize;
+
+    #[cfg(feature = "metrics")]
+    pub use crate::metrics::record as record_migration;
+
+    /// Borrows the source of an error declared with `#[obake(error)]` as a trait object, whether
+    /// it is a concrete error or already a trait object (e.g. in a `Box`).
+    pub trait AsDynError<'a> {
+        fn as_dyn_error(&self) -> &(dyn core::error::Error + 'a);
+    }
+
+    impl<'a, T: core::error::Error + 'a> AsDynError<'a> for T {
+        fn as_dyn_error(&self) -> &(dyn core::error::Error + 'a) {
+            self
+        }
+    }
+
+    impl<'a> AsDynError<'a> for dyn core::error::Error + 'a {
+        fn as_dyn_error(&self) -> &(dyn core::error::Error + 'a) {
+            self
+        }
+    }
+
+    impl<'a> AsDynError<'a> for dyn core::error::Error + Send + 'a {
+        fn as_dyn_error(&self) -> &(dyn core::error::Error + 'a) {
+            self
+        }
+    }
+
+    impl<'a> AsDynError<'a> for dyn core::error::Error + Send + Sync + 'a {
+        fn as_dyn_error(&self) -> &(dyn core::error::Error + 'a) {
+            self
+        }
+    }
+
+    /// Calls the expression of `#[obake(computed = ...)]` with the previous version, which (unlike
+    /// calling the closure in place) lets its argument's type be inferred.
+    #[inline]
+    pub fn compute<T, U>(from: &T, with: impl FnOnce(&T) -> U) -> U {
+        with(from)
+    }
+
+    /// Converts the result of the function of `#[obake(validate = ...)]` for `TryUpgrade`.
+    #[cfg(feature = "std")]
+    pub fn check_upgraded<T, E>(result: Result<(), E>) -> Result<(), crate::Error>
+    where
+        T: crate::Versioned,
+        E: Into<crate::BoxError>,
+    {
+        result.map_err(crate::Error::validation::<T>)
+    }
+
+    /// Compares strings in constant contexts, for `assert_repr!`.
+    #[cfg(feature = "serde")]
+    #[must_use]
+    pub const fn str_eq(a: &str, b: &str) -> bool {
+        let (a, b) = (a.as_bytes(), b.as_bytes());
+        if a.len() != b.len() {
+            return false;
+        }
+        let mut i = 0;
+        while i < a.len() {
+            if a[i] != b[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    /// Compares lists of strings in constant contexts, for `assert_repr!`.
+    #[cfg(feature = "serde")]
+    #[must_use]
+    pub const fn strs_eq(a: &[&str], b: &[&str]) -> bool {
+        if a.len() != b.len() {
+            return false;
+        }
+        let mut i = 0;
+        while i < a.len() {
+            if !str_eq(a[i], b[i]) {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    /// The error returned by the generated `DeserializeVersion` impls for unknown versions.
+    #[cfg(feature = "serde")]
+    #[must_use]
+    pub fn unknown_version<T, E>(found: &str) -> E
+    where
+        T: crate::Versioned,
+        E: serde::de::Error,
+    {
+        #[cfg(feature = "std")]
+        let err = E::custom(crate::UnknownVersionError::new::<T>(found));
+        #[cfg(not(feature = "std"))]
+        let err = E::unknown_variant(found, T::VERSIONS);
+        err
+    }
+
+    /// The default value of a field added with `#[obake(dynamic)]`, serialized as JSON, which is
+    /// found through auto-ref specialization, as not every field type implements `Default`.
+    #[cfg(feature = "dynamic")]
+    pub struct DynamicDefault<T>(pub core::marker::PhantomData<T>);
+
+    #[cfg(feature = "dynamic")]
+    pub trait ViaDefault {
+        fn dynamic_default(&self) -> Option<serde_json::Value>;
+    }
+
+    #[cfg(feature = "dynamic")]
+    impl<T: Default + serde::Serialize> ViaDefault for &DynamicDefault<T> {
+        fn dynamic_default(&self) -> Option<serde_json::Value> {
+            serde_json::to_value(T::default()).ok()
+        }
+    }
+
+    #[cfg(feature = "dynamic")]
+    pub trait NoDynamicDefault {
+        fn dynamic_default(&self) -> Option<serde_json::Value>;
+    }
+
+    #[cfg(feature = "dynamic")]
+    impl<T> NoDynamicDefault for DynamicDefault<T> {
+        fn dynamic_default(&self) -> Option<serde_json::Value> {
+            None
+        }
+    }
+
+    /// Validates a version deserialized by the generated `deserialize_validated` constructors.
+    #[cfg(feature = "garde")]
+    pub fn garde_validate<T, V>(version: &'static str, value: &V) -> Result<(), crate::Error>
+    where
+        T: crate::Versioned,
+        V: ::garde::Validate,
+        V::Context: Default,
+    {
+        value.validate().map_err(|report| {
+            crate::Error::validation::<T>(crate::garde::Invalid { version, report })
+        })
+    }
+
+    /// Validates a version passed through by the generated `ValidatedUpgrade` impls.
+    #[cfg(feature = "validator")]
+    pub fn validate_version<T, V>(version: &'static str, value: &V) -> Result<(), crate::Error>
+    where
+        T: crate::Versioned,
+        V: ::validator::Validate,
+    {
+        value.validate().map_err(|errors| {
+            crate::Error::validation::<T>(crate::validator::Invalid { version, errors })
+        })
+    }
+}
+
 /// The whole point.
 pub use obake_macros::versioned;
 
+/// Declares a set of versions once, for sharing between the types of an application which are
+/// versioned in lockstep.
+///
+/// ```
+/// obake::version_set!(AppVersions = ["0.1.0", "0.2.0"]);
+///
+/// #[obake::versioned]
+/// #[obake(use_versions(AppVersions))]
+/// struct Settings {
+///     #[obake(cfg(">=0.2"))]
+///     theme: u8,
+/// }
+///
+/// impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+///     fn from(_: Settings!["0.1.0"]) -> Self {
+///         Self { theme: 0 }
+///     }
+/// }
+///
+/// assert_eq!(<Settings as obake::Versioned>::VERSIONS, ["0.1.0", "0.2.0"]);
+/// ```
+///
+/// The set is a macro, so it can only be used after its declaration (or from other modules
+/// through its path, e.g. `#[obake(use_versions(crate::AppVersions))]`). It can be made visible
+/// to the rest of the crate with `pub(crate)`, but not exported from it.
+pub use obake_macros::version_set;
+
+/// Matches on any version of a versioned data-structure by version, rather than by the names of
+/// the generated `enum`'s variants.
+///
+/// Each arm names a version (or `latest`) and binds that version's value, closure-style. As with
+/// any `match`, every version must be covered (or the remaining versions ignored with
+/// `_ => ...`).
+///
+/// ```
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(version("0.3.0"))]
+/// #[obake(trivial_migrations)]
+/// struct Account {
+///     #[obake(cfg(">=0.2"))]
+///     email: String,
+/// }
+/// # impl From<Account!["0.1.0"]> for Account!["0.2.0"] {
+/// #     fn from(_: Account!["0.1.0"]) -> Self {
+/// #         Self { email: String::new() }
+/// #     }
+/// # }
+///
+/// fn describe(account: &VersionedAccount) -> String {
+///     obake::match_versions!(Account, account, {
+///         "0.1.0" => |_| "an account without an email address".to_owned(),
+///         "0.2.0" => |old| format!("an old account for {}", old.email),
+///         latest => |account| format!("an account for {}", account.email),
+///     })
+/// }
+///
+/// let account = VersionedAccount::from(Account { email: "ferris@rust-lang.org".to_owned() });
+/// assert_eq!(describe(&account), "an account for ferris@rust-lang.org");
+/// ```
+///
+/// The type is named by its identifier, which must be in scope (as with `Account!["0.1.0"]`).
+#[macro_export]
+macro_rules! match_versions {
+    (@arms $ty:ident, $versioned:expr, ($($arms:tt)*), $(,)?) => {
+        match $versioned {
+            $($arms)*
+        }
+    };
+    (@arms $ty:ident, $versioned:expr, ($($arms:tt)*), _ => $default:expr $(,)?) => {
+        match $versioned {
+            $($arms)*
+            _ => $default,
+        }
+    };
+    (
+        @arms $ty:ident, $versioned:expr, ($($arms:tt)*),
+        $label:tt => |$binding:pat_param| $body:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::match_versions!(
+            @arms $ty, $versioned, ($($arms)* $ty![@pattern $label, $binding] => $body,),
+            $($($rest)*)?
+        )
+    };
+    ($ty:ident, $versioned:expr, { $($arms:tt)* }) => {
+        $crate::match_versions!(@arms $ty, $versioned, (), $($arms)*)
+    };
+}
+
+/// Fails compilation if the serialized shape of a released version changes, such as when a
+/// refactor renames or reorders its fields.
+///
+/// Requires the `serde` feature, and the item must be declared with `#[obake(serde_repr)]`, which
+/// records the names every version's fields are serialized under (following
+/// `#[serde(rename = "...")]`, `#[serde(rename_all = "...")]` and `#[serde(skip)]`, and leaving
+/// out flattened fields) and the tag of its variant in the generated `enum`. The `tag` is
+/// optional.
+///
+/// ```
+/// # use serde::{Deserialize, Serialize};
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+/// #[obake(serde_repr)]
+/// #[derive(Serialize, Deserialize)]
+/// #[serde(rename_all = "camelCase")]
+/// struct User {
+///     user_id: u64,
+///     #[obake(cfg(">=0.2"))]
+///     #[serde(rename = "displayName")]
+///     name: String,
+/// }
+/// # impl From<User!["0.1.0"]> for User!["0.2.0"] {
+/// #     fn from(from: User!["0.1.0"]) -> Self {
+/// #         Self { user_id: from.user_id, name: String::new() }
+/// #     }
+/// # }
+///
+/// obake::assert_repr!(User, "0.1.0", fields = ["userId"], tag = "0.1.0");
+/// obake::assert_repr!(User, "0.2.0", fields = ["userId", "displayName"]);
+/// ```
+///
+/// The type is named by its identifier, which must be in scope (as with `User!["0.1.0"]`).
+#[cfg(feature = "serde")]
+#[macro_export]
+macro_rules! assert_repr {
+    ($ty:ident, $version:tt, fields = [$($field:literal),* $(,)?] $(, tag = $tag:literal)? $(,)?) => {
+        const _: () = {
+            ::core::assert!(
+                $crate::__private::strs_eq(<$ty![$version]>::SERDE_FIELDS, &[$($field),*]),
+                ::core::concat!(
+                    "the serialized fields of version ",
+                    $version,
+                    " of `",
+                    ::core::stringify!($ty),
+                    "` changed",
+                ),
+            );
+            $(
+                ::core::assert!(
+                    $crate::__private::str_eq(<$ty![$version]>::SERDE_TAG, $tag),
+                    ::core::concat!(
+                        "the serialized tag of version ",
+                        $version,
+                        " of `",
+                        ::core::stringify!($ty),
+                        "` changed",
+                    ),
+                );
+            )?
+        };
+    };
+}
+
+#[cfg(feature = "std")]
+pub use error::{BoxError, Error, Location, UnknownVersionError};
+#[cfg(feature = "metrics")]
+pub use metrics::{clear_metrics_hook, set_metrics_hook};
+pub use migrated::{Migrated, MigrationTrace};
+#[cfg(feature = "rayon")]
+pub use par::{par_migrate_all, par_try_migrate_all};
+#[cfg(feature = "std")]
+pub use peek::{peek_version, PeekFormat};
+
+/// Re-exported for use with [`Downgrade::serialize_as`].
+#[cfg(feature = "semver")]
+pub use semver;
+
+/// The fields of a document which aren't declared in the version it was deserialized as (e.g.
+/// those added by a newer version), captured by `#[obake(preserve_unknown)]` so that they survive
+/// upgrades and are serialized again.
+#[cfg(feature = "json")]
+pub type UnknownFields = serde_json::Map<std::string::String, serde_json::Value>;
+
 /// Automatically implemented for the latest version of a versioned data-structure.
 pub trait Versioned: Sized {
     /// Aliases the versioned encoding of a versioned data-structure.
     type Versioned: From<Self> + Into<Self>;
+
+    /// The name of the data-structure, as declared.
+    const NAME: &'static str;
+
+    /// Every declared version of the data-structure, from oldest to latest.
+    const VERSIONS: &'static [&'static str];
+
+    /// The version of each variant of the versioned encoding, in the order they're declared
+    /// (which isn't necessarily the order of [`Versioned::VERSIONS`]).
+    #[doc(hidden)]
+    const VARIANT_VERSIONS: &'static [&'static str] = Self::VERSIONS;
+
+    /// The version of a value of the versioned encoding, one of [`Versioned::VERSIONS`].
+    fn version_of(versioned: &Self::Versioned) -> &'static str;
+}
+
+/// Automatically implemented for versioned data-structures declared with
+/// `#[obake(validate = path::to::check)]`, where `check` is a `fn(&Foo) -> Result<(), E>` run
+/// once a value has been upgraded all the way to the latest version (e.g. to check invariants
+/// which span several fields, and which older versions couldn't express).
+///
+/// ```
+/// use obake::TryUpgrade;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(validate = check_range)]
+/// struct Range {
+///     start: u32,
+///     #[obake(cfg(">=0.2"))]
+///     end: u32,
+/// }
+///
+/// impl From<Range!["0.1.0"]> for Range!["0.2.0"] {
+///     fn from(from: Range!["0.1.0"]) -> Self {
+///         Self { start: from.start, end: 100 }
+///     }
+/// }
+///
+/// fn check_range(range: &Range) -> Result<(), String> {
+///     if range.start > range.end {
+///         return Err(format!("{} is after {}", range.start, range.end));
+///     }
+///     Ok(())
+/// }
+///
+/// let range = Range::try_upgrade(VersionedRange::Range_v0_1_0(Range_v0_1_0 { start: 20 }));
+/// assert_eq!(range.unwrap().end, 100);
+///
+/// let err = Range::try_upgrade(VersionedRange::Range_v0_1_0(Range_v0_1_0 { start: 200 }));
+/// assert_eq!(err.err().unwrap().to_string(), "`Range` failed validation: 200 is after 100");
+/// ```
+#[cfg(feature = "std")]
+pub trait TryUpgrade: Versioned {
+    /// Upgrades any version of the data-structure to the latest version, then validates it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Validation`], with the error returned by the validation function as its
+    /// source, if the upgraded value is rejected.
+    fn try_upgrade(versioned: Self::Versioned) -> Result<Self, Error>;
+
+    /// Deserializes any version of the data-structure, upgrading it with
+    /// [`TryUpgrade::try_upgrade`] (requires the `serde` feature).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Parse`] if the payload can't be deserialized, or see
+    /// [`TryUpgrade::try_upgrade`].
+    #[cfg(feature = "serde")]
+    fn try_deserialize<'de, D>(deserializer: D) -> Result<Self, Error>
+    where
+        Self::Versioned: serde::Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+        D::Error: Into<BoxError>,
+    {
+        let versioned = <Self::Versioned as serde::Deserialize>::deserialize(deserializer)
+            .map_err(Error::parse::<Self>)?;
+        Self::try_upgrade(versioned)
+    }
+}
+
+/// Automatically implemented for versioned data-structures declared with `#[obake(downgrade)]`.
+///
+/// Downgrades are described in the same way as migrations, by implementing `From` for each
+/// pair of adjacent versions (e.g. `impl From<Foo!["0.2.0"]> for Foo!["0.1.0"]`).
+pub trait Downgrade: Versioned {
+    /// Converts the latest version of a versioned data-structure into an older version,
+    /// returning `None` if `version` isn't one of [`Versioned::VERSIONS`].
+    fn downgrade(self, version: &str) -> Option<Self::Versioned>;
+
+    /// Serializes a value as the latest version matching `req` (e.g. a version negotiated with
+    /// a client), through the versioned encoding, so the payload is tagged with that version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no version matches `req`, or if the downgraded value can't be
+    /// serialized.
+    #[cfg(feature = "semver")]
+    fn serialize_as<S>(&self, req: &semver::VersionReq, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        Self: Clone,
+        Self::Versioned: serde::Serialize,
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+
+        let version = Self::VERSIONS.iter().rev().find(|version| {
+            semver::Version::parse(version).is_ok_and(|version| req.matches(&version))
+        });
+        match version.and_then(|version| self.clone().downgrade(version)) {
+            Some(versioned) => serde::Serialize::serialize(&versioned, serializer),
+            None => Err(S::Error::custom(format_args!(
+                "no version of `{}` matches `{req}`",
+                Self::NAME
+            ))),
+        }
+    }
+}
+
+/// Automatically implemented for versioned data-structures declared with `#[obake(graph)]`.
+///
+/// Each graph has a node for every version, listing its fields (or variants), and an edge for
+/// every migration, labelled with the fields added (`+`) and removed (`-`). Downgrades declared
+/// with `#[obake(downgrade)]` are drawn as dashed edges.
+pub trait MigrationGraph: Versioned {
+    /// The migration graph in [DOT](https://graphviz.org/doc/info/lang.html) format.
+    const DOT: &'static str;
+
+    /// The migration graph in [Mermaid](https://mermaid.js.org/syntax/flowchart.html) format.
+    const MERMAID: &'static str;
+}
+
+/// Automatically implemented for versioned data-structures declared with `#[obake(schema_hash)]`.
+///
+/// Each version also has an inherent `SCHEMA_HASH` constant (e.g. `<Foo!["0.1.0"]>::SCHEMA_HASH`),
+/// a fingerprint of the names and types of its fields (or variants), in order. Embedding it
+/// alongside serialized data allows checking that a version hasn't changed since the data was
+/// written. Types are hashed as written, so changes inside nested types aren't detected.
+pub trait SchemaHash: Versioned {
+    /// The schema hash of every version, from oldest to latest.
+    const SCHEMA_HASHES: &'static [(&'static str, u64)];
+
+    /// Finds the schema hash of `version`, returning `None` if it isn't one of
+    /// [`Versioned::VERSIONS`].
+    #[must_use]
+    fn schema_hash(version: &str) -> Option<u64> {
+        Self::SCHEMA_HASHES
+            .iter()
+            .find(|(other, _)| *other == version)
+            .map(|(_, hash)| *hash)
+    }
+}
+
+/// The error returned when a builder generated by `#[obake(builder)]` is built without setting
+/// every field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BuildError {
+    /// The name of the versioned data-structure.
+    pub type_name: &'static str,
+    /// The version being built.
+    pub version: &'static str,
+    /// The first field which wasn't set.
+    pub field: &'static str,
+}
+
+impl core::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "field `{}` of version {} of `{}` wasn't set",
+            self.field, self.version, self.type_name
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuildError {}
+
+/// Whether a version of a data-structure implements one of the traits listed in
+/// `#[obake(coverage(...))]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraitCoverage {
+    /// The version.
+    pub version: &'static str,
+    /// The path of the trait, as written in `#[obake(coverage(...))]`.
+    pub trait_name: &'static str,
+    /// Whether the version implements the trait.
+    pub implemented: bool,
+}
+
+/// Automatically implemented for versioned data-structures declared with
+/// `#[obake(coverage(...))]`.
+pub trait Coverage: Versioned {
+    /// Whether every version implements every listed trait, from oldest to latest.
+    const COVERAGE: &'static [TraitCoverage];
+}
+
+/// Lists the traits implemented by the latest version of `T` which an older version doesn't
+/// implement.
+pub fn missing_impls<T: Coverage>() -> impl Iterator<Item = &'static TraitCoverage> {
+    let latest = T::VERSIONS.last().copied();
+    T::COVERAGE.iter().filter(move |coverage| {
+        !coverage.implemented
+            && T::COVERAGE.iter().any(|other| {
+                Some(other.version) == latest
+                    && other.trait_name == coverage.trait_name
+                    && other.implemented
+            })
+    })
+}
+
+/// Aliases the versioned encoding of the versioned data-structure `T`.
+pub type AnyVersion<T> = <T as Versioned>::Versioned;
+
+/// Automatically implemented for versioned data-structures whose versioned encoding derives
+/// `serde::Serialize` (e.g. with `#[obake(derive(Serialize))]`).
+#[cfg(feature = "serde")]
+pub trait SerializeLatest: Versioned {
+    /// Serializes the latest version of a versioned data-structure as its versioned encoding
+    /// (i.e. along with its version), borrowing it rather than converting it into the encoding.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value can't be serialized.
+    fn serialize_latest<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer;
+}
+
+/// Automatically implemented for versioned data-structures declared with `#[obake(serde(...))]`
+/// when every version implements `serde::Deserialize`.
+#[cfg(feature = "serde")]
+pub trait DeserializeVersion<'de>: Versioned {
+    /// The field naming the version of payloads of the versioned encoding (as set with
+    /// `#[obake(serde(tag = "..."))]`), if it is internally tagged.
+    const TAG: Option<&'static str> = None;
+
+    /// Deserializes the given version of a versioned data-structure, where the version has been
+    /// determined out-of-band (e.g. from a message header rather than a tag in the payload).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `version` isn't one of [`Versioned::VERSIONS`], or if the payload
+    /// can't be deserialized as that version.
+    fn deserialize_version<D>(version: &str, deserializer: D) -> Result<Self::Versioned, D::Error>
+    where
+        D: serde::Deserializer<'de>;
 }
@@ -0,0 +1,45 @@
+//! A global hook counting migrations, for monitoring which versions are still in use.
+
+use std::boxed::Box;
+use std::sync::{PoisonError, RwLock};
+
+type Hook = Box<dyn Fn(&'static str, &'static str, &'static str) + Send + Sync>;
+
+static HOOK: RwLock<Option<Hook>> = RwLock::new(None);
+
+/// Sets the hook called with the name of a versioned data-structure and the versions migrated
+/// from and to, for every migration of any versioned data-structure from one version to the next
+/// (e.g. to count migrations from each version, so old versions can be dropped once nothing is
+/// migrated from them any more). Replaces any hook set before.
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static FROM_OLD_VERSIONS: AtomicUsize = AtomicUsize::new(0);
+///
+/// obake::set_metrics_hook(|type_name, from, to| {
+///     if type_name == "Settings" && from == "0.1.0" {
+///         FROM_OLD_VERSIONS.fetch_add(1, Ordering::Relaxed);
+///     }
+/// });
+/// ```
+pub fn set_metrics_hook(
+    hook: impl Fn(&'static str, &'static str, &'static str) + Send + Sync + 'static,
+) {
+    let hook: Hook = Box::new(hook);
+    *HOOK.write().unwrap_or_else(PoisonError::into_inner) = Some(hook);
+}
+
+/// Removes the hook set with [`set_metrics_hook`], if any.
+pub fn clear_metrics_hook() {
+    *HOOK.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Calls the hook set with [`set_metrics_hook`] for a migration of `type_name` from version
+/// `from` to version `to`.
+#[doc(hidden)]
+pub fn record(type_name: &'static str, from: &'static str, to: &'static str) {
+    if let Some(hook) = &*HOOK.read().unwrap_or_else(PoisonError::into_inner) {
+        hook(type_name, from, to);
+    }
+}
@@ -0,0 +1,163 @@
+//! Upgraded values which remember the versions they were migrated through.
+
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+use crate::{AnyVersion, Versioned};
+
+/// The versions a value was migrated through on its way to the latest version, for logging or
+/// storing the provenance of upgraded records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MigrationTrace {
+    type_name: &'static str,
+    path: &'static [&'static str],
+}
+
+impl MigrationTrace {
+    /// The trace of upgrading a value of `T` from version `from`, one of
+    /// [`Versioned::VERSIONS`].
+    #[must_use]
+    pub fn new<T: Versioned>(from: &'static str) -> Self {
+        let start = T::VERSIONS
+            .iter()
+            .position(|version| *version == from)
+            .unwrap_or(T::VERSIONS.len() - 1);
+        Self {
+            type_name: T::NAME,
+            path: &T::VERSIONS[start..],
+        }
+    }
+
+    /// The name of the versioned data-structure.
+    #[must_use]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// The version the value was originally in.
+    #[must_use]
+    pub fn from(&self) -> &'static str {
+        self.path[0]
+    }
+
+    /// The version the value was migrated to (the latest version).
+    #[must_use]
+    pub fn to(&self) -> &'static str {
+        self.path[self.path.len() - 1]
+    }
+
+    /// Every version the value was in, from the original version to the latest version.
+    #[must_use]
+    pub fn path(&self) -> &'static [&'static str] {
+        self.path
+    }
+
+    /// Each migration applied, as the versions migrated from and to, in order.
+    pub fn hops(&self) -> impl Iterator<Item = (&'static str, &'static str)> {
+        self.path.windows(2).map(|pair| (pair[0], pair[1]))
+    }
+
+    /// Whether the value had to be migrated at all.
+    #[must_use]
+    pub fn was_migrated(&self) -> bool {
+        self.path.len() > 1
+    }
+}
+
+/// Formats as the versions migrated through, like `Settings 0.1.0 -> 0.2.0 -> 0.3.0`.
+impl fmt::Display for MigrationTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.type_name, self.path[0])?;
+        for version in &self.path[1..] {
+            write!(f, " -> {version}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Serializes as a map of the `type_name` and the `path` of versions migrated through.
+#[cfg(feature = "serde")]
+impl serde::Serialize for MigrationTrace {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("MigrationTrace", 2)?;
+        state.serialize_field("type_name", self.type_name)?;
+        state.serialize_field("path", self.path)?;
+        state.end()
+    }
+}
+
+/// The latest version of `T`, upgraded from any version, along with the [`MigrationTrace`] of
+/// how it was upgraded.
+///
+/// ```
+/// use obake::Migrated;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// #[obake(version("0.3.0"))]
+/// #[obake(trivial_migrations)]
+/// struct Record {
+///     id: u64,
+/// }
+///
+/// let record = Migrated::<Record>::upgrade(VersionedRecord::Record_v0_1_0(Record_v0_1_0 { id: 7 }));
+/// assert_eq!(record.id, 7);
+/// assert_eq!(record.trace.from(), "0.1.0");
+/// assert_eq!(record.trace.to_string(), "Record 0.1.0 -> 0.2.0 -> 0.3.0");
+/// ```
+///
+/// With the `serde` feature, values can be deserialized directly as any version of `T`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Migrated<T> {
+    /// The upgraded value.
+    pub value: T,
+    /// How the value was upgraded.
+    pub trace: MigrationTrace,
+}
+
+impl<T: Versioned> Migrated<T> {
+    /// Upgrades any version of `T` to the latest version, recording the versions it's migrated
+    /// through.
+    pub fn upgrade(versioned: AnyVersion<T>) -> Self {
+        let trace = MigrationTrace::new::<T>(T::version_of(&versioned));
+        Self {
+            value: versioned.into(),
+            trace,
+        }
+    }
+}
+
+impl<T> Migrated<T> {
+    /// Discards the trace, returning the upgraded value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Migrated<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Migrated<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for Migrated<T>
+where
+    T: Versioned,
+    AnyVersion<T>: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        AnyVersion::<T>::deserialize(deserializer).map(Self::upgrade)
+    }
+}
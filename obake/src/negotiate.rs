@@ -0,0 +1,157 @@
+//! Version negotiation for client/server handshakes.
+//!
+//! Each side of a connection advertises the versions it supports (or a requirement on them), and
+//! both then speak the highest version they have in common. [`Negotiated`] records the outcome
+//! of that handshake, and encodes values as the negotiated version for types declared with
+//! `#[obake(downgrade)]`.
+//!
+//! ```
+//! # use serde::Serialize;
+//! use obake::negotiate::Negotiated;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(version("0.3.0"))]
+//! #[obake(trivial_migrations, downgrade)]
+//! #[obake(derive(Serialize), serde(tag = "version"))]
+//! #[derive(Clone, Serialize)]
+//! struct Ping {
+//!     seq: u32,
+//! }
+//!
+//! let negotiated = Negotiated::<Ping>::with_versions(&["0.1.0", "0.2.0"]).unwrap();
+//! assert_eq!(negotiated.version(), "0.2.0");
+//!
+//! let encoded = negotiated.serialize(&Ping { seq: 7 }, serde_json::value::Serializer);
+//! assert_eq!(encoded.unwrap(), serde_json::json!({ "version": "0.2.0", "seq": 7 }));
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use semver::{Version, VersionReq};
+
+use crate::{Downgrade, Versioned};
+
+/// The error returned when a peer supports none of the versions of a versioned data-structure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NoCommonVersion {
+    /// The name of the versioned data-structure.
+    pub name: &'static str,
+}
+
+impl fmt::Display for NoCommonVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the peer supports no version of `{}`", self.name)
+    }
+}
+
+impl std::error::Error for NoCommonVersion {}
+
+/// The version of `T` agreed with a peer.
+pub struct Negotiated<T> {
+    version: &'static str,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Versioned> Negotiated<T> {
+    /// Picks the highest version of `T` which the peer also advertises. Advertised versions
+    /// which aren't valid semantic versions are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the peer advertises none of [`Versioned::VERSIONS`].
+    pub fn with_versions<S: AsRef<str>>(peer: &[S]) -> Result<Self, NoCommonVersion> {
+        let peer: std::vec::Vec<_> = peer
+            .iter()
+            .filter_map(|version| Version::parse(version.as_ref()).ok())
+            .collect();
+        Self::pick(|version| peer.contains(version))
+    }
+
+    /// Picks the highest version of `T` matching the peer's requirement.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if none of [`Versioned::VERSIONS`] match `req`.
+    pub fn with_req(req: &VersionReq) -> Result<Self, NoCommonVersion> {
+        Self::pick(|version| req.matches(version))
+    }
+
+    fn pick(mut supported: impl FnMut(&Version) -> bool) -> Result<Self, NoCommonVersion> {
+        T::VERSIONS
+            .iter()
+            .rev()
+            .find(|version| Version::parse(version).is_ok_and(|version| supported(&version)))
+            .map(|version| Self {
+                version,
+                _marker: PhantomData,
+            })
+            .ok_or(NoCommonVersion { name: T::NAME })
+    }
+
+    /// The negotiated version, one of [`Versioned::VERSIONS`].
+    #[must_use]
+    pub fn version(&self) -> &'static str {
+        self.version
+    }
+
+    /// Whether the negotiated version is the latest version of `T`, in which case values can be
+    /// sent without downgrading them.
+    #[must_use]
+    pub fn is_latest(&self) -> bool {
+        T::VERSIONS.last() == Some(&self.version)
+    }
+}
+
+impl<T: Downgrade> Negotiated<T> {
+    /// Converts a value into the negotiated version.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T::downgrade` rejects one of [`Versioned::VERSIONS`], which the implementations
+    /// generated by `#[obake(downgrade)]` never do.
+    pub fn downgrade(&self, value: T) -> T::Versioned {
+        value
+            .downgrade(self.version)
+            .expect("negotiated versions are always declared")
+    }
+
+    /// Serializes a value as the negotiated version, through the versioned encoding, so the
+    /// payload is tagged with that version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the downgraded value can't be serialized.
+    pub fn serialize<S>(&self, value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Clone,
+        T::Versioned: serde::Serialize,
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(&self.downgrade(value.clone()), serializer)
+    }
+}
+
+impl<T> Clone for Negotiated<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Negotiated<T> {}
+
+impl<T> fmt::Debug for Negotiated<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Negotiated").field(&self.version).finish()
+    }
+}
+
+impl<T> PartialEq for Negotiated<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.version == other.version
+    }
+}
+
+impl<T> Eq for Negotiated<T> {}
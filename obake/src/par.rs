@@ -0,0 +1,69 @@
+//! Parallel bulk upgrades with [`rayon`](https://docs.rs/rayon/).
+
+use std::vec::Vec;
+
+use rayon::prelude::*;
+
+use crate::{AnyVersion, Versioned};
+
+/// Upgrades every record to the latest version of `T`, in parallel, preserving their order.
+pub fn par_migrate_all<T>(records: Vec<AnyVersion<T>>) -> Vec<T>
+where
+    T: Versioned + Send,
+    AnyVersion<T>: Send,
+{
+    records.into_par_iter().map(Into::into).collect()
+}
+
+/// Decodes every record with `decode` and upgrades it to the latest version of `T`, in
+/// parallel, preserving their order.
+///
+/// # Errors
+///
+/// Returns every error returned by `decode`, along with the index of the record which caused
+/// it, in order.
+pub fn par_try_migrate_all<T, R, E, F>(
+    records: Vec<R>,
+    decode: F,
+) -> Result<Vec<T>, Vec<(usize, E)>>
+where
+    T: Versioned + Send,
+    R: Send,
+    E: Send,
+    F: Fn(R) -> Result<AnyVersion<T>, E> + Sync,
+{
+    try_migrate_all(records, &decode)
+}
+
+// Every call site passes a closure of its own type, so decode through a trait object to avoid
+// instantiating the whole pipeline once per closure
+fn try_migrate_all<T, R, E>(
+    records: Vec<R>,
+    decode: &(dyn Fn(R) -> Result<AnyVersion<T>, E> + Sync),
+) -> Result<Vec<T>, Vec<(usize, E)>>
+where
+    T: Versioned + Send,
+    R: Send,
+    E: Send,
+{
+    let results: Vec<_> = records
+        .into_par_iter()
+        .enumerate()
+        .map(|(i, record)| decode(record).map(Into::into).map_err(|err| (i, err)))
+        .collect();
+
+    let mut migrated = Vec::with_capacity(results.len());
+    let mut errors = Vec::new();
+    for result in results {
+        match result {
+            Ok(value) => migrated.push(value),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(migrated)
+    } else {
+        Err(errors)
+    }
+}
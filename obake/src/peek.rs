@@ -0,0 +1,206 @@
+//! Reading the version of a payload without deserializing the rest of it.
+
+use core::convert::TryFrom;
+#[cfg(feature = "json")]
+use core::fmt;
+
+#[cfg(feature = "json")]
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor};
+
+use crate::{Error, UnknownVersionError, Versioned};
+
+/// How the version of a payload passed to [`peek_version`] is encoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PeekFormat {
+    /// A JSON object naming its version in the given field, as serialized by items declared with
+    /// `#[obake(serde(tag = "..."))]` (requires the `json` feature).
+    #[cfg(feature = "json")]
+    JsonTag(&'static str),
+    /// A JSON object whose only key is its version (or the name of its variant in the generated
+    /// `enum`), as serialized by items declared with `#[obake(derive(Serialize))]` and no
+    /// `serde(tag = "...")` (requires the `json` feature).
+    #[cfg(feature = "json")]
+    Json,
+    /// A `bincode` encoding of the generated `enum` (as with `bincode::serialize`), which starts
+    /// with the index of its version.
+    Bincode,
+    /// A file written with [`crate::container::Container::save`] (requires the `container`
+    /// feature).
+    #[cfg(feature = "container")]
+    Container(crate::container::Container),
+}
+
+/// Reads only the version a payload of `T` is encoded in, without deserializing the rest of it
+/// (e.g. to route or shard payloads by version).
+///
+/// JSON objects are scanned for the version without materializing the other fields, while
+/// `bincode` and container payloads only have their first few bytes read.
+///
+/// ```
+/// use obake::PeekFormat;
+///
+/// #[obake::versioned]
+/// #[obake(version("0.1.0"))]
+/// #[obake(version("0.2.0"))]
+/// struct Event {
+///     payload: u32,
+/// }
+/// # impl From<Event!["0.1.0"]> for Event!["0.2.0"] {
+/// #     fn from(from: Event!["0.1.0"]) -> Self {
+/// #         Self { payload: from.payload }
+/// #     }
+/// # }
+///
+/// // `VersionedEvent::Event_v0_2_0(Event_v0_2_0 { payload: 7 })`, encoded with `bincode`
+/// let bytes = [1, 0, 0, 0, 7, 0, 0, 0];
+/// assert_eq!(obake::peek_version::<Event>(bytes, PeekFormat::Bincode).unwrap(), "0.2.0");
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the version can't be read from the payload, or if it isn't one of
+/// [`Versioned::VERSIONS`].
+pub fn peek_version<T: Versioned>(
+    bytes: impl AsRef<[u8]>,
+    format: PeekFormat,
+) -> Result<&'static str, Error> {
+    let bytes = bytes.as_ref();
+    match format {
+        #[cfg(feature = "json")]
+        PeekFormat::JsonTag(tag) => peek_json::<T>(bytes, Some(tag)),
+        #[cfg(feature = "json")]
+        PeekFormat::Json => peek_json::<T>(bytes, None),
+        PeekFormat::Bincode => {
+            // Variants are encoded as a little-endian `u32` index, in the order they're declared
+            // in the generated `enum` (as written, with an `untagged_legacy` version last)
+            let index = bytes
+                .get(..4)
+                .and_then(|index| <[u8; 4]>::try_from(index).ok())
+                .map(u32::from_le_bytes)
+                .ok_or_else(|| Error::parse::<T>("payload too short to name a version"))?;
+            usize::try_from(index)
+                .ok()
+                .and_then(|index| T::VARIANT_VERSIONS.get(index).copied())
+                .ok_or_else(|| UnknownVersionError::new::<T>(std::format!("#{index}")).into())
+        }
+        #[cfg(feature = "container")]
+        PeekFormat::Container(container) => {
+            let version = container.read_version(bytes).map_err(Error::parse::<T>)?;
+            Ok(UnknownVersionError::check::<T>(&version)?)
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+fn peek_json<T: Versioned>(bytes: &[u8], tag: Option<&str>) -> Result<&'static str, Error> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let found = deserializer
+        .deserialize_map(VersionVisitor { tag })
+        .map_err(Error::parse::<T>)?
+        .ok_or_else(|| Error::parse::<T>("payload doesn't name a version"))?;
+    if tag.is_none() {
+        // Without `#[obake(serde(...))]`, versions are keyed by the names of their variants
+        if let Some(version) = T::VERSIONS
+            .iter()
+            .find(|version| is_variant_of(T::NAME, version, &found))
+        {
+            return Ok(version);
+        }
+    }
+    Ok(UnknownVersionError::check::<T>(&found)?)
+}
+
+/// Whether `variant` is the name of the variant of the generated `enum` for `version` of `name`
+/// (e.g. `Foo_v0_1_0_beta_1` for `0.1.0-beta.1` of `Foo`).
+#[cfg(feature = "json")]
+fn is_variant_of(name: &str, version: &str, variant: &str) -> bool {
+    let Some(suffix) = variant
+        .strip_prefix(name)
+        .and_then(|variant| variant.strip_prefix("_v"))
+    else {
+        return false;
+    };
+    let version = version.split('+').next().unwrap_or_default();
+    let expected = version.chars().map(|c| match c {
+        c if c.is_ascii_alphanumeric() => c,
+        _ => '_',
+    });
+    suffix.chars().eq(expected)
+}
+
+/// Visits the entries of a JSON object, skipping everything but its version.
+#[cfg(feature = "json")]
+struct VersionVisitor<'a> {
+    tag: Option<&'a str>,
+}
+
+#[cfg(feature = "json")]
+impl<'de> Visitor<'de> for VersionVisitor<'_> {
+    type Value = Option<std::string::String>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an object naming its version")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut version = None;
+        while let Some(is_tag) = map.next_key_seed(KeySeed { tag: self.tag })? {
+            match is_tag {
+                Key::Tag if version.is_none() => {
+                    version = Some(map.next_value::<std::string::String>()?);
+                }
+                Key::Version(found) if version.is_none() => {
+                    version = Some(found);
+                    map.next_value::<IgnoredAny>()?;
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(version)
+    }
+}
+
+/// A key of a JSON object being scanned for its version.
+#[cfg(feature = "json")]
+enum Key {
+    /// The field naming the version.
+    Tag,
+    /// The version itself, as the key of an externally tagged object.
+    Version(std::string::String),
+    /// Any other field.
+    Other,
+}
+
+#[cfg(feature = "json")]
+struct KeySeed<'a> {
+    tag: Option<&'a str>,
+}
+
+#[cfg(feature = "json")]
+impl<'de> DeserializeSeed<'de> for KeySeed<'_> {
+    type Value = Key;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Key, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+#[cfg(feature = "json")]
+impl Visitor<'_> for KeySeed<'_> {
+    type Value = Key;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a field name")
+    }
+
+    fn visit_str<E: de::Error>(self, key: &str) -> Result<Key, E> {
+        Ok(match self.tag {
+            Some(tag) if key == tag => Key::Tag,
+            Some(_) => Key::Other,
+            None => Key::Version(key.into()),
+        })
+    }
+}
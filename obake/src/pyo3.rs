@@ -0,0 +1,110 @@
+//! Python bindings for versioned data-structures with [`pyo3`](https://docs.rs/pyo3).
+//!
+//! Items declared with `#[obake(pyo3)]` export their latest version as a `#[pyclass]` named after
+//! the item, with the latest version's `pub` fields exposed as properties (so they must be
+//! supported by `pyo3` and implement `Clone`). [`add_to_module`] adds the class to a Python
+//! module along with an `upgrade(version, json)` function wrapping [`upgrade`], so Python tooling
+//! can migrate historical records with exactly the same logic as Rust:
+//!
+//! ```no_run
+//! # use serde::{Deserialize, Serialize};
+//! use pyo3::prelude::*;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(pyo3)]
+//! #[obake(derive(Deserialize), serde(tag = "version"))]
+//! #[derive(Serialize, Deserialize)]
+//! pub struct Reading {
+//!     #[obake(cfg("0.1.0"))]
+//!     pub celsius: f64,
+//!     #[obake(cfg(">=0.2"))]
+//!     pub kelvin: f64,
+//! }
+//!
+//! impl From<Reading!["0.1.0"]> for Reading!["0.2.0"] {
+//!     fn from(from: Reading!["0.1.0"]) -> Self {
+//!         Self { kelvin: from.celsius + 273.15 }
+//!     }
+//! }
+//!
+//! #[pymodule]
+//! fn readings(module: &Bound<'_, PyModule>) -> PyResult<()> {
+//!     obake::pyo3::add_to_module::<Reading>(module)
+//! }
+//! ```
+//!
+//! ```python
+//! import readings
+//! readings.upgrade("0.1.0", '{"celsius": 20.0}')  # '{"kelvin":293.15}'
+//! ```
+
+use core::ffi::CStr;
+use std::string::{String, ToString};
+
+use ::pyo3::exceptions::PyValueError;
+use ::pyo3::prelude::*;
+use ::pyo3::types::{PyCFunction, PyDict, PyTuple};
+use ::pyo3::PyClass;
+use serde::Serialize;
+
+use crate::{DeserializeVersion, Error, UnknownVersionError};
+
+const UPGRADE_NAME: &CStr = c_str(b"upgrade\0");
+const UPGRADE_DOC: &CStr = c_str(
+    b"upgrade(version, json)\n--\n\nUpgrades a JSON document in the given version to the latest \
+      version, returning it as JSON.\0",
+);
+
+const fn c_str(bytes: &'static [u8]) -> &'static CStr {
+    match CStr::from_bytes_with_nul(bytes) {
+        Ok(c_str) => c_str,
+        Err(_) => panic!("invalid C string"),
+    }
+}
+
+/// Upgrades a JSON document in the given version of `T` to the latest version, returning it as
+/// JSON.
+///
+/// # Errors
+///
+/// Returns an error if `version` isn't one of [`crate::Versioned::VERSIONS`], or if the document
+/// isn't valid JSON in that version.
+pub fn upgrade<T>(version: &str, json: &str) -> Result<String, Error>
+where
+    T: for<'de> DeserializeVersion<'de> + Serialize,
+{
+    let version = UnknownVersionError::check::<T>(version)?;
+    let invalid = |err| Error::parse_json::<T>(json, err);
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let versioned = T::deserialize_version(version, &mut deserializer).map_err(invalid)?;
+    deserializer.end().map_err(invalid)?;
+
+    serde_json::to_string(&versioned.into()).map_err(|err| Error::parse::<T>(err))
+}
+
+/// Adds the class of the latest version of `T` to `module`, along with an
+/// `upgrade(version: str, json: str) -> str` function wrapping [`upgrade`], which raises
+/// `ValueError` when the document can't be upgraded.
+///
+/// # Errors
+///
+/// Returns an error if the class or the function can't be added to `module`.
+pub fn add_to_module<T>(module: &Bound<'_, PyModule>) -> PyResult<()>
+where
+    T: PyClass + for<'de> DeserializeVersion<'de> + Serialize,
+{
+    module.add_class::<T>()?;
+
+    let upgrade = PyCFunction::new_closure(
+        module.py(),
+        Some(UPGRADE_NAME),
+        Some(UPGRADE_DOC),
+        |args: &Bound<'_, PyTuple>, _: Option<&Bound<'_, PyDict>>| -> PyResult<String> {
+            let (version, json) = args.extract::<(String, String)>()?;
+            upgrade::<T>(&version, &json).map_err(|err| PyValueError::new_err(err.to_string()))
+        },
+    )?;
+    module.add_function(upgrade)
+}
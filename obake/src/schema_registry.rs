@@ -0,0 +1,229 @@
+//! Integration with the [Confluent Schema
+//! Registry](https://docs.confluent.io/platform/current/schema-registry/index.html).
+//!
+//! Records produced for a registry-aware Kafka consumer are framed with the Confluent wire
+//! format: a zero magic byte, followed by the big-endian ID of the schema the record was written
+//! with, followed by the payload. By registering a schema for every version of a versioned
+//! data-structure, consumers can decode records produced by any version of an application and
+//! upgrade them to the latest version.
+//!
+//! Obake doesn't generate schema documents itself, so registering schemas is delegated to a
+//! [`SchemaRegistry`] client which chooses (or generates) the schema for each version.
+//! Payloads are encoded as JSON.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::format;
+use std::marker::PhantomData;
+use std::string::String;
+use std::vec::Vec;
+
+use serde::Serialize;
+
+use crate::{DeserializeVersion, Versioned};
+
+/// The magic byte that begins every record in the Confluent wire format.
+pub const MAGIC_BYTE: u8 = 0;
+
+/// Frames a payload with the Confluent wire format.
+#[must_use]
+pub fn encode(schema_id: u32, payload: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(payload.len() + 5);
+    bytes.push(MAGIC_BYTE);
+    bytes.extend_from_slice(&schema_id.to_be_bytes());
+    bytes.extend_from_slice(payload);
+    bytes
+}
+
+/// Splits a record in the Confluent wire format into its schema ID and payload.
+///
+/// # Errors
+///
+/// Returns an error if the record is too short or doesn't begin with [`MAGIC_BYTE`].
+pub fn decode(bytes: &[u8]) -> Result<(u32, &[u8]), Error> {
+    match bytes {
+        [MAGIC_BYTE, a, b, c, d, payload @ ..] => {
+            Ok((u32::from_be_bytes([*a, *b, *c, *d]), payload))
+        }
+        [magic, _, _, _, _, ..] => Err(Error::InvalidMagicByte(*magic)),
+        _ => Err(Error::Truncated),
+    }
+}
+
+/// How the subject a schema is registered under is derived.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SubjectNameStrategy {
+    /// `<topic>-key` or `<topic>-value`.
+    TopicName,
+    /// The fully-qualified name of the record type.
+    RecordName,
+    /// `<topic>-<fully-qualified record name>`.
+    TopicRecordName,
+}
+
+impl SubjectNameStrategy {
+    /// Derives the subject for the versioned data-structure `T`. The record name of `T` is the
+    /// path of the module it's declared in followed by its name, separated by `.`, and is the
+    /// same for every version of `T`.
+    #[must_use]
+    pub fn subject<T: Versioned>(self, topic: &str, is_key: bool) -> String {
+        let path = core::any::type_name::<T>();
+        let module = path.rsplit_once("::").map_or("", |(module, _)| module);
+        let record_name = format!("{module}::{}", T::NAME).replace("::", ".");
+        match self {
+            Self::TopicName if is_key => format!("{topic}-key"),
+            Self::TopicName => format!("{topic}-value"),
+            Self::RecordName => record_name,
+            Self::TopicRecordName => format!("{topic}-{record_name}"),
+        }
+    }
+}
+
+/// A client for a schema registry.
+pub trait SchemaRegistry {
+    /// The error returned when the registry can't be reached or rejects a schema.
+    type Error;
+
+    /// Registers (or looks up, if already registered) the schema for `version` of a versioned
+    /// data-structure under `subject`, returning its schema ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the schema could not be registered.
+    fn register(&mut self, subject: &str, version: &str) -> Result<u32, Self::Error>;
+}
+
+/// The error returned when a record can't be encoded or decoded.
+#[derive(Debug)]
+pub enum Error {
+    /// The record was too short to be in the Confluent wire format.
+    Truncated,
+    /// The record didn't begin with [`MAGIC_BYTE`].
+    InvalidMagicByte(u8),
+    /// The record was written with a schema that isn't registered for this data-structure.
+    UnknownSchemaId(u32),
+    /// The payload couldn't be serialized or deserialized.
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => f.write_str("record is too short for the Confluent wire format"),
+            Self::InvalidMagicByte(magic) => write!(f, "invalid magic byte {magic:#04x}"),
+            Self::UnknownSchemaId(id) => write!(f, "unknown schema ID {id}"),
+            Self::Json(err) => write!(f, "invalid payload: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+/// The schema IDs registered for every version of the versioned data-structure `T`.
+pub struct SchemaIds<T> {
+    subject: String,
+    versions: BTreeMap<u32, &'static str>,
+    latest: u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Versioned> SchemaIds<T> {
+    /// Registers a schema for every version of `T` under `subject`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error encountered while registering schemas.
+    pub fn register<R>(registry: &mut R, subject: &str) -> Result<Self, R::Error>
+    where
+        R: SchemaRegistry + ?Sized,
+    {
+        let mut versions = BTreeMap::new();
+        let mut latest = 0;
+        for &version in T::VERSIONS {
+            latest = registry.register(subject, version)?;
+            versions.insert(latest, version);
+        }
+
+        Ok(Self {
+            subject: subject.into(),
+            versions,
+            latest,
+            _marker: PhantomData,
+        })
+    }
+
+    /// The subject the schemas were registered under.
+    #[must_use]
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// The schema ID registered for `version`.
+    #[must_use]
+    pub fn schema_id(&self, version: &str) -> Option<u32> {
+        self.versions
+            .iter()
+            .find_map(|(&id, &v)| (v == version).then_some(id))
+    }
+
+    /// The version whose schema was registered with the ID `schema_id`.
+    #[must_use]
+    pub fn version(&self, schema_id: u32) -> Option<&'static str> {
+        self.versions.get(&schema_id).copied()
+    }
+
+    /// Encodes a value with the schema ID of the latest version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value can't be serialized.
+    pub fn encode(&self, value: &T) -> Result<Vec<u8>, Error>
+    where
+        T: Serialize,
+    {
+        Ok(encode(self.latest, &serde_json::to_vec(value)?))
+    }
+
+    /// Decodes a record written with any registered version, upgrading it to the latest version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the record isn't in the Confluent wire format, was written with an
+    /// unknown schema, or doesn't match the shape of the version it was written with.
+    pub fn decode<'de>(&self, bytes: &'de [u8]) -> Result<T, Error>
+    where
+        T: DeserializeVersion<'de>,
+    {
+        let (schema_id, payload) = decode(bytes)?;
+        let version = self
+            .version(schema_id)
+            .ok_or(Error::UnknownSchemaId(schema_id))?;
+        let mut deserializer = serde_json::Deserializer::from_slice(payload);
+        let versioned = T::deserialize_version(version, &mut deserializer)?;
+        deserializer.end()?;
+        Ok(versioned.into())
+    }
+}
+
+impl<T> fmt::Debug for SchemaIds<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SchemaIds")
+            .field("subject", &self.subject)
+            .field("versions", &self.versions)
+            .field("latest", &self.latest)
+            .finish()
+    }
+}
@@ -0,0 +1,155 @@
+//! Recognizing the version of payloads which don't record it.
+//!
+//! Payloads written before a data-structure was versioned (or by tools which strip the version)
+//! can't be routed to the right version by a tag. A [`Sniffer`] holds a "recognizer" for each
+//! such version (a predicate on the raw payload, such as the presence of a key or a byte
+//! signature), so ancient payloads can be classified and then upgraded through the normal
+//! migrations.
+//!
+//! ```
+//! # use serde::Deserialize;
+//! use obake::sniff::{self, Sniffer};
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(derive(Deserialize), serde(tag = "version"))]
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     #[obake(cfg("0.1.0"))]
+//!     host: String,
+//!     #[obake(cfg(">=0.2"))]
+//!     hosts: Vec<String>,
+//! }
+//!
+//! impl From<Config!["0.1.0"]> for Config!["0.2.0"] {
+//!     fn from(from: Config!["0.1.0"]) -> Self {
+//!         Self { hosts: vec![from.host] }
+//!     }
+//! }
+//!
+//! let mut sniffer = Sniffer::<Config>::new();
+//! sniffer
+//!     .recognize("0.1.0", sniff::has_key("host"))
+//!     .recognize("0.2.0", sniff::has_key("hosts"));
+//!
+//! let config = sniffer.upgrade_json(br#"{ "host": "localhost" }"#).unwrap();
+//! assert_eq!(config.hosts, ["localhost"]);
+//! ```
+
+use core::marker::PhantomData;
+use std::boxed::Box;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::string::String;
+use std::vec::Vec;
+
+use serde::de::IgnoredAny;
+
+use crate::{DeserializeVersion, Error, UnknownVersionError, Versioned};
+
+type Recognizer = Box<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+/// A registry of recognizers for versions of `T`, used to classify payloads without a version
+/// tag.
+pub struct Sniffer<T> {
+    recognizers: Vec<(&'static str, Recognizer)>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Versioned> Sniffer<T> {
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            recognizers: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Registers a recognizer for `version`, returning whether a payload is in that version.
+    /// Recognizers are tried in the order they are registered, so more specific recognizers
+    /// should be registered first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `version` isn't one of [`Versioned::VERSIONS`].
+    pub fn recognize(
+        &mut self,
+        version: &str,
+        recognizer: impl Fn(&[u8]) -> bool + Send + Sync + 'static,
+    ) -> &mut Self {
+        let version = match UnknownVersionError::check::<T>(version) {
+            Ok(version) => version,
+            Err(err) => panic!("{}", err),
+        };
+        self.recognizers.push((version, Box::new(recognizer)));
+        self
+    }
+
+    /// The version of the first recognizer to recognize `bytes`, if any.
+    #[must_use]
+    pub fn sniff(&self, bytes: &[u8]) -> Option<&'static str> {
+        self.recognizers
+            .iter()
+            .find(|(_, recognizer)| recognizer(bytes))
+            .map(|(version, _)| *version)
+    }
+
+    /// Deserializes a JSON payload as the version it is recognized as, upgrading it to the latest
+    /// version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no recognizer recognizes `bytes`, or if they can't be deserialized as
+    /// the version they're recognized as.
+    pub fn upgrade_json(&self, bytes: &[u8]) -> Result<T, Error>
+    where
+        T: for<'de> DeserializeVersion<'de>,
+    {
+        let version = self
+            .sniff(bytes)
+            .ok_or_else(|| Error::parse::<T>("payload isn't recognized as any version"))?;
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        let versioned = T::deserialize_version(version, &mut deserializer)
+            .and_then(|versioned| deserializer.end().map(|()| versioned))
+            .map_err(Error::parse::<T>)?;
+        Ok(versioned.into())
+    }
+}
+
+impl<T: Versioned> Default for Sniffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for Sniffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sniffer")
+            .field(
+                "versions",
+                &self
+                    .recognizers
+                    .iter()
+                    .map(|(version, _)| version)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Recognizes payloads starting with `signature` (e.g. magic bytes).
+pub fn starts_with(signature: &'static [u8]) -> impl Fn(&[u8]) -> bool + Send + Sync {
+    move |bytes| bytes.starts_with(signature)
+}
+
+/// Recognizes JSON objects with a field named `key`.
+pub fn has_key(key: &'static str) -> impl Fn(&[u8]) -> bool + Send + Sync {
+    // Only the keys are kept, as the values are skipped without being materialized
+    #[allow(clippy::zero_sized_map_values)]
+    move |bytes| {
+        serde_json::from_slice::<BTreeMap<String, IgnoredAny>>(bytes)
+            .is_ok_and(|object| object.contains_key(key))
+    }
+}
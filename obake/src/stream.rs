@@ -0,0 +1,180 @@
+//! Streaming migrations of line-delimited JSON ([NDJSON](https://github.com/ndjson/ndjson-spec)).
+//!
+//! Exports of versioned records are often too large to read into memory at once, so
+//! [`migrate_ndjson`] reads one record per line, upgrades it, and writes it straight back out.
+//! Each record is deserialized through the generated `enum`, so they should be tagged with their
+//! version (e.g. with `#[obake(serde(tag = "version"))]`).
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! # use obake::stream::{migrate_ndjson, ErrorPolicy};
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(derive(Serialize, Deserialize))]
+//! #[obake(serde(tag = "version"))]
+//! #[derive(Serialize, Deserialize)]
+//! struct Deposited {
+//!     #[obake(cfg("0.1.0"))]
+//!     amount: u32,
+//!     #[obake(cfg("0.2.0"))]
+//!     cents: u64,
+//! }
+//!
+//! impl From<Deposited!["0.1.0"]> for Deposited!["0.2.0"] {
+//!     fn from(from: Deposited!["0.1.0"]) -> Self {
+//!         Self { cents: u64::from(from.amount) * 100 }
+//!     }
+//! }
+//!
+//! let input = b"{\"version\":\"0.1.0\",\"amount\":3}\n{\"version\":\"0.2.0\",\"cents\":5}\n";
+//! let mut output = Vec::new();
+//! let report = migrate_ndjson::<Deposited, _, _>(&input[..], &mut output, ErrorPolicy::Abort)
+//!     .unwrap();
+//!
+//! assert_eq!(report.migrated, 2);
+//! assert_eq!(
+//!     output,
+//!     b"{\"version\":\"0.2.0\",\"cents\":300}\n{\"version\":\"0.2.0\",\"cents\":5}\n",
+//! );
+//! ```
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::string::String;
+use std::vec::Vec;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{AnyVersion, Versioned};
+
+/// What to do with a line which can't be deserialized as any version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorPolicy {
+    /// Stop at the first invalid line, returning [`Error::Json`].
+    Abort,
+    /// Leave invalid lines out of the output.
+    Skip,
+    /// Leave invalid lines out of the output, recording their errors in [`Report::errors`].
+    Collect,
+}
+
+/// A summary of a completed migration.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// The number of records written.
+    pub migrated: usize,
+    /// The number of invalid lines left out of the output.
+    pub skipped: usize,
+    /// The (1-based) line number and error of every invalid line, if collected with
+    /// [`ErrorPolicy::Collect`].
+    pub errors: Vec<(usize, serde_json::Error)>,
+}
+
+/// The error returned when a migration can't be completed.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing failed.
+    Io(io::Error),
+    /// A line couldn't be deserialized (or its upgraded record couldn't be serialized).
+    Json {
+        /// The (1-based) line number.
+        line: usize,
+        /// The underlying error.
+        error: serde_json::Error,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to migrate records: {err}"),
+            Self::Json { line, error } => write!(f, "invalid record on line {line}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Json { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Reads version-tagged records from `reader`, one per line, upgrades each of them to the latest
+/// version of `T`, and writes them to `writer`, one per line, tagged with the latest version.
+/// Blank lines are ignored, and invalid lines are handled according to `policy`.
+///
+/// Only a single line is held in memory at a time, so `reader` should be buffered.
+///
+/// # Errors
+///
+/// Returns an error if reading or writing fails, if an upgraded record can't be serialized, or,
+/// with [`ErrorPolicy::Abort`], if a line can't be deserialized as any version.
+pub fn migrate_ndjson<T, R, W>(
+    mut reader: R,
+    mut writer: W,
+    policy: ErrorPolicy,
+) -> Result<Report, Error>
+where
+    T: Versioned,
+    AnyVersion<T>: Serialize + DeserializeOwned,
+    R: BufRead,
+    W: Write,
+{
+    let mut report = Report::default();
+    let mut line = String::new();
+    let mut encoded = Vec::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+
+        line_number += 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let versioned = match serde_json::from_str::<AnyVersion<T>>(&line) {
+            Ok(versioned) => versioned,
+            Err(error) => {
+                match policy {
+                    ErrorPolicy::Abort => {
+                        return Err(Error::Json {
+                            line: line_number,
+                            error,
+                        })
+                    }
+                    ErrorPolicy::Skip => {}
+                    ErrorPolicy::Collect => report.errors.push((line_number, error)),
+                }
+                report.skipped += 1;
+                continue;
+            }
+        };
+
+        let latest = AnyVersion::<T>::from(versioned.into());
+        encoded.clear();
+        serde_json::to_writer(&mut encoded, &latest).map_err(|error| Error::Json {
+            line: line_number,
+            error,
+        })?;
+        encoded.push(b'\n');
+        writer.write_all(&encoded)?;
+        report.migrated += 1;
+    }
+
+    writer.flush()?;
+    Ok(report)
+}
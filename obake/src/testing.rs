@@ -0,0 +1,210 @@
+//! Golden-file tests of historical payloads.
+//!
+//! [`check_fixtures`] walks a directory of payloads serialized by released versions (e.g. with the
+//! `write_fixtures` helper generated by `#[obake(fixtures)]`), asserting that each one still
+//! deserializes as the version it was written in and upgrades to the latest version. Fixtures
+//! are named after their version, as `{version}.json` or `{name}-{version}.json` (and, with the
+//! `bincode` feature, `.bin`), and deserialized through the generated `enum`, so they should be
+//! tagged with their version (e.g. with `#[obake(serde(tag = "version"))]`).
+//!
+//! ```no_run
+//! # use serde::{Deserialize, Serialize};
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(trivial_migrations)]
+//! #[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+//! #[derive(Serialize, Deserialize)]
+//! struct Save {
+//!     level: u32,
+//! }
+//!
+//! #[test]
+//! fn old_saves_still_load() {
+//!     obake::testing::check_fixtures::<Save>("tests/fixtures/save");
+//! }
+//! ```
+
+use std::ffi::OsString;
+use std::format;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::string::String;
+use std::vec::Vec;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{AnyVersion, UnknownVersionError, Versioned};
+
+/// The suffix of the snapshots written by [`check_fixtures_with_snapshots`], which aren't
+/// fixtures themselves.
+const SNAPSHOT_SUFFIX: &str = ".upgraded";
+
+/// Setting this environment variable makes [`check_fixtures_with_snapshots`] overwrite existing
+/// snapshots rather than comparing against them.
+pub const UPDATE_SNAPSHOTS: &str = "OBAKE_UPDATE_SNAPSHOTS";
+
+/// Checks that every fixture in `dir` deserializes as the version it is named after and
+/// upgrades to the latest version of `T`.
+///
+/// # Panics
+///
+/// Panics (listing every fixture which failed) if a fixture isn't named after a version of `T`,
+/// can't be deserialized as that version, or panics while upgrading, or if `dir` has no fixtures.
+pub fn check_fixtures<T>(dir: impl AsRef<Path>)
+where
+    T: Versioned,
+    AnyVersion<T>: DeserializeOwned,
+{
+    check::<T>(dir.as_ref(), |_, _| Ok(()));
+}
+
+/// Checks every fixture in `dir` as with [`check_fixtures`], also comparing the upgraded value
+/// (serialized as JSON through the generated `enum`) against a snapshot next to the fixture,
+/// named `{fixture}.upgraded.json`.
+///
+/// Missing snapshots are written rather than compared against, as are all snapshots when the
+/// [`UPDATE_SNAPSHOTS`] environment variable is set, so that intended changes to migrations can
+/// be accepted by re-running the tests.
+///
+/// # Panics
+///
+/// Panics as with [`check_fixtures`], or if an upgraded value differs from its snapshot.
+pub fn check_fixtures_with_snapshots<T>(dir: impl AsRef<Path>)
+where
+    T: Versioned,
+    AnyVersion<T>: Serialize + DeserializeOwned,
+{
+    let update = std::env::var_os(UPDATE_SNAPSHOTS).is_some();
+    check::<T>(dir.as_ref(), |fixture, upgraded| {
+        let mut snapshot = OsString::from(fixture.file_stem().unwrap_or_default());
+        snapshot.push(SNAPSHOT_SUFFIX);
+        snapshot.push(".json");
+        let snapshot = fixture.with_file_name(snapshot);
+
+        let upgraded = serde_json::to_value(AnyVersion::<T>::from(upgraded))
+            .map_err(|err| format!("failed to serialize the upgraded value: {err}"))?;
+        if update || !snapshot.exists() {
+            let mut encoded = serde_json::to_vec_pretty(&upgraded).unwrap_or_default();
+            encoded.push(b'\n');
+            return fs::write(&snapshot, encoded)
+                .map_err(|err| format!("failed to write {}: {err}", snapshot.display()));
+        }
+
+        let expected = fs::read(&snapshot)
+            .map_err(|err| format!("failed to read {}: {err}", snapshot.display()))
+            .and_then(|bytes| {
+                serde_json::from_slice::<serde_json::Value>(&bytes)
+                    .map_err(|err| format!("invalid snapshot {}: {err}", snapshot.display()))
+            })?;
+        if upgraded != expected {
+            return Err(format!(
+                "upgraded to {upgraded}, but {} expects {expected} (set {UPDATE_SNAPSHOTS} to \
+                 update it)",
+                snapshot.display()
+            ));
+        }
+        Ok(())
+    });
+}
+
+fn check<T>(dir: &Path, mut snapshot: impl FnMut(&Path, T) -> Result<(), String>)
+where
+    T: Versioned,
+    AnyVersion<T>: DeserializeOwned,
+{
+    let fixtures = match fixtures(dir) {
+        Ok(fixtures) => fixtures,
+        Err(err) => panic!("failed to read fixtures of `{}`: {err}", T::NAME),
+    };
+    assert!(
+        !fixtures.is_empty(),
+        "no fixtures of `{}` found in {}",
+        T::NAME,
+        dir.display()
+    );
+
+    let failures: Vec<_> = fixtures
+        .iter()
+        .filter_map(|fixture| {
+            check_fixture::<T>(fixture, &mut snapshot)
+                .err()
+                .map(|err| format!("  {}: {err}", fixture.display()))
+        })
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "{} of {} fixtures of `{}` failed:\n{}",
+        failures.len(),
+        fixtures.len(),
+        T::NAME,
+        failures.join("\n")
+    );
+}
+
+fn check_fixture<T>(
+    fixture: &Path,
+    snapshot: &mut impl FnMut(&Path, T) -> Result<(), String>,
+) -> Result<(), String>
+where
+    T: Versioned,
+    AnyVersion<T>: DeserializeOwned,
+{
+    let stem = fixture
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    let version = stem
+        .strip_prefix(T::NAME)
+        .and_then(|stem| stem.strip_prefix('-'))
+        .unwrap_or(stem);
+    let version = UnknownVersionError::check::<T>(version).map_err(|err| format!("{err}"))?;
+
+    let bytes = fs::read(fixture).map_err(|err| format!("failed to read fixture: {err}"))?;
+    #[cfg(feature = "bincode")]
+    let decoded = if fixture
+        .extension()
+        .is_some_and(|extension| extension == "bin")
+    {
+        bincode::deserialize(&bytes).map_err(|err| format!("invalid fixture: {err}"))
+    } else {
+        serde_json::from_slice(&bytes).map_err(|err| format!("invalid fixture: {err}"))
+    };
+    #[cfg(not(feature = "bincode"))]
+    let decoded = serde_json::from_slice(&bytes).map_err(|err| format!("invalid fixture: {err}"));
+    let versioned: AnyVersion<T> = decoded?;
+    let found = T::version_of(&versioned);
+    if found != version {
+        return Err(format!(
+            "named after version {version}, but deserialized as version {found}"
+        ));
+    }
+
+    let upgraded = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| versioned.into()))
+        .map_err(|_| format!("panicked while upgrading from version {version}"))?;
+    snapshot(fixture, upgraded)
+}
+
+/// The fixtures in `dir`, in order of their paths.
+fn fixtures(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut fixtures = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_fixture = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => true,
+            #[cfg(feature = "bincode")]
+            Some("bin") => true,
+            _ => false,
+        };
+        let is_snapshot = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem.ends_with(SNAPSHOT_SUFFIX));
+        if is_fixture && !is_snapshot && path.is_file() {
+            fixtures.push(path);
+        }
+    }
+    fixtures.sort();
+    Ok(fixtures)
+}
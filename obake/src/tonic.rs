@@ -0,0 +1,253 @@
+//! Adapters for versioned [`tonic`](https://docs.rs/tonic) gRPC messages.
+//!
+//! Items declared with `#[obake(tonic)]` implement [`VersionedMessage`], encoding and decoding
+//! each version with `prost`, so every version must derive `prost::Message`. Messages are sent in
+//! an [`Envelope`] recording the version of the payload, and [`VersionedCodec`] decodes incoming
+//! messages of any version and hands the service (or client) the upgraded latest version.
+//!
+//! Codecs can't see request metadata, so clients can instead ask for replies in an older version
+//! through the [`VERSION_METADATA_KEY`] metadata entry, which services read with
+//! [`requested_version`] before replying with [`Outgoing::downgraded`].
+//!
+//! ```
+//! use obake::tonic::{Envelope, Outgoing, VersionedMessage};
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(tonic)]
+//! #[derive(Clone, PartialEq, prost::Message)]
+//! struct Greeting {
+//!     #[prost(string, tag = "1")]
+//!     message: String,
+//!     #[obake(cfg(">=0.2"))]
+//!     #[prost(uint32, tag = "2")]
+//!     priority: u32,
+//! }
+//!
+//! impl From<Greeting!["0.1.0"]> for Greeting!["0.2.0"] {
+//!     fn from(from: Greeting!["0.1.0"]) -> Self {
+//!         Self { message: from.message, priority: 0 }
+//!     }
+//! }
+//!
+//! let old = VersionedGreeting::Greeting_v0_1_0(Greeting_v0_1_0 { message: "hi".to_owned() });
+//! let envelope = Envelope::from(Outgoing::<Greeting>::new(old));
+//! assert_eq!(envelope.version, "0.1.0");
+//!
+//! let greeting = envelope.open::<Greeting>().unwrap();
+//! assert_eq!((greeting.message.as_str(), greeting.priority), ("hi", 0));
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+use std::format;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use ::tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use ::tonic::{Request, Status};
+use prost::{DecodeError, Message};
+
+use crate::{Downgrade, UnknownVersionError, Versioned};
+
+/// The metadata entry in which clients request the version of the replies they expect.
+pub const VERSION_METADATA_KEY: &str = "x-schema-version";
+
+/// Automatically implemented for versioned data-structures declared with `#[obake(tonic)]`.
+pub trait VersionedMessage: Versioned {
+    /// Decodes a payload as `version`, returning `None` if `version` isn't one of
+    /// [`Versioned::VERSIONS`].
+    fn decode_as(version: &str, payload: &[u8]) -> Option<Result<Self::Versioned, DecodeError>>;
+
+    /// Encodes a payload, returning it along with its version.
+    fn encode_versioned(versioned: &Self::Versioned) -> (&'static str, Vec<u8>);
+}
+
+/// The message sent on the wire, holding the version of the payload alongside it.
+#[derive(Clone, PartialEq, Message)]
+pub struct Envelope {
+    /// The version of the payload, one of [`Versioned::VERSIONS`].
+    #[prost(string, tag = "1")]
+    pub version: String,
+    /// The encoded payload.
+    #[prost(bytes = "vec", tag = "2")]
+    pub payload: Vec<u8>,
+}
+
+impl Envelope {
+    /// Decodes the payload as the version it is tagged with, upgrading it to the latest version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `INVALID_ARGUMENT` status listing the supported versions if this version is
+    /// unsupported, or if the payload can't be decoded.
+    pub fn open<T: VersionedMessage>(&self) -> Result<T, Status> {
+        match T::decode_as(&self.version, &self.payload) {
+            Some(Ok(versioned)) => Ok(versioned.into()),
+            Some(Err(err)) => Err(Status::invalid_argument(format!(
+                "invalid `{}` message (version {}): {err}",
+                T::NAME,
+                self.version
+            ))),
+            None => Err(Status::invalid_argument(
+                UnknownVersionError::new::<T>(&*self.version).to_string(),
+            )),
+        }
+    }
+}
+
+impl<T: VersionedMessage> From<Outgoing<T>> for Envelope {
+    fn from(outgoing: Outgoing<T>) -> Self {
+        let (version, payload) = T::encode_versioned(&outgoing.versioned);
+        Self {
+            version: version.into(),
+            payload,
+        }
+    }
+}
+
+/// A message on its way out, in any version of `T`.
+pub struct Outgoing<T: Versioned> {
+    versioned: T::Versioned,
+}
+
+impl<T: Versioned> Outgoing<T> {
+    /// Sends a specific version.
+    pub fn new(versioned: T::Versioned) -> Self {
+        Self { versioned }
+    }
+
+    /// Sends `value` downgraded to `version`, returning `None` if `version` isn't one of
+    /// [`Versioned::VERSIONS`].
+    pub fn downgraded(value: T, version: &str) -> Option<Self>
+    where
+        T: Downgrade,
+    {
+        value.downgrade(version).map(Self::new)
+    }
+
+    /// The version being sent.
+    pub fn into_inner(self) -> T::Versioned {
+        self.versioned
+    }
+}
+
+impl<T: Versioned> From<T> for Outgoing<T> {
+    fn from(value: T) -> Self {
+        Self::new(value.into())
+    }
+}
+
+impl<T: Versioned> fmt::Debug for Outgoing<T>
+where
+    T::Versioned: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Outgoing").field(&self.versioned).finish()
+    }
+}
+
+/// Reads the version of `T` a client requested in the [`VERSION_METADATA_KEY`] metadata entry,
+/// returning `None` if it is missing or isn't one of [`Versioned::VERSIONS`].
+pub fn requested_version<T: Versioned, M>(request: &Request<M>) -> Option<&'static str> {
+    let requested = request
+        .metadata()
+        .get(VERSION_METADATA_KEY)?
+        .to_str()
+        .ok()?;
+    T::VERSIONS
+        .iter()
+        .find(|version| **version == requested)
+        .copied()
+}
+
+/// A `tonic` codec sending [`Outgoing`] messages of any version of `E` and receiving messages of
+/// any version of `D`, upgraded to the latest version, in [`Envelope`]s. For use as the
+/// `codec_path` of services and clients generated by `tonic-build`.
+pub struct VersionedCodec<E, D> {
+    _marker: PhantomData<fn(E) -> D>,
+}
+
+impl<E, D> Default for VersionedCodec<E, D> {
+    fn default() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<E, D> fmt::Debug for VersionedCodec<E, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VersionedCodec").finish()
+    }
+}
+
+impl<E, D> Codec for VersionedCodec<E, D>
+where
+    E: VersionedMessage + 'static,
+    E::Versioned: Send,
+    D: VersionedMessage + Send + 'static,
+{
+    type Encode = Outgoing<E>;
+    type Decode = D;
+    type Encoder = VersionedEncoder<E>;
+    type Decoder = VersionedDecoder<D>;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        VersionedEncoder {
+            _marker: PhantomData,
+        }
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        VersionedDecoder {
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// The encoder of a [`VersionedCodec`].
+pub struct VersionedEncoder<T> {
+    _marker: PhantomData<fn(T)>,
+}
+
+impl<T> fmt::Debug for VersionedEncoder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VersionedEncoder").finish()
+    }
+}
+
+impl<T: VersionedMessage> Encoder for VersionedEncoder<T> {
+    type Item = Outgoing<T>;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        Envelope::from(item)
+            .encode(dst)
+            .map_err(|err| Status::internal(format!("failed to encode `{}`: {err}", T::NAME)))
+    }
+}
+
+/// The decoder of a [`VersionedCodec`].
+pub struct VersionedDecoder<T> {
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for VersionedDecoder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VersionedDecoder").finish()
+    }
+}
+
+impl<T: VersionedMessage> Decoder for VersionedDecoder<T> {
+    type Item = T;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        let envelope = Envelope::decode(src).map_err(|err| {
+            Status::invalid_argument(format!("invalid `{}` envelope: {err}", T::NAME))
+        })?;
+        envelope.open().map(Some)
+    }
+}
@@ -0,0 +1,92 @@
+//! Instrumentation of migrations with [`tracing`](https://docs.rs/tracing).
+//!
+//! With the `tracing` feature, every versioned data-structure reports what it does under the
+//! `obake` target, without wrapping each call site:
+//!
+//! - Upgrading a value to the latest version (e.g. with `From`) enters an `upgrade` span, with
+//!   `type_name`, `from` (the version being upgraded) and `to` (the latest version) fields.
+//! - Each migration from one version to the next enters a `migrate` span with the same fields,
+//!   and emits a `DEBUG` event once the migration is done, with its `duration_us` in
+//!   microseconds.
+//! - Deserializing a version named out-of-band (with `DeserializeVersion`) emits a `DEBUG` event
+//!   with `type_name` and `version` fields, or a `WARN` event for unknown versions.
+
+use core::convert::TryFrom;
+use std::time::Instant;
+
+use ::tracing::span::EnteredSpan;
+
+use crate::Versioned;
+
+/// The span entered while upgrading a value of `T` from version `from`.
+#[doc(hidden)]
+pub fn upgrade<T: Versioned>(from: &'static str) -> EnteredSpan {
+    let to = T::VERSIONS.last().copied().unwrap_or_default();
+    ::tracing::debug_span!(target: "obake", "upgrade", type_name = T::NAME, from, to).entered()
+}
+
+/// Traces a single migration of a value of a versioned data-structure for as long as it is
+/// alive.
+#[doc(hidden)]
+pub struct Hop {
+    type_name: &'static str,
+    from: &'static str,
+    to: &'static str,
+    start: Instant,
+    _span: EnteredSpan,
+}
+
+/// The guard tracing the migration of a value of `T` from version `from` to version `to`.
+#[doc(hidden)]
+#[must_use]
+pub fn hop<T: Versioned>(from: &'static str, to: &'static str) -> Hop {
+    let type_name = T::NAME;
+    Hop {
+        type_name,
+        from,
+        to,
+        start: Instant::now(),
+        _span: ::tracing::debug_span!(target: "obake", "migrate", type_name, from, to).entered(),
+    }
+}
+
+impl Drop for Hop {
+    fn drop(&mut self) {
+        let duration_us = u64::try_from(self.start.elapsed().as_micros()).unwrap_or(u64::MAX);
+        ::tracing::debug!(
+            target: "obake",
+            type_name = self.type_name,
+            from = self.from,
+            to = self.to,
+            duration_us,
+            "migrated `{}` from version {} to {}",
+            self.type_name,
+            self.from,
+            self.to,
+        );
+    }
+}
+
+/// Traces the version of `T` about to be deserialized, as named out-of-band.
+#[doc(hidden)]
+pub fn detected<T: Versioned>(version: &str) {
+    if T::VERSIONS.contains(&version) {
+        ::tracing::debug!(
+            target: "obake",
+            type_name = T::NAME,
+            version,
+            "deserializing version {} of `{}`",
+            version,
+            T::NAME,
+        );
+    } else {
+        ::tracing::warn!(
+            target: "obake",
+            type_name = T::NAME,
+            version,
+            "unknown version {} of `{}`",
+            version,
+            T::NAME,
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Versioned [`typetag`](https://docs.rs/typetag) trait objects.
+//!
+//! `typetag` tags a serialized trait object with the name of its concrete type, and the
+//! generated `enum` tags a versioned data-structure with its version. [`Versioned<T>`] holds the
+//! latest version of `T`, serialized as the generated `enum` and deserialized from any version
+//! (upgrading it to the latest version), so implementing a `typetag` trait for it composes both
+//! tags: the trait object's tag picks the type, and the version tag (e.g. with
+//! `#[obake(serde(tag = "version"))]`) picks the version of that type.
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! use obake::typetag::Versioned;
+//!
+//! #[typetag::serde(tag = "type")]
+//! trait Event {
+//!     fn cents(&self) -> u64;
+//! }
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+//! #[derive(Serialize, Deserialize)]
+//! struct Deposited {
+//!     #[obake(cfg("0.1.0"))]
+//!     amount: u32,
+//!     #[obake(cfg(">=0.2"))]
+//!     cents: u64,
+//! }
+//!
+//! impl From<Deposited!["0.1.0"]> for Deposited!["0.2.0"] {
+//!     fn from(from: Deposited!["0.1.0"]) -> Self {
+//!         Self { cents: u64::from(from.amount) * 100 }
+//!     }
+//! }
+//!
+//! #[typetag::serde(name = "deposited")]
+//! impl Event for Versioned<Deposited> {
+//!     fn cents(&self) -> u64 {
+//!         self.cents
+//!     }
+//! }
+//!
+//! let old = r#"{ "type": "deposited", "version": "0.1.0", "amount": 3 }"#;
+//! let event: Box<dyn Event> = serde_json::from_str(old).unwrap();
+//! assert_eq!(event.cents(), 300);
+//! assert_eq!(
+//!     serde_json::to_value(&event).unwrap(),
+//!     serde_json::json!({ "type": "deposited", "version": "0.2.0", "cents": 300 }),
+//! );
+//! ```
+
+use core::ops::{Deref, DerefMut};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{AnyVersion, SerializeLatest};
+
+/// The latest version of `T`, serialized as the generated `enum` (so along with its version) and
+/// deserialized from any version, for use as the concrete type of a `typetag` trait object.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Versioned<T>(pub T);
+
+impl<T> Deref for Versioned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Versioned<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for Versioned<T> {
+    fn from(from: T) -> Self {
+        Self(from)
+    }
+}
+
+impl<T> Serialize for Versioned<T>
+where
+    T: SerializeLatest,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_latest(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Versioned<T>
+where
+    T: crate::Versioned,
+    AnyVersion<T>: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        AnyVersion::<T>::deserialize(deserializer).map(|versioned| Self(versioned.into()))
+    }
+}
@@ -0,0 +1,207 @@
+//! An upcaster registry for event-sourced systems.
+//!
+//! Events persisted to an event store are immutable, so when the shape of an event changes, old
+//! events have to be "upcast" to the latest version as they are read back. An [`Upcaster`] maps
+//! each `(event_type, version)` pair to a closure that deserializes that version of the event and
+//! upgrades it, allowing streams of mixed-version events to be decoded uniformly.
+//!
+//! ```
+//! # use serde::Deserialize;
+//! # use obake::upcast::{RawEvent, Upcaster};
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(derive(Deserialize))]
+//! #[obake(serde())]
+//! #[derive(Deserialize)]
+//! struct Deposited {
+//!     #[obake(cfg("0.1.0"))]
+//!     amount: u32,
+//!     #[obake(cfg("0.2.0"))]
+//!     cents: u64,
+//! }
+//!
+//! impl From<Deposited!["0.1.0"]> for Deposited!["0.2.0"] {
+//!     fn from(from: Deposited!["0.1.0"]) -> Self {
+//!         Self { cents: u64::from(from.amount) * 100 }
+//!     }
+//! }
+//!
+//! enum Event {
+//!     Deposited(Deposited),
+//! }
+//!
+//! impl From<Deposited> for Event {
+//!     fn from(from: Deposited) -> Self {
+//!         Event::Deposited(from)
+//!     }
+//! }
+//!
+//! let mut upcaster = Upcaster::<serde_json::Value, Event>::new();
+//! upcaster.register::<Deposited>("deposited");
+//!
+//! let raw = RawEvent {
+//!     event_type: "deposited".to_owned(),
+//!     version: "0.1.0".to_owned(),
+//!     payload: serde_json::json!({ "amount": 3 }),
+//! };
+//!
+//! let Event::Deposited(event) = upcaster.upcast(raw).unwrap();
+//! assert_eq!(event.cents, 300);
+//! ```
+
+use std::boxed::Box;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::string::String;
+
+use serde::Deserializer;
+
+use crate::DeserializeVersion;
+
+/// An event as read from an event store, before it has been decoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawEvent<P> {
+    /// The name the event was stored under.
+    pub event_type: String,
+    /// The version of the event that was stored.
+    pub version: String,
+    /// The serialized event.
+    pub payload: P,
+}
+
+/// The error returned when an event can't be upcast.
+#[derive(Debug)]
+pub enum UpcastError<E> {
+    /// No upcaster has been registered for this `(event_type, version)` pair.
+    Unregistered {
+        /// The name the event was stored under.
+        event_type: String,
+        /// The version of the event that was stored.
+        version: String,
+    },
+    /// The payload didn't match the version of the event it was stored as.
+    Deserialize(E),
+}
+
+impl<E: fmt::Display> fmt::Display for UpcastError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unregistered {
+                event_type,
+                version,
+            } => write!(
+                f,
+                "no upcaster registered for version {version} of `{event_type}`"
+            ),
+            Self::Deserialize(err) => write!(f, "failed to deserialize event: {err}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for UpcastError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Unregistered { .. } => None,
+            Self::Deserialize(err) => Some(err),
+        }
+    }
+}
+
+type UpcastFn<P, E> = Box<dyn Fn(P) -> Result<E, <P as Deserializer<'static>>::Error>>;
+
+/// A registry of upcasters from serialized payloads of type `P` to events of type `E`.
+pub struct Upcaster<P, E>
+where
+    P: Deserializer<'static>,
+{
+    upcasters: BTreeMap<(String, String), UpcastFn<P, E>>,
+}
+
+impl<P, E> Upcaster<P, E>
+where
+    P: Deserializer<'static>,
+{
+    /// Creates an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            upcasters: BTreeMap::new(),
+        }
+    }
+
+    /// Registers an upcaster for every version of `T` under the name `event_type`. Each
+    /// upcaster deserializes the payload as the stored version, upgrades it to the latest version
+    /// and converts it into an `E`.
+    pub fn register<T>(&mut self, event_type: &str) -> &mut Self
+    where
+        T: DeserializeVersion<'static> + 'static,
+        E: From<T>,
+    {
+        for &version in T::VERSIONS {
+            self.upcasters.insert(
+                (event_type.into(), version.into()),
+                Box::new(move |payload| {
+                    T::deserialize_version(version, payload)
+                        .map(|versioned| E::from(versioned.into()))
+                }),
+            );
+        }
+
+        self
+    }
+
+    /// Returns whether an upcaster has been registered for this `(event_type, version)` pair.
+    #[must_use]
+    pub fn contains(&self, event_type: &str, version: &str) -> bool {
+        self.upcasters
+            .contains_key(&(event_type.into(), version.into()))
+    }
+
+    /// Decodes and upgrades a single event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no upcaster has been registered for the event, or if its payload
+    /// can't be deserialized.
+    pub fn upcast(&self, event: RawEvent<P>) -> Result<E, UpcastError<P::Error>> {
+        let key = (event.event_type, event.version);
+        match self.upcasters.get(&key) {
+            Some(upcaster) => upcaster(event.payload).map_err(UpcastError::Deserialize),
+            None => Err(UpcastError::Unregistered {
+                event_type: key.0,
+                version: key.1,
+            }),
+        }
+    }
+
+    /// Lazily decodes and upgrades a stream of events.
+    pub fn upcast_stream<'a, I>(
+        &'a self,
+        events: I,
+    ) -> impl Iterator<Item = Result<E, UpcastError<P::Error>>> + 'a
+    where
+        I: IntoIterator<Item = RawEvent<P>>,
+        I::IntoIter: 'a,
+    {
+        events.into_iter().map(move |event| self.upcast(event))
+    }
+}
+
+impl<P, E> Default for Upcaster<P, E>
+where
+    P: Deserializer<'static>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P, E> fmt::Debug for Upcaster<P, E>
+where
+    P: Deserializer<'static>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.upcasters.keys()).finish()
+    }
+}
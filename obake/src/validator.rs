@@ -0,0 +1,111 @@
+//! Validation of versioned data-structures with the [`validator`](https://docs.rs/validator)
+//! crate.
+//!
+//! Items declared with `#[obake(validator)]` implement [`ValidatedUpgrade`], calling
+//! `Validate::validate()` on the version a value was deserialized as and on every version it
+//! passes through on the way to the latest version, so every version must derive `Validate`. With
+//! `#[obake(validator(latest))]`, only the upgraded latest version is validated (and only it needs
+//! to derive `Validate`). [`deserialize`] and [`from_str`] deserialize and upgrade in one step,
+//! returning an [`Error`] for both parse and validation failures.
+//!
+//! ```
+//! # use serde::Deserialize;
+//! use validator::Validate;
+//!
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(validator)]
+//! #[obake(derive(Deserialize), serde(tag = "version"))]
+//! #[derive(Deserialize, Validate)]
+//! struct Pool {
+//!     #[validate(range(min = 1, max = 64))]
+//!     workers: u32,
+//! }
+//!
+//! impl From<Pool!["0.1.0"]> for Pool!["0.2.0"] {
+//!     fn from(from: Pool!["0.1.0"]) -> Self {
+//!         Self { workers: from.workers * 2 }
+//!     }
+//! }
+//!
+//! let load = |json| {
+//!     obake::validator::deserialize::<Pool, _>(&mut serde_json::Deserializer::from_str(json))
+//! };
+//! assert_eq!(load(r#"{ "version": "0.1.0", "workers": 8 }"#).unwrap().workers, 16);
+//!
+//! // Valid in 0.1.0, but not once upgraded
+//! let err = load(r#"{ "version": "0.1.0", "workers": 40 }"#).err().unwrap();
+//! assert!(err.to_string().contains("version 0.2.0 is invalid"));
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer};
+use validator::ValidationErrors;
+
+use crate::{AnyVersion, BoxError, Error, Versioned};
+
+/// Automatically implemented for versioned data-structures declared with `#[obake(validator)]`.
+pub trait ValidatedUpgrade: Versioned {
+    /// Upgrades any version of the data-structure to the latest version, validating the versions
+    /// passed through along the way.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Validation`] (with an [`Invalid`] source) for the first version which
+    /// fails validation.
+    fn upgrade_validated(versioned: Self::Versioned) -> Result<Self, Error>;
+}
+
+/// The source of an [`Error::Validation`] returned by [`ValidatedUpgrade::upgrade_validated`],
+/// recording which version failed validation.
+#[derive(Debug)]
+pub struct Invalid {
+    /// The version which failed validation.
+    pub version: &'static str,
+    /// Why the version failed validation.
+    pub errors: ValidationErrors,
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "version {} is invalid: {}", self.version, self.errors)
+    }
+}
+
+impl std::error::Error for Invalid {}
+
+/// Deserializes any version of `T`, upgrading it to the latest version with
+/// [`ValidatedUpgrade::upgrade_validated`].
+///
+/// # Errors
+///
+/// Returns [`Error::Parse`] if the payload can't be deserialized, or see
+/// [`ValidatedUpgrade::upgrade_validated`].
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, Error>
+where
+    T: ValidatedUpgrade,
+    AnyVersion<T>: Deserialize<'de>,
+    D: Deserializer<'de>,
+    D::Error: Into<BoxError>,
+{
+    let versioned = AnyVersion::<T>::deserialize(deserializer).map_err(Error::parse::<T>)?;
+    T::upgrade_validated(versioned)
+}
+
+/// Deserializes a JSON document with [`deserialize`], recording where parsing failed.
+///
+/// # Errors
+///
+/// See [`deserialize`].
+#[cfg(feature = "json")]
+pub fn from_str<'de, T>(json: &'de str) -> Result<T, Error>
+where
+    T: ValidatedUpgrade,
+    AnyVersion<T>: Deserialize<'de>,
+{
+    let versioned = serde_json::from_str::<AnyVersion<T>>(json)
+        .map_err(|err| Error::parse_json::<T>(json, err))?;
+    T::upgrade_validated(versioned)
+}
@@ -0,0 +1,33 @@
+//! Helpers for exporting versioned data-structures to JavaScript with
+//! [`wasm-bindgen`](https://docs.rs/wasm-bindgen).
+//!
+//! Items declared with `#[obake(wasm)]` export their latest version as a class named after the
+//! item, with a static `upgradeJson` method wrapping [`upgrade_json`], so a web frontend can
+//! migrate locally-stored documents with exactly the same logic as the backend. The latest
+//! version's `pub` fields are exposed as properties, so must be supported by `wasm-bindgen`.
+//!
+//! Documents are deserialized through the generated `enum`, so they should be tagged with their
+//! version (e.g. with `#[obake(serde(tag = "version"))]`).
+
+use std::string::String;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{AnyVersion, Versioned};
+
+/// Upgrades a version-tagged JSON document to the latest version of `T`, returning it as JSON
+/// tagged with the latest version.
+///
+/// # Errors
+///
+/// Returns an error if the document isn't valid JSON, or doesn't match the shape of the version
+/// it is tagged with.
+pub fn upgrade_json<T>(json: &str) -> Result<String, serde_json::Error>
+where
+    T: Versioned,
+    AnyVersion<T>: Serialize + DeserializeOwned,
+{
+    let versioned = serde_json::from_str::<AnyVersion<T>>(json)?;
+    serde_json::to_string(&AnyVersion::<T>::from(versioned.into()))
+}
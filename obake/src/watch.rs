@@ -0,0 +1,255 @@
+//! Hot-reloading of versioned configuration files.
+//!
+//! [`watch`] watches a JSON configuration file with [`notify`](https://docs.rs/notify) and, each
+//! time its contents change, deserializes it as any version of `T` through the generated `enum`,
+//! upgrades it, validates it, and delivers the latest version to a [`Watcher`]. Files should be
+//! tagged with their version (e.g. with `#[obake(serde(tag = "version"))]`), so files that are
+//! still written in an older version keep working.
+//!
+//! ```no_run
+//! # use serde::Deserialize;
+//! #[obake::versioned]
+//! #[obake(version("0.1.0"))]
+//! #[obake(version("0.2.0"))]
+//! #[obake(trivial_migrations)]
+//! #[obake(derive(Deserialize), serde(tag = "version"))]
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     workers: u32,
+//! }
+//!
+//! let config = obake::watch::watch_with::<Config, _>("config.json", |config| {
+//!     if config.workers == 0 {
+//!         return Err("`workers` must be positive".into());
+//!     }
+//!     Ok(())
+//! })
+//! .unwrap();
+//!
+//! for update in config.iter() {
+//!     match update {
+//!         Ok(config) => println!("now running {} workers", config.workers),
+//!         Err(err) => eprintln!("ignoring invalid configuration: {err}"),
+//!     }
+//! }
+//! ```
+
+use std::boxed::Box;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use std::vec::Vec;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use serde::de::DeserializeOwned;
+
+use crate::{AnyVersion, Versioned};
+
+/// The error returned by a validation function passed to [`watch_with`].
+pub type ValidationError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The error returned when a configuration file can't be watched or loaded.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The file couldn't be watched.
+    Notify(notify::Error),
+    /// The file couldn't be read.
+    Io(io::Error),
+    /// The file couldn't be deserialized as any version.
+    Json(serde_json::Error),
+    /// The upgraded configuration was rejected by validation.
+    Invalid(ValidationError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Notify(err) => write!(f, "failed to watch configuration: {err}"),
+            Self::Io(err) => write!(f, "failed to read configuration: {err}"),
+            Self::Json(err) => write!(f, "invalid configuration: {err}"),
+            Self::Invalid(err) => write!(f, "configuration failed validation: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Notify(err) => Some(err),
+            Self::Io(err) => Some(err),
+            Self::Json(err) => Some(err),
+            Self::Invalid(err) => Some(&**err),
+        }
+    }
+}
+
+impl From<notify::Error> for Error {
+    fn from(err: notify::Error) -> Self {
+        Self::Notify(err)
+    }
+}
+
+/// Receives the latest version of a configuration file every time it changes.
+///
+/// The file stops being watched when the `Watcher` is dropped.
+pub struct Watcher<T> {
+    receiver: Receiver<Result<T, Error>>,
+    // Kept alive to keep receiving events
+    _watcher: RecommendedWatcher,
+}
+
+impl<T> Watcher<T> {
+    /// Blocks until the file next changes, returning `None` if it can no longer be watched.
+    #[must_use]
+    pub fn recv(&self) -> Option<Result<T, Error>> {
+        self.receiver.recv().ok()
+    }
+
+    /// Returns the next change to the file, if it has already been loaded.
+    #[must_use]
+    pub fn try_recv(&self) -> Option<Result<T, Error>> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Blocks until the file next changes or `timeout` elapses.
+    #[must_use]
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Result<T, Error>> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    /// Iterates over every change to the file, blocking between them.
+    pub fn iter(&self) -> impl Iterator<Item = Result<T, Error>> + '_ {
+        self.receiver.iter()
+    }
+}
+
+impl<T> fmt::Debug for Watcher<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watcher").finish_non_exhaustive()
+    }
+}
+
+/// Watches the configuration file at `path`, without validation. See [`watch_with`].
+///
+/// # Errors
+///
+/// Returns an error if the file's directory can't be watched.
+pub fn watch<T>(path: impl AsRef<Path>) -> Result<Watcher<T>, Error>
+where
+    T: Versioned + Send + 'static,
+    AnyVersion<T>: DeserializeOwned,
+{
+    watch_with(path, |_: &T| Ok(()))
+}
+
+/// Watches the configuration file at `path`, delivering its current contents immediately and
+/// then every time they change. Each version is upgraded to the latest version and passed to
+/// `validate` before it is delivered, along with any errors reading or validating the file.
+///
+/// The file's directory is watched rather than the file itself, so that files which are replaced
+/// (as many editors do when saving) or which don't exist yet are picked up.
+///
+/// # Errors
+///
+/// Returns an error if the file's directory can't be watched.
+pub fn watch_with<T, F>(path: impl AsRef<Path>, validate: F) -> Result<Watcher<T>, Error>
+where
+    T: Versioned + Send + 'static,
+    AnyVersion<T>: DeserializeOwned,
+    F: Fn(&T) -> Result<(), ValidationError> + Send + 'static,
+{
+    let path = path.as_ref().to_path_buf();
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let reloader = Arc::new(Mutex::new(Reloader {
+        path,
+        validate,
+        last: None,
+    }));
+
+    let events = Arc::clone(&reloader);
+    let events_sender = sender.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        // Updates are sent while the reloader is locked, so they're delivered in the order the
+        // file was read
+        let mut reloader = events.lock().unwrap_or_else(PoisonError::into_inner);
+        let update = match event {
+            Ok(event) if reloader.is_affected_by(&event) => reloader.reload(),
+            Ok(_) => None,
+            Err(err) => Some(Err(Error::Notify(err))),
+        };
+        if let Some(update) = update {
+            // The `Watcher` may have been dropped while handling the event
+            let _ = events_sender.send(update);
+        }
+    })?;
+    // The directory is watched before the file is first loaded, so that changes made while it's
+    // being loaded aren't missed (changes to contents already loaded are ignored)
+    watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+
+    let mut reloader = reloader.lock().unwrap_or_else(PoisonError::into_inner);
+    if reloader.path.exists() {
+        if let Some(update) = reloader.reload() {
+            let _ = sender.send(update);
+        }
+    }
+    drop(reloader);
+
+    Ok(Watcher {
+        receiver,
+        _watcher: watcher,
+    })
+}
+
+struct Reloader<F> {
+    path: PathBuf,
+    validate: F,
+    /// The contents last loaded, so that events which don't change them are ignored.
+    last: Option<Vec<u8>>,
+}
+
+impl<F> Reloader<F> {
+    fn is_affected_by(&self, event: &Event) -> bool {
+        matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+            && event
+                .paths
+                .iter()
+                .any(|path| path.file_name() == self.path.file_name())
+    }
+
+    fn reload<T>(&mut self) -> Option<Result<T, Error>>
+    where
+        T: Versioned,
+        AnyVersion<T>: DeserializeOwned,
+        F: Fn(&T) -> Result<(), ValidationError>,
+    {
+        let contents = match std::fs::read(&self.path) {
+            Ok(contents) => contents,
+            // The file may have been moved away since the event was raised
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return None,
+            Err(err) => return Some(Err(Error::Io(err))),
+        };
+        if self.last.as_ref() == Some(&contents) {
+            return None;
+        }
+
+        let update = serde_json::from_slice::<AnyVersion<T>>(&contents)
+            .map_err(Error::Json)
+            .map(Into::into)
+            .and_then(|latest| {
+                (self.validate)(&latest)
+                    .map(|()| latest)
+                    .map_err(Error::Invalid)
+            });
+        self.last = Some(contents);
+        Some(update)
+    }
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "abi_stable")]
+
+use abi_stable::abi_stability::abi_checking::check_layout_compatibility;
+use abi_stable::std_types::RString;
+use abi_stable::StableAbi;
+
+/// The format as seen by a host which knows about the latest version.
+mod host {
+    use abi_stable::std_types::RString;
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(version("0.2.0"))]
+    #[obake(abi_stable)]
+    pub struct Message {
+        pub id: u32,
+        #[obake(cfg(">=0.2"))]
+        pub body: RString,
+    }
+
+    impl From<Message!["0.1.0"]> for Message!["0.2.0"] {
+        fn from(from: Message!["0.1.0"]) -> Self {
+            Self {
+                id: from.id,
+                body: RString::new(),
+            }
+        }
+    }
+}
+
+/// The format as seen by a plugin built before version 0.2.0 existed.
+mod plugin {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(abi_stable)]
+    pub struct Message {
+        pub id: u32,
+    }
+}
+
+#[test]
+fn versions_are_stable_across_builds() {
+    check_layout_compatibility(host::Message_v0_1_0::LAYOUT, plugin::Message_v0_1_0::LAYOUT)
+        .unwrap();
+
+    assert!(check_layout_compatibility(
+        host::Message_v0_2_0::LAYOUT,
+        plugin::Message_v0_1_0::LAYOUT,
+    )
+    .is_err());
+}
+
+#[test]
+fn versioned_enum_is_stable() {
+    let versioned = host::VersionedMessage::Message_v0_2_0(host::Message {
+        id: 1,
+        body: RString::from("hi"),
+    });
+    check_layout_compatibility(
+        <host::VersionedMessage>::LAYOUT,
+        <host::VersionedMessage>::LAYOUT,
+    )
+    .unwrap();
+
+    let message: host::Message = versioned.into();
+    assert_eq!(message.body, "hi");
+    assert!(check_layout_compatibility(
+        <host::VersionedMessage>::LAYOUT,
+        <plugin::VersionedMessage>::LAYOUT,
+    )
+    .is_err());
+}
@@ -0,0 +1,100 @@
+#![cfg(feature = "actix")]
+
+use actix_web::http::header::HeaderName;
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use actix_web::{FromRequest, ResponseError};
+use obake::actix::{VersionHeader, VersionRejection, Versioned};
+use serde::Deserialize;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Deserialize)]
+struct Comment {
+    text: String,
+    #[obake(cfg(">=0.2"))]
+    pinned: bool,
+}
+
+impl From<Comment!["0.1.0"]> for Comment!["0.2.0"] {
+    fn from(from: Comment!["0.1.0"]) -> Self {
+        Self {
+            text: from.text,
+            pinned: false,
+        }
+    }
+}
+
+async fn extract(request: TestRequest) -> Result<Comment, VersionRejection> {
+    let (request, mut payload) = request.to_http_parts();
+    Versioned::<Comment>::from_request(&request, &mut payload)
+        .await
+        .map(|Versioned(comment)| comment)
+}
+
+fn unpinned(text: &str) -> Comment {
+    Comment {
+        text: text.to_owned(),
+        pinned: false,
+    }
+}
+
+#[actix_web::test]
+async fn version_header() {
+    let request = TestRequest::default()
+        .insert_header(("X-Schema-Version", "0.1.0"))
+        .set_payload(r#"{"text":"hello"}"#);
+    assert_eq!(extract(request).await.unwrap(), unpinned("hello"));
+}
+
+#[actix_web::test]
+async fn tagged_body() {
+    let request = TestRequest::default().set_payload(r#"{"version":"0.1.0","text":"hello"}"#);
+    assert_eq!(extract(request).await.unwrap(), unpinned("hello"));
+}
+
+#[actix_web::test]
+async fn configured_header() {
+    let request = TestRequest::default()
+        .app_data(VersionHeader(HeaderName::from_static("x-api-version")))
+        .insert_header(("X-Api-Version", "0.1.0"))
+        .set_payload(r#"{"text":"hello"}"#);
+    assert_eq!(extract(request).await.unwrap(), unpinned("hello"));
+}
+
+#[actix_web::test]
+async fn unsupported_version() {
+    let request = TestRequest::default()
+        .insert_header(("X-Schema-Version", "0.3.0"))
+        .set_payload(r#"{"text":"hello"}"#);
+    let rejection = extract(request).await.unwrap_err();
+    assert_eq!(
+        rejection.to_string(),
+        "unsupported version 0.3.0 of `Comment`"
+    );
+
+    let response = rejection.error_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = actix_web::body::to_bytes(response.into_body())
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+        serde_json::json!({
+            "error": "unsupported version 0.3.0 of `Comment`",
+            "supported_versions": ["0.1.0", "0.2.0"],
+        })
+    );
+}
+
+#[actix_web::test]
+async fn invalid_body() {
+    let request = TestRequest::default()
+        .insert_header(("X-Schema-Version", "0.2.0"))
+        .set_payload(r#"{"text":"hello"}"#);
+    let rejection = extract(request).await.unwrap_err();
+    assert!(matches!(rejection, VersionRejection::InvalidBody { .. }));
+    assert_eq!(rejection.status_code(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,46 @@
+mod config {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(version("0.2.0"))]
+    #[obake(aliases)]
+    pub struct PlayerConfig {
+        pub name: String,
+        #[obake(cfg(">=0.2"))]
+        pub score: u32,
+    }
+
+    impl From<PlayerConfig!["0.1.0"]> for PlayerConfig!["0.2.0"] {
+        fn from(from: PlayerConfig!["0.1.0"]) -> Self {
+            Self {
+                name: from.name,
+                score: 0,
+            }
+        }
+    }
+}
+
+use config::player_config;
+
+#[test]
+fn aliases() {
+    let old = player_config::V0_1_0 {
+        name: "ferris".to_owned(),
+    };
+    let any: player_config::Any = player_config::Any::PlayerConfig_v0_1_0(old);
+    let latest: player_config::Latest = any.into();
+    let latest: player_config::V0_2_0 = latest;
+    assert_eq!((latest.name.as_str(), latest.score), ("ferris", 0));
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(aliases)]
+struct Private {
+    field_0: u32,
+}
+
+#[test]
+fn private_aliases() {
+    let private: private::Latest = private::V0_1_0 { field_0: 7 };
+    assert_eq!(private.field_0, 7);
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "arbitrary")]
+#![allow(dead_code)]
+
+use arbitrary::{Arbitrary, Unstructured};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(arbitrary)]
+#[derive(Debug)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    field_0: u8,
+    #[obake(cfg(">=0.2"))]
+    field_1: u16,
+    #[obake(inherit)]
+    bar: Bar,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(arbitrary)]
+#[derive(Debug)]
+enum Bar {
+    X(u8, bool),
+    #[obake(cfg(">=0.2"))]
+    Y {
+        field_0: u32,
+    },
+    Z,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_1: from.field_0.into(),
+            bar: from.bar.into(),
+        }
+    }
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(from: Bar!["0.1.0"]) -> Self {
+        type Bar = Bar!["0.1.0"];
+        match from {
+            Bar::X(x, y) => Self::X(x, y),
+            Bar::Z => Self::Z,
+        }
+    }
+}
+
+#[test]
+fn arbitrary_old_values_upgrade() {
+    let bytes: Vec<u8> = (0..=255).cycle().take(4096).collect();
+    let mut u = Unstructured::new(&bytes);
+
+    while !u.is_empty() {
+        let old = <Foo!["0.1.0"]>::arbitrary(&mut u).unwrap();
+        let field_0 = old.field_0;
+        let new: Foo = obake::AnyVersion::<Foo>::Foo_v0_1_0(old).into();
+        assert_eq!(new.field_1, u16::from(field_0));
+        assert!(!matches!(new.bar, Bar::Y { .. }));
+    }
+}
+
+#[test]
+fn arbitrary_versioned_values_upgrade() {
+    let bytes: Vec<u8> = (0..=255).rev().cycle().take(4096).collect();
+    let mut u = Unstructured::new(&bytes);
+
+    while !u.is_empty() {
+        let _: Foo = obake::AnyVersion::<Foo>::arbitrary(&mut u).unwrap().into();
+    }
+}
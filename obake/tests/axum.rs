@@ -0,0 +1,107 @@
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use axum::extract::FromRequest;
+use axum::http::{HeaderName, Request, StatusCode};
+use axum::response::IntoResponse;
+use obake::axum::{VersionHeader, VersionRejection, Versioned};
+use serde::Deserialize;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Deserialize)]
+struct Comment {
+    text: String,
+    #[obake(cfg(">=0.2"))]
+    pinned: bool,
+}
+
+impl From<Comment!["0.1.0"]> for Comment!["0.2.0"] {
+    fn from(from: Comment!["0.1.0"]) -> Self {
+        Self {
+            text: from.text,
+            pinned: false,
+        }
+    }
+}
+
+async fn extract(request: Request<Body>) -> Result<Comment, VersionRejection> {
+    Versioned::<Comment>::from_request(request, &())
+        .await
+        .map(|Versioned(comment)| comment)
+}
+
+fn unpinned(text: &str) -> Comment {
+    Comment {
+        text: text.to_owned(),
+        pinned: false,
+    }
+}
+
+#[tokio::test]
+async fn version_header() {
+    let request = Request::builder()
+        .header("X-Schema-Version", "0.1.0")
+        .body(Body::from(r#"{"text":"hello"}"#))
+        .unwrap();
+    assert_eq!(extract(request).await.unwrap(), unpinned("hello"));
+}
+
+#[tokio::test]
+async fn tagged_body() {
+    let request = Request::builder()
+        .body(Body::from(r#"{"version":"0.1.0","text":"hello"}"#))
+        .unwrap();
+    assert_eq!(extract(request).await.unwrap(), unpinned("hello"));
+}
+
+#[tokio::test]
+async fn configured_header() {
+    let mut request = Request::builder()
+        .header("X-Api-Version", "0.1.0")
+        .body(Body::from(r#"{"text":"hello"}"#))
+        .unwrap();
+    request
+        .extensions_mut()
+        .insert(VersionHeader(HeaderName::from_static("x-api-version")));
+    assert_eq!(extract(request).await.unwrap(), unpinned("hello"));
+}
+
+#[tokio::test]
+async fn unsupported_version() {
+    let request = Request::builder()
+        .header("X-Schema-Version", "0.3.0")
+        .body(Body::from(r#"{"text":"hello"}"#))
+        .unwrap();
+    let rejection = extract(request).await.unwrap_err();
+    assert_eq!(
+        rejection.to_string(),
+        "unsupported version 0.3.0 of `Comment`"
+    );
+
+    let response = rejection.into_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+        serde_json::json!({
+            "error": "unsupported version 0.3.0 of `Comment`",
+            "supported_versions": ["0.1.0", "0.2.0"],
+        })
+    );
+}
+
+#[tokio::test]
+async fn invalid_body() {
+    let request = Request::builder()
+        .header("X-Schema-Version", "0.2.0")
+        .body(Body::from(r#"{"text":"hello"}"#))
+        .unwrap();
+    let rejection = extract(request).await.unwrap_err();
+    assert!(matches!(rejection, VersionRejection::InvalidBody { .. }));
+    assert_eq!(rejection.into_response().status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "best_effort")]
+
+use obake::best_effort::{self, NewerThanSupported};
+use serde::Deserialize;
+use serde_json::json;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Deserialize)]
+struct Profile {
+    name: String,
+    #[obake(cfg(">=0.2"))]
+    age: u32,
+}
+
+impl From<Profile!["0.1.0"]> for Profile!["0.2.0"] {
+    fn from(from: Profile!["0.1.0"]) -> Self {
+        Self {
+            name: from.name,
+            age: 0,
+        }
+    }
+}
+
+#[test]
+fn known_versions_are_upgraded() {
+    let loaded = best_effort::from_value::<Profile>(
+        json!({ "version": "0.1.0", "name": "ferris" }),
+        "version",
+    )
+    .unwrap();
+    assert_eq!(
+        loaded.value,
+        Profile {
+            name: "ferris".to_owned(),
+            age: 0,
+        }
+    );
+    assert_eq!(loaded.warning, None);
+}
+
+#[test]
+fn newer_versions_are_read_as_latest() {
+    let json = r#"{ "version": "1.3.0", "name": "ferris", "age": 7, "email": "f@rust-lang.org" }"#;
+    let loaded = best_effort::from_str::<Profile>(json, "version").unwrap();
+    assert_eq!(loaded.value.age, 7);
+    assert_eq!(
+        loaded.warning,
+        Some(NewerThanSupported {
+            found: "1.3.0".to_owned(),
+            latest: "0.2.0",
+        })
+    );
+}
+
+#[test]
+fn unknown_versions_still_fail() {
+    // Only newer versions are read on a best-effort basis
+    let older = json!({ "version": "0.0.1", "name": "ferris" });
+    assert!(best_effort::from_value::<Profile>(older, "version").is_err());
+
+    let invalid = json!({ "version": "next", "name": "ferris", "age": 7 });
+    assert!(best_effort::from_value::<Profile>(invalid, "version").is_err());
+
+    // Newer documents must still match the shape of the latest version
+    let missing = json!({ "version": "0.3.0", "name": "ferris" });
+    assert!(best_effort::from_value::<Profile>(missing, "version").is_err());
+}
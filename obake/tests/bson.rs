@@ -0,0 +1,74 @@
+#![cfg(feature = "bson")]
+
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Serialize, Deserialize))]
+#[obake(bson)]
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    name: String,
+    #[obake(cfg(">=0.2"))]
+    names: Vec<String>,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            names: vec![from.name],
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(derive(Serialize, Deserialize))]
+#[obake(bson(field = "v"))]
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
+struct Bar {
+    x: i32,
+}
+
+#[test]
+fn to_document_tags_version() {
+    let doc = obake::bson::to_document(Foo {
+        names: vec!["a".to_owned()],
+    })
+    .unwrap();
+    assert_eq!(
+        doc,
+        bson::doc! { "_schema_version": "0.2.0", "names": ["a"] }
+    );
+    assert_eq!(obake::bson::version_of::<Foo>(&doc), Some("0.2.0"));
+}
+
+#[test]
+fn from_document_upgrades() {
+    let doc = bson::doc! { "_schema_version": "0.1.0", "name": "a" };
+    let foo: Foo = obake::bson::from_document(doc).unwrap();
+    assert_eq!(
+        foo,
+        Foo {
+            names: vec!["a".to_owned()],
+        }
+    );
+}
+
+#[test]
+fn custom_version_field() {
+    let doc = obake::bson::to_document(Bar { x: 1 }).unwrap();
+    assert_eq!(doc, bson::doc! { "v": "0.1.0", "x": 1 });
+    assert_eq!(
+        obake::bson::from_document::<Bar>(doc).unwrap(),
+        Bar { x: 1 }
+    );
+}
+
+#[test]
+fn from_document_rejects_unknown_version() {
+    let doc = bson::doc! { "_schema_version": "9.9.9", "names": [] };
+    assert!(obake::bson::from_document::<Foo>(doc).is_err());
+}
@@ -0,0 +1,71 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(trivial_migrations, builder)]
+#[derive(Debug, PartialEq)]
+struct Account {
+    name: String,
+    #[obake(cfg(">=0.2"))]
+    email: String,
+    #[obake(cfg("<0.2"))]
+    legacy_id: u32,
+}
+
+impl From<Account!["0.1.0"]> for Account!["0.2.0"] {
+    fn from(from: Account!["0.1.0"]) -> Self {
+        Self {
+            name: from.name,
+            email: format!("{}@example.com", from.legacy_id),
+        }
+    }
+}
+
+#[test]
+fn builds_historical_version() {
+    let old = Account_v0_1_0::builder()
+        .name("ghost".to_owned())
+        .legacy_id(7)
+        .build()
+        .unwrap();
+    assert_eq!(old.legacy_id, 7);
+
+    let upgraded: Account = VersionedAccount::Account_v0_1_0(old).into();
+    assert_eq!(upgraded.email, "7@example.com");
+}
+
+#[test]
+fn builds_latest_version() {
+    let latest = Account::builder()
+        .name("ghost".to_owned())
+        .email("ghost@example.com".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(
+        latest,
+        Account {
+            name: "ghost".to_owned(),
+            email: "ghost@example.com".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn reports_missing_field() {
+    let err = Account_v0_2_0::builder()
+        .name("ghost".to_owned())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        obake::BuildError {
+            type_name: "Account",
+            version: "0.2.0",
+            field: "email",
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "field `email` of version 0.2.0 of `Account` wasn't set"
+    );
+}
@@ -0,0 +1,266 @@
+#![cfg(feature = "cbindgen")]
+
+use obake::cbindgen::{expand_str, ExpandError};
+
+/// Collapses the whitespace of `tokens`, so expansions can be compared with readable source.
+fn normalize(tokens: &str) -> String {
+    tokens.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn expand(source: &str) -> String {
+    normalize(&expand_str(source).unwrap())
+}
+
+#[test]
+fn every_version_is_declared() {
+    let expanded = expand(
+        r#"
+        #[obake::versioned]
+        #[obake(version("0.1.0"))]
+        #[obake(version("0.2.0"))]
+        #[repr(C)]
+        pub struct Header {
+            pub magic: u32,
+            #[obake(cfg(">=0.2"))]
+            pub flags: u16,
+        }
+        "#,
+    );
+
+    assert!(expanded.contains(
+        "# [repr (C)] # [allow (non_camel_case_types)] pub struct Header_v0_1_0 { pub magic : u32 }"
+    ));
+    assert!(expanded.contains(
+        "# [repr (C)] # [allow (non_camel_case_types)] pub struct Header_v0_2_0 { pub magic : u32 , pub flags : u16 }"
+    ));
+    assert!(expanded.contains("pub type Header = Header_v0_2_0 ;"));
+    assert!(!expanded.contains("obake"));
+}
+
+#[test]
+fn inherited_fields_use_matching_versions() {
+    let expanded = expand(
+        r#"
+        mod ffi {
+            #[obake::versioned]
+            #[obake(version("0.1.0"))]
+            #[obake(version("0.2.0"))]
+            #[repr(C)]
+            pub struct Point {
+                x: f32,
+            }
+
+            #[obake::versioned]
+            #[obake(version("0.1.0"))]
+            #[obake(version("0.2.0"))]
+            #[obake(version("0.3.0"))]
+            #[repr(C)]
+            pub struct Shape {
+                #[obake(inherit)]
+                #[obake(inherit(outer = "0.3.0", inner = "0.2.0"))]
+                origin: Point,
+            }
+        }
+        "#,
+    );
+
+    assert!(expanded.contains("pub struct Shape_v0_1_0 { origin : Point_v0_1_0 }"));
+    assert!(expanded.contains("pub struct Shape_v0_2_0 { origin : Point_v0_2_0 }"));
+    assert!(expanded.contains("pub struct Shape_v0_3_0 { origin : Point_v0_2_0 }"));
+}
+
+#[test]
+fn enums_and_renames_are_expanded() {
+    let expanded = expand(
+        r#"
+        #[obake::versioned]
+        #[obake(version("0.1.0"))]
+        #[obake(version("0.2.0"))]
+        #[obake(renamed_from("Mode", until = "0.2.0"))]
+        #[repr(u8)]
+        pub enum BlendMode {
+            Normal,
+            #[obake(cfg(">=0.2"))]
+            Additive,
+        }
+        "#,
+    );
+
+    assert!(expanded.contains("pub enum Mode_v0_1_0 { Normal }"));
+    assert!(expanded.contains("pub enum BlendMode_v0_2_0 { Normal , Additive }"));
+    assert!(expanded.contains("pub type BlendMode_v0_1_0 = Mode_v0_1_0 ;"));
+    assert!(expanded.contains("pub type BlendMode = BlendMode_v0_2_0 ;"));
+}
+
+#[test]
+fn feature_gated_versions_are_gated() {
+    let expanded = expand(
+        r#"
+        #[obake::versioned]
+        #[obake(version("0.1.0"))]
+        #[obake(version("0.2.0", feature = "next"))]
+        struct Config {
+            level: u8,
+        }
+        "#,
+    );
+
+    assert!(expanded.contains("# [cfg (feature = \"next\")] struct Config_v0_2_0"));
+    assert!(expanded.contains("# [cfg (feature = \"next\")] type Config = Config_v0_2_0 ;"));
+    assert!(expanded.contains("# [cfg (not (feature = \"next\"))] type Config = Config_v0_1_0 ;"));
+}
+
+#[test]
+fn cfg_combinators_are_expanded() {
+    let expanded = expand(
+        r#"
+        #[obake::versioned]
+        #[obake(version("0.1.0"))]
+        #[obake(version("0.2.0"))]
+        #[obake(version("0.9.0"))]
+        struct Config {
+            #[obake(cfg(any("<0.2", ">=0.9")))]
+            legacy: u8,
+            #[obake(cfg(all(">=0.2", not(">=0.9"))))]
+            level: u8,
+        }
+        "#,
+    );
+
+    assert!(expanded.contains("struct Config_v0_1_0 { legacy : u8 }"));
+    assert!(expanded.contains("struct Config_v0_2_0 { level : u8 }"));
+    assert!(expanded.contains("struct Config_v0_9_0 { legacy : u8 }"));
+}
+
+#[test]
+fn cfg_version_lists_are_expanded() {
+    let expanded = expand(
+        r#"
+        #[obake::versioned]
+        #[obake(version("0.1.0"))]
+        #[obake(version("0.2.0"))]
+        #[obake(version("0.3.0"))]
+        enum Command {
+            Start,
+            #[obake(cfg(in("0.1.0", "0.3.0")))]
+            Pause,
+        }
+        "#,
+    );
+
+    assert!(expanded.contains("enum Command_v0_1_0 { Start , Pause }"));
+    assert!(expanded.contains("enum Command_v0_2_0 { Start }"));
+    assert!(expanded.contains("enum Command_v0_3_0 { Start , Pause }"));
+}
+
+#[test]
+fn cfg_version_lists_must_be_declared() {
+    let err = expand_str(
+        r#"
+        #[obake::versioned]
+        #[obake(version("0.1.0"))]
+        struct Config {
+            #[obake(cfg(in("0.2.0")))]
+            level: u8,
+        }
+        "#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("`Config` has no version 0.2.0"));
+}
+
+#[test]
+fn other_items_are_untouched() {
+    let source = "fn main () { }";
+    assert_eq!(expand(source), source);
+}
+
+#[test]
+fn trailing_features_gate_the_previous_version() {
+    let expanded = expand(
+        r#"
+        #[obake::versioned]
+        #[obake(version("0.1.0"))]
+        #[obake(version("0.9.0-experimental"), feature = "unstable")]
+        struct Config {
+            level: u8,
+        }
+        "#,
+    );
+
+    assert!(expanded.contains("# [cfg (feature = \"unstable\")] struct Config_v0_9_0_experimental"));
+    assert!(
+        expanded.contains("# [cfg (not (feature = \"unstable\"))] type Config = Config_v0_1_0 ;")
+    );
+}
+
+#[test]
+fn version_lists_are_expanded() {
+    let expanded = expand(
+        r#"
+        #[obake::versioned]
+        #[obake(versions("0.1.0", "0.2.0"))]
+        struct Config {
+            #[obake(cfg(">=0.2"))]
+            level: u8,
+        }
+        "#,
+    );
+
+    assert!(expanded.contains("struct Config_v0_1_0 { }"));
+    assert!(expanded.contains("struct Config_v0_2_0 { level : u8 }"));
+}
+
+#[test]
+fn version_sets_are_resolved() {
+    let expanded = expand(
+        r#"
+        obake::version_set!(AppVersions = ["0.1.0", "0.2.0"]);
+
+        mod config {
+            #[obake::versioned]
+            #[obake(use_versions(crate::AppVersions))]
+            struct Config {
+                #[obake(cfg(">=0.2"))]
+                level: u8,
+            }
+        }
+        "#,
+    );
+
+    assert!(expanded.contains("struct Config_v0_1_0 { }"));
+    assert!(expanded.contains("struct Config_v0_2_0 { level : u8 }"));
+}
+
+#[test]
+fn manifest_versions_are_expanded() {
+    let expanded = expand(
+        r#"
+        #[obake::versioned]
+        #[obake(versions_from = "tests/manifests/versions.toml")]
+        struct Release {
+            name: u8,
+        }
+        "#,
+    );
+
+    assert!(expanded.contains("# [cfg (feature = \"legacy\")] struct Release_v0_1_0"));
+    assert!(expanded.contains("struct Release_v0_2_0 { name : u8 }"));
+    assert!(expanded.contains("type Release = Release_v0_3_0 ;"));
+}
+
+#[test]
+fn undeclared_version_sets_are_errors() {
+    let err = expand_str(
+        r#"
+        #[obake::versioned]
+        #[obake(use_versions(AppVersions))]
+        struct Config {}
+        "#,
+    )
+    .unwrap_err();
+    assert!(matches!(err, ExpandError::Syntax(_)));
+    assert!(err
+        .to_string()
+        .contains("`AppVersions` isn't declared with `obake::version_set!` in this file"));
+}
@@ -0,0 +1,55 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[cfg_attr(all(), obake(version("0.2.0")))]
+#[derive(Debug, PartialEq)]
+struct Enabled {
+    name: String,
+    #[cfg_attr(all(), obake(cfg(">=0.2")))]
+    nickname: String,
+}
+
+impl From<Enabled!["0.1.0"]> for Enabled!["0.2.0"] {
+    fn from(from: Enabled!["0.1.0"]) -> Self {
+        Self {
+            nickname: from.name.clone(),
+            name: from.name,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[cfg_attr(any(), obake(version("0.2.0")))]
+#[derive(Debug, PartialEq)]
+struct Disabled {
+    name: String,
+    #[cfg_attr(any(), obake(cfg(">=0.2")))]
+    #[cfg_attr(all(), cfg_attr(all(), obake(cfg("0.1.0"))))]
+    nickname: String,
+}
+
+#[test]
+fn enabled_helpers_apply() {
+    assert_eq!(<Enabled as obake::Versioned>::VERSIONS, ["0.1.0", "0.2.0"]);
+    let enabled: Enabled = VersionedEnabled::Enabled_v0_1_0(Enabled_v0_1_0 {
+        name: "ferris".to_owned(),
+    })
+    .into();
+    assert_eq!(
+        enabled,
+        Enabled {
+            name: "ferris".to_owned(),
+            nickname: "ferris".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn disabled_helpers_are_dropped() {
+    assert_eq!(<Disabled as obake::Versioned>::VERSIONS, ["0.1.0"]);
+    let disabled = Disabled {
+        name: "ferris".to_owned(),
+        nickname: "crab".to_owned(),
+    };
+    assert_eq!(disabled.nickname, "crab");
+}
@@ -0,0 +1,135 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(version("0.4.0"))]
+#[obake(version("0.5.0"))]
+#[derive(Debug, Default, PartialEq)]
+struct Account {
+    name: String,
+    #[obake(cfg(any(">=0.2, <0.3", ">=0.5")))]
+    nickname: String,
+    #[obake(cfg(all(">=0.2", not("0.4.0"))))]
+    email: String,
+    #[obake(cfg(not(any("0.1.0", "0.3.0"),)))]
+    verified: bool,
+}
+
+impl From<Account!["0.1.0"]> for Account!["0.2.0"] {
+    fn from(from: Account!["0.1.0"]) -> Self {
+        Self {
+            nickname: from.name.clone(),
+            name: from.name,
+            email: String::new(),
+            verified: false,
+        }
+    }
+}
+
+impl From<Account!["0.2.0"]> for Account!["0.3.0"] {
+    fn from(from: Account!["0.2.0"]) -> Self {
+        Self {
+            name: from.name,
+            email: from.email,
+        }
+    }
+}
+
+impl From<Account!["0.3.0"]> for Account!["0.4.0"] {
+    fn from(from: Account!["0.3.0"]) -> Self {
+        Self {
+            name: from.name,
+            verified: false,
+        }
+    }
+}
+
+impl From<Account!["0.4.0"]> for Account!["0.5.0"] {
+    fn from(from: Account!["0.4.0"]) -> Self {
+        Self {
+            nickname: from.name.clone(),
+            name: from.name,
+            email: String::new(),
+            verified: from.verified,
+        }
+    }
+}
+
+#[test]
+fn fields_are_enabled_by_combined_requirements() {
+    let _ = Account_v0_2_0 {
+        name: String::new(),
+        nickname: String::new(),
+        email: String::new(),
+        verified: false,
+    };
+    let _ = Account_v0_3_0 {
+        name: String::new(),
+        email: String::new(),
+    };
+    let _ = Account_v0_4_0 {
+        name: String::new(),
+        verified: false,
+    };
+
+    let account: Account = VersionedAccount::Account_v0_3_0(Account_v0_3_0 {
+        name: "ferris".to_owned(),
+        email: "ferris@example.com".to_owned(),
+    })
+    .into();
+    assert_eq!(
+        account,
+        Account {
+            nickname: "ferris".to_owned(),
+            name: "ferris".to_owned(),
+            email: String::new(),
+            verified: false,
+        }
+    );
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(version("0.4.0"))]
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Circle(f64),
+    #[obake(cfg(in("0.1.0", "0.3.0", "0.4.0")))]
+    Square(f64),
+    #[obake(cfg(any(in("0.2.0"), ">=0.4")))]
+    Triangle(f64),
+}
+
+impl From<Shape!["0.1.0"]> for Shape!["0.2.0"] {
+    fn from(from: Shape!["0.1.0"]) -> Self {
+        match from {
+            Shape_v0_1_0::Circle(r) | Shape_v0_1_0::Square(r) => Self::Circle(r),
+        }
+    }
+}
+
+impl From<Shape!["0.2.0"]> for Shape!["0.3.0"] {
+    fn from(from: Shape!["0.2.0"]) -> Self {
+        match from {
+            Shape_v0_2_0::Circle(r) | Shape_v0_2_0::Triangle(r) => Self::Circle(r),
+        }
+    }
+}
+
+impl From<Shape!["0.3.0"]> for Shape!["0.4.0"] {
+    fn from(from: Shape!["0.3.0"]) -> Self {
+        match from {
+            Shape_v0_3_0::Circle(r) => Self::Circle(r),
+            Shape_v0_3_0::Square(side) => Self::Square(side),
+        }
+    }
+}
+
+#[test]
+fn variants_are_enabled_in_listed_versions() {
+    let shape: Shape = VersionedShape::Shape_v0_3_0(Shape_v0_3_0::Square(2.0)).into();
+    assert_eq!(shape, Shape::Square(2.0));
+    let _ = Shape_v0_2_0::Triangle(1.0);
+}
@@ -0,0 +1,38 @@
+use std::cmp::Ordering;
+
+#[obake::versioned]
+#[obake(version("0.10.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.2.0-beta"))]
+#[obake(trivial_migrations)]
+#[obake(version_enum)]
+#[derive(Debug, PartialEq)]
+struct Record {
+    id: u32,
+}
+
+#[test]
+fn versions_are_ordered_by_semver() {
+    let beta = VersionedRecord::Record_v0_2_0_beta(Record_v0_2_0_beta { id: 3 });
+    let old = VersionedRecord::Record_v0_2_0(Record_v0_2_0 { id: 1 });
+    let new = VersionedRecord::Record_v0_10_0(Record_v0_10_0 { id: 2 });
+    assert_eq!(old.cmp_version(&new), Ordering::Less);
+    assert_eq!(new.cmp_version(&old), Ordering::Greater);
+    assert_eq!(beta.cmp_version(&old), Ordering::Less);
+    assert_eq!(
+        old.cmp_version(&VersionedRecord::Record_v0_2_0(Record_v0_2_0 { id: 9 })),
+        Ordering::Equal
+    );
+
+    let mut records = [new, old, beta];
+    records.sort_by(VersionedRecord::cmp_version);
+    let versions: Vec<_> = records
+        .iter()
+        .map(<Record as obake::Versioned>::version_of)
+        .collect();
+    assert_eq!(versions, ["0.2.0-beta", "0.2.0", "0.10.0"]);
+
+    records.reverse();
+    records.sort_by_key(VersionedRecord::version);
+    assert_eq!(records[0].version(), RecordVersion::V0_2_0_BETA);
+}
@@ -0,0 +1,61 @@
+#[obake::versioned]
+#[obake(version("0.1.0"), version("0.2.0"), derive(Debug, PartialEq))]
+#[obake(strict, downgrade)]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg("0.1.0"), discard)]
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_1: from.field_0.into(),
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_1 as u32,
+        }
+    }
+}
+
+#[test]
+fn combined_helpers() {
+    use obake::Downgrade;
+
+    assert_eq!(<Foo as obake::Versioned>::VERSIONS, ["0.1.0", "0.2.0"]);
+    assert_eq!(
+        Foo { field_1: 7 }.downgrade("0.1.0"),
+        Some(VersionedFoo::Foo_v0_1_0(Foo_v0_1_0 { field_0: 7 }))
+    );
+}
+
+#[obake::versioned]
+#[obake(versions("0.1.0", "0.2.0", "0.3.0"), trivial_migrations)]
+struct Bar {
+    field_0: u32,
+}
+
+#[test]
+fn several_versions() {
+    assert_eq!(
+        <Bar as obake::Versioned>::VERSIONS,
+        ["0.1.0", "0.2.0", "0.3.0"]
+    );
+
+    let old = VersionedBar::Bar_v0_1_0(Bar_v0_1_0 { field_0: 7 });
+    assert_eq!(<Bar as obake::Versioned>::version_of(&old), "0.1.0");
+
+    let bar: Bar = old.into();
+    assert_eq!(bar.field_0, 7);
+    assert_eq!(
+        <Bar as obake::Versioned>::version_of(&VersionedBar::from(bar)),
+        "0.3.0"
+    );
+}
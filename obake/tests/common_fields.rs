@@ -0,0 +1,58 @@
+#![allow(dead_code)]
+
+use obake::AnyVersion;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(trivial_migrations, common_fields)]
+struct Document {
+    id: u64,
+    title: String,
+    #[obake(cfg("<0.3"))]
+    body: String,
+    #[obake(cfg(">=0.3"))]
+    paragraphs: Vec<String>,
+}
+
+impl From<Document!["0.2.0"]> for Document!["0.3.0"] {
+    fn from(from: Document!["0.2.0"]) -> Self {
+        Self {
+            id: from.id,
+            title: from.title,
+            paragraphs: from.body.lines().map(str::to_owned).collect(),
+        }
+    }
+}
+
+fn index(documents: &[AnyVersion<Document>]) -> Vec<(u64, &str)> {
+    documents
+        .iter()
+        .map(|document| (*document.id(), document.title().as_str()))
+        .collect()
+}
+
+#[test]
+fn common_fields() {
+    let documents = [
+        VersionedDocument::Document_v0_1_0(Document_v0_1_0 {
+            id: 1,
+            title: "first".to_owned(),
+            body: String::new(),
+        }),
+        VersionedDocument::from(Document {
+            id: 2,
+            title: "second".to_owned(),
+            paragraphs: vec![],
+        }),
+    ];
+    assert_eq!(index(&documents), [(1, "first"), (2, "second")]);
+
+    let old = Document_v0_2_0 {
+        id: 3,
+        title: "third".to_owned(),
+        body: String::new(),
+    };
+    assert_eq!(*DocumentCommon::id(&old), 3);
+}
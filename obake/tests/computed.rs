@@ -0,0 +1,47 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[derive(Debug, PartialEq)]
+struct Order {
+    items: Vec<String>,
+    #[obake(cfg(">=0.2"), computed = |old| old.items.len() as u32)]
+    item_count: u32,
+    #[obake(cfg("<0.3"))]
+    total_cents: u32,
+    #[obake(cfg(">=0.3"))]
+    #[obake(retyped)]
+    total_cents: u64,
+    #[obake(cfg(">=0.3"))]
+    #[obake(computed = |old| old.items.iter().map(|item| item.len() as u64).sum())]
+    checksum: u64,
+}
+
+#[test]
+fn computed_fields_are_derived_from_the_previous_version() {
+    let order: Order = VersionedOrder::Order_v0_1_0(Order_v0_1_0 {
+        items: vec!["apple".to_owned(), "pear".to_owned()],
+        total_cents: 250,
+    })
+    .into();
+    assert_eq!(
+        order,
+        Order {
+            items: vec!["apple".to_owned(), "pear".to_owned()],
+            item_count: 2,
+            total_cents: 250,
+            checksum: 9,
+        }
+    );
+}
+
+#[test]
+fn computed_fields_are_carried_over_once_added() {
+    let order: Order = Order_v0_2_0 {
+        items: vec![],
+        item_count: 7,
+        total_cents: 0,
+    }
+    .into();
+    assert_eq!(order.item_count, 7);
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "config")]
+
+use config::{Config, Environment, File, FileFormat, Map};
+use obake::config::ConfigExt;
+use serde::Deserialize;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Settings {
+    name: String,
+    #[obake(cfg("0.1.0"))]
+    timeout_secs: u32,
+    #[obake(cfg(">=0.2"))]
+    timeout_ms: u64,
+}
+
+impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+    fn from(from: Settings!["0.1.0"]) -> Self {
+        Self {
+            name: from.name,
+            timeout_ms: u64::from(from.timeout_secs) * 1000,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(derive(Debug, Deserialize), serde(deny_unknown_fields))]
+#[derive(Debug, Deserialize)]
+struct Untagged {}
+
+fn source(json: &str) -> File<config::FileSourceString, FileFormat> {
+    File::from_str(json, FileFormat::Json)
+}
+
+#[test]
+fn layered_configuration_is_upgraded() {
+    let env = Map::from([("APP_TIMEOUT_SECS".to_owned(), "5".to_owned())]);
+    let config = Config::builder()
+        .set_default("name", "default")
+        .unwrap()
+        .add_source(source(r#"{ "version": "0.1.0", "timeout_secs": 1 }"#))
+        .add_source(Environment::with_prefix("APP").source(Some(env)))
+        .build()
+        .unwrap();
+
+    let settings: Settings = config.try_deserialize_versioned().unwrap();
+    assert_eq!(
+        settings,
+        Settings {
+            name: "default".to_owned(),
+            timeout_ms: 5000,
+        }
+    );
+}
+
+#[test]
+fn latest_versions_are_deserialized() {
+    let config = Config::builder()
+        .add_source(source(
+            r#"{ "version": "0.2.0", "name": "app", "timeout_ms": 250 }"#,
+        ))
+        .build()
+        .unwrap();
+    let settings: Settings = config.try_deserialize_versioned().unwrap();
+    assert_eq!(settings.timeout_ms, 250);
+}
+
+#[test]
+fn unknown_versions_are_rejected() {
+    let config = Config::builder()
+        .add_source(source(r#"{ "version": "9.9.9", "name": "app" }"#))
+        .build()
+        .unwrap();
+    assert!(config.try_deserialize_versioned::<Settings>().is_err());
+}
+
+#[test]
+fn missing_versions_are_rejected() {
+    let config = Config::builder()
+        .add_source(source(r#"{ "name": "app", "timeout_ms": 250 }"#))
+        .build()
+        .unwrap();
+    assert!(config.try_deserialize_versioned::<Settings>().is_err());
+}
+
+#[test]
+fn untagged_items_are_rejected() {
+    let config = Config::builder().add_source(source("{}")).build().unwrap();
+    let err = config.try_deserialize_versioned::<Untagged>().unwrap_err();
+    assert!(err.to_string().contains("serde(tag"));
+}
@@ -0,0 +1,159 @@
+#![cfg(feature = "container")]
+
+use obake::container::{Compression, Container, Error};
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Save {
+    #[obake(cfg("0.1.0"))]
+    name: String,
+    #[obake(cfg(">=0.2"))]
+    names: Vec<String>,
+    level: u32,
+}
+
+impl From<Save!["0.1.0"]> for Save!["0.2.0"] {
+    fn from(from: Save!["0.1.0"]) -> Self {
+        Self {
+            names: vec![from.name],
+            level: from.level,
+        }
+    }
+}
+
+fn latest() -> Save {
+    Save {
+        names: vec!["ash".to_owned(), "misty".to_owned()],
+        level: 12,
+    }
+}
+
+/// Builds a container by hand, as written by an older version of the application.
+fn legacy_container() -> Vec<u8> {
+    let payload = bincode::serialize(&Save_v0_1_0 {
+        name: "ash".to_owned(),
+        level: 3,
+    })
+    .unwrap();
+
+    let mut file = b"OBK\0".to_vec();
+    file.extend_from_slice(&[1, 0, 5]);
+    file.extend_from_slice(b"0.1.0");
+    file.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    file.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    file.extend_from_slice(&payload);
+    file
+}
+
+#[test]
+fn round_trip() {
+    let mut file = Vec::new();
+    obake::container::save(&latest(), &mut file).unwrap();
+    assert_eq!(&file[..4], b"OBK\0");
+    assert_eq!(&file[7..12], b"0.2.0");
+
+    let save: Save = obake::container::load(&file[..]).unwrap();
+    assert_eq!(save, latest());
+}
+
+#[test]
+fn load_upgrades_older_versions() {
+    let save: Save = obake::container::load(&legacy_container()[..]).unwrap();
+    assert_eq!(
+        save,
+        Save {
+            names: vec!["ash".to_owned()],
+            level: 3,
+        }
+    );
+}
+
+#[test]
+fn custom_magic() {
+    let container = Container::new(*b"GAME");
+    let mut file = Vec::new();
+    container.save(&latest(), &mut file).unwrap();
+    assert_eq!(&file[..4], b"GAME");
+    assert_eq!(container.load::<Save, _>(&file[..]).unwrap(), latest());
+
+    let err = obake::container::load::<Save, _>(&file[..]).unwrap_err();
+    assert!(matches!(err, Error::BadMagic(magic) if &magic == b"GAME"));
+}
+
+#[test]
+fn corrupted_payload() {
+    let mut file = legacy_container();
+    *file.last_mut().unwrap() ^= 0xff;
+
+    let err = obake::container::load::<Save, _>(&file[..]).unwrap_err();
+    assert!(matches!(err, Error::Checksum { .. }));
+}
+
+#[test]
+fn truncated_payload() {
+    let file = legacy_container();
+
+    let err = obake::container::load::<Save, _>(&file[..file.len() - 1]).unwrap_err();
+    assert!(matches!(err, Error::Io(_)));
+}
+
+#[test]
+fn unsupported_header() {
+    let mut file = legacy_container();
+    file[4] = 2;
+    let err = obake::container::load::<Save, _>(&file[..]).unwrap_err();
+    assert!(matches!(err, Error::UnsupportedFormat(2)));
+    assert_eq!(err.to_string(), "unsupported container format version 2");
+
+    let mut file = legacy_container();
+    file[5] = 0xff;
+    let err = obake::container::load::<Save, _>(&file[..]).unwrap_err();
+    assert!(matches!(err, Error::UnsupportedCompression(0xff)));
+}
+
+#[test]
+fn unknown_version() {
+    let mut file = legacy_container();
+    file[7..12].copy_from_slice(b"9.9.9");
+
+    let err = obake::container::load::<Save, _>(&file[..]).unwrap_err();
+    assert!(matches!(err, Error::Encoding(_)));
+}
+
+#[cfg(feature = "deflate")]
+#[test]
+fn deflate() {
+    let container = Container::default().with_compression(Compression::Deflate);
+    let mut file = Vec::new();
+    container.save(&latest(), &mut file).unwrap();
+    assert_eq!(file[5], 1);
+
+    // Compression is read from the header, so any container can load it
+    assert_eq!(
+        obake::container::load::<Save, _>(&file[..]).unwrap(),
+        latest()
+    );
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd() {
+    let container = Container::default().with_compression(Compression::Zstd);
+    let mut file = Vec::new();
+    container.save(&latest(), &mut file).unwrap();
+    assert_eq!(file[5], 2);
+
+    assert_eq!(
+        obake::container::load::<Save, _>(&file[..]).unwrap(),
+        latest()
+    );
+}
+
+#[test]
+fn default_compression() {
+    assert_eq!(Compression::default(), Compression::None);
+}
@@ -0,0 +1,51 @@
+#![allow(dead_code)]
+
+use obake::{Coverage, TraitCoverage};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(coverage(Clone, core::fmt::Debug, Default))]
+#[derive(Debug)]
+struct Foo {
+    field_0: u32,
+}
+
+impl Clone for Foo!["0.2.0"] {
+    fn clone(&self) -> Self {
+        Self {
+            field_0: self.field_0,
+        }
+    }
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+#[test]
+fn coverage_lists_every_version() {
+    assert_eq!(Foo::COVERAGE.len(), 6);
+    assert!(Foo::COVERAGE.contains(&TraitCoverage {
+        version: "0.1.0",
+        trait_name: "core::fmt::Debug",
+        implemented: true,
+    }));
+}
+
+#[test]
+fn missing_impls_are_reported() {
+    let missing: Vec<_> = obake::missing_impls::<Foo>().collect();
+    assert_eq!(
+        missing,
+        [&TraitCoverage {
+            version: "0.1.0",
+            trait_name: "Clone",
+            implemented: false,
+        }]
+    );
+}
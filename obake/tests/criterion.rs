@@ -0,0 +1,68 @@
+#![cfg(feature = "criterion")]
+// `bench` isn't one of obake's features, so the benchmarks of `Gated` are always pruned here
+#![allow(dead_code, unexpected_cfgs)]
+
+use std::time::Duration;
+
+use criterion::Criterion;
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(arbitrary, generate_benches)]
+#[derive(Serialize, Deserialize)]
+pub struct Settings {
+    #[obake(cfg("0.1.0"))]
+    timeout_secs: u32,
+    #[obake(cfg(">=0.2"))]
+    timeout_ms: u64,
+    #[obake(cfg(">=0.3"))]
+    retries: Option<u8>,
+}
+
+impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+    fn from(from: Settings!["0.1.0"]) -> Self {
+        Self {
+            timeout_ms: u64::from(from.timeout_secs) * 1000,
+        }
+    }
+}
+
+impl From<Settings!["0.2.0"]> for Settings!["0.3.0"] {
+    fn from(from: Settings!["0.2.0"]) -> Self {
+        Self {
+            timeout_ms: from.timeout_ms,
+            retries: None,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(arbitrary, generate_benches(feature = "bench"))]
+#[derive(Serialize, Deserialize)]
+pub struct Gated {
+    field: u8,
+}
+
+impl From<Gated!["0.1.0"]> for Gated!["0.2.0"] {
+    fn from(from: Gated!["0.1.0"]) -> Self {
+        Self { field: from.field }
+    }
+}
+
+#[test]
+fn benches_run_for_every_version() {
+    let mut c = Criterion::default()
+        .sample_size(10)
+        .warm_up_time(Duration::from_millis(1))
+        .measurement_time(Duration::from_millis(10))
+        .without_plots();
+    settings_benches::migrations(&mut c);
+}
+
+// Would conflict with the generated module, were it not gated
+mod gated_benches {}
@@ -0,0 +1,76 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(default)]
+#[derive(Debug, PartialEq)]
+struct Settings {
+    #[obake(default = String::from("localhost"))]
+    host: String,
+    #[obake(cfg("<0.3"))]
+    #[obake(default = 8080)]
+    port: u16,
+    #[obake(cfg(">=0.2"))]
+    retries: u8,
+    #[obake(cfg(">=0.3"))]
+    #[obake(default = vec![String::from("localhost:8080")])]
+    addresses: Vec<String>,
+}
+
+impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+    fn from(from: Settings!["0.1.0"]) -> Self {
+        Self {
+            host: from.host,
+            port: from.port,
+            retries: 3,
+        }
+    }
+}
+
+impl From<Settings!["0.2.0"]> for Settings!["0.3.0"] {
+    fn from(from: Settings!["0.2.0"]) -> Self {
+        Self {
+            addresses: vec![format!("{}:{}", from.host, from.port)],
+            host: from.host,
+            retries: from.retries,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(default)]
+#[derive(Debug, PartialEq)]
+struct Retries(#[obake(default = 3)] u8);
+
+#[test]
+fn old_versions_have_defaults() {
+    assert_eq!(
+        Settings_v0_1_0::default(),
+        Settings_v0_1_0 {
+            host: "localhost".to_owned(),
+            port: 8080,
+        }
+    );
+    assert_eq!(
+        Settings_v0_2_0::default(),
+        Settings_v0_2_0 {
+            host: "localhost".to_owned(),
+            port: 8080,
+            retries: 0,
+        }
+    );
+}
+
+#[test]
+fn latest_version_has_defaults() {
+    assert_eq!(
+        Settings::default(),
+        Settings {
+            host: "localhost".to_owned(),
+            retries: 0,
+            addresses: vec!["localhost:8080".to_owned()],
+        }
+    );
+    assert_eq!(Retries::default().0, 3);
+}
@@ -0,0 +1,29 @@
+#![deny(deprecated)]
+
+#[obake::versioned]
+#[obake(version("0.1.0", deprecated = "upgrade to 0.2+"))]
+#[obake(version("0.2.0"))]
+#[obake(aliases, derive(Debug, PartialEq))]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u64,
+}
+
+// Migrations from deprecated versions have to opt out of the warning
+#[allow(deprecated)]
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_1: from.field_0.into(),
+        }
+    }
+}
+
+#[test]
+fn deprecated_versions_still_migrate() {
+    let foo: Foo = VersionedFoo::Foo_v0_2_0(Foo { field_1: 1 }).into();
+    assert_eq!(foo, Foo { field_1: 1 });
+}
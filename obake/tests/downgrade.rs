@@ -0,0 +1,81 @@
+#![allow(dead_code)]
+
+use obake::Downgrade;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(downgrade)]
+#[obake(derive(Debug, PartialEq, Eq))]
+#[derive(Debug, PartialEq, Eq)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    name: String,
+    #[obake(cfg(">=0.2"))]
+    names: Vec<String>,
+    #[obake(cfg(">=0.3"))]
+    count: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            names: vec![from.name],
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            name: from.names.into_iter().next().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            names: from.names,
+            count: 0,
+        }
+    }
+}
+
+impl From<Foo!["0.3.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.3.0"]) -> Self {
+        Self { names: from.names }
+    }
+}
+
+fn foo() -> Foo {
+    Foo {
+        names: vec!["a".to_owned(), "b".to_owned()],
+        count: 3,
+    }
+}
+
+#[test]
+fn downgrade_to_latest_is_identity() {
+    assert_eq!(
+        foo().downgrade("0.3.0"),
+        Some(VersionedFoo::Foo_v0_3_0(foo()))
+    );
+}
+
+#[test]
+fn downgrade_steps_through_every_version() {
+    type Foo1 = Foo!["0.1.0"];
+    type Foo2 = Foo!["0.2.0"];
+    let expected = Foo1::from(Foo2::from(foo()));
+    assert_eq!(
+        foo().downgrade("0.1.0"),
+        Some(VersionedFoo::Foo_v0_1_0(expected))
+    );
+}
+
+#[test]
+fn downgrade_to_unknown_version_fails() {
+    assert!(foo().downgrade("0.4.0").is_none());
+}
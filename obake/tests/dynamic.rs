@@ -0,0 +1,144 @@
+#![cfg(feature = "dynamic")]
+
+use obake::dynamic::{self, Change, Dynamic};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(dynamic)]
+#[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Account {
+    #[obake(serde_cfg("0.1.0", rename = "login"))]
+    name: String,
+    #[obake(cfg("0.1.0"))]
+    legacy_id: u32,
+    #[obake(cfg(">=0.2"))]
+    tags: Vec<String>,
+    #[obake(cfg(">=0.3"))]
+    limit: Option<u32>,
+}
+
+impl From<Account!["0.1.0"]> for Account!["0.2.0"] {
+    fn from(from: Account!["0.1.0"]) -> Self {
+        Self {
+            name: from.name,
+            tags: Vec::new(),
+        }
+    }
+}
+
+impl From<Account!["0.2.0"]> for Account!["0.3.0"] {
+    fn from(from: Account!["0.2.0"]) -> Self {
+        Self {
+            name: from.name,
+            tags: from.tags,
+            limit: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Region(String);
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(dynamic)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Deployment {
+    #[obake(cfg("0.1.0"))]
+    replica_count: u32,
+    #[obake(cfg(">=0.2"))]
+    home_region: Region,
+    #[obake(cfg(">=0.2"))]
+    max_replicas: u32,
+}
+
+impl From<Deployment!["0.1.0"]> for Deployment!["0.2.0"] {
+    fn from(from: Deployment!["0.1.0"]) -> Self {
+        Self {
+            home_region: Region("us".to_owned()),
+            max_replicas: from.replica_count,
+        }
+    }
+}
+
+#[test]
+fn steps_describe_the_serialized_fields() {
+    assert_eq!(Account::TAG, Some("version"));
+    assert_eq!(Account::STEPS.len(), 2);
+    assert_eq!(
+        (Account::STEPS[0].from, Account::STEPS[0].to),
+        ("0.1.0", "0.2.0")
+    );
+    assert!(matches!(
+        Account::STEPS[0].changes,
+        [
+            Change::Rename {
+                from: "login",
+                to: "name"
+            },
+            Change::Remove { field: "legacy_id" },
+            Change::Add { field: "tags", .. },
+        ]
+    ));
+}
+
+#[test]
+fn payloads_are_upgraded_to_the_latest_version() {
+    let payload = json!({ "version": "0.1.0", "login": "ferris", "legacy_id": 7 });
+    let upgraded = dynamic::upgrade_to_latest::<Account>(payload.clone()).unwrap();
+    assert_eq!(
+        upgraded,
+        json!({ "version": "0.3.0", "name": "ferris", "tags": [], "limit": null })
+    );
+
+    // The structural upgrade agrees with the typed migrations
+    let typed: Account = serde_json::from_value::<obake::AnyVersion<Account>>(payload)
+        .unwrap()
+        .into();
+    let typed = serde_json::to_value(obake::AnyVersion::<Account>::from(typed)).unwrap();
+    assert_eq!(upgraded, typed);
+}
+
+#[test]
+fn payloads_are_upgraded_between_versions() {
+    let payload = json!({ "version": "0.1.0", "login": "ferris", "legacy_id": 7, "tags": ["a"] });
+    let upgraded = dynamic::upgrade::<Account>(payload, "0.1.0", "0.2.0").unwrap();
+    assert_eq!(
+        upgraded,
+        json!({ "version": "0.2.0", "name": "ferris", "tags": ["a"] })
+    );
+}
+
+#[test]
+fn downgrades_are_rejected() {
+    let payload = json!({ "version": "0.2.0", "name": "ferris", "tags": [] });
+    assert!(dynamic::upgrade::<Account>(payload.clone(), "0.2.0", "0.1.0").is_err());
+    assert!(dynamic::upgrade::<Account>(payload, "0.2.0", "9.9.9").is_err());
+}
+
+#[test]
+fn payloads_must_name_their_version() {
+    let payload = json!({ "name": "ferris", "tags": [] });
+    assert!(dynamic::upgrade_to_latest::<Account>(payload).is_err());
+    assert!(dynamic::upgrade_to_latest::<Deployment>(json!({ "replicaCount": 1 })).is_err());
+}
+
+#[test]
+fn fields_without_defaults_must_be_present() {
+    assert_eq!(Deployment::TAG, None);
+
+    let payload = json!({ "replicaCount": 1 });
+    let err = dynamic::upgrade::<Deployment>(payload, "0.1.0", "0.2.0").unwrap_err();
+    assert!(err.to_string().contains("homeRegion"));
+
+    let payload = json!({ "replicaCount": 1, "homeRegion": "eu" });
+    let upgraded = dynamic::upgrade::<Deployment>(payload, "0.1.0", "0.2.0").unwrap();
+    assert_eq!(upgraded, json!({ "homeRegion": "eu", "maxReplicas": 0 }));
+}
@@ -0,0 +1,69 @@
+use obake::Versioned;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(emit_original, derive(Debug, PartialEq))]
+#[derive(Debug, Default, PartialEq)]
+struct Profile {
+    name: String,
+    #[obake(cfg("0.1.0"))]
+    age: u8,
+    #[obake(cfg(">=0.2"))]
+    birth_year: u16,
+}
+
+impl From<Profile!["0.1.0"]> for Profile!["0.2.0"] {
+    fn from(from: Profile!["0.1.0"]) -> Self {
+        Self {
+            name: from.name,
+            birth_year: 2020 - u16::from(from.age),
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(emit_original)]
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Circle(f64),
+    #[obake(cfg(">=0.2"))]
+    Square {
+        side: f64,
+    },
+}
+
+impl From<Shape!["0.1.0"]> for Shape!["0.2.0"] {
+    fn from(from: Shape!["0.1.0"]) -> Self {
+        match from {
+            Shape_v0_1_0::Circle(r) => Self::Circle(r),
+        }
+    }
+}
+
+#[test]
+fn original_items_are_emitted_as_written() {
+    let profile = Profile {
+        name: "ferris".to_owned(),
+        age: 5,
+        birth_year: 2015,
+    };
+    assert_ne!(profile, Profile::default());
+    let _ = Shape::Square { side: 1.0 };
+}
+
+#[test]
+fn versions_are_generated_alongside_the_original() {
+    assert_eq!(<Profile!["0.2.0"] as Versioned>::NAME, "Profile");
+    let profile: Profile!["0.2.0"] = VersionedProfile::Profile_v0_1_0(Profile_v0_1_0 {
+        name: "ferris".to_owned(),
+        age: 5,
+    })
+    .into();
+    assert_eq!(profile.birth_year, 2015);
+
+    let shape: Shape_v0_2_0 = VersionedShape::Shape_v0_1_0(Shape_v0_1_0::Circle(2.0)).into();
+    assert_eq!(shape, Shape_v0_2_0::Circle(2.0));
+}
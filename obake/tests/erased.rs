@@ -0,0 +1,82 @@
+#![cfg(feature = "erased")]
+
+use obake::erased::{Any, AnyVersionedSerialize, Envelope};
+use serde::Serialize;
+use serde_json::json;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Serialize), serde(tag = "version"))]
+#[derive(Serialize)]
+struct Order {
+    #[obake(cfg("0.1.0"))]
+    total: u32,
+    #[obake(cfg(">=0.2"))]
+    cents: u64,
+}
+
+impl From<Order!["0.1.0"]> for Order!["0.2.0"] {
+    fn from(from: Order!["0.1.0"]) -> Self {
+        Self {
+            cents: u64::from(from.total) * 100,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[derive(Serialize)]
+struct Customer {
+    name: String,
+}
+
+fn registry() -> Vec<Box<dyn AnyVersionedSerialize>> {
+    vec![
+        Box::new(Order { cents: 250 }),
+        Box::new(Any::<Order>(VersionedOrder::Order_v0_1_0(Order_v0_1_0 {
+            total: 3,
+        }))),
+        Box::new(Customer {
+            name: "ferris".to_owned(),
+        }),
+    ]
+}
+
+#[test]
+fn trait_objects_know_their_versions() {
+    let versions: Vec<_> = registry()
+        .iter()
+        .map(|value| (value.type_name(), value.version()))
+        .collect();
+    assert_eq!(
+        versions,
+        [
+            ("Order", "0.2.0"),
+            ("Order", "0.1.0"),
+            ("Customer", "0.1.0")
+        ]
+    );
+}
+
+#[test]
+fn trait_objects_serialize_uniformly() {
+    assert_eq!(
+        serde_json::to_value(registry()).unwrap(),
+        json!([
+            { "cents": 250 },
+            { "version": "0.1.0", "total": 3 },
+            { "name": "ferris" },
+        ])
+    );
+}
+
+#[test]
+fn envelopes_name_the_type_and_version() {
+    let registry = registry();
+    let envelopes: Vec<_> = registry.iter().map(|value| Envelope(&**value)).collect();
+    assert_eq!(
+        serde_json::to_value(envelopes[1]).unwrap(),
+        json!({ "type": "Order", "version": "0.1.0", "value": { "version": "0.1.0", "total": 3 } })
+    );
+}
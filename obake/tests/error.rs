@@ -0,0 +1,98 @@
+#![cfg(feature = "json")]
+#![allow(dead_code)]
+
+use std::error::Error as _;
+
+use obake::{Error, UnknownVersionError};
+use serde::Deserialize;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(trivial_migrations)]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Debug, Deserialize)]
+struct Invoice {
+    total: u64,
+}
+
+fn parse(input: &str) -> Result<Invoice, Error> {
+    let versioned = serde_json::from_str::<VersionedInvoice>(input)
+        .map_err(|err| Error::parse_json::<Invoice>(input, err))?;
+    let invoice: Invoice = versioned.into();
+    if invoice.total == 0 {
+        return Err(Error::validation::<Invoice>("`total` must be positive"));
+    }
+    Ok(invoice)
+}
+
+#[test]
+fn parse_errors_record_their_location() {
+    let input = "{\n  \"version\": \"0.2.0\",\n  \"total\": 1,,\n}";
+    let err = parse(input).unwrap_err();
+    assert_eq!(err.type_name(), "Invoice");
+    let Error::Parse {
+        location: Some(location),
+        ..
+    } = &err
+    else {
+        panic!("expected a parse error with a location, found {}", err);
+    };
+    assert_eq!(&input[location.offset..], ",\n}");
+    assert_eq!(
+        err.to_string(),
+        "invalid `Invoice`: key must be a string at line 3 column 14"
+    );
+    assert!(err.source().is_some());
+}
+
+#[test]
+fn validation_errors() {
+    let err = parse(r#"{ "version": "0.1.0", "total": 0 }"#).unwrap_err();
+    assert!(matches!(err, Error::Validation { .. }));
+    assert_eq!(
+        err.to_string(),
+        "`Invoice` failed validation: `total` must be positive"
+    );
+}
+
+#[test]
+fn migration_errors() {
+    let err = Error::migration::<Invoice>("0.1.0", "currency has no exchange rate");
+    assert_eq!(
+        err.to_string(),
+        "failed to migrate `Invoice` from version 0.1.0: currency has no exchange rate"
+    );
+}
+
+#[test]
+fn unknown_versions() {
+    let err: Error = UnknownVersionError::check::<Invoice>("0.3.0")
+        .unwrap_err()
+        .into();
+    assert_eq!(
+        err.to_string(),
+        "unsupported version 0.3.0 of `Invoice` (expected one of 0.1.0, 0.2.0)"
+    );
+}
+
+#[cfg(feature = "miette")]
+#[test]
+fn diagnostics() {
+    use miette::Diagnostic;
+
+    let err = parse(r#"{ "version": "0.1.0", "total": } "#).unwrap_err();
+    assert_eq!(err.code().unwrap().to_string(), "obake::parse");
+    assert!(err.source_code().is_some());
+    let labels: Vec<_> = err.labels().unwrap().collect();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].offset(), 31);
+
+    let err = Error::from(UnknownVersionError::new::<Invoice>("1.0.0"));
+    assert_eq!(err.code().unwrap().to_string(), "obake::unknown_version");
+    assert_eq!(
+        err.help().unwrap().to_string(),
+        "supported versions are 0.1.0, 0.2.0"
+    );
+    assert!(err.labels().is_none());
+}
@@ -0,0 +1,102 @@
+#![allow(dead_code)]
+
+use std::error::Error as _;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+struct Timeout;
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "timed out")
+    }
+}
+
+impl std::error::Error for Timeout {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(error)]
+#[obake(derive(Debug))]
+#[derive(Debug)]
+enum ApiError {
+    #[obake(display("not found: {path}"))]
+    NotFound { path: String },
+    #[obake(display("I/O error"))]
+    Io(#[obake(source)] io::Error),
+    #[obake(cfg(">=0.2"))]
+    #[obake(display("request {id} failed after {} attempts", attempts + 1))]
+    Retried {
+        id: u32,
+        attempts: u32,
+        #[obake(source)]
+        cause: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[obake(display("unavailable"))]
+    Unavailable,
+}
+
+impl From<ApiError!["0.1.0"]> for ApiError!["0.2.0"] {
+    fn from(from: ApiError!["0.1.0"]) -> Self {
+        match from {
+            ApiError_v0_1_0::NotFound { path } => Self::NotFound { path },
+            ApiError_v0_1_0::Io(err) => Self::Io(err),
+            ApiError_v0_1_0::Unavailable => Self::Unavailable,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(error)]
+#[obake(display("invalid field `{field}`"))]
+#[derive(Debug)]
+struct FieldError {
+    field: &'static str,
+}
+
+#[test]
+fn versions_implement_error() {
+    let not_found = ApiError_v0_1_0::NotFound {
+        path: "/users".to_owned(),
+    };
+    assert_eq!(not_found.to_string(), "not found: /users");
+    assert!(not_found.source().is_none());
+
+    let retried = ApiError::Retried {
+        id: 7,
+        attempts: 2,
+        cause: Box::new(Timeout),
+    };
+    assert_eq!(retried.to_string(), "request 7 failed after 3 attempts");
+    assert_eq!(retried.source().unwrap().to_string(), "timed out");
+
+    assert_eq!(ApiError::Unavailable.to_string(), "unavailable");
+
+    let field = FieldError { field: "email" };
+    assert_eq!(field.to_string(), "invalid field `email`");
+    assert_eq!(
+        VersionedFieldError::from(field).to_string(),
+        "invalid field `email`"
+    );
+}
+
+#[test]
+fn migrations_preserve_sources() {
+    let io = ApiError_v0_1_0::Io(io::Error::other("disk full"));
+    let upgraded: ApiError = VersionedApiError::ApiError_v0_1_0(io).into();
+    assert_eq!(upgraded.to_string(), "I/O error");
+    assert_eq!(upgraded.source().unwrap().to_string(), "disk full");
+}
+
+#[test]
+fn versioned_enum_delegates() {
+    let versioned = VersionedApiError::from(ApiError::Io(io::Error::other("disk full")));
+    assert_eq!(versioned.to_string(), "I/O error");
+    assert_eq!(versioned.source().unwrap().to_string(), "disk full");
+
+    let boxed: Box<dyn std::error::Error> = Box::new(versioned);
+    assert_eq!(boxed.to_string(), "I/O error");
+}
@@ -0,0 +1,87 @@
+// The experimental version of `Foo` is gated behind `std`, so that it gets tested both with and
+// without it enabled, while `unstable-format` isn't one of obake's features, so the experimental
+// version of `Bar` is always pruned here
+#![allow(unexpected_cfgs)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.9.0-experimental"), feature = "std")]
+#[obake(aliases)]
+#[derive(Debug, Default, PartialEq)]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: bool,
+    #[obake(cfg(">=0.9"))]
+    field_2: u64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: false,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Foo!["0.2.0"]> for Foo!["0.9.0-experimental"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: from.field_1,
+            field_2: 0,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn experimental_version_is_latest_when_enabled() {
+    assert_eq!(
+        <Foo as obake::Versioned>::VERSIONS,
+        ["0.1.0", "0.2.0", "0.9.0-experimental"]
+    );
+
+    let foo: Foo = VersionedFoo::Foo_v0_1_0(Foo_v0_1_0 { field_0: 3 }).into();
+    assert_eq!(
+        foo,
+        Foo_v0_9_0_experimental {
+            field_0: 3,
+            field_1: false,
+            field_2: 0,
+        }
+    );
+    let _: foo::V0_9_0_EXPERIMENTAL = foo::Latest::default();
+}
+
+#[cfg(not(feature = "std"))]
+#[test]
+fn previous_version_is_latest_when_disabled() {
+    assert_eq!(<Foo as obake::Versioned>::VERSIONS, ["0.1.0", "0.2.0"]);
+
+    let foo: Foo = VersionedFoo::Foo_v0_1_0(Foo_v0_1_0 { field_0: 3 }).into();
+    assert_eq!(
+        foo,
+        Foo_v0_2_0 {
+            field_0: 3,
+            field_1: false,
+        }
+    );
+    let _: foo::V0_2_0 = foo::Latest::default();
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0", feature = "legacy"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0-rc.1", feature = "unstable-format"))]
+#[obake(trivial_migrations)]
+struct Bar {}
+
+#[test]
+fn only_ungated_versions_remain() {
+    assert_eq!(<Bar as obake::Versioned>::VERSIONS, ["0.2.0"]);
+    let _: Bar = Bar_v0_2_0 {};
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "figment")]
+
+use figment::providers::{Env, Format, Json, Serialized};
+use figment::{Figment, Jail};
+use serde::Deserialize;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    name: String,
+    #[obake(cfg("0.1.0"))]
+    timeout_secs: u32,
+    #[obake(cfg(">=0.2"))]
+    timeout_ms: u64,
+}
+
+impl From<Config!["0.1.0"]> for Config!["0.2.0"] {
+    fn from(from: Config!["0.1.0"]) -> Self {
+        Self {
+            name: from.name,
+            timeout_ms: u64::from(from.timeout_secs) * 1000,
+        }
+    }
+}
+
+#[test]
+// `Jail` returns `figment`'s own error
+#[allow(clippy::result_large_err)]
+fn layered_configuration_is_upgraded() {
+    Jail::expect_with(|jail| {
+        jail.set_env("APP_TIMEOUT_SECS", "5");
+        let figment = Figment::new()
+            .merge(Serialized::default("name", "default"))
+            .merge(Json::string(r#"{ "version": "0.1.0", "timeout_secs": 1 }"#))
+            .merge(Env::prefixed("APP_"));
+
+        let config: Config = obake::figment::extract(&figment, "version")?;
+        assert_eq!(
+            config,
+            Config {
+                name: "default".to_owned(),
+                timeout_ms: 5000,
+            }
+        );
+        Ok(())
+    });
+}
+
+#[test]
+fn latest_versions_are_extracted() {
+    let figment = Figment::new().merge(Json::string(
+        r#"{ "version": "0.2.0", "name": "app", "timeout_ms": 250 }"#,
+    ));
+    let config: Config = obake::figment::extract(&figment, "version").unwrap();
+    assert_eq!(config.timeout_ms, 250);
+}
+
+#[test]
+fn unknown_and_missing_versions_are_rejected() {
+    let figment = Figment::new().merge(Json::string(r#"{ "version": "9.0.0", "name": "app" }"#));
+    let err = obake::figment::extract::<Config>(&figment, "version").unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("unsupported version 9.0.0 of `Config`"));
+
+    let figment = Figment::new().merge(Json::string(r#"{ "name": "app" }"#));
+    assert!(obake::figment::extract::<Config>(&figment, "version").is_err());
+}
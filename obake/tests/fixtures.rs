@@ -0,0 +1,54 @@
+#![cfg(feature = "fixtures")]
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Serialize, Deserialize))]
+#[obake(serde(tag = "version"))]
+#[obake(fixtures)]
+#[derive(Default, Serialize, Deserialize)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_1: from.field_0.into(),
+        }
+    }
+}
+
+#[test]
+fn fixtures_are_written_for_every_version() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("obake-fixtures");
+    Foo::write_fixtures(&dir).unwrap();
+
+    let old = fs::read(dir.join("Foo-0.1.0.json")).unwrap();
+    let old: VersionedFoo = serde_json::from_slice(&old).unwrap();
+    assert!(matches!(old, VersionedFoo::Foo_v0_1_0(_)));
+
+    let new = fs::read(dir.join("Foo-0.2.0.json")).unwrap();
+    let new: VersionedFoo = serde_json::from_slice(&new).unwrap();
+    assert!(matches!(new, VersionedFoo::Foo_v0_2_0(_)));
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn bincode_fixtures_are_written_for_every_version() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("obake-fixtures-bincode");
+    Foo::write_fixtures(&dir).unwrap();
+
+    for version in <Foo as obake::Versioned>::VERSIONS {
+        assert!(dir.join(format!("Foo-{version}.bin")).exists());
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Position {
+    x: i32,
+    #[obake(cfg(">=0.2"))]
+    y: i32,
+}
+
+impl From<Position!["0.1.0"]> for Position!["0.2.0"] {
+    fn from(from: Position!["0.1.0"]) -> Self {
+        Self { x: from.x, y: 0 }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(trivial_migrations)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Player {
+    name: String,
+    #[obake(inherit, flatten)]
+    position: Position,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(partial_migrations)]
+#[derive(Debug, PartialEq)]
+struct Enemy {
+    #[obake(cfg("0.1.0"))]
+    health: u8,
+    #[obake(cfg("0.2.0"))]
+    hit_points: u32,
+    #[obake(inherit, flatten)]
+    position: Position,
+}
+
+impl From<Enemy!["0.1.0"]> for Enemy!["0.2.0"] {
+    fn from(from: Enemy!["0.1.0"]) -> Self {
+        from.migrate_with(|removed| Enemy_v0_2_0_Added {
+            hit_points: removed.health.into(),
+        })
+    }
+}
+
+// `SerializeDisplay` isn't a `serde` derive, so the flattened field isn't marked
+// `#[serde(flatten)]` (which nothing would accept)
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[derive(serde_with::SerializeDisplay)]
+struct Marker {
+    #[obake(inherit, flatten)]
+    position: Position,
+}
+
+impl std::fmt::Display for Marker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "x = {}", self.position.x)
+    }
+}
+
+#[test]
+fn flattened_fields_serialize_inline() {
+    let player = Player_v0_1_0 {
+        name: "ferris".to_owned(),
+        position: Position_v0_1_0 { x: 3 },
+    };
+    let json = serde_json::to_string(&player).unwrap();
+    assert_eq!(json, r#"{"name":"ferris","x":3}"#);
+    let _: Player_v0_1_0 = serde_json::from_str(&json).unwrap();
+}
+
+#[test]
+fn trivial_migrations_compose_flattened_migrations() {
+    let player: Player = VersionedPlayer::Player_v0_1_0(Player_v0_1_0 {
+        name: "ferris".to_owned(),
+        position: Position_v0_1_0 { x: 3 },
+    })
+    .into();
+    assert_eq!(player.position, Position { x: 3, y: 0 });
+}
+
+#[test]
+fn partial_migrations_compose_flattened_migrations() {
+    let enemy: Enemy = VersionedEnemy::Enemy_v0_1_0(Enemy_v0_1_0 {
+        health: 5,
+        position: Position_v0_1_0 { x: 1 },
+    })
+    .into();
+    assert_eq!(
+        enemy,
+        Enemy {
+            hit_points: 5,
+            position: Position { x: 1, y: 0 }
+        }
+    );
+}
+
+#[test]
+fn only_serde_derives_flatten_fields() {
+    let marker = Marker {
+        position: Position_v0_1_0 { x: 1 },
+    };
+    assert_eq!(serde_json::to_string(&marker).unwrap(), r#""x = 1""#);
+}
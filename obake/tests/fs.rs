@@ -0,0 +1,117 @@
+#![cfg(feature = "fs")]
+
+use std::fs;
+use std::path::PathBuf;
+
+use obake::fs::{migrate_dir, migrate_file, Error, Format};
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+#[derive(Serialize, Deserialize)]
+struct Save {
+    #[obake(cfg("0.1.0"))]
+    level: u8,
+    #[obake(cfg(">=0.2"))]
+    stage: u32,
+}
+
+impl From<Save!["0.1.0"]> for Save!["0.2.0"] {
+    fn from(from: Save!["0.1.0"]) -> Self {
+        Self {
+            stage: u32::from(from.level) + 1,
+        }
+    }
+}
+
+fn saves_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn migrates_a_file() {
+    let path = saves_dir("obake-fs-file").join("save.json");
+    fs::write(&path, r#"{"version":"0.1.0","level":2}"#).unwrap();
+
+    assert_eq!(migrate_file::<Save>(&path, Format::Json).unwrap(), "0.1.0");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "{\"version\":\"0.2.0\",\"stage\":3}\n"
+    );
+
+    assert_eq!(migrate_file::<Save>(&path, Format::Json).unwrap(), "0.2.0");
+}
+
+#[test]
+fn pretty_prints() {
+    let path = saves_dir("obake-fs-pretty").join("save.json");
+    fs::write(&path, r#"{"version":"0.1.0","level":0}"#).unwrap();
+
+    migrate_file::<Save>(&path, Format::JsonPretty).unwrap();
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "{\n  \"version\": \"0.2.0\",\n  \"stage\": 1\n}\n"
+    );
+}
+
+#[test]
+fn leaves_invalid_files_untouched() {
+    let path = saves_dir("obake-fs-invalid").join("save.json");
+    fs::write(&path, r#"{"version":"0.3.0","stage":1}"#).unwrap();
+
+    let err = migrate_file::<Save>(&path, Format::Json).unwrap_err();
+    assert!(matches!(err, Error::Json(_)));
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        r#"{"version":"0.3.0","stage":1}"#
+    );
+
+    let err = migrate_file::<Save>(path.with_file_name("missing.json"), Format::Json).unwrap_err();
+    assert!(matches!(err, Error::Io(_)));
+}
+
+#[test]
+fn migrates_a_directory() {
+    let dir = saves_dir("obake-fs-dir");
+    fs::write(dir.join("a.json"), r#"{"version":"0.1.0","level":1}"#).unwrap();
+    fs::write(dir.join("b.json"), r#"{"version":"0.2.0","stage":5}"#).unwrap();
+    fs::write(dir.join("c.json"), "not json").unwrap();
+    fs::write(dir.join("notes.txt"), "not a save").unwrap();
+
+    let pattern = dir.join("*.json");
+    let report = migrate_dir::<Save>(pattern.to_str().unwrap(), Format::Json).unwrap();
+
+    let outcomes: Vec<_> = report
+        .files
+        .iter()
+        .map(|file| {
+            let name = file.path.file_name().unwrap().to_str().unwrap();
+            (name, file.result.as_ref().ok().copied())
+        })
+        .collect();
+    assert_eq!(
+        outcomes,
+        [
+            ("a.json", Some("0.1.0")),
+            ("b.json", Some("0.2.0")),
+            ("c.json", None)
+        ]
+    );
+    assert!(!report.is_success());
+    assert_eq!(report.failures().count(), 1);
+
+    assert_eq!(
+        fs::read_to_string(dir.join("a.json")).unwrap(),
+        "{\"version\":\"0.2.0\",\"stage\":2}\n"
+    );
+}
+
+#[test]
+fn rejects_invalid_patterns() {
+    assert!(migrate_dir::<Save>("saves/***.json", Format::Json).is_err());
+}
@@ -0,0 +1,75 @@
+#![cfg(all(feature = "fuzz", feature = "json"))]
+#![allow(dead_code)]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+static UPGRADES: AtomicUsize = AtomicUsize::new(0);
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[obake(fuzz)]
+#[derive(Serialize, Deserialize)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        UPGRADES.fetch_add(1, Ordering::Relaxed);
+        Self {
+            field_1: from.field_0.into(),
+        }
+    }
+}
+
+#[test]
+fn valid_payloads_are_upgraded() {
+    let before = UPGRADES.load(Ordering::Relaxed);
+    Foo::fuzz_decode_upgrade(br#"{ "version": "0.1.0", "field_0": 1 }"#);
+    assert_eq!(UPGRADES.load(Ordering::Relaxed), before + 1);
+}
+
+#[test]
+fn invalid_payloads_are_ignored() {
+    Foo::fuzz_decode_upgrade(b"");
+    Foo::fuzz_decode_upgrade(b"\xff\x00{");
+    Foo::fuzz_decode_upgrade(br#"{ "version": "9.9.9", "field_0": 1 }"#);
+    Foo::fuzz_decode_upgrade(br#"{ "version": "0.1.0", "field_0": -1 }"#);
+}
+
+#[cfg(feature = "bincode")]
+mod bincode_format {
+    use serde::{Deserialize, Serialize};
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(version("0.2.0"))]
+    #[obake(derive(Serialize, Deserialize))]
+    #[obake(fuzz(format = obake::fuzz::Bincode))]
+    #[derive(Serialize, Deserialize)]
+    pub struct Bar {
+        #[obake(cfg(">=0.2"))]
+        pub items: Vec<u8>,
+    }
+
+    impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+        fn from(_: Bar!["0.1.0"]) -> Self {
+            Self { items: Vec::new() }
+        }
+    }
+
+    #[test]
+    fn bincode_payloads_are_decoded() {
+        let old = bincode::serialize(&VersionedBar::Bar_v0_1_0(Bar_v0_1_0 {})).unwrap();
+        Bar::fuzz_decode_upgrade(&old);
+        // A length prefix far longer than the payload
+        Bar::fuzz_decode_upgrade(&[1, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f]);
+    }
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "garde")]
+#![allow(dead_code)]
+
+use std::error::Error as _;
+
+use obake::garde::Invalid;
+use obake::Error;
+use serde::Deserialize;
+use serde_json::Deserializer;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(garde)]
+#[obake(derive(Debug, Deserialize), serde(tag = "version"))]
+#[derive(Debug, Deserialize, garde::Validate)]
+struct Upload {
+    #[garde(length(min = 1))]
+    name: String,
+    #[obake(garde_cfg("<0.3", range(max = 1024)))]
+    #[obake(garde_cfg(">=0.3", range(max = 65536)))]
+    size_kb: u32,
+    #[obake(cfg(">=0.2"))]
+    #[garde(skip)]
+    public: bool,
+}
+
+impl From<Upload!["0.1.0"]> for Upload!["0.2.0"] {
+    fn from(from: Upload!["0.1.0"]) -> Self {
+        Self {
+            name: from.name,
+            size_kb: from.size_kb,
+            public: false,
+        }
+    }
+}
+
+impl From<Upload!["0.2.0"]> for Upload!["0.3.0"] {
+    fn from(from: Upload!["0.2.0"]) -> Self {
+        Self {
+            name: from.name,
+            size_kb: from.size_kb,
+            public: from.public,
+        }
+    }
+}
+
+fn invalid_version(err: &Error) -> &'static str {
+    assert!(matches!(err, Error::Validation { .. }));
+    err.source()
+        .and_then(|source| source.downcast_ref::<Invalid>())
+        .unwrap()
+        .version
+}
+
+#[test]
+fn validates_each_version_against_its_rules() {
+    let json = r#"{ "version": "0.3.0", "name": "video", "size_kb": 4096, "public": true }"#;
+    let versioned = VersionedUpload::deserialize_validated(&mut Deserializer::from_str(json));
+    assert!(matches!(versioned, Ok(VersionedUpload::Upload_v0_3_0(_))));
+
+    let json = r#"{ "version": "0.2.0", "name": "video", "size_kb": 4096, "public": true }"#;
+    let err = VersionedUpload::deserialize_validated(&mut Deserializer::from_str(json))
+        .err()
+        .unwrap();
+    assert_eq!(invalid_version(&err), "0.2.0");
+}
+
+#[test]
+fn validates_specific_versions() {
+    let json = r#"{ "name": "photo", "size_kb": 512 }"#;
+    let old = Upload_v0_1_0::deserialize_validated(&mut Deserializer::from_str(json)).unwrap();
+    assert_eq!(old.size_kb, 512);
+
+    let json = r#"{ "name": "", "size_kb": 512, "public": false }"#;
+    let err = Upload::deserialize_validated(&mut Deserializer::from_str(json))
+        .err()
+        .unwrap();
+    assert_eq!(invalid_version(&err), "0.3.0");
+}
+
+#[test]
+fn parse_errors() {
+    let json = r#"{ "version": "0.1.0" }"#;
+    let err = VersionedUpload::deserialize_validated(&mut Deserializer::from_str(json))
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Parse { .. }));
+}
@@ -0,0 +1,48 @@
+#![allow(unused)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(generate_tests)]
+#[derive(Default)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_1: from.field_0.into(),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+mod json {
+    use serde::{Deserialize, Serialize};
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(version("0.2.0"))]
+    #[obake(derive(Serialize, Deserialize))]
+    #[obake(serde(tag = "version"))]
+    #[obake(generate_tests)]
+    #[derive(Default, Serialize, Deserialize)]
+    struct Bar {
+        #[obake(cfg("0.1.0"))]
+        field_0: u32,
+        #[obake(cfg(">=0.2"))]
+        field_1: u64,
+    }
+
+    impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+        fn from(from: Bar!["0.1.0"]) -> Self {
+            Self {
+                field_1: from.field_0.into(),
+            }
+        }
+    }
+}
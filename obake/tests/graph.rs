@@ -0,0 +1,79 @@
+#![allow(dead_code)]
+
+use obake::MigrationGraph;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(graph)]
+struct Foo {
+    id: u32,
+    #[obake(cfg("0.1.0"))]
+    name: String,
+    #[obake(cfg(">=0.2"))]
+    names: Vec<String>,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            id: from.id,
+            names: vec![from.name],
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(graph)]
+#[obake(downgrade)]
+enum Bar {
+    X,
+    #[obake(cfg(">=0.2"))]
+    Y,
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(_: Bar!["0.1.0"]) -> Self {
+        Self::X
+    }
+}
+
+impl From<Bar!["0.2.0"]> for Bar!["0.1.0"] {
+    fn from(_: Bar!["0.2.0"]) -> Self {
+        Self::X
+    }
+}
+
+#[test]
+fn dot() {
+    assert_eq!(
+        Foo::DOT,
+        r#"digraph Foo {
+    node [shape=box];
+    Foo_v0_1_0 [label="Foo 0.1.0\nid\nname"];
+    Foo_v0_2_0 [label="Foo 0.2.0\nid\nnames"];
+    Foo_v0_1_0 -> Foo_v0_2_0 [label="+names\n-name"];
+}"#
+    );
+}
+
+#[test]
+fn mermaid() {
+    assert_eq!(
+        Foo::MERMAID,
+        r#"graph LR
+    Foo_v0_1_0["Foo 0.1.0<br/>id<br/>name"]
+    Foo_v0_2_0["Foo 0.2.0<br/>id<br/>names"]
+    Foo_v0_1_0 -->|"+names<br/>-name"| Foo_v0_2_0
+"#
+    );
+}
+
+#[test]
+fn downgrades_are_dashed() {
+    assert!(Bar::DOT.contains("Bar_v0_2_0 -> Bar_v0_1_0 [style=dashed];"));
+    assert!(Bar::MERMAID.contains("Bar_v0_2_0 -.-> Bar_v0_1_0"));
+    assert!(Bar::MERMAID.contains(r#"Bar_v0_1_0 -->|"+Y"| Bar_v0_2_0"#));
+}
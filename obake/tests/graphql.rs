@@ -0,0 +1,112 @@
+#![cfg(feature = "graphql")]
+
+use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(graphql)]
+#[derive(Clone)]
+struct Invoice {
+    total: u32,
+    #[obake(cfg(">=0.2"))]
+    currency: String,
+}
+
+impl From<Invoice!["0.1.0"]> for Invoice!["0.2.0"] {
+    fn from(from: Invoice!["0.1.0"]) -> Self {
+        Self {
+            total: from.total,
+            currency: "EUR".to_owned(),
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(graphql(input))]
+struct InvoiceFilter {
+    #[obake(cfg("0.1.0"))]
+    min_total: u32,
+    #[obake(cfg(">=0.2"))]
+    currency: String,
+}
+
+impl From<InvoiceFilter!["0.1.0"]> for InvoiceFilter!["0.2.0"] {
+    fn from(_: InvoiceFilter!["0.1.0"]) -> Self {
+        Self {
+            currency: "EUR".to_owned(),
+        }
+    }
+}
+
+struct Query;
+
+#[Object]
+impl Query {
+    async fn history(&self, filter: VersionedInvoiceFilter) -> Vec<VersionedInvoice> {
+        let old = VersionedInvoice::Invoice_v0_1_0(Invoice_v0_1_0 { total: 3 });
+        let new = VersionedInvoice::Invoice_v0_2_0(Invoice {
+            total: 5,
+            currency: "EUR".to_owned(),
+        });
+        match filter {
+            VersionedInvoiceFilter::InvoiceFilter_v0_1_0(filter) => vec![old, new]
+                .into_iter()
+                .filter(|invoice| match invoice {
+                    VersionedInvoice::Invoice_v0_1_0(invoice) => invoice.total >= filter.min_total,
+                    VersionedInvoice::Invoice_v0_2_0(invoice) => invoice.total >= filter.min_total,
+                })
+                .collect(),
+            VersionedInvoiceFilter::InvoiceFilter_v0_2_0(filter) => vec![old, new]
+                .into_iter()
+                .filter(|invoice| match invoice {
+                    VersionedInvoice::Invoice_v0_1_0(_) => false,
+                    VersionedInvoice::Invoice_v0_2_0(invoice) => {
+                        invoice.currency == filter.currency
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+fn schema() -> Schema<Query, EmptyMutation, EmptySubscription> {
+    Schema::new(Query, EmptyMutation, EmptySubscription)
+}
+
+#[test]
+fn type_names() {
+    let sdl = schema().sdl();
+    assert!(sdl.contains("type Invoice_v0_1_0 {"));
+    assert!(sdl.contains("type Invoice_v0_2_0 {"));
+    assert!(sdl.contains("union VersionedInvoice = Invoice_v0_1_0 | Invoice_v0_2_0"));
+    assert!(sdl.contains("input InvoiceFilter_v0_1_0 {"));
+    assert!(sdl.contains("input VersionedInvoiceFilter @oneOf {"));
+}
+
+#[tokio::test]
+async fn query_versions() {
+    let response = schema()
+        .execute(
+            "{
+                history(filter: { v0_1_0: { minTotal: 0 } }) {
+                    __typename
+                    ... on Invoice_v0_1_0 { total }
+                    ... on Invoice_v0_2_0 { total currency }
+                }
+            }",
+        )
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data.into_json().unwrap(),
+        serde_json::json!({
+            "history": [
+                { "__typename": "Invoice_v0_1_0", "total": 3 },
+                { "__typename": "Invoice_v0_2_0", "total": 5, "currency": "EUR" },
+            ],
+        })
+    );
+}
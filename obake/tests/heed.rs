@@ -0,0 +1,44 @@
+#![cfg(feature = "heed")]
+
+use heed_traits::{BytesDecode, BytesEncode};
+use heed_types::SerdeJson;
+use obake::heed::VersionedCodec;
+use obake::AnyVersion;
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Serialize, Deserialize))]
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct Entry {
+    #[obake(cfg("0.1.0"))]
+    hits: u32,
+    #[obake(cfg("0.2.0"))]
+    hits: u64,
+}
+
+impl From<Entry!["0.1.0"]> for Entry!["0.2.0"] {
+    fn from(from: Entry!["0.1.0"]) -> Self {
+        Self {
+            hits: from.hits.into(),
+        }
+    }
+}
+
+type Codec = VersionedCodec<Entry, SerdeJson<AnyVersion<Entry>>>;
+
+#[test]
+fn old_records_are_upgraded_on_read() {
+    type Old = Entry!["0.1.0"];
+    let old = AnyVersion::<Entry>::Entry_v0_1_0(Old { hits: 3 });
+    let bytes = Codec::bytes_encode(&old).unwrap();
+    assert_eq!(Codec::bytes_decode(&bytes).unwrap(), Entry { hits: 3 });
+}
+
+#[test]
+fn latest_records_round_trip() {
+    let latest = AnyVersion::<Entry>::from(Entry { hits: 4 });
+    let bytes = Codec::bytes_encode(&latest).unwrap();
+    assert_eq!(Codec::bytes_decode(&bytes).unwrap(), Entry { hits: 4 });
+}
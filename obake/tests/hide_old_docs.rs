@@ -0,0 +1,37 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(hide_old_docs, aliases, builder, partial_migrations)]
+#[derive(Debug, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    #[obake(cfg("0.1.0"))]
+    pub age: u8,
+    #[obake(cfg(">=0.2"))]
+    pub birth_year: u16,
+}
+
+impl From<Profile!["0.1.0"]> for Profile!["0.2.0"] {
+    fn from(from: Profile!["0.1.0"]) -> Self {
+        from.migrate_with(|removed| Profile_v0_2_0_Added {
+            birth_year: 2024 - u16::from(removed.age),
+        })
+    }
+}
+
+#[test]
+fn hidden_versions_are_still_usable() {
+    let old: profile::V0_1_0 = Profile_v0_1_0::builder()
+        .name("ferris".to_owned())
+        .age(9)
+        .build()
+        .unwrap();
+    let profile: profile::Latest = profile::Any::Profile_v0_1_0(old).into();
+    assert_eq!(
+        profile,
+        Profile {
+            name: "ferris".to_owned(),
+            birth_year: 2015,
+        }
+    );
+}
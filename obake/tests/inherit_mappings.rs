@@ -0,0 +1,62 @@
+#![allow(dead_code)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq)]
+struct Inner {
+    #[obake(cfg("0.1.0"))]
+    a: u32,
+    #[obake(cfg("0.2.0"))]
+    b: u64,
+}
+
+impl From<Inner!["0.1.0"]> for Inner!["0.2.0"] {
+    fn from(from: Inner!["0.1.0"]) -> Self {
+        Self { b: from.a.into() }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(version("0.4.0"))]
+#[obake(trivial_migrations)]
+#[derive(Debug, PartialEq)]
+struct Outer {
+    #[obake(inherit(outer = "0.1.0", inner = "0.1.0"))]
+    #[obake(inherit(outer = "0.4.0", inner = "0.2.0"))]
+    inner: Inner,
+}
+
+impl From<Outer!["0.3.0"]> for Outer!["0.4.0"] {
+    fn from(from: Outer!["0.3.0"]) -> Self {
+        Self {
+            inner: from.inner.into(),
+        }
+    }
+}
+
+#[test]
+fn inner_versions_follow_mappings() {
+    let _: Inner!["0.1.0"] = Outer_v0_2_0 {
+        inner: Inner_v0_1_0 { a: 1 },
+    }
+    .inner;
+    let _: Inner!["0.1.0"] = Outer_v0_3_0 {
+        inner: Inner_v0_1_0 { a: 1 },
+    }
+    .inner;
+
+    let outer: Outer = VersionedOuter::Outer_v0_1_0(Outer_v0_1_0 {
+        inner: Inner_v0_1_0 { a: 7 },
+    })
+    .into();
+    assert_eq!(
+        outer,
+        Outer {
+            inner: Inner { b: 7 }
+        }
+    );
+}
@@ -0,0 +1,83 @@
+#![allow(dead_code)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[derive(Debug, PartialEq)]
+struct Point {
+    #[obake(cfg("0.1.0"))]
+    x: u8,
+    #[obake(cfg(">=0.2"))]
+    x: u32,
+    #[obake(cfg(">=0.3"))]
+    y: u32,
+}
+
+impl From<Point!["0.1.0"]> for Point!["0.2.0"] {
+    fn from(from: Point!["0.1.0"]) -> Self {
+        Self { x: from.x.into() }
+    }
+}
+
+impl From<Point!["0.2.0"]> for Point!["0.3.0"] {
+    fn from(from: Point!["0.2.0"]) -> Self {
+        Self { x: from.x, y: 0 }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(trivial_migrations)]
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Dot(#[obake(inherit)] Point),
+    Line {
+        #[obake(inherit)]
+        from: Point,
+        #[obake(inherit)]
+        to: Point,
+    },
+    Labelled(String, #[obake(inherit)] Point),
+    Empty,
+}
+
+#[test]
+fn variant_fields_follow_outer_version() {
+    let _: Shape!["0.1.0"] = Shape_v0_1_0::Dot(Point_v0_1_0 { x: 1 });
+    let _: Shape!["0.2.0"] = Shape_v0_2_0::Line {
+        from: Point_v0_2_0 { x: 1 },
+        to: Point_v0_2_0 { x: 2 },
+    };
+}
+
+#[test]
+fn migrations_recurse_into_inner_type() {
+    let shape: Shape = VersionedShape::Shape_v0_1_0(Shape_v0_1_0::Line {
+        from: Point_v0_1_0 { x: 1 },
+        to: Point_v0_1_0 { x: 2 },
+    })
+    .into();
+    assert_eq!(
+        shape,
+        Shape::Line {
+            from: Point { x: 1, y: 0 },
+            to: Point { x: 2, y: 0 },
+        }
+    );
+
+    let shape: Shape = VersionedShape::Shape_v0_2_0(Shape_v0_2_0::Labelled(
+        "origin".to_owned(),
+        Point_v0_2_0 { x: 0 },
+    ))
+    .into();
+    assert_eq!(
+        shape,
+        Shape::Labelled("origin".to_owned(), Point { x: 0, y: 0 })
+    );
+
+    let shape: Shape = VersionedShape::Shape_v0_1_0(Shape_v0_1_0::Empty).into();
+    assert_eq!(shape, Shape::Empty);
+}
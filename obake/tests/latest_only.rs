@@ -0,0 +1,33 @@
+#![allow(dead_code)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(latest_only(derive(Debug, Clone, PartialEq)))]
+#[derive(Default)]
+struct Foo {
+    field_0: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+#[test]
+fn latest_version_has_latest_only_attrs() {
+    let foo: Foo = VersionedFoo::Foo_v0_1_0(Default::default()).into();
+    assert_eq!(foo.clone(), Foo { field_0: 0 });
+}
@@ -0,0 +1,37 @@
+use core::mem::{align_of, offset_of, size_of};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(assert_layout("0.1.0", size = 8, align = 4))]
+#[obake(assert_layout("0.2.0", size = 24, align = 8))]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct PluginInfo {
+    api: u32,
+    #[obake(cfg(">=0.2"))]
+    capabilities: u64,
+    flags: u16,
+}
+
+impl From<PluginInfo!["0.1.0"]> for PluginInfo!["0.2.0"] {
+    fn from(from: PluginInfo!["0.1.0"]) -> Self {
+        Self {
+            api: from.api,
+            capabilities: 0,
+            flags: from.flags,
+        }
+    }
+}
+
+#[test]
+fn layout_is_preserved() {
+    // Fields keep their declared order, with `#[repr(C)]` padding
+    assert_eq!(offset_of!(PluginInfo_v0_1_0, api), 0);
+    assert_eq!(offset_of!(PluginInfo_v0_1_0, flags), 4);
+    assert_eq!(offset_of!(PluginInfo_v0_2_0, api), 0);
+    assert_eq!(offset_of!(PluginInfo_v0_2_0, capabilities), 8);
+    assert_eq!(offset_of!(PluginInfo_v0_2_0, flags), 16);
+    assert_eq!(size_of::<PluginInfo>(), 24);
+    assert_eq!(align_of::<PluginInfo_v0_1_0>(), 4);
+}
@@ -0,0 +1,60 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(trivial_migrations)]
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Circle(u32),
+    #[obake(cfg(">=0.2"))]
+    Square(u32),
+}
+
+impl From<Shape!["0.1.0"]> for Shape!["0.2.0"] {
+    fn from(from: Shape!["0.1.0"]) -> Self {
+        match from {
+            Shape_v0_1_0::Circle(radius) => Self::Circle(radius),
+        }
+    }
+}
+
+fn sides(shape: VersionedShape) -> Vec<u32> {
+    obake::match_versions!(Shape, shape, {
+        "0.1.0" => |Shape_v0_1_0::Circle(_)| vec![],
+        "0.2.0" => |shape| match shape {
+            Shape_v0_2_0::Circle(_) => vec![],
+            Shape_v0_2_0::Square(side) => vec![side; 4],
+        },
+        latest => |shape| match shape {
+            Shape::Circle(_) => vec![],
+            Shape::Square(side) => vec![side; 4],
+        },
+    })
+}
+
+#[test]
+fn match_versions() {
+    assert_eq!(
+        sides(VersionedShape::Shape_v0_1_0(Shape_v0_1_0::Circle(1))),
+        Vec::<u32>::new()
+    );
+    assert_eq!(
+        sides(VersionedShape::Shape_v0_2_0(Shape_v0_2_0::Square(2))),
+        [2; 4]
+    );
+    assert_eq!(sides(Shape::Square(3).into()), [3; 4]);
+}
+
+#[test]
+fn match_versions_with_default() {
+    let latest = |shape: &VersionedShape| {
+        obake::match_versions!(Shape, shape, {
+            latest => |_| true,
+            _ => false,
+        })
+    };
+    assert!(latest(&Shape::Circle(1).into()));
+    assert!(!latest(&VersionedShape::Shape_v0_2_0(
+        Shape_v0_2_0::Circle(1)
+    )));
+}
@@ -0,0 +1,12 @@
+use obake::Versioned;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(max_versions = 2, trivial_migrations)]
+struct Settings {}
+
+#[test]
+fn versions_within_the_limit_are_accepted() {
+    assert_eq!(Settings::VERSIONS, ["0.1.0", "0.2.0"]);
+}
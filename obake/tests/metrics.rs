@@ -0,0 +1,38 @@
+#![cfg(feature = "metrics")]
+#![allow(dead_code)]
+
+use std::sync::Mutex;
+
+static MIGRATIONS: Mutex<Vec<(&str, &str, &str)>> = Mutex::new(Vec::new());
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(trivial_migrations)]
+struct Settings {
+    volume: u8,
+}
+
+#[test]
+fn hook_is_called_for_every_migration() {
+    obake::set_metrics_hook(|type_name, from, to| {
+        MIGRATIONS.lock().unwrap().push((type_name, from, to));
+    });
+
+    let _: Settings = VersionedSettings::Settings_v0_1_0(Settings_v0_1_0 { volume: 1 }).into();
+    let _: Settings = VersionedSettings::Settings_v0_2_0(Settings_v0_2_0 { volume: 1 }).into();
+    let _: Settings = VersionedSettings::from(Settings { volume: 1 }).into();
+    assert_eq!(
+        *MIGRATIONS.lock().unwrap(),
+        [
+            ("Settings", "0.1.0", "0.2.0"),
+            ("Settings", "0.2.0", "0.3.0"),
+            ("Settings", "0.2.0", "0.3.0"),
+        ]
+    );
+
+    obake::clear_metrics_hook();
+    let _: Settings = VersionedSettings::Settings_v0_1_0(Settings_v0_1_0 { volume: 1 }).into();
+    assert_eq!(MIGRATIONS.lock().unwrap().len(), 3);
+}
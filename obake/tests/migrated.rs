@@ -0,0 +1,54 @@
+use obake::{Migrated, MigrationTrace};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(trivial_migrations)]
+#[cfg_attr(
+    feature = "serde",
+    obake(derive(serde::Deserialize), serde(tag = "version"))
+)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[derive(Debug, PartialEq)]
+struct Record {
+    id: u64,
+}
+
+#[test]
+fn traces_every_hop() {
+    let record =
+        Migrated::<Record>::upgrade(VersionedRecord::Record_v0_1_0(Record_v0_1_0 { id: 7 }));
+    assert_eq!(record.value, Record { id: 7 });
+    assert_eq!(record.trace.type_name(), "Record");
+    assert_eq!(record.trace.from(), "0.1.0");
+    assert_eq!(record.trace.to(), "0.3.0");
+    assert!(record.trace.was_migrated());
+    assert_eq!(
+        record.trace.hops().collect::<Vec<_>>(),
+        [("0.1.0", "0.2.0"), ("0.2.0", "0.3.0")]
+    );
+    assert_eq!(record.trace.to_string(), "Record 0.1.0 -> 0.2.0 -> 0.3.0");
+}
+
+#[test]
+fn latest_values_are_not_migrated() {
+    let record = Migrated::<Record>::upgrade(Record { id: 1 }.into());
+    assert_eq!(record.trace, MigrationTrace::new::<Record>("0.3.0"));
+    assert!(!record.trace.was_migrated());
+    assert_eq!(record.trace.path(), ["0.3.0"]);
+    assert_eq!(record.trace.hops().count(), 0);
+    assert_eq!(record.into_inner(), Record { id: 1 });
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn deserializes_with_trace() {
+    let record: Migrated<Record> =
+        serde_json::from_str(r#"{ "version": "0.2.0", "id": 3 }"#).unwrap();
+    assert_eq!(record.id, 3);
+    assert_eq!(
+        serde_json::to_string(&record.trace).unwrap(),
+        r#"{"type_name":"Record","path":["0.2.0","0.3.0"]}"#
+    );
+}
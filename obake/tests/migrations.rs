@@ -1,4 +1,4 @@
-#![allow(unused)]
+#![allow(unused, clippy::disallowed_names)]
 
 #[obake::versioned]
 #[obake(version("0.1.0"))]
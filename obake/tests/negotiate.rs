@@ -0,0 +1,61 @@
+#![cfg(feature = "negotiate")]
+
+use obake::negotiate::{Negotiated, NoCommonVersion};
+use obake::semver::VersionReq;
+use serde::Serialize;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("1.0.0"))]
+#[obake(trivial_migrations, downgrade)]
+#[obake(derive(Serialize), serde(tag = "version"))]
+#[derive(Clone, Serialize)]
+struct Hello {
+    client: String,
+}
+
+#[test]
+fn negotiate_with_versions() {
+    let negotiated = Negotiated::<Hello>::with_versions(&["0.1.0", "0.2.0", "2.0.0"]).unwrap();
+    assert_eq!(negotiated.version(), "0.2.0");
+    assert!(!negotiated.is_latest());
+
+    let negotiated = Negotiated::<Hello>::with_versions(&["1.0.0", "not a version"]).unwrap();
+    assert!(negotiated.is_latest());
+
+    assert_eq!(
+        Negotiated::<Hello>::with_versions(&["0.3.0"]),
+        Err(NoCommonVersion { name: "Hello" })
+    );
+}
+
+#[test]
+fn negotiate_with_req() {
+    let req = VersionReq::parse("<1").unwrap();
+    assert_eq!(
+        Negotiated::<Hello>::with_req(&req).unwrap().version(),
+        "0.2.0"
+    );
+
+    let req = VersionReq::parse(">=2").unwrap();
+    assert!(Negotiated::<Hello>::with_req(&req).is_err());
+}
+
+#[test]
+fn serialize_negotiated() {
+    let hello = Hello {
+        client: "ferris".to_owned(),
+    };
+    let negotiated = Negotiated::<Hello>::with_versions(&["0.1.0"]).unwrap();
+    assert!(matches!(
+        negotiated.downgrade(hello.clone()),
+        VersionedHello::Hello_v0_1_0(_)
+    ));
+    assert_eq!(
+        negotiated
+            .serialize(&hello, serde_json::value::Serializer)
+            .unwrap(),
+        serde_json::json!({ "version": "0.1.0", "client": "ferris" })
+    );
+}
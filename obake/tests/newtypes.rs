@@ -0,0 +1,52 @@
+use core::mem::{align_of, size_of};
+
+fn widen(id: u32) -> u64 {
+    u64::from(id) | 1 << 32
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(trivial_migrations)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(transparent)]
+struct UserId(
+    #[obake(cfg("<0.3"))] u32,
+    #[obake(cfg(">=0.3"), map_from(widen))] pub u64,
+);
+
+#[test]
+fn newtypes() {
+    let old = VersionedUserId::UserId_v0_1_0(UserId_v0_1_0(7));
+    let latest: UserId = old.into();
+    assert_eq!(latest.0, 0x1_0000_0007);
+}
+
+#[test]
+fn repr_transparent() {
+    assert_eq!(size_of::<UserId_v0_1_0>(), size_of::<u32>());
+    assert_eq!(align_of::<UserId_v0_2_0>(), align_of::<u32>());
+    assert_eq!(size_of::<UserId>(), size_of::<u64>());
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq)]
+struct Name(
+    #[obake(cfg("0.1.0"))] &'static str,
+    #[obake(cfg("0.2.0"))] String,
+);
+
+impl From<Name!["0.1.0"]> for Name!["0.2.0"] {
+    fn from(from: Name!["0.1.0"]) -> Self {
+        Self(from.0.to_owned())
+    }
+}
+
+#[test]
+fn handwritten_migrations() {
+    let name: Name = VersionedName::Name_v0_1_0(Name_v0_1_0("ferris")).into();
+    assert_eq!(name.0, "ferris");
+}
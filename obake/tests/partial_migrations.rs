@@ -0,0 +1,57 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(partial_migrations)]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    items: Vec<String>,
+    index: HashMap<String, usize>,
+    #[obake(cfg("0.1.0"))]
+    name: String,
+    #[obake(cfg(">=0.2"))]
+    names: Vec<String>,
+    #[obake(cfg("<0.3"))]
+    count: u32,
+    #[obake(cfg(">=0.3"))]
+    count: u64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        from.migrate_with(|removed| Foo_v0_2_0_Added {
+            names: vec![removed.name],
+        })
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        from.migrate_with(|removed| Foo_v0_3_0_Added {
+            count: removed.count.into(),
+        })
+    }
+}
+
+#[test]
+fn unchanged_fields_are_moved() {
+    type OldFoo = Foo!["0.1.0"];
+    let items = vec!["a".to_owned(), "b".to_owned()];
+    let items_ptr = items.as_ptr();
+
+    let foo: Foo = VersionedFoo::Foo_v0_1_0(OldFoo {
+        items,
+        index: HashMap::new(),
+        name: "foo".to_owned(),
+        count: 2,
+    })
+    .into();
+
+    assert_eq!(foo.items.as_ptr(), items_ptr);
+    assert_eq!(foo.names, ["foo"]);
+    assert_eq!(foo.count, 2);
+}
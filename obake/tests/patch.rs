@@ -0,0 +1,91 @@
+#[derive(Debug, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(patch(
+    "0.1.0",
+    rename(login -> name),
+    move(host -> server.host),
+    move(port -> server.port),
+    default(tags),
+    default(limit = Some(10)),
+    delete(legacy_id),
+))]
+#[obake(patch("0.2.0", file = "tests/patches/settings-0.2.0.patch"))]
+#[derive(Debug, PartialEq)]
+struct Settings {
+    #[obake(cfg("0.1.0"))]
+    login: String,
+    #[obake(cfg(">=0.2"))]
+    name: String,
+    #[obake(cfg("0.1.0"))]
+    host: String,
+    #[obake(cfg("0.1.0"))]
+    port: u16,
+    #[obake(cfg("0.1.0"))]
+    legacy_id: u32,
+    #[obake(cfg(">=0.2"))]
+    server: Server,
+    #[obake(cfg(">=0.2"))]
+    tags: Vec<String>,
+    #[obake(cfg(">=0.2"))]
+    limit: Option<u32>,
+    #[obake(cfg("<0.3"))]
+    retries: u8,
+    #[obake(cfg(">=0.3"))]
+    max_retries: u8,
+    #[obake(cfg(">=0.3"))]
+    timeout_ms: u64,
+}
+
+#[test]
+fn patches_build_the_next_version() {
+    let upgraded: Settings = VersionedSettings::Settings_v0_1_0(Settings_v0_1_0 {
+        login: "ferris".to_owned(),
+        host: "localhost".to_owned(),
+        port: 8080,
+        legacy_id: 7,
+        retries: 3,
+    })
+    .into();
+    assert_eq!(
+        upgraded,
+        Settings {
+            name: "ferris".to_owned(),
+            server: Server {
+                host: "localhost".to_owned(),
+                port: 8080,
+            },
+            tags: Vec::new(),
+            limit: Some(10),
+            max_retries: 3,
+            timeout_ms: 30_000,
+        }
+    );
+}
+
+#[test]
+fn patches_keep_the_fields_they_dont_mention() {
+    let server = Server {
+        host: "example.com".to_owned(),
+        port: 443,
+    };
+    let upgraded: Settings_v0_3_0 = Settings_v0_2_0 {
+        name: "ferris".to_owned(),
+        server,
+        tags: vec!["prod".to_owned()],
+        limit: None,
+        retries: 1,
+    }
+    .into();
+    assert_eq!(upgraded.server.port, 443);
+    assert_eq!(upgraded.tags, ["prod"]);
+    assert_eq!(upgraded.limit, None);
+    assert_eq!(upgraded.max_retries, 1);
+}
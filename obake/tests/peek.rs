@@ -0,0 +1,109 @@
+#![cfg(feature = "std")]
+#![allow(dead_code)]
+
+use obake::{Error, PeekFormat};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[cfg_attr(feature = "serde", obake(derive(serde::Serialize, serde::Deserialize)))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Foo {
+    field: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self { field: from.field }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.2.0"))]
+#[obake(version("0.1.0"))]
+#[cfg_attr(feature = "serde", obake(derive(serde::Serialize, serde::Deserialize)))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct OutOfOrder {
+    field: u32,
+}
+
+impl From<OutOfOrder!["0.1.0"]> for OutOfOrder!["0.2.0"] {
+    fn from(from: OutOfOrder!["0.1.0"]) -> Self {
+        Self { field: from.field }
+    }
+}
+
+#[test]
+fn bincode_versions_are_read_from_the_variant_index() {
+    let version = obake::peek_version::<Foo>([0, 0, 0, 0, 1, 0, 0, 0], PeekFormat::Bincode);
+    assert_eq!(version.unwrap(), "0.1.0");
+
+    let unknown = obake::peek_version::<Foo>([2, 0, 0, 0], PeekFormat::Bincode);
+    assert!(matches!(unknown, Err(Error::UnknownVersion(err)) if err.found == "#2"));
+
+    let short = obake::peek_version::<Foo>([0, 0], PeekFormat::Bincode);
+    assert!(matches!(short, Err(Error::Parse { .. })));
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn bincode_versions_match_serialized_payloads() {
+    let bytes = bincode::serialize(&VersionedFoo::Foo_v0_2_0(Foo_v0_2_0 { field: 3 })).unwrap();
+    let version = obake::peek_version::<Foo>(&bytes, PeekFormat::Bincode);
+    assert_eq!(version.unwrap(), "0.2.0");
+}
+
+#[test]
+fn bincode_variant_indices_follow_the_declaration_order() {
+    let version = obake::peek_version::<OutOfOrder>([0, 0, 0, 0], PeekFormat::Bincode);
+    assert_eq!(version.unwrap(), "0.2.0");
+
+    let version = obake::peek_version::<OutOfOrder>([1, 0, 0, 0], PeekFormat::Bincode);
+    assert_eq!(version.unwrap(), "0.1.0");
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn out_of_order_bincode_versions_match_serialized_payloads() {
+    let bytes = bincode::serialize(&VersionedOutOfOrder::OutOfOrder_v0_2_0(OutOfOrder {
+        field: 3,
+    }))
+    .unwrap();
+    let version = obake::peek_version::<OutOfOrder>(&bytes, PeekFormat::Bincode);
+    assert_eq!(version.unwrap(), "0.2.0");
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_tags_are_found_among_other_fields() {
+    let json = r#"{ "nested": { "version": "9.9.9" }, "version": "0.1.0", "field": 1 }"#;
+    let version = obake::peek_version::<Foo>(json, PeekFormat::JsonTag("version"));
+    assert_eq!(version.unwrap(), "0.1.0");
+
+    let untagged = obake::peek_version::<Foo>(r#"{ "field": 1 }"#, PeekFormat::JsonTag("version"));
+    assert!(matches!(untagged, Err(Error::Parse { .. })));
+
+    let unknown = obake::peek_version::<Foo>(r#"{ "v": "0.3.0" }"#, PeekFormat::JsonTag("v"));
+    assert!(matches!(unknown, Err(Error::UnknownVersion(err)) if err.found == "0.3.0"));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn externally_tagged_json_versions_are_the_key() {
+    let json = serde_json::to_string(&VersionedFoo::Foo_v0_1_0(Foo_v0_1_0 { field: 1 })).unwrap();
+    let version = obake::peek_version::<Foo>(json.as_bytes(), PeekFormat::Json);
+    assert_eq!(version.unwrap(), "0.1.0");
+}
+
+#[cfg(feature = "container")]
+#[test]
+fn container_versions_are_read_from_the_header() {
+    let mut file = Vec::new();
+    obake::container::save(&Foo { field: 1 }, &mut file).unwrap();
+    // The payload isn't read, so its checksum isn't checked either
+    file.truncate(file.len() - 1);
+
+    let version = obake::container::Container::default();
+    let version = obake::peek_version::<Foo>(&file, PeekFormat::Container(version));
+    assert_eq!(version.unwrap(), "0.2.0");
+}
@@ -0,0 +1,83 @@
+// Fields are gated on `unix`/`not(unix)` rather than on Windows, so that each side gets
+// compiled on the platforms CI runs on
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(partial_migrations)]
+#[derive(Debug, Default, PartialEq)]
+struct Settings {
+    name: String,
+    #[cfg(unix)]
+    #[obake(cfg(">=0.3"))]
+    xdg_dir: String,
+    #[cfg(not(unix))]
+    #[obake(cfg(">=0.3"))]
+    registry_key: String,
+}
+
+// `0.1.0` and `0.2.0` have identical fields on every platform
+impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+    fn from(from: Settings!["0.1.0"]) -> Self {
+        Self { name: from.name }
+    }
+}
+
+impl From<Settings!["0.2.0"]> for Settings!["0.3.0"] {
+    fn from(from: Settings!["0.2.0"]) -> Self {
+        from.migrate_with(|_| Settings_v0_3_0_Added {
+            #[cfg(unix)]
+            xdg_dir: "~/.config".to_owned(),
+            #[cfg(not(unix))]
+            registry_key: "HKCU".to_owned(),
+        })
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(trivial_migrations, downgrade)]
+#[derive(Debug, PartialEq)]
+struct Paths {
+    #[cfg(unix)]
+    home: String,
+    #[cfg(not(unix))]
+    profile: String,
+}
+
+#[test]
+fn platform_fields_are_migrated() {
+    let settings: Settings = VersionedSettings::Settings_v0_1_0(Settings_v0_1_0 {
+        name: "obake".to_owned(),
+    })
+    .into();
+
+    #[cfg(unix)]
+    assert_eq!(settings.xdg_dir, "~/.config");
+    #[cfg(not(unix))]
+    assert_eq!(settings.registry_key, "HKCU");
+    assert_eq!(settings.name, "obake");
+}
+
+#[test]
+fn trivial_migrations_move_platform_fields() {
+    let paths: Paths = VersionedPaths::Paths_v0_1_0(Paths_v0_1_0 {
+        #[cfg(unix)]
+        home: "/home/obake".to_owned(),
+        #[cfg(not(unix))]
+        profile: "C:\\Users\\obake".to_owned(),
+    })
+    .into();
+
+    #[cfg(unix)]
+    assert_eq!(paths.home, "/home/obake");
+    #[cfg(not(unix))]
+    assert_eq!(paths.profile, "C:\\Users\\obake");
+
+    use obake::Downgrade;
+    assert!(matches!(
+        paths.downgrade("0.1.0"),
+        Some(VersionedPaths::Paths_v0_1_0(_))
+    ));
+}
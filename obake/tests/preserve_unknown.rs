@@ -0,0 +1,102 @@
+#![cfg(feature = "json")]
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(preserve_unknown)]
+#[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Document {
+    title: String,
+    #[obake(cfg(">=0.2"))]
+    pages: u32,
+    #[obake(cfg(">=0.3"))]
+    author: Option<String>,
+}
+
+impl From<Document!["0.1.0"]> for Document!["0.2.0"] {
+    fn from(from: Document!["0.1.0"]) -> Self {
+        Self {
+            title: from.title,
+            pages: 1,
+            unknown_fields: from.unknown_fields,
+        }
+    }
+}
+
+impl From<Document!["0.2.0"]> for Document!["0.3.0"] {
+    fn from(from: Document!["0.2.0"]) -> Self {
+        Self {
+            title: from.title,
+            pages: from.pages,
+            author: None,
+            unknown_fields: obake::UnknownFields::default(),
+        }
+    }
+}
+
+// Derives are recognised by the last segment of their path
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(preserve_unknown)]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Note {
+    text: String,
+}
+
+#[test]
+fn unknown_fields_are_captured() {
+    let json = json!({
+        "version": "0.2.0",
+        "title": "Obake",
+        "pages": 3,
+        "author": "ferris",
+        "isbn": "978-0",
+    });
+    let versioned: VersionedDocument = serde_json::from_value(json.clone()).unwrap();
+    let VersionedDocument::Document_v0_2_0(document) = &versioned else {
+        panic!("expected version 0.2.0");
+    };
+    assert_eq!(document.unknown_fields.len(), 2);
+    assert_eq!(serde_json::to_value(&versioned).unwrap(), json);
+}
+
+#[test]
+fn unknown_fields_survive_upgrades() {
+    let json = json!({
+        "version": "0.1.0",
+        "title": "Obake",
+        "isbn": "978-0",
+        "tags": ["folklore"],
+    });
+    let versioned: VersionedDocument = serde_json::from_value(json).unwrap();
+    let latest: Document = versioned.into();
+    assert_eq!(latest.pages, 1);
+    assert_eq!(latest.unknown_fields["isbn"], "978-0");
+    assert_eq!(latest.unknown_fields["tags"], json!(["folklore"]));
+
+    let upgraded = serde_json::to_value(VersionedDocument::from(latest)).unwrap();
+    assert_eq!(
+        upgraded,
+        json!({
+            "version": "0.3.0",
+            "title": "Obake",
+            "pages": 1,
+            "author": null,
+            "isbn": "978-0",
+            "tags": ["folklore"],
+        })
+    );
+}
+
+#[test]
+fn qualified_derives_preserve_unknown_fields() {
+    let json = json!({ "text": "hi", "pinned": true });
+    let note: Note = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(note.unknown_fields.len(), 1);
+    assert_eq!(serde_json::to_value(&note).unwrap(), json);
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "proptest")]
+#![allow(dead_code)]
+
+use proptest::prelude::*;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(proptest)]
+#[obake(downgrade)]
+#[obake(generate_tests)]
+#[obake(derive(Debug))]
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Foo {
+    #[obake(strategy(1..100_u32))]
+    id: u32,
+    #[obake(cfg("0.1.0"))]
+    name: String,
+    #[obake(cfg(">=0.2"))]
+    names: Vec<String>,
+    #[obake(inherit)]
+    bar: Bar,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(proptest)]
+#[obake(downgrade)]
+#[obake(derive(Debug))]
+#[derive(Debug, Clone, Default, PartialEq)]
+enum Bar {
+    X(u8, bool),
+    Y {
+        #[obake(strategy(Just(7)))]
+        field_0: u32,
+    },
+    #[default]
+    Z,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            id: from.id,
+            names: vec![from.name],
+            bar: from.bar.into(),
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.1.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            id: from.id,
+            name: from.names.into_iter().next().unwrap_or_default(),
+            bar: from.bar.into(),
+        }
+    }
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(from: Bar!["0.1.0"]) -> Self {
+        type Bar = Bar!["0.1.0"];
+        match from {
+            Bar::X(x, y) => Self::X(x, y),
+            Bar::Y { field_0 } => Self::Y { field_0 },
+            Bar::Z => Self::Z,
+        }
+    }
+}
+
+impl From<Bar!["0.2.0"]> for Bar!["0.1.0"] {
+    fn from(from: Bar!["0.2.0"]) -> Self {
+        type Bar = Bar!["0.2.0"];
+        match from {
+            Bar::X(x, y) => Self::X(x, y),
+            Bar::Y { field_0 } => Self::Y { field_0 },
+            Bar::Z => Self::Z,
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn upgrading_old_values_preserves_id(old in any::<Foo!["0.1.0"]>()) {
+        let id = old.id;
+        let new: Foo = obake::AnyVersion::<Foo>::Foo_v0_1_0(old).into();
+        prop_assert_eq!(new.id, id);
+        prop_assert_eq!(new.names.len(), 1);
+    }
+
+    #[test]
+    fn field_strategies_are_respected(versioned in any::<obake::AnyVersion<Foo>>()) {
+        let new: Foo = versioned.into();
+        prop_assert!((1..100).contains(&new.id));
+        if let Bar::Y { field_0 } = new.bar {
+            prop_assert_eq!(field_0, 7);
+        }
+    }
+}
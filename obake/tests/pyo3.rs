@@ -0,0 +1,89 @@
+#![cfg(feature = "pyo3")]
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(pyo3)]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Reading {
+    pub sensor: String,
+    #[obake(cfg("0.1.0"))]
+    pub celsius: f64,
+    #[obake(cfg(">=0.2"))]
+    pub kelvin: f64,
+    calibrated: bool,
+}
+
+impl From<Reading!["0.1.0"]> for Reading!["0.2.0"] {
+    fn from(from: Reading!["0.1.0"]) -> Self {
+        Self {
+            sensor: from.sensor,
+            kelvin: from.celsius + 273.0,
+            calibrated: from.calibrated,
+        }
+    }
+}
+
+#[test]
+fn upgrade_migrates_json() {
+    let json = obake::pyo3::upgrade::<Reading>(
+        "0.1.0",
+        r#"{ "sensor": "a", "celsius": 20.0, "calibrated": true }"#,
+    )
+    .unwrap();
+    assert_eq!(json, r#"{"sensor":"a","kelvin":293.0,"calibrated":true}"#);
+
+    let err = obake::pyo3::upgrade::<Reading>("0.3.0", "{}")
+        .err()
+        .unwrap();
+    assert!(matches!(err, obake::Error::UnknownVersion(_)));
+}
+
+#[test]
+fn module_exports_class_and_upgrade() {
+    Python::initialize();
+    Python::attach(|py| {
+        let module = PyModule::new(py, "readings").unwrap();
+        obake::pyo3::add_to_module::<Reading>(&module).unwrap();
+
+        let locals = PyDict::new(py);
+        locals.set_item("readings", &module).unwrap();
+        let run = |code: &str| py.run(&std::ffi::CString::new(code).unwrap(), None, Some(&locals));
+
+        run(concat!(
+            "upgraded = readings.upgrade('0.1.0', ",
+            "'{\"sensor\": \"a\", \"celsius\": 20.0, \"calibrated\": false}')\n",
+            "assert upgraded == '{\"sensor\":\"a\",\"kelvin\":293.0,\"calibrated\":false}'\n",
+            "assert readings.Reading.__name__ == 'Reading'\n",
+            "try:\n",
+            "    readings.upgrade('0.1.0', '{}')\n",
+            "    assert False\n",
+            "except ValueError as err:\n",
+            "    assert 'sensor' in str(err)\n",
+        ))
+        .unwrap();
+
+        // `pub` fields of the latest version are exposed as properties
+        let reading = Bound::new(
+            py,
+            Reading {
+                sensor: "b".to_owned(),
+                kelvin: 300.0,
+                calibrated: true,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            reading.getattr("kelvin").unwrap().extract::<f64>().unwrap(),
+            300.0
+        );
+        reading.setattr("sensor", "c").unwrap();
+        assert_eq!(reading.borrow().sensor, "c");
+        assert!(reading.getattr("calibrated").is_err());
+    });
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "rayon")]
+#![allow(dead_code)]
+
+use std::convert::TryFrom;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_1: from.field_0.into(),
+        }
+    }
+}
+
+type OldFoo = Foo!["0.1.0"];
+
+#[test]
+fn par_migrate_all_preserves_order() {
+    let records: Vec<_> = (0..10_000)
+        .map(|i| {
+            if i % 2 == 0 {
+                VersionedFoo::Foo_v0_1_0(OldFoo { field_0: i })
+            } else {
+                VersionedFoo::Foo_v0_2_0(Foo { field_1: i.into() })
+            }
+        })
+        .collect();
+
+    let migrated = obake::par_migrate_all::<Foo>(records);
+    assert!(migrated
+        .iter()
+        .enumerate()
+        .all(|(i, foo)| foo.field_1 == i as u64));
+}
+
+#[test]
+fn par_try_migrate_all_collects_errors_by_index() {
+    let decode = |field_0: i64| {
+        u32::try_from(field_0)
+            .map(|field_0| VersionedFoo::Foo_v0_1_0(OldFoo { field_0 }))
+            .map_err(|_| field_0)
+    };
+
+    let migrated = obake::par_try_migrate_all::<Foo, _, _, _>(vec![1, 2, 3], decode);
+    assert_eq!(
+        migrated,
+        Ok(vec![
+            Foo { field_1: 1 },
+            Foo { field_1: 2 },
+            Foo { field_1: 3 }
+        ])
+    );
+
+    let migrated = obake::par_try_migrate_all::<Foo, _, _, _>(vec![1, -2, 3, -4], decode);
+    assert_eq!(migrated, Err(vec![(1, -2), (3, -4)]));
+}
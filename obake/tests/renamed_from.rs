@@ -0,0 +1,103 @@
+#![allow(dead_code)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(renamed_from("Player", until = "0.3.0"))]
+#[derive(Debug, PartialEq)]
+struct Character {
+    name: String,
+    #[obake(cfg(">=0.2"))]
+    level: u32,
+}
+
+impl From<Player_v0_1_0> for Player_v0_2_0 {
+    fn from(from: Player_v0_1_0) -> Self {
+        Self {
+            name: from.name,
+            level: 1,
+        }
+    }
+}
+
+impl From<Character!["0.2.0"]> for Character!["0.3.0"] {
+    fn from(from: Character!["0.2.0"]) -> Self {
+        Self {
+            name: from.name,
+            level: from.level,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Party {
+    #[obake(inherit)]
+    leader: Character,
+}
+
+#[test]
+fn old_versions_keep_old_name() {
+    let old = Player_v0_1_0 {
+        name: "ferris".to_owned(),
+    };
+    let _: Character!["0.1.0"] = Party_v0_1_0 { leader: old }.leader;
+
+    let character: Character = VersionedCharacter::Player_v0_1_0(Player_v0_1_0 {
+        name: "ferris".to_owned(),
+    })
+    .into();
+    assert_eq!(
+        character,
+        Character_v0_3_0 {
+            name: "ferris".to_owned(),
+            level: 1
+        }
+    );
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(trivial_migrations, downgrade)]
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+enum Mode {
+    #[obake(variant_renamed_from("Legcy", until = "0.2.0"))]
+    Legacy,
+    #[obake(variant_renamed_from("Fst", until = "0.2.0"))]
+    #[obake(variant_renamed_from("Quick", until = "0.3.0"))]
+    Fast { level: u8 },
+}
+
+#[test]
+fn old_versions_keep_old_variant_names() {
+    let _ = (Mode_v0_1_0::Legcy, Mode_v0_2_0::Legacy, Mode::Legacy);
+    let _ = (
+        Mode_v0_1_0::Fst { level: 1 },
+        Mode_v0_2_0::Quick { level: 1 },
+    );
+
+    let mode: Mode = VersionedMode::Mode_v0_1_0(Mode_v0_1_0::Fst { level: 3 }).into();
+    assert_eq!(mode, Mode::Fast { level: 3 });
+    assert_eq!(Mode_v0_1_0::from(Mode_v0_2_0::Legacy), Mode_v0_1_0::Legcy);
+}
+
+#[test]
+fn renamed_variants_accept_old_names() {
+    assert_eq!(
+        serde_json::from_str::<Mode>(r#""Legcy""#).unwrap(),
+        Mode::Legacy
+    );
+    assert_eq!(
+        serde_json::from_str::<Mode>(r#"{ "Quick": { "level": 2 } }"#).unwrap(),
+        Mode::Fast { level: 2 }
+    );
+    assert_eq!(
+        serde_json::from_str::<Mode_v0_2_0>(r#"{ "Fst": { "level": 2 } }"#).unwrap(),
+        Mode_v0_2_0::Quick { level: 2 }
+    );
+    assert_eq!(serde_json::to_string(&Mode::Legacy).unwrap(), r#""Legacy""#);
+    assert!(serde_json::from_str::<Mode_v0_1_0>(r#""Legacy""#).is_err());
+}
@@ -0,0 +1,65 @@
+use core::num::NonZeroU64;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[derive(Debug, PartialEq)]
+struct Limits {
+    name: String,
+    #[obake(cfg("0.1.0"))]
+    max_connections: u16,
+    #[obake(cfg(">=0.2"))]
+    #[obake(retyped)]
+    max_connections: u32,
+    #[obake(cfg("<0.3"))]
+    timeout_ms: u64,
+    #[obake(cfg(">=0.3"))]
+    #[obake(retyped(try_from, or = NonZeroU64::MIN))]
+    timeout_ms: NonZeroU64,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[derive(Debug, PartialEq)]
+struct Port(
+    #[obake(cfg("0.1.0"))] u8,
+    #[obake(cfg(">=0.2"))]
+    #[obake(retyped)]
+    u16,
+);
+
+#[test]
+fn retyped_fields_are_converted() {
+    let limits: Limits = VersionedLimits::Limits_v0_1_0(Limits_v0_1_0 {
+        name: "default".to_owned(),
+        max_connections: 16,
+        timeout_ms: 500,
+    })
+    .into();
+    assert_eq!(
+        limits,
+        Limits {
+            name: "default".to_owned(),
+            max_connections: 16,
+            timeout_ms: NonZeroU64::new(500).unwrap(),
+        }
+    );
+}
+
+#[test]
+fn failed_conversions_fall_back() {
+    let limits: Limits = Limits_v0_2_0 {
+        name: "unlimited".to_owned(),
+        max_connections: 0,
+        timeout_ms: 0,
+    }
+    .into();
+    assert_eq!(limits.timeout_ms, NonZeroU64::MIN);
+}
+
+#[test]
+fn retyped_tuple_structs() {
+    assert_eq!(Port::from(Port_v0_1_0(80)), Port_v0_2_0(80));
+}
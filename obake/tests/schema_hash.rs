@@ -0,0 +1,93 @@
+#![allow(dead_code)]
+
+use obake::SchemaHash;
+
+mod before {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(version("0.2.0"))]
+    #[obake(schema_hash)]
+    pub struct Foo {
+        pub field_0: u32,
+        #[obake(cfg(">=0.2"))]
+        pub field_1: Vec<String>,
+    }
+
+    impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+        fn from(from: Foo!["0.1.0"]) -> Self {
+            Self {
+                field_0: from.field_0,
+                field_1: vec![],
+            }
+        }
+    }
+}
+
+mod after {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(version("0.2.0"))]
+    #[obake(schema_hash)]
+    pub struct Foo {
+        pub field_0: u32,
+        #[obake(cfg(">=0.2"))]
+        pub field_1: Vec<u8>,
+    }
+
+    impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+        fn from(from: Foo!["0.1.0"]) -> Self {
+            Self {
+                field_0: from.field_0,
+                field_1: vec![],
+            }
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(schema_hash)]
+enum Bar {
+    X(u32),
+    Y { field_0: u32 },
+    Z,
+}
+
+#[test]
+fn unchanged_versions_have_equal_hashes() {
+    assert_eq!(
+        before::Foo_v0_1_0::SCHEMA_HASH,
+        after::Foo_v0_1_0::SCHEMA_HASH
+    );
+}
+
+#[test]
+fn changed_versions_have_different_hashes() {
+    assert_ne!(
+        before::Foo_v0_1_0::SCHEMA_HASH,
+        before::Foo_v0_2_0::SCHEMA_HASH
+    );
+    assert_ne!(
+        before::Foo_v0_2_0::SCHEMA_HASH,
+        after::Foo_v0_2_0::SCHEMA_HASH
+    );
+}
+
+#[test]
+fn hashes_are_stable() {
+    // FNV-1a of `enum{X(u32);Y{field_0:u32;};Z;}`
+    assert_eq!(Bar::schema_hash("0.1.0"), Some(Bar::SCHEMA_HASH));
+    assert_eq!(Bar::SCHEMA_HASH, 8_689_593_446_457_015_625);
+}
+
+#[test]
+fn hashes_are_listed_for_every_version() {
+    assert_eq!(
+        before::Foo::SCHEMA_HASHES,
+        [
+            ("0.1.0", before::Foo_v0_1_0::SCHEMA_HASH),
+            ("0.2.0", before::Foo_v0_2_0::SCHEMA_HASH),
+        ]
+    );
+    assert!(before::Foo::schema_hash("0.3.0").is_none());
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "schema_registry")]
+
+use obake::schema_registry::{self, Error, SchemaIds, SchemaRegistry, SubjectNameStrategy};
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Deserialize))]
+#[obake(serde())]
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct Order {
+    id: u32,
+    #[obake(cfg(">=0.2"))]
+    note: String,
+}
+
+impl From<Order!["0.1.0"]> for Order!["0.2.0"] {
+    fn from(from: Order!["0.1.0"]) -> Self {
+        Self {
+            id: from.id,
+            note: String::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct MockRegistry {
+    schemas: Vec<(String, String)>,
+}
+
+impl SchemaRegistry for MockRegistry {
+    type Error = ();
+
+    fn register(&mut self, subject: &str, version: &str) -> Result<u32, ()> {
+        self.schemas.push((subject.to_owned(), version.to_owned()));
+        Ok(100 + self.schemas.len() as u32)
+    }
+}
+
+#[test]
+fn wire_format_round_trip() {
+    let bytes = schema_registry::encode(0x0102_0304, b"{}");
+    assert_eq!(bytes, [0, 1, 2, 3, 4, b'{', b'}']);
+    assert_eq!(
+        schema_registry::decode(&bytes).unwrap(),
+        (0x0102_0304, &b"{}"[..])
+    );
+    assert!(matches!(
+        schema_registry::decode(&[1, 0, 0, 0, 0]),
+        Err(Error::InvalidMagicByte(1))
+    ));
+    assert!(matches!(
+        schema_registry::decode(&[0, 0]),
+        Err(Error::Truncated)
+    ));
+}
+
+#[test]
+fn subject_names() {
+    assert_eq!(
+        SubjectNameStrategy::TopicName.subject::<Order>("orders", false),
+        "orders-value"
+    );
+    assert_eq!(
+        SubjectNameStrategy::TopicName.subject::<Order>("orders", true),
+        "orders-key"
+    );
+    assert_eq!(
+        SubjectNameStrategy::RecordName.subject::<Order>("orders", false),
+        "schema_registry.Order"
+    );
+}
+
+#[test]
+fn registers_and_decodes_every_version() {
+    let mut registry = MockRegistry::default();
+    let ids = SchemaIds::<Order>::register(&mut registry, "orders-value").unwrap();
+    assert_eq!(registry.schemas.len(), 2);
+    assert_eq!(ids.schema_id("0.1.0"), Some(101));
+    assert_eq!(ids.schema_id("0.2.0"), Some(102));
+
+    let old = schema_registry::encode(101, br#"{"id":7}"#);
+    assert_eq!(
+        ids.decode(&old).unwrap(),
+        Order {
+            id: 7,
+            note: String::new()
+        }
+    );
+
+    let latest = Order {
+        id: 8,
+        note: "x".to_owned(),
+    };
+    let new = ids.encode(&latest).unwrap();
+    assert_eq!(&new[..5], [0, 0, 0, 0, 102]);
+    assert_eq!(ids.decode(&new).unwrap(), latest);
+
+    assert!(matches!(
+        ids.decode(&schema_registry::encode(7, b"{}")),
+        Err(Error::UnknownSchemaId(7))
+    ));
+}
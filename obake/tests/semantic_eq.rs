@@ -0,0 +1,77 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(semantic_eq)]
+#[derive(Clone, Debug, PartialEq)]
+struct Settings {
+    volume: u8,
+    #[obake(cfg(">=0.2"))]
+    muted: bool,
+    #[obake(cfg(">=0.3"))]
+    theme: String,
+}
+
+impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+    fn from(from: Settings!["0.1.0"]) -> Self {
+        Self {
+            volume: from.volume,
+            muted: from.volume == 0,
+        }
+    }
+}
+
+impl From<Settings!["0.2.0"]> for Settings!["0.3.0"] {
+    fn from(from: Settings!["0.2.0"]) -> Self {
+        Self {
+            volume: from.volume,
+            muted: from.muted,
+            theme: "light".to_owned(),
+        }
+    }
+}
+
+#[test]
+fn semantically_eq() {
+    let old = VersionedSettings::Settings_v0_1_0(Settings_v0_1_0 { volume: 0 });
+    let new = VersionedSettings::Settings_v0_3_0(Settings {
+        volume: 0,
+        muted: true,
+        theme: "light".to_owned(),
+    });
+    assert!(VersionedSettings::semantically_eq(&old, &new));
+    assert!(VersionedSettings::semantically_eq(&new, &old));
+
+    let middle = VersionedSettings::Settings_v0_2_0(Settings_v0_2_0 {
+        volume: 0,
+        muted: false,
+    });
+    assert!(!VersionedSettings::semantically_eq(&old, &middle));
+    assert!(VersionedSettings::semantically_eq(&middle, &middle));
+}
+
+#[test]
+fn partial_eq_older_versions() {
+    let latest = Settings {
+        volume: 4,
+        muted: false,
+        theme: "light".to_owned(),
+    };
+    let old = Settings_v0_1_0 { volume: 4 };
+    assert_eq!(latest, old);
+    assert_eq!(old, latest);
+    assert_ne!(Settings_v0_1_0 { volume: 0 }, latest);
+    assert_ne!(
+        latest,
+        Settings_v0_2_0 {
+            volume: 4,
+            muted: true,
+        }
+    );
+
+    let dark = Settings {
+        theme: "dark".to_owned(),
+        ..latest
+    };
+    assert_ne!(dark, old);
+}
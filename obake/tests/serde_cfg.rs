@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(trivial_migrations)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Player {
+    #[obake(serde_cfg(">=0.3.0", rename = "playerName"))]
+    name: String,
+    #[obake(serde_cfg("<0.3.0", skip_serializing_if = "Option::is_none"))]
+    team: Option<String>,
+    #[obake(serde_cfg(">=0.2.0", default))]
+    score: u32,
+}
+
+#[test]
+fn renamed_in_later_versions() {
+    let old = Player_v0_2_0 {
+        name: "obake".to_owned(),
+        team: None,
+        score: 0,
+    };
+    assert_eq!(
+        serde_json::to_value(&old).unwrap(),
+        json!({ "name": "obake", "score": 0 })
+    );
+
+    let latest = Player {
+        name: "obake".to_owned(),
+        team: None,
+        score: 0,
+    };
+    assert_eq!(
+        serde_json::to_value(&latest).unwrap(),
+        json!({ "playerName": "obake", "team": null, "score": 0 })
+    );
+}
+
+#[test]
+fn defaulted_in_later_versions() {
+    assert!(serde_json::from_value::<Player_v0_1_0>(json!({ "name": "obake" })).is_err());
+
+    let old: Player_v0_2_0 = serde_json::from_value(json!({ "name": "obake" })).unwrap();
+    assert_eq!(old.score, 0);
+
+    let latest: Player = serde_json::from_value(json!({ "playerName": "obake" })).unwrap();
+    assert_eq!(latest.name, "obake");
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(trivial_migrations)]
+#[obake(serde_cfg(">=0.3.0", rename_all = "camelCase"))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Profile {
+    display_name: String,
+    #[obake(serde_cfg(">=0.2.0", default))]
+    avatar_url: Option<String>,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(trivial_migrations)]
+#[obake(serde_cfg(">=0.2.0", rename_all = "SCREAMING_SNAKE_CASE"))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Status {
+    #[obake(serde_cfg("0.1.0", rename = "active"))]
+    Active,
+    PendingReview,
+}
+
+#[test]
+fn container_options_per_version() {
+    let old = Profile_v0_2_0 {
+        display_name: "obake".to_owned(),
+        avatar_url: None,
+    };
+    assert_eq!(
+        serde_json::to_value(&old).unwrap(),
+        json!({ "display_name": "obake", "avatar_url": null })
+    );
+
+    let latest = Profile {
+        display_name: "obake".to_owned(),
+        avatar_url: None,
+    };
+    assert_eq!(
+        serde_json::to_value(&latest).unwrap(),
+        json!({ "displayName": "obake", "avatarUrl": null })
+    );
+    assert_eq!(
+        serde_json::from_value::<Profile>(json!({ "displayName": "obake" })).unwrap(),
+        latest
+    );
+}
+
+#[test]
+fn variant_options_per_version() {
+    assert_eq!(
+        serde_json::to_value(Status_v0_1_0::Active).unwrap(),
+        json!("active")
+    );
+    assert_eq!(
+        serde_json::to_value(Status_v0_1_0::PendingReview).unwrap(),
+        json!("PendingReview")
+    );
+    assert_eq!(
+        serde_json::to_value(Status::Active).unwrap(),
+        json!("ACTIVE")
+    );
+    assert_eq!(
+        serde_json::to_value(Status::PendingReview).unwrap(),
+        json!("PENDING_REVIEW")
+    );
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Envelope {
+    sender: String,
+    sent_at: u64,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Debug, Serialize, Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Message {
+    #[serde(flatten)]
+    envelope: Envelope,
+    body: String,
+    #[obake(cfg(">=0.2"))]
+    #[serde(flatten)]
+    headers: BTreeMap<String, String>,
+}
+
+impl From<Message!["0.1.0"]> for Message!["0.2.0"] {
+    fn from(from: Message!["0.1.0"]) -> Self {
+        Self {
+            envelope: from.envelope,
+            body: from.body,
+            headers: BTreeMap::new(),
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(trivial_migrations)]
+#[obake(derive(Debug, Serialize, Deserialize))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Externally {
+    #[serde(flatten)]
+    envelope: Envelope,
+    body: String,
+}
+
+// Only the latest version derives `serde` traits, so only it keeps `#[serde(flatten)]`
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(trivial_migrations)]
+#[obake(latest_only(derive(Serialize, Deserialize)))]
+#[derive(Debug, PartialEq)]
+struct LatestOnly {
+    #[serde(flatten)]
+    envelope: Envelope,
+    body: String,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Sender {
+    name: String,
+}
+
+// Only a bare `flatten` counts as `#[serde(flatten)]`, not options which happen to mention it
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Inherited {
+    #[obake(inherit, flatten)]
+    #[serde(rename = "flattened")]
+    sender: Sender,
+    body: String,
+}
+
+#[test]
+fn internally_tagged() {
+    let old = json!({ "version": "0.1.0", "sender": "obake", "sent_at": 1, "body": "boo" });
+    let versioned: VersionedMessage = serde_json::from_value(old.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&versioned).unwrap(), old);
+
+    let message: Message = versioned.into();
+    assert_eq!(message.envelope.sender, "obake");
+    assert!(message.headers.is_empty());
+
+    let latest =
+        json!({ "version": "0.2.0", "sender": "obake", "sent_at": 2, "body": "boo", "lang": "en" });
+    let versioned: VersionedMessage = serde_json::from_value(latest.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&versioned).unwrap(), latest);
+
+    let message: Message = versioned.into();
+    assert_eq!(message.headers["lang"], "en");
+}
+
+#[test]
+fn externally_tagged() {
+    let versioned = VersionedExternally::from(Externally {
+        envelope: Envelope::default(),
+        body: "boo".to_owned(),
+    });
+    let value = serde_json::to_value(&versioned).unwrap();
+    assert_eq!(
+        value,
+        json!({ "Externally_v0_2_0": { "sender": "", "sent_at": 0, "body": "boo" } })
+    );
+
+    let round_tripped: VersionedExternally = serde_json::from_value(value).unwrap();
+    assert!(matches!(
+        round_tripped,
+        VersionedExternally::Externally_v0_2_0(_)
+    ));
+}
+
+#[test]
+fn latest_only() {
+    let latest = LatestOnly {
+        envelope: Envelope::default(),
+        body: "boo".to_owned(),
+    };
+    assert_eq!(
+        serde_json::to_value(&latest).unwrap(),
+        json!({ "sender": "", "sent_at": 0, "body": "boo" })
+    );
+}
+
+#[test]
+fn inherited_fields_are_flattened() {
+    let inherited = Inherited {
+        sender: Sender_v0_1_0 {
+            name: "obake".to_owned(),
+        },
+        body: "boo".to_owned(),
+    };
+    assert_eq!(
+        serde_json::to_value(&inherited).unwrap(),
+        json!({ "name": "obake", "body": "boo" })
+    );
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "serde")]
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+#[obake(serde_repr)]
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct User {
+    user_id: u64,
+    #[obake(cfg(">=0.2"))]
+    #[serde(rename = "displayName")]
+    name: String,
+    #[serde(skip)]
+    cache: Option<u32>,
+    #[obake(cfg(">=0.3"))]
+    #[obake(serde_cfg(">=0.3", rename(serialize = "mail", deserialize = "email")))]
+    email_address: String,
+    r#type: u8,
+}
+
+impl From<User!["0.1.0"]> for User!["0.2.0"] {
+    fn from(from: User!["0.1.0"]) -> Self {
+        Self {
+            user_id: from.user_id,
+            name: String::new(),
+            cache: from.cache,
+            r#type: from.r#type,
+        }
+    }
+}
+
+impl From<User!["0.2.0"]> for User!["0.3.0"] {
+    fn from(from: User!["0.2.0"]) -> Self {
+        Self {
+            user_id: from.user_id,
+            name: from.name,
+            cache: from.cache,
+            email_address: String::new(),
+            r#type: from.r#type,
+        }
+    }
+}
+
+obake::assert_repr!(User, "0.1.0", fields = ["userId", "type"], tag = "0.1.0");
+obake::assert_repr!(User, "0.2.0", fields = ["userId", "displayName", "type"]);
+obake::assert_repr!(
+    User,
+    "0.3.0",
+    fields = ["userId", "displayName", "mail", "type"],
+    tag = "0.3.0",
+);
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(serde_repr)]
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+struct Untagged {
+    field_name: u8,
+}
+
+obake::assert_repr!(
+    Untagged,
+    "0.1.0",
+    fields = ["FIELD-NAME"],
+    tag = "Untagged_v0_1_0"
+);
+
+#[cfg(feature = "json")]
+#[test]
+fn fields_match_serialized_fields() {
+    let value = serde_json::to_value(VersionedUser::User_v0_3_0(User::default())).unwrap();
+    let mut fields: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+    fields.sort();
+
+    let mut expected: Vec<_> = <User!["0.3.0"]>::SERDE_FIELDS
+        .iter()
+        .map(ToString::to_string)
+        .chain(["version".to_owned()])
+        .collect();
+    expected.sort();
+    assert_eq!(fields, expected);
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(serde_repr, trivial_migrations)]
+#[obake(serde_cfg(">=0.2", rename_all = "camelCase"))]
+#[derive(Serialize)]
+struct Renamed {
+    field_name: u8,
+}
+
+obake::assert_repr!(Renamed, "0.1.0", fields = ["field_name"]);
+obake::assert_repr!(Renamed, "0.2.0", fields = ["fieldName"]);
@@ -0,0 +1,99 @@
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_with::{serde_as, DisplayFromStr};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(
+    derive(Debug, PartialEq, Serialize, Deserialize),
+    serde(tag = "version")
+)]
+#[serde_as]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Settings {
+    #[serde_as(as = "DisplayFromStr")]
+    port: u16,
+    #[obake(cfg(">=0.2"))]
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    retries: Vec<u8>,
+    #[obake(serde_cfg("0.1.0", with = "serde_with::As::<DisplayFromStr>"))]
+    timeout: u32,
+}
+
+impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+    fn from(from: Settings!["0.1.0"]) -> Self {
+        Self {
+            port: from.port,
+            retries: vec![],
+            timeout: from.timeout,
+        }
+    }
+}
+
+#[test]
+fn adapters_in_tagged_enum() {
+    let latest = VersionedSettings::from(Settings {
+        port: 8080,
+        retries: vec![1, 2],
+        timeout: 30,
+    });
+    let value = serde_json::to_value(&latest).unwrap();
+    assert_eq!(
+        value,
+        json!({ "version": "0.2.0", "port": "8080", "retries": ["1", "2"], "timeout": 30 })
+    );
+    assert_eq!(
+        serde_json::from_value::<VersionedSettings>(value).unwrap(),
+        latest
+    );
+}
+
+#[test]
+fn adapters_per_version() {
+    let old: VersionedSettings =
+        serde_json::from_value(json!({ "version": "0.1.0", "port": "80", "timeout": "5" }))
+            .unwrap();
+    assert_eq!(
+        old,
+        VersionedSettings::Settings_v0_1_0(Settings_v0_1_0 {
+            port: 80,
+            timeout: 5,
+        })
+    );
+
+    let settings: Settings = old.into();
+    assert_eq!(settings.timeout, 5);
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(latest_only(derive(Serialize, Deserialize)))]
+#[serde_as]
+#[derive(Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct Endpoint {
+    #[serde_as(as = "DisplayFromStr")]
+    max_port: u16,
+}
+
+impl From<Endpoint!["0.1.0"]> for Endpoint!["0.2.0"] {
+    fn from(from: Endpoint!["0.1.0"]) -> Self {
+        Self {
+            max_port: from.max_port,
+        }
+    }
+}
+
+#[test]
+fn adapters_on_latest_only_derives() {
+    let endpoint = Endpoint { max_port: 443 };
+    let value = serde_json::to_value(&endpoint).unwrap();
+    assert_eq!(value, json!({ "maxPort": "443" }));
+    assert_eq!(serde_json::from_value::<Endpoint>(value).unwrap(), endpoint);
+
+    let _ = Endpoint_v0_1_0 { max_port: 443 };
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "semver")]
+
+use obake::semver::VersionReq;
+use obake::Downgrade;
+use serde::Serialize;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("1.0.0"))]
+#[obake(downgrade)]
+#[obake(derive(Serialize))]
+#[obake(serde(tag = "version"))]
+#[derive(Clone, Serialize)]
+struct Order {
+    id: u32,
+    #[obake(cfg(">=0.2"))]
+    note: String,
+}
+
+impl From<Order!["0.1.0"]> for Order!["0.2.0"] {
+    fn from(from: Order!["0.1.0"]) -> Self {
+        Self {
+            id: from.id,
+            note: String::new(),
+        }
+    }
+}
+
+impl From<Order!["0.2.0"]> for Order!["0.1.0"] {
+    fn from(from: Order!["0.2.0"]) -> Self {
+        Self { id: from.id }
+    }
+}
+
+impl From<Order!["0.2.0"]> for Order!["1.0.0"] {
+    fn from(from: Order!["0.2.0"]) -> Self {
+        Self {
+            id: from.id,
+            note: from.note,
+        }
+    }
+}
+
+impl From<Order!["1.0.0"]> for Order!["0.2.0"] {
+    fn from(from: Order!["1.0.0"]) -> Self {
+        Self {
+            id: from.id,
+            note: from.note,
+        }
+    }
+}
+
+fn serialize_as(order: &Order, req: &str) -> Result<String, serde_json::Error> {
+    let mut bytes = Vec::new();
+    order.serialize_as(
+        &VersionReq::parse(req).unwrap(),
+        &mut serde_json::Serializer::new(&mut bytes),
+    )?;
+    Ok(String::from_utf8(bytes).unwrap())
+}
+
+#[test]
+fn serialize_as_negotiated_version() {
+    let order = Order {
+        id: 7,
+        note: "hi".to_owned(),
+    };
+
+    assert_eq!(
+        serialize_as(&order, "*").unwrap(),
+        r#"{"version":"1.0.0","id":7,"note":"hi"}"#
+    );
+    assert_eq!(
+        serialize_as(&order, "<1").unwrap(),
+        r#"{"version":"0.2.0","id":7,"note":"hi"}"#
+    );
+    assert_eq!(
+        serialize_as(&order, "=0.1.0").unwrap(),
+        r#"{"version":"0.1.0","id":7}"#
+    );
+    assert_eq!(
+        serialize_as(&order, ">=2").unwrap_err().to_string(),
+        "no version of `Order` matches `>=2`"
+    );
+}
@@ -0,0 +1,174 @@
+// The latest version of `Experimental` is gated behind `std`, so that it gets tested both with
+// and without it enabled
+#![cfg(feature = "serde")]
+
+use obake::{AnyVersion, SerializeLatest};
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Serializes the latest version through `SerializeLatest`.
+struct Latest<'a, T>(&'a T);
+
+impl<T: SerializeLatest> Serialize for Latest<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_latest(serializer)
+    }
+}
+
+/// Checks the latest version is serialized as its versioned encoding would be.
+fn assert_encoded<T>(value: T)
+where
+    T: SerializeLatest,
+    AnyVersion<T>: Serialize,
+{
+    let latest = serde_json::to_value(Latest(&value)).unwrap();
+    #[cfg(feature = "bincode")]
+    let latest_bytes = bincode::serialize(&Latest(&value)).unwrap();
+
+    let versioned = AnyVersion::<T>::from(value);
+    assert_eq!(latest, serde_json::to_value(&versioned).unwrap());
+    #[cfg(feature = "bincode")]
+    assert_eq!(latest_bytes, bincode::serialize(&versioned).unwrap());
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Serialize))]
+#[derive(Serialize)]
+struct Untagged {
+    id: u32,
+}
+
+impl From<Untagged!["0.1.0"]> for Untagged!["0.2.0"] {
+    fn from(from: Untagged!["0.1.0"]) -> Self {
+        Self { id: from.id }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Serialize), serde(tag = "version"))]
+#[derive(Serialize)]
+struct Tagged {
+    #[obake(cfg("0.1.0"))]
+    name: String,
+    #[obake(cfg(">=0.2"))]
+    display_name: String,
+}
+
+impl From<Tagged!["0.1.0"]> for Tagged!["0.2.0"] {
+    fn from(from: Tagged!["0.1.0"]) -> Self {
+        Self {
+            display_name: from.name,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0", untagged_legacy))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(derive(Serialize), serde(rename_all = "snake_case"))]
+#[derive(Serialize)]
+struct Legacy {
+    id: u32,
+}
+
+impl From<Legacy!["0.1.0"]> for Legacy!["0.2.0"] {
+    fn from(from: Legacy!["0.1.0"]) -> Self {
+        Self { id: from.id }
+    }
+}
+
+impl From<Legacy!["0.2.0"]> for Legacy!["0.3.0"] {
+    fn from(from: Legacy!["0.2.0"]) -> Self {
+        Self { id: from.id }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.2.0"))]
+#[obake(version("0.1.0"))]
+#[obake(derive(Serialize), serde(tag = "version"))]
+#[derive(Serialize)]
+struct OutOfOrder {
+    #[obake(cfg(">=0.2"))]
+    id: u32,
+}
+
+impl From<OutOfOrder!["0.1.0"]> for OutOfOrder!["0.2.0"] {
+    fn from(_: OutOfOrder!["0.1.0"]) -> Self {
+        Self { id: 0 }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"), feature = "std")]
+#[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+#[derive(Serialize, Deserialize)]
+struct Experimental {
+    #[obake(cfg(">=0.2"))]
+    id: u32,
+}
+
+#[cfg(feature = "std")]
+impl From<Experimental!["0.1.0"]> for Experimental!["0.2.0"] {
+    fn from(_: Experimental!["0.1.0"]) -> Self {
+        Self { id: 0 }
+    }
+}
+
+#[test]
+fn externally_tagged() {
+    assert_encoded(Untagged { id: 7 });
+}
+
+#[test]
+fn internally_tagged() {
+    assert_encoded(Tagged {
+        display_name: "ferris".to_owned(),
+    });
+    assert_eq!(
+        serde_json::to_value(Latest(&Tagged {
+            display_name: "ferris".to_owned(),
+        }))
+        .unwrap(),
+        serde_json::json!({ "version": "0.2.0", "display_name": "ferris" }),
+    );
+}
+
+#[test]
+fn untagged_legacy_versions_keep_their_place() {
+    assert_encoded(Legacy { id: 7 });
+}
+
+#[test]
+fn versions_declared_out_of_order() {
+    assert_eq!(
+        serde_json::to_value(Latest(&OutOfOrder { id: 7 })).unwrap(),
+        serde_json::json!({ "version": "0.2.0", "id": 7 }),
+    );
+    assert_encoded(OutOfOrder { id: 7 });
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn gated_latest_versions() {
+    assert_eq!(
+        serde_json::to_value(Latest(&Experimental { id: 7 })).unwrap(),
+        serde_json::json!({ "version": "0.2.0", "id": 7 }),
+    );
+    assert_encoded(Experimental { id: 7 });
+}
+
+#[test]
+#[cfg(not(feature = "std"))]
+fn gated_latest_versions() {
+    assert_eq!(
+        serde_json::to_value(Latest(&Experimental {})).unwrap(),
+        serde_json::json!({ "version": "0.1.0" }),
+    );
+    assert_encoded(Experimental {});
+}
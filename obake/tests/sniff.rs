@@ -0,0 +1,91 @@
+#![cfg(feature = "sniff")]
+
+use obake::sniff::{self, Sniffer};
+use obake::Error;
+use serde::Deserialize;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    #[obake(cfg("0.1.0"))]
+    host: String,
+    #[obake(cfg(">=0.2"))]
+    hosts: Vec<String>,
+    #[obake(cfg(">=0.3"))]
+    port: u16,
+}
+
+impl From<Config!["0.1.0"]> for Config!["0.2.0"] {
+    fn from(from: Config!["0.1.0"]) -> Self {
+        Self {
+            hosts: vec![from.host],
+        }
+    }
+}
+
+impl From<Config!["0.2.0"]> for Config!["0.3.0"] {
+    fn from(from: Config!["0.2.0"]) -> Self {
+        Self {
+            hosts: from.hosts,
+            port: 80,
+        }
+    }
+}
+
+fn sniffer() -> Sniffer<Config> {
+    let mut sniffer = Sniffer::new();
+    sniffer
+        .recognize("0.3.0", sniff::has_key("port"))
+        .recognize("0.2.0", sniff::has_key("hosts"))
+        .recognize("0.1.0", sniff::has_key("host"));
+    sniffer
+}
+
+#[test]
+fn recognizers_are_tried_in_order() {
+    let sniffer = sniffer();
+    assert_eq!(sniffer.sniff(br#"{ "host": "a" }"#), Some("0.1.0"));
+    assert_eq!(sniffer.sniff(br#"{ "hosts": [] }"#), Some("0.2.0"));
+    assert_eq!(
+        sniffer.sniff(br#"{ "hosts": [], "port": 1 }"#),
+        Some("0.3.0")
+    );
+    assert_eq!(sniffer.sniff(br#"{ "address": "a" }"#), None);
+    assert_eq!(sniffer.sniff(b"not json"), None);
+}
+
+#[test]
+fn recognized_payloads_are_upgraded() {
+    let config = sniffer().upgrade_json(br#"{ "host": "a" }"#).unwrap();
+    assert_eq!(
+        config,
+        Config {
+            hosts: vec!["a".to_owned()],
+            port: 80,
+        }
+    );
+
+    let unrecognized = sniffer().upgrade_json(br#"{ "address": "a" }"#);
+    assert!(matches!(unrecognized, Err(Error::Parse { .. })));
+
+    let invalid = sniffer().upgrade_json(br#"{ "host": 1 }"#);
+    assert!(matches!(invalid, Err(Error::Parse { .. })));
+}
+
+#[test]
+fn signatures_are_recognized() {
+    let mut sniffer = Sniffer::<Config>::new();
+    sniffer.recognize("0.1.0", sniff::starts_with(b"CFG1"));
+    assert_eq!(sniffer.sniff(b"CFG1..."), Some("0.1.0"));
+    assert_eq!(sniffer.sniff(b"CFG"), None);
+}
+
+#[test]
+#[should_panic(expected = "unsupported version 9.9.9 of `Config`")]
+fn unknown_versions_panic() {
+    Sniffer::<Config>::new().recognize("9.9.9", |_| true);
+}
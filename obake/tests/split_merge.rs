@@ -0,0 +1,89 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.4.0"))]
+#[obake(split_into(UiSettings, AudioSettings, with = split_settings))]
+struct Settings {
+    theme: String,
+    #[obake(cfg(">=0.4"))]
+    volume: u8,
+}
+
+impl From<Settings!["0.1.0"]> for Settings!["0.4.0"] {
+    fn from(from: Settings!["0.1.0"]) -> Self {
+        Self {
+            theme: from.theme,
+            volume: 100,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.5.0"))]
+#[derive(Debug, PartialEq)]
+struct UiSettings {
+    theme: String,
+}
+
+#[obake::versioned]
+#[obake(version("0.5.0"))]
+#[derive(Debug, PartialEq)]
+struct AudioSettings {
+    volume: u8,
+}
+
+fn split_settings(settings: Settings) -> (UiSettings, AudioSettings) {
+    (
+        UiSettings {
+            theme: settings.theme,
+        },
+        AudioSettings {
+            volume: settings.volume,
+        },
+    )
+}
+
+#[obake::versioned]
+#[obake(version("0.6.0"))]
+#[obake(merge_from(UiSettings, AudioSettings, with = merge_settings))]
+#[derive(Debug, PartialEq)]
+struct Preferences {
+    theme: String,
+    volume: u8,
+}
+
+fn merge_settings(ui: UiSettings, audio: AudioSettings) -> Preferences {
+    Preferences {
+        theme: ui.theme,
+        volume: audio.volume,
+    }
+}
+
+#[test]
+fn split_old_settings() {
+    let old = VersionedSettings::Settings_v0_1_0(Settings_v0_1_0 {
+        theme: "dark".to_owned(),
+    });
+    let (ui, audio): (UiSettings, AudioSettings) = old.into();
+    assert_eq!(
+        ui,
+        UiSettings {
+            theme: "dark".to_owned()
+        }
+    );
+    assert_eq!(audio, AudioSettings { volume: 100 });
+}
+
+#[test]
+fn merge_settings_into_preferences() {
+    let ui = VersionedUiSettings::from(UiSettings {
+        theme: "light".to_owned(),
+    });
+    let audio = VersionedAudioSettings::from(AudioSettings { volume: 5 });
+    assert_eq!(
+        Preferences::from((ui, audio)),
+        Preferences {
+            theme: "light".to_owned(),
+            volume: 5
+        }
+    );
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "stream")]
+
+use obake::stream::{migrate_ndjson, Error, ErrorPolicy};
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Serialize, Deserialize))]
+#[obake(serde(tag = "version"))]
+#[derive(Serialize, Deserialize)]
+struct Order {
+    id: u32,
+    #[obake(cfg(">=0.2"))]
+    note: String,
+}
+
+impl From<Order!["0.1.0"]> for Order!["0.2.0"] {
+    fn from(from: Order!["0.1.0"]) -> Self {
+        Self {
+            id: from.id,
+            note: String::new(),
+        }
+    }
+}
+
+const INPUT: &str = r#"{"version":"0.1.0","id":1}
+
+{"version":"0.3.0","id":2}
+{"version":"0.2.0","id":3,"note":"hi"}
+not json
+"#;
+
+const OUTPUT: &str = r#"{"version":"0.2.0","id":1,"note":""}
+{"version":"0.2.0","id":3,"note":"hi"}
+"#;
+
+#[test]
+fn skip_invalid_lines() {
+    let mut output = Vec::new();
+    let report =
+        migrate_ndjson::<Order, _, _>(INPUT.as_bytes(), &mut output, ErrorPolicy::Skip).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), OUTPUT);
+    assert_eq!((report.migrated, report.skipped), (2, 2));
+    assert!(report.errors.is_empty());
+}
+
+#[test]
+fn collect_invalid_lines() {
+    let mut output = Vec::new();
+    let report =
+        migrate_ndjson::<Order, _, _>(INPUT.as_bytes(), &mut output, ErrorPolicy::Collect).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), OUTPUT);
+    let lines: Vec<_> = report.errors.iter().map(|(line, _)| *line).collect();
+    assert_eq!(lines, [3, 5]);
+}
+
+#[test]
+fn abort_on_invalid_line() {
+    let mut output = Vec::new();
+    let err = migrate_ndjson::<Order, _, _>(INPUT.as_bytes(), &mut output, ErrorPolicy::Abort)
+        .unwrap_err();
+    assert!(matches!(err, Error::Json { line: 3, .. }));
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "{\"version\":\"0.2.0\",\"id\":1,\"note\":\"\"}\n"
+    );
+}
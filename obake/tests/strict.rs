@@ -0,0 +1,43 @@
+#![allow(dead_code)]
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(strict)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    #[obake(discard)]
+    field_0: u32,
+    #[obake(cfg(">=0.2"))]
+    field_1: u64,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(_: Foo!["0.1.0"]) -> Self {
+        Self { field_1: 0 }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(strict)]
+enum Bar {
+    X,
+    #[obake(cfg("0.1.0"))]
+    #[obake(discard)]
+    Y,
+}
+
+impl From<Bar!["0.1.0"]> for Bar!["0.2.0"] {
+    fn from(_: Bar!["0.1.0"]) -> Self {
+        Self::X
+    }
+}
+
+#[test]
+fn discarded_fields_migrate() {
+    type OldFoo = Foo!["0.1.0"];
+    let foo: Foo = VersionedFoo::Foo_v0_1_0(OldFoo { field_0: 7 }).into();
+    assert_eq!(foo.field_1, 0);
+}
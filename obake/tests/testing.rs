@@ -0,0 +1,88 @@
+#![cfg(feature = "testing")]
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+#[derive(Serialize, Deserialize)]
+struct Settings {
+    #[obake(cfg("0.1.0"))]
+    timeout_secs: u32,
+    #[obake(cfg(">=0.2"))]
+    timeout_ms: u64,
+}
+
+impl From<Settings!["0.1.0"]> for Settings!["0.2.0"] {
+    fn from(from: Settings!["0.1.0"]) -> Self {
+        Self {
+            timeout_ms: u64::from(from.timeout_secs) * 1000,
+        }
+    }
+}
+
+/// A fresh directory for fixtures written by a test.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("obake-testing-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn historical_fixtures_upgrade() {
+    obake::testing::check_fixtures::<Settings>("tests/fixtures/settings");
+}
+
+#[test]
+fn upgraded_fixtures_match_snapshots() {
+    obake::testing::check_fixtures_with_snapshots::<Settings>("tests/fixtures/settings");
+}
+
+#[test]
+fn missing_snapshots_are_written() {
+    let dir = scratch_dir("snapshots");
+    std::fs::write(
+        dir.join("0.1.0.json"),
+        r#"{ "version": "0.1.0", "timeout_secs": 3 }"#,
+    )
+    .unwrap();
+
+    obake::testing::check_fixtures_with_snapshots::<Settings>(&dir);
+    let snapshot = std::fs::read_to_string(dir.join("0.1.0.upgraded.json")).unwrap();
+    let snapshot: serde_json::Value = serde_json::from_str(&snapshot).unwrap();
+    assert_eq!(
+        snapshot,
+        serde_json::json!({ "version": "0.2.0", "timeout_ms": 3000 })
+    );
+
+    // The snapshot isn't checked as a fixture itself
+    obake::testing::check_fixtures::<Settings>(&dir);
+}
+
+#[test]
+#[should_panic(expected = "named after version 0.2.0, but deserialized as version 0.1.0")]
+fn fixtures_must_match_their_version() {
+    let dir = scratch_dir("mismatch");
+    std::fs::write(
+        dir.join("Settings-0.2.0.json"),
+        r#"{ "version": "0.1.0", "timeout_secs": 3 }"#,
+    )
+    .unwrap();
+    obake::testing::check_fixtures::<Settings>(&dir);
+}
+
+#[test]
+#[should_panic(expected = "unsupported version 0.3.0 of `Settings`")]
+fn fixtures_must_be_named_after_versions() {
+    let dir = scratch_dir("unknown");
+    std::fs::write(
+        dir.join("0.3.0.json"),
+        r#"{ "version": "0.2.0", "timeout_ms": 3 }"#,
+    )
+    .unwrap();
+    obake::testing::check_fixtures::<Settings>(&dir);
+}
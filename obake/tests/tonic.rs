@@ -0,0 +1,101 @@
+#![cfg(feature = "tonic")]
+
+use obake::tonic::{requested_version, Envelope, Outgoing, VersionedMessage, VERSION_METADATA_KEY};
+use prost::Message;
+use tonic::{Code, Request};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(tonic, downgrade)]
+#[derive(Clone, PartialEq, prost::Message)]
+struct Transfer {
+    #[prost(uint64, tag = "1")]
+    amount: u64,
+    #[obake(cfg(">=0.2"))]
+    #[prost(string, tag = "2")]
+    currency: String,
+}
+
+impl From<Transfer!["0.1.0"]> for Transfer!["0.2.0"] {
+    fn from(from: Transfer!["0.1.0"]) -> Self {
+        Self {
+            amount: from.amount,
+            currency: "EUR".to_owned(),
+        }
+    }
+}
+
+impl From<Transfer!["0.2.0"]> for Transfer!["0.1.0"] {
+    fn from(from: Transfer!["0.2.0"]) -> Self {
+        Self {
+            amount: from.amount,
+        }
+    }
+}
+
+#[test]
+fn envelope_round_trip() {
+    let envelope = Envelope {
+        version: "0.1.0".to_owned(),
+        payload: Transfer_v0_1_0 { amount: 5 }.encode_to_vec(),
+    };
+    let decoded = Envelope::decode(&envelope.encode_to_vec()[..]).unwrap();
+    assert_eq!(
+        decoded.open::<Transfer>().unwrap(),
+        Transfer {
+            amount: 5,
+            currency: "EUR".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn encode_versions() {
+    let latest = Transfer {
+        amount: 7,
+        currency: "GBP".to_owned(),
+    };
+    let envelope = Envelope::from(Outgoing::from(latest.clone()));
+    assert_eq!(envelope.version, "0.2.0");
+    assert_eq!(envelope.payload, latest.encode_to_vec());
+
+    let envelope = Envelope::from(Outgoing::downgraded(latest, "0.1.0").unwrap());
+    assert_eq!(envelope.version, "0.1.0");
+    assert!(matches!(
+        Transfer::decode_as("0.1.0", &envelope.payload),
+        Some(Ok(VersionedTransfer::Transfer_v0_1_0(Transfer_v0_1_0 {
+            amount: 7
+        })))
+    ));
+}
+
+#[test]
+fn unsupported_version() {
+    let envelope = Envelope {
+        version: "9.0.0".to_owned(),
+        payload: Vec::new(),
+    };
+    let status = envelope.open::<Transfer>().unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert_eq!(
+        status.message(),
+        "unsupported version 9.0.0 of `Transfer` (expected one of 0.1.0, 0.2.0)"
+    );
+}
+
+#[test]
+fn requested_versions() {
+    let mut request = Request::new(());
+    assert_eq!(requested_version::<Transfer, _>(&request), None);
+
+    request
+        .metadata_mut()
+        .insert(VERSION_METADATA_KEY, "0.1.0".parse().unwrap());
+    assert_eq!(requested_version::<Transfer, _>(&request), Some("0.1.0"));
+
+    request
+        .metadata_mut()
+        .insert(VERSION_METADATA_KEY, "0.3.0".parse().unwrap());
+    assert_eq!(requested_version::<Transfer, _>(&request), None);
+}
@@ -0,0 +1,138 @@
+#![cfg(all(feature = "tracing", feature = "json"))]
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use obake::DeserializeVersion;
+use serde::Deserialize;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Records the name and fields of every span created and event emitted, as strings.
+#[derive(Clone, Default)]
+struct Recorder {
+    records: Arc<Mutex<Vec<String>>>,
+    next_id: Arc<Mutex<u64>>,
+}
+
+struct Fields(String);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() != "message" {
+            self.0 += &format!(" {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, _: u64) {
+        // Durations vary between runs, so only their presence is recorded
+        self.0 += &format!(" {}", field.name());
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target() == "obake"
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = Fields(format!("span {}", span.metadata().name()));
+        span.record(&mut fields);
+        self.records.lock().unwrap().push(fields.0);
+
+        let mut next_id = self.next_id.lock().unwrap();
+        *next_id += 1;
+        Id::from_u64(*next_id)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields(format!("event {}", event.metadata().level()));
+        event.record(&mut fields);
+        self.records.lock().unwrap().push(fields.0);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+fn record(f: impl FnOnce()) -> Vec<String> {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), f);
+    let records = recorder.records.lock().unwrap().clone();
+    records
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Deserialize)]
+struct Job {
+    #[obake(cfg("<0.3"))]
+    retries: u8,
+    #[obake(cfg(">=0.3"))]
+    attempts: u8,
+}
+
+impl From<Job!["0.1.0"]> for Job!["0.2.0"] {
+    fn from(from: Job!["0.1.0"]) -> Self {
+        Self {
+            retries: from.retries,
+        }
+    }
+}
+
+impl From<Job!["0.2.0"]> for Job!["0.3.0"] {
+    fn from(from: Job!["0.2.0"]) -> Self {
+        Self {
+            attempts: from.retries + 1,
+        }
+    }
+}
+
+#[test]
+fn upgrades_are_traced() {
+    let records = record(|| {
+        let job: Job = VersionedJob::Job_v0_1_0(Job_v0_1_0 { retries: 2 }).into();
+        assert_eq!(job.attempts, 3);
+    });
+
+    let fields = r#"type_name="Job""#;
+    assert_eq!(
+        records,
+        [
+            format!(r#"span upgrade {fields} from="0.1.0" to="0.3.0""#),
+            format!(r#"span migrate {fields} from="0.1.0" to="0.2.0""#),
+            format!(r#"event DEBUG {fields} from="0.1.0" to="0.2.0" duration_us"#),
+            format!(r#"span migrate {fields} from="0.2.0" to="0.3.0""#),
+            format!(r#"event DEBUG {fields} from="0.2.0" to="0.3.0" duration_us"#),
+        ]
+    );
+}
+
+#[test]
+fn version_detection_is_traced() {
+    let records = record(|| {
+        let mut deserializer = serde_json::Deserializer::from_str(r#"{ "retries": 1 }"#);
+        Job::deserialize_version("0.2.0", &mut deserializer).unwrap();
+        let mut deserializer = serde_json::Deserializer::from_str("{}");
+        Job::deserialize_version("0.4.0", &mut deserializer)
+            .err()
+            .unwrap();
+    });
+
+    assert_eq!(
+        records,
+        [
+            r#"event DEBUG type_name="Job" version="0.2.0""#,
+            r#"event WARN type_name="Job" version="0.4.0""#,
+        ]
+    );
+}
@@ -0,0 +1,82 @@
+#![allow(dead_code)]
+
+use obake::Downgrade;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(trivial_migrations)]
+#[obake(downgrade)]
+#[obake(derive(Debug, PartialEq))]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    field_0: String,
+    #[obake(cfg(">=0.3"))]
+    field_1: u32,
+}
+
+// 0.1.0 -> 0.2.0 is provided by `#[obake(trivial_migrations)]`
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+            field_1: 0,
+        }
+    }
+}
+
+impl From<Foo!["0.3.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.3.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(trivial_migrations)]
+#[derive(Debug, PartialEq)]
+enum Bar {
+    X(u32, String),
+    Y { field_0: u64 },
+    Z,
+}
+
+#[test]
+fn identical_versions_migrate_trivially() {
+    type OldFoo = Foo!["0.1.0"];
+    let foo: Foo = VersionedFoo::Foo_v0_1_0(OldFoo {
+        field_0: "foo".to_owned(),
+    })
+    .into();
+    assert_eq!(
+        foo,
+        Foo {
+            field_0: "foo".to_owned(),
+            field_1: 0,
+        }
+    );
+
+    let old = foo.downgrade("0.1.0").unwrap();
+    assert_eq!(
+        old,
+        VersionedFoo::Foo_v0_1_0(OldFoo {
+            field_0: "foo".to_owned()
+        })
+    );
+}
+
+#[test]
+fn identical_enum_versions_migrate_trivially() {
+    type OldBar = Bar!["0.1.0"];
+    let bar: Bar = VersionedBar::Bar_v0_1_0(OldBar::X(1, "x".to_owned())).into();
+    assert_eq!(bar, Bar::X(1, "x".to_owned()));
+    let bar: Bar = VersionedBar::Bar_v0_1_0(OldBar::Y { field_0: 2 }).into();
+    assert_eq!(bar, Bar::Y { field_0: 2 });
+    let bar: Bar = VersionedBar::Bar_v0_1_0(OldBar::Z).into();
+    assert_eq!(bar, Bar::Z);
+}
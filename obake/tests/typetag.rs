@@ -0,0 +1,113 @@
+#![cfg(feature = "typetag")]
+
+use obake::typetag::Versioned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[typetag::serde(tag = "type")]
+trait Event {
+    fn describe(&self) -> String;
+}
+
+#[typetag::serde]
+trait Command {
+    fn name(&self) -> String;
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Renamed {
+    #[obake(cfg("0.1.0"))]
+    name: String,
+    #[obake(cfg(">=0.2"))]
+    display_name: String,
+}
+
+impl From<Renamed!["0.1.0"]> for Renamed!["0.2.0"] {
+    fn from(from: Renamed!["0.1.0"]) -> Self {
+        Self {
+            display_name: from.name,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Deleted {
+    id: u32,
+}
+
+#[typetag::serde(name = "renamed")]
+impl Event for Versioned<Renamed> {
+    fn describe(&self) -> String {
+        format!("renamed to {}", self.display_name)
+    }
+}
+
+#[typetag::serde(name = "deleted")]
+impl Event for Versioned<Deleted> {
+    fn describe(&self) -> String {
+        format!("deleted {}", self.id)
+    }
+}
+
+#[typetag::serde(name = "rename")]
+impl Command for Versioned<Renamed> {
+    fn name(&self) -> String {
+        self.display_name.clone()
+    }
+}
+
+#[test]
+fn old_versions_are_upgraded_behind_the_type_tag() {
+    let events: Vec<Box<dyn Event>> = serde_json::from_value(json!([
+        { "type": "renamed", "version": "0.1.0", "name": "ferris" },
+        { "type": "deleted", "version": "0.1.0", "id": 7 },
+        { "type": "renamed", "version": "0.2.0", "display_name": "corro" },
+    ]))
+    .unwrap();
+    let descriptions: Vec<_> = events.iter().map(|event| event.describe()).collect();
+    assert_eq!(
+        descriptions,
+        ["renamed to ferris", "deleted 7", "renamed to corro"]
+    );
+}
+
+#[test]
+fn trait_objects_are_serialized_as_the_latest_version() {
+    let events: Vec<Box<dyn Event>> = vec![
+        Box::new(Versioned(Renamed {
+            display_name: "ferris".to_owned(),
+        })),
+        Box::new(Versioned(Deleted { id: 7 })),
+    ];
+    assert_eq!(
+        serde_json::to_value(&events).unwrap(),
+        json!([
+            { "type": "renamed", "version": "0.2.0", "display_name": "ferris" },
+            { "type": "deleted", "version": "0.1.0", "id": 7 },
+        ])
+    );
+}
+
+#[test]
+fn externally_tagged_trait_objects_round_trip() {
+    let command: Box<dyn Command> = serde_json::from_value(json!({
+        "rename": { "version": "0.1.0", "name": "ferris" },
+    }))
+    .unwrap();
+    assert_eq!(command.name(), "ferris");
+
+    let encoded = serde_json::to_value(&command).unwrap();
+    assert_eq!(
+        encoded,
+        json!({ "rename": { "version": "0.2.0", "display_name": "ferris" } })
+    );
+    let decoded: Box<dyn Command> = serde_json::from_value(encoded).unwrap();
+    assert_eq!(decoded.name(), "ferris");
+}
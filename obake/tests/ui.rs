@@ -3,3 +3,10 @@ fn ui() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/ui/*.rs");
 }
+
+#[cfg(feature = "json")]
+#[test]
+fn ui_json() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/json/*.rs");
+}
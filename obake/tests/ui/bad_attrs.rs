@@ -36,4 +36,17 @@ enum Flam {
     },
 }
 
+#[obake::versioned]
+#[obake(verison("0.1.0"))]
+struct Flop {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake()]
+struct Flap {}
+
+#[obake::versioned]
+#[obake(versions())]
+struct Flub {}
+
 fn main() {}
@@ -56,4 +56,74 @@ mod derives {
     }
 }
 
+mod generate_tests {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(generate_tests)]
+        field_0: u32,
+    }
+}
+
+mod downgrade {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(downgrade)]
+        field_0: u32,
+    }
+}
+
+mod graph {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(graph)]
+        field_0: u32,
+    }
+}
+
+mod coverage {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(coverage(Clone))]
+        field_0: u32,
+    }
+}
+
+mod schema_hash {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(schema_hash)]
+        field_0: u32,
+    }
+}
+
+mod trivial_migrations {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(trivial_migrations)]
+        field_0: u32,
+    }
+}
+
+mod partial_migrations {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(partial_migrations)]
+        field_0: u32,
+    }
+
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(partial_migrations)]
+    enum Bar {
+        X,
+    }
+}
+
 fn main() {}
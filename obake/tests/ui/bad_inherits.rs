@@ -18,4 +18,37 @@ enum Baz {
     }
 }
 
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Flim {
+    #[obake(inherit(outer = "0.1.0"))]
+    field_0: Foo,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Flam {
+    #[obake(inherit(outer = "0.1.0", inner = "0.1.0"))]
+    #[obake(inherit(outer = "0.1.0", inner = "0.1.0"))]
+    field_0: Foo,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Flub {
+    #[obake(flatten)]
+    field_0: Foo,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(flatten)]
+struct Flop {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+enum Flap {
+    B(u32, #[obake(cfg("0.1.0"))] Foo),
+}
+
 fn main() {}
@@ -0,0 +1,23 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Foo {
+    #[obake(cfg(either("0.1.0")))]
+    field_0: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Bar {
+    #[obake(cfg(not("0.1.0", "0.2.0")))]
+    field_0: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+struct Baz {
+    #[obake(cfg(in("0.1.0", "0.3.0")))]
+    field_0: u32,
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+struct Foo {
+    field_0: u8,
+    #[obake(cfg(">=0.2"), computed = |old| old.field_0 as u16)]
+    field_1: u16,
+    #[obake(cfg(">=0.2"))]
+    field_2: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+enum Bar {
+    A {
+        #[obake(computed = |_| 0)]
+        field_0: u8,
+    },
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(computed = |_| Baz {})]
+struct Baz {}
+
+fn main() {}
@@ -0,0 +1,31 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Foo {
+    #[obake(default = 1)]
+    field_0: u8,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(default)]
+struct Bar {
+    #[obake(default = 1)]
+    #[obake(default = 2)]
+    field_0: u8,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(default)]
+enum Baz {
+    A,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(default = 1)]
+struct Qux {
+    field_0: u8,
+}
+
+fn main() {}
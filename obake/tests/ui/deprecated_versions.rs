@@ -0,0 +1,17 @@
+#![deny(deprecated)]
+
+#[obake::versioned]
+#[obake(version("0.1.0", deprecated = "upgrade to 0.2+"))]
+#[obake(version("0.2.0"))]
+#[obake(trivial_migrations)]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0", deprecated = "use something else"))]
+struct Bar {}
+
+fn main() {
+    let _ = Foo_v0_1_0 {};
+    let _ = VersionedFoo::Foo_v0_1_0;
+}
@@ -0,0 +1,19 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(emit_original)]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    field_0: u8,
+    #[obake(cfg(">=0.2"))]
+    field_0: u16,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Bar {
+    #[obake(emit_original)]
+    field_0: u8,
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(error)]
+#[derive(Debug)]
+enum MissingDisplay {
+    #[obake(display("first"))]
+    First,
+    Second,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(display("no error"))]
+#[derive(Debug)]
+struct NotAnError {
+    x: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(error)]
+#[derive(Debug)]
+struct DisplayOnField {
+    #[obake(display("x"))]
+    x: u32,
+}
+
+fn main() {}
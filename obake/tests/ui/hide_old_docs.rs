@@ -0,0 +1,8 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Foo {
+    #[obake(hide_old_docs)]
+    field_0: u8,
+}
+
+fn main() {}
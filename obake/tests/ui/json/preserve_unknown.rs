@@ -0,0 +1,10 @@
+// `SerializeDisplay` isn't a `serde` derive, however it's named
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(preserve_unknown)]
+#[derive(serde_with::SerializeDisplay)]
+struct Note {
+    text: String,
+}
+
+fn main() {}
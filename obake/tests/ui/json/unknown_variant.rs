@@ -0,0 +1,10 @@
+// `DeserializeFromStr` isn't a `serde` derive, however it's named
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(unknown_variant)]
+#[derive(serde_with::DeserializeFromStr)]
+enum Shape {
+    Circle,
+}
+
+fn main() {}
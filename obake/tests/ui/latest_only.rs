@@ -0,0 +1,21 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(latest_only(derive(Clone)))]
+struct Foo {
+    field_0: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+fn clone_old(foo: &Foo!["0.1.0"]) -> Foo!["0.1.0"] {
+    foo.clone()
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(assert_layout("0.2.0", size = 4))]
+#[repr(C)]
+struct Undeclared {
+    x: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(assert_layout("0.1.0"))]
+#[repr(C)]
+struct Empty {
+    x: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(assert_layout("0.1.0", size = 4, align = 8))]
+#[repr(C)]
+struct Misaligned {
+    x: u32,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[obake::versioned]
+#[obake(versions("0.1.0", "0.2.0", "0.3.0", "0.4.0"))]
+#[obake(max_versions = 2)]
+struct Foo {}
+
+fn main() {}
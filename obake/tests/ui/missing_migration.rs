@@ -0,0 +1,26 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+struct Foo {
+    field_0: u32,
+    #[obake(cfg(">=0.3"))]
+    field_1: u32,
+}
+
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_0: from.field_0,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+struct Bar {
+    field_0: u32,
+}
+
+fn main() {}
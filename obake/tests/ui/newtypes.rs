@@ -0,0 +1,23 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+struct Pair(u32, #[obake(cfg("0.2.0"))] u32);
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+struct Empty(#[obake(cfg("0.1.0"))] u32);
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct First(#[obake(map_from(u64::from))] u32);
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+struct Named {
+    #[obake(cfg("0.2.0"), map_from(u64::from))]
+    field: u64,
+}
+
+fn main() {}
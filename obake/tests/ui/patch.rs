@@ -0,0 +1,59 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(patch("0.1.0", rename(a -> b)))]
+struct Dropped {
+    #[obake(cfg("0.1.0"))]
+    a: u32,
+    #[obake(cfg("0.1.0"))]
+    old: u32,
+    #[obake(cfg(">=0.2"))]
+    b: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(patch("0.1.0", delete(a)))]
+struct Unset {
+    #[obake(cfg("0.1.0"))]
+    a: u32,
+    #[obake(cfg(">=0.2"))]
+    b: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(patch("0.1.0", move(missing -> b)))]
+struct NoSuchField {
+    #[obake(cfg(">=0.2"))]
+    b: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(patch("0.1.0", default(b), default(b = 1)))]
+struct SetTwice {
+    #[obake(cfg(">=0.2"))]
+    b: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(patch("0.2.0", default(b)))]
+struct Latest {
+    b: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(patch("0.1.0", swap(a, b)))]
+struct UnknownStep {
+    a: u32,
+}
+
+fn main() {}
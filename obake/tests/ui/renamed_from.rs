@@ -0,0 +1,25 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(renamed_from("Player", until = "0.2.0"))]
+struct Character {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(renamed_from("Player", after = "0.2.0"))]
+struct Monster {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+enum Status {
+    #[obake(variant_renamed_from("Actve", until = "0.2.0"))]
+    Active,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Account {
+    #[obake(variant_renamed_from("nme", until = "0.1.0"))]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,43 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+struct Foo {
+    #[obake(cfg("0.1.0"))]
+    field_0: u8,
+    #[obake(cfg(">=0.2"))]
+    #[obake(retyped)]
+    field_0: u16,
+    #[obake(cfg(">=0.2"))]
+    field_1: u32,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+struct Bar {
+    #[obake(cfg("0.1.0"))]
+    field_0: u8,
+    #[obake(cfg(">=0.2"))]
+    #[obake(retyped)]
+    field_0: u16,
+    #[obake(cfg("0.1.0"))]
+    field_1: u8,
+    #[obake(cfg(">=0.2"))]
+    field_1: u16,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+enum Baz {
+    A {
+        #[obake(retyped)]
+        field_0: u8,
+    },
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(retyped)]
+struct Qux {}
+
+fn main() {}
@@ -20,4 +20,16 @@ struct Flim {
     field_0: u32,
 }
 
+#[obake::versioned]
+#[obake(version("0.2.o"))]
+struct Flam {}
+
+#[obake::versioned]
+#[obake(version("01.0.0"))]
+struct Flop {}
+
+#[obake::versioned]
+#[obake(version("0.1.0-beta..1"))]
+struct Flub {}
+
 fn main() {}
@@ -0,0 +1,11 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(split_into(with = split))]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(merge_from(Foo, Bar))]
+struct Bar {}
+
+fn main() {}
@@ -0,0 +1,43 @@
+mod field {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(version("0.2.0"))]
+    #[obake(strict)]
+    struct Foo {
+        #[obake(cfg("0.1.0"))]
+        field_0: u32,
+    }
+}
+
+mod variant {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(version("0.2.0"))]
+    #[obake(version("0.3.0"))]
+    #[obake(strict)]
+    enum Foo {
+        X,
+        #[obake(cfg("<0.3"))]
+        Y,
+    }
+}
+
+mod discard {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    #[obake(discard)]
+    struct Foo {
+        field_0: u32,
+    }
+}
+
+mod strict {
+    #[obake::versioned]
+    #[obake(version("0.1.0"))]
+    struct Foo {
+        #[obake(strict)]
+        field_0: u32,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+#[obake::versioned]
+#[obake(version("0.0.0"))]
+#[obake(version("0.1.0", untagged_legacy))]
+#[obake(version("0.2.0"))]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.0.0", untagged_legacy))]
+#[obake(version("0.1.0"))]
+struct Bar {}
+
+fn main() {}
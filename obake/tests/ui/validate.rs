@@ -0,0 +1,8 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Foo {
+    #[obake(validate = check)]
+    field_0: u32,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version_enum(derives(Debug)))]
+struct Foo {
+    field_0: u8,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Bar {
+    #[obake(version_enum)]
+    field_0: u8,
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0", feature = "legacy"))]
+#[obake(version("0.3.0"))]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(version("0.1.0", feature = "legacy"))]
+#[obake(version("0.2.0", feature = "unstable"))]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(version("0.1.0", flag = "legacy"))]
+struct Baz {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0-beta", feature = "unstable"))]
+#[obake(version("0.3.0-beta", feature = "unstable"))]
+struct Qux {}
+
+#[obake::versioned]
+#[obake(feature = "unstable", version("0.1.0"))]
+struct Quux {}
+
+fn main() {}
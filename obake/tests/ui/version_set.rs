@@ -0,0 +1,22 @@
+obake::version_set!(Versions = ["0.1.0", "0.2.0"]);
+obake::version_set!(Other = ["0.1.0"]);
+obake::version_set!(Empty = []);
+obake::version_set!(Invalid = ["0.1"]);
+
+#[obake::versioned]
+#[obake(use_versions(Versions))]
+#[obake(version("0.3.0"))]
+struct Foo {}
+
+#[obake::versioned]
+#[obake(use_versions(Versions), use_versions(Other))]
+struct Bar {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+struct Baz {
+    #[obake(use_versions(Versions))]
+    field_0: u32,
+}
+
+fn main() {}
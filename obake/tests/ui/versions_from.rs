@@ -0,0 +1,5 @@
+#[obake::versioned]
+#[obake(versions_from = "schema_versions.toml")]
+struct Missing {}
+
+fn main() {}
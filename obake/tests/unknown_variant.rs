@@ -0,0 +1,58 @@
+#![cfg(feature = "json")]
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(unknown_variant, trivial_migrations)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+enum Event {
+    Started { at: u64 },
+    Stopped,
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(unknown_variant(latest_only))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Command {
+    Ping,
+    #[obake(cfg(">=0.2"))]
+    Echo(String),
+}
+
+impl From<Command!["0.1.0"]> for Command!["0.2.0"] {
+    fn from(from: Command!["0.1.0"]) -> Self {
+        match from {
+            Command_v0_1_0::Ping => Self::Ping,
+        }
+    }
+}
+
+#[test]
+fn unrecognized_variants_are_captured() {
+    let event: Event = serde_json::from_value(json!({ "kind": "Started", "at": 3 })).unwrap();
+    assert_eq!(event, Event::Started { at: 3 });
+
+    let unknown = json!({ "kind": "Paused", "for": 5 });
+    let old: Event_v0_1_0 = serde_json::from_value(unknown.clone()).unwrap();
+    assert_eq!(old, Event_v0_1_0::Unknown(unknown.clone()));
+    assert_eq!(serde_json::to_value(&old).unwrap(), unknown);
+
+    let event: Event = old.into();
+    assert_eq!(event, Event::Unknown(unknown));
+}
+
+#[test]
+fn unrecognized_variants_in_latest_only() {
+    let command: Command = serde_json::from_value(json!("Restart")).unwrap();
+    assert_eq!(command, Command::Unknown(json!("Restart")));
+    let command: Command = serde_json::from_value(json!({ "Echo": "hi" })).unwrap();
+    assert_eq!(command, Command::Echo("hi".to_owned()));
+
+    assert!(serde_json::from_value::<Command_v0_1_0>(json!("Restart")).is_err());
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "json")]
+#![allow(dead_code)]
+
+use obake::{DeserializeVersion, UnknownVersionError};
+use serde::Deserialize;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(trivial_migrations)]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Deserialize)]
+struct Order {
+    quantity: u32,
+}
+
+#[test]
+fn check() {
+    assert_eq!(UnknownVersionError::check::<Order>("0.2.0"), Ok("0.2.0"));
+    assert_eq!(
+        UnknownVersionError::check::<Order>("0.3.0"),
+        Err(UnknownVersionError {
+            type_name: "Order",
+            found: "0.3.0".to_owned(),
+            supported: &["0.1.0", "0.2.0"],
+        })
+    );
+}
+
+#[test]
+fn display() {
+    assert_eq!(
+        UnknownVersionError::new::<Order>("1.0.0").to_string(),
+        "unsupported version 1.0.0 of `Order` (expected one of 0.1.0, 0.2.0)"
+    );
+}
+
+#[test]
+fn deserialize_version() {
+    let mut deserializer = serde_json::Deserializer::from_str(r#"{ "quantity": 3 }"#);
+    let err = Order::deserialize_version("0.3.0", &mut deserializer)
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "unsupported version 0.3.0 of `Order` (expected one of 0.1.0, 0.2.0)"
+    );
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "json")]
+
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.0.0", untagged_legacy))]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Debug, Serialize, Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    #[obake(cfg("<0.2"))]
+    host: String,
+    #[obake(cfg(">=0.2"))]
+    hosts: Vec<String>,
+}
+
+impl From<Config!["0.0.0"]> for Config!["0.1.0"] {
+    fn from(from: Config!["0.0.0"]) -> Self {
+        Self { host: from.host }
+    }
+}
+
+impl From<Config!["0.1.0"]> for Config!["0.2.0"] {
+    fn from(from: Config!["0.1.0"]) -> Self {
+        Self {
+            hosts: vec![from.host],
+        }
+    }
+}
+
+#[test]
+fn untagged_payloads_are_the_legacy_version() {
+    let legacy: VersionedConfig = serde_json::from_str(r#"{ "host": "a" }"#).unwrap();
+    assert!(matches!(legacy, VersionedConfig::Config_v0_0_0(_)));
+
+    let config: Config = legacy.into();
+    assert_eq!(config.hosts, ["a"]);
+}
+
+#[test]
+fn tagged_payloads_are_their_version() {
+    let tagged: VersionedConfig =
+        serde_json::from_str(r#"{ "version": "0.1.0", "host": "a" }"#).unwrap();
+    assert!(matches!(tagged, VersionedConfig::Config_v0_1_0(_)));
+
+    let latest: VersionedConfig =
+        serde_json::from_str(r#"{ "version": "0.2.0", "hosts": ["a", "b"] }"#).unwrap();
+    assert!(matches!(latest, VersionedConfig::Config_v0_2_0(_)));
+}
+
+#[test]
+fn legacy_versions_serialize_without_a_tag() {
+    let legacy = VersionedConfig::Config_v0_0_0(Config_v0_0_0 { host: "a".into() });
+    assert_eq!(serde_json::to_string(&legacy).unwrap(), r#"{"host":"a"}"#);
+}
+
+#[test]
+fn legacy_versions_are_still_listed_in_order() {
+    assert_eq!(
+        <Config as obake::Versioned>::VERSIONS,
+        ["0.0.0", "0.1.0", "0.2.0"]
+    );
+}
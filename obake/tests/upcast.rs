@@ -0,0 +1,133 @@
+#![cfg(feature = "upcast")]
+
+use obake::upcast::{RawEvent, UpcastError, Upcaster};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Deserialize))]
+#[obake(serde())]
+#[derive(PartialEq, Debug, Deserialize)]
+struct Renamed {
+    #[obake(cfg("0.1.0"))]
+    name: String,
+    #[obake(cfg("0.2.0"))]
+    display_name: String,
+}
+
+impl From<Renamed!["0.1.0"]> for Renamed!["0.2.0"] {
+    fn from(from: Renamed!["0.1.0"]) -> Self {
+        Self {
+            display_name: from.name,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(derive(Deserialize))]
+#[obake(serde())]
+#[derive(PartialEq, Debug, Deserialize)]
+struct Deleted {}
+
+// Only serializable, so this shouldn't implement `obake::DeserializeVersion`
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(derive(Serialize))]
+#[obake(serde())]
+#[derive(Serialize)]
+struct WriteOnly {}
+
+#[derive(PartialEq, Debug)]
+enum Event {
+    Renamed(Renamed),
+    Deleted(Deleted),
+}
+
+impl From<Renamed> for Event {
+    fn from(from: Renamed) -> Self {
+        Self::Renamed(from)
+    }
+}
+
+impl From<Deleted> for Event {
+    fn from(from: Deleted) -> Self {
+        Self::Deleted(from)
+    }
+}
+
+fn raw(event_type: &str, version: &str, payload: Value) -> RawEvent<Value> {
+    RawEvent {
+        event_type: event_type.to_owned(),
+        version: version.to_owned(),
+        payload,
+    }
+}
+
+fn upcaster() -> Upcaster<Value, Event> {
+    let mut upcaster = Upcaster::new();
+    upcaster
+        .register::<Renamed>("renamed")
+        .register::<Deleted>("deleted");
+    upcaster
+}
+
+#[test]
+fn registers_every_version() {
+    let upcaster = upcaster();
+    assert!(upcaster.contains("renamed", "0.1.0"));
+    assert!(upcaster.contains("renamed", "0.2.0"));
+    assert!(upcaster.contains("deleted", "0.1.0"));
+    assert!(!upcaster.contains("deleted", "0.2.0"));
+}
+
+#[test]
+fn upcast_stream_upgrades_mixed_versions() {
+    let events = vec![
+        raw("renamed", "0.1.0", json!({ "name": "a" })),
+        raw("deleted", "0.1.0", json!({})),
+        raw("renamed", "0.2.0", json!({ "display_name": "b" })),
+    ];
+
+    let upcast = upcaster()
+        .upcast_stream(events)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        upcast,
+        vec![
+            Event::Renamed(Renamed {
+                display_name: "a".to_owned()
+            }),
+            Event::Deleted(Deleted {}),
+            Event::Renamed(Renamed {
+                display_name: "b".to_owned()
+            }),
+        ]
+    );
+}
+
+#[test]
+fn upcast_reports_unregistered_events() {
+    match upcaster().upcast(raw("deleted", "0.2.0", json!({}))) {
+        Err(UpcastError::Unregistered {
+            event_type,
+            version,
+        }) => {
+            assert_eq!(event_type, "deleted");
+            assert_eq!(version, "0.2.0");
+        }
+        _ => panic!("expected an unregistered event"),
+    }
+}
+
+#[test]
+fn upcast_reports_bad_payloads() {
+    assert!(matches!(
+        upcaster().upcast(raw("renamed", "0.2.0", json!({ "name": "a" }))),
+        Err(UpcastError::Deserialize(_))
+    ));
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "json")]
+
+use std::fmt;
+
+use obake::{Error, TryUpgrade};
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq)]
+struct Overlap;
+
+impl fmt::Display for Overlap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the primary and fallback ports overlap")
+    }
+}
+
+impl std::error::Error for Overlap {}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(validate = checks::ports)]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Deserialize)]
+struct Listener {
+    port: u16,
+    #[obake(cfg(">=0.2"))]
+    fallback_port: u16,
+}
+
+impl From<Listener!["0.1.0"]> for Listener!["0.2.0"] {
+    fn from(from: Listener!["0.1.0"]) -> Self {
+        Self {
+            port: from.port,
+            fallback_port: 8080,
+        }
+    }
+}
+
+mod checks {
+    pub fn ports(listener: &super::Listener) -> Result<(), super::Overlap> {
+        if listener.port == listener.fallback_port {
+            return Err(super::Overlap);
+        }
+        Ok(())
+    }
+}
+
+fn load(json: &str) -> Result<Listener, Error> {
+    Listener::try_deserialize(&mut serde_json::Deserializer::from_str(json))
+}
+
+#[test]
+fn upgraded_values_are_validated() {
+    assert_eq!(
+        load(r#"{ "version": "0.1.0", "port": 80 }"#).unwrap(),
+        Listener {
+            port: 80,
+            fallback_port: 8080,
+        }
+    );
+
+    // Valid in 0.1.0, but not once upgraded
+    let err = load(r#"{ "version": "0.1.0", "port": 8080 }"#).unwrap_err();
+    assert!(matches!(err, Error::Validation { .. }));
+    let source = std::error::Error::source(&err).unwrap();
+    assert_eq!(source.downcast_ref::<Overlap>(), Some(&Overlap));
+}
+
+#[test]
+fn latest_versions_are_validated() {
+    let err = Listener::try_upgrade(VersionedListener::Listener_v0_2_0(Listener_v0_2_0 {
+        port: 443,
+        fallback_port: 443,
+    }))
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`Listener` failed validation: the primary and fallback ports overlap"
+    );
+}
+
+#[test]
+fn parse_errors_are_reported() {
+    let err = load(r#"{ "version": "0.1.0" }"#).unwrap_err();
+    assert!(matches!(err, Error::Parse { .. }));
+}
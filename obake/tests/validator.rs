@@ -0,0 +1,101 @@
+#![cfg(all(feature = "validator", feature = "json"))]
+
+use std::error::Error as _;
+
+use obake::validator::{Invalid, ValidatedUpgrade};
+use obake::Error;
+use serde::Deserialize;
+use validator::Validate;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(trivial_migrations, validator)]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Debug, Deserialize, Validate)]
+struct Account {
+    #[validate(length(min = 1))]
+    name: String,
+    #[obake(cfg(">=0.3"))]
+    #[validate(email)]
+    email: String,
+}
+
+impl From<Account!["0.2.0"]> for Account!["0.3.0"] {
+    fn from(from: Account!["0.2.0"]) -> Self {
+        Self {
+            email: format!("{}@example.com", from.name),
+            name: from.name,
+        }
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(validator(latest))]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Debug, Deserialize, Validate)]
+struct Limits {
+    #[validate(range(max = 10))]
+    retries: u32,
+}
+
+// Retries used to be counted twice
+impl From<Limits!["0.1.0"]> for Limits!["0.2.0"] {
+    fn from(from: Limits!["0.1.0"]) -> Self {
+        Self {
+            retries: from.retries / 2,
+        }
+    }
+}
+
+fn invalid_version(err: &Error) -> &'static str {
+    assert!(matches!(err, Error::Validation { .. }));
+    err.source()
+        .and_then(|source| source.downcast_ref::<Invalid>())
+        .unwrap()
+        .version
+}
+
+#[test]
+fn validates_every_version() {
+    let account: Account =
+        obake::validator::from_str(r#"{ "version": "0.1.0", "name": "ghost" }"#).unwrap();
+    assert_eq!(account.email, "ghost@example.com");
+
+    let err = obake::validator::from_str::<Account>(r#"{ "version": "0.2.0", "name": "" }"#)
+        .err()
+        .unwrap();
+    assert_eq!(invalid_version(&err), "0.2.0");
+
+    // Only invalid once upgraded
+    let err = Account::upgrade_validated(VersionedAccount::Account_v0_2_0(Account_v0_2_0 {
+        name: "not an address".to_owned(),
+    }))
+    .err()
+    .unwrap();
+    assert_eq!(invalid_version(&err), "0.3.0");
+}
+
+#[test]
+fn validates_latest_version() {
+    // Out of range for 0.1.0, but only the latest version is validated
+    let limits: Limits =
+        obake::validator::from_str(r#"{ "version": "0.1.0", "retries": 16 }"#).unwrap();
+    assert_eq!(limits.retries, 8);
+
+    let err = obake::validator::from_str::<Limits>(r#"{ "version": "0.1.0", "retries": 30 }"#)
+        .err()
+        .unwrap();
+    assert_eq!(invalid_version(&err), "0.2.0");
+}
+
+#[test]
+fn parse_errors() {
+    let err = obake::validator::from_str::<Account>(r#"{ "version": "0.1.0" }"#)
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Parse { .. }));
+}
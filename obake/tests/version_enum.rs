@@ -0,0 +1,88 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.10.0-rc.1"))]
+#[obake(version_enum, trivial_migrations)]
+#[derive(Debug, PartialEq)]
+pub struct Document {
+    pub title: String,
+}
+
+#[test]
+fn versions_of_values() {
+    let versioned = VersionedDocument::Document_v0_2_0(Document_v0_2_0 {
+        title: "Draft".to_owned(),
+    });
+    assert_eq!(versioned.version(), DocumentVersion::V0_2_0);
+    assert_eq!(DocumentVersion::from(&versioned).as_str(), "0.2.0");
+    assert_eq!(DocumentVersion::LATEST, DocumentVersion::V0_10_0_RC_1);
+    assert_eq!(<&str>::from(DocumentVersion::LATEST), "0.10.0-rc.1");
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version_enum(derive(serde::Serialize)), trivial_migrations)]
+pub struct Export {}
+
+#[test]
+fn versions_are_enumerated() {
+    assert_eq!(
+        DocumentVersion::ALL,
+        [
+            DocumentVersion::V0_1_0,
+            DocumentVersion::V0_2_0,
+            DocumentVersion::V0_10_0_RC_1
+        ]
+    );
+    let versions: Vec<_> = DocumentVersion::iter()
+        .map(DocumentVersion::as_str)
+        .collect();
+    assert_eq!(versions, <Document as obake::Versioned>::VERSIONS);
+}
+
+#[test]
+fn versions_are_strings() {
+    assert_eq!(DocumentVersion::V0_2_0.as_ref(), "0.2.0");
+    assert_eq!(DocumentVersion::V0_10_0_RC_1.to_string(), "0.10.0-rc.1");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn versions_are_parsed() {
+    assert_eq!("0.2.0".parse(), Ok(DocumentVersion::V0_2_0));
+    let err = "0.3.0".parse::<DocumentVersion>().unwrap_err();
+    assert_eq!(err.supported, <Document as obake::Versioned>::VERSIONS);
+}
+
+#[test]
+fn derives_apply_to_the_version_enum() {
+    assert_eq!(
+        serde_json::to_string(&ExportVersion::V0_2_0).unwrap(),
+        "\"V0_2_0\""
+    );
+}
+
+#[test]
+fn versions_are_ordered_by_semver() {
+    assert!(DocumentVersion::V0_1_0 < DocumentVersion::V0_2_0);
+    assert!(DocumentVersion::V0_2_0 < DocumentVersion::V0_10_0_RC_1);
+}
+
+#[cfg(feature = "semver")]
+#[test]
+fn semver_conversions() {
+    use obake::semver::Version;
+    use std::convert::TryFrom;
+
+    assert_eq!(
+        Version::from(DocumentVersion::V0_10_0_RC_1),
+        Version::parse("0.10.0-rc.1").unwrap()
+    );
+    assert_eq!(
+        DocumentVersion::try_from(&Version::new(0, 2, 0)),
+        Ok(DocumentVersion::V0_2_0)
+    );
+    let err = DocumentVersion::try_from(&Version::new(0, 3, 0)).unwrap_err();
+    assert_eq!(err.found, "0.3.0");
+}
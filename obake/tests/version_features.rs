@@ -0,0 +1,51 @@
+// `legacy` isn't one of obake's features, so the oldest version is always pruned here
+#![allow(unexpected_cfgs)]
+
+#[obake::versioned]
+#[obake(version("0.1.0", feature = "legacy"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[derive(Debug, PartialEq)]
+struct Foo {
+    #[obake(cfg("<0.2"))]
+    field_0: u32,
+    field_1: u64,
+    #[obake(cfg(">=0.3"))]
+    field_2: bool,
+}
+
+#[cfg(feature = "legacy")]
+impl From<Foo!["0.1.0"]> for Foo!["0.2.0"] {
+    fn from(from: Foo!["0.1.0"]) -> Self {
+        Self {
+            field_1: from.field_1,
+        }
+    }
+}
+
+impl From<Foo!["0.2.0"]> for Foo!["0.3.0"] {
+    fn from(from: Foo!["0.2.0"]) -> Self {
+        Self {
+            field_1: from.field_1,
+            field_2: false,
+        }
+    }
+}
+
+#[test]
+fn gated_versions_are_pruned() {
+    assert_eq!(<Foo as obake::Versioned>::VERSIONS, ["0.2.0", "0.3.0"]);
+}
+
+#[test]
+fn remaining_versions_migrate() {
+    type OldFoo = Foo!["0.2.0"];
+    let foo: Foo = VersionedFoo::Foo_v0_2_0(OldFoo { field_1: 7 }).into();
+    assert_eq!(
+        foo,
+        Foo {
+            field_1: 7,
+            field_2: false
+        }
+    );
+}
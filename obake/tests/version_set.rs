@@ -0,0 +1,77 @@
+obake::version_set!(AppVersions = ["0.1.0", "0.2.0", "0.3.0"]);
+
+#[obake::versioned]
+#[obake(use_versions(AppVersions))]
+#[obake(trivial_migrations)]
+#[derive(Debug, PartialEq, Eq, Default)]
+struct Settings {
+    volume: u8,
+}
+
+#[obake::versioned]
+#[obake(use_versions(AppVersions), derive(Debug))]
+#[derive(Debug, PartialEq, Eq)]
+enum Event {
+    Opened,
+    #[obake(cfg(">=0.2"))]
+    Closed,
+}
+
+impl From<Event!["0.1.0"]> for Event!["0.2.0"] {
+    fn from(from: Event!["0.1.0"]) -> Self {
+        match from {
+            <Event!["0.1.0"]>::Opened => Self::Opened,
+        }
+    }
+}
+
+impl From<Event!["0.2.0"]> for Event!["0.3.0"] {
+    fn from(from: Event!["0.2.0"]) -> Self {
+        match from {
+            <Event!["0.2.0"]>::Opened => Self::Opened,
+            <Event!["0.2.0"]>::Closed => Self::Closed,
+        }
+    }
+}
+
+mod nested {
+    #[obake::versioned]
+    #[obake(use_versions(super::AppVersions))]
+    #[obake(trivial_migrations)]
+    pub struct Window {
+        pub width: u32,
+    }
+}
+
+#[test]
+fn versions_are_shared() {
+    assert_eq!(
+        <Settings as obake::Versioned>::VERSIONS,
+        ["0.1.0", "0.2.0", "0.3.0"]
+    );
+    assert_eq!(
+        <Event as obake::Versioned>::VERSIONS,
+        <Settings as obake::Versioned>::VERSIONS
+    );
+    assert_eq!(
+        <nested::Window as obake::Versioned>::VERSIONS,
+        <Settings as obake::Versioned>::VERSIONS
+    );
+}
+
+#[test]
+fn versions_migrate() {
+    let settings: Settings =
+        VersionedSettings::Settings_v0_1_0(Settings_v0_1_0 { volume: 3 }).into();
+    assert_eq!(settings, Settings { volume: 3 });
+
+    let event: Event = VersionedEvent::Event_v0_1_0(Event_v0_1_0::Opened).into();
+    assert_eq!(event, Event::Opened);
+
+    let event: Event = VersionedEvent::Event_v0_2_0(Event_v0_2_0::Closed).into();
+    assert_eq!(event, Event::Closed);
+
+    let window: nested::Window =
+        nested::VersionedWindow::Window_v0_2_0(nested::Window_v0_2_0 { width: 80 }).into();
+    assert_eq!(window.width, 80);
+}
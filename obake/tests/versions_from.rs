@@ -0,0 +1,25 @@
+#![allow(unexpected_cfgs)]
+
+#[obake::versioned]
+#[obake(versions_from = "tests/manifests/versions.toml")]
+#[obake(trivial_migrations)]
+#[derive(Debug, PartialEq, Eq)]
+struct Release {
+    name: String,
+}
+
+#[test]
+fn versions_from_manifest() {
+    assert_eq!(<Release as obake::Versioned>::VERSIONS, ["0.2.0", "0.3.0"]);
+
+    let release: Release = VersionedRelease::Release_v0_2_0(Release_v0_2_0 {
+        name: "ferris".to_owned(),
+    })
+    .into();
+    assert_eq!(
+        release,
+        Release {
+            name: "ferris".to_owned()
+        }
+    );
+}
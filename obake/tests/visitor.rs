@@ -0,0 +1,64 @@
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(trivial_migrations, visitor)]
+struct Page {
+    body: String,
+    #[obake(cfg(">=0.3"))]
+    title: String,
+}
+
+impl From<Page!["0.2.0"]> for Page!["0.3.0"] {
+    fn from(from: Page!["0.2.0"]) -> Self {
+        Self {
+            body: from.body,
+            title: String::new(),
+        }
+    }
+}
+
+/// Renders every historical format of a page, counting the pages rendered.
+#[derive(Default)]
+struct Renderer {
+    rendered: usize,
+}
+
+impl Renderer {
+    fn render(&mut self, body: &str) -> String {
+        self.rendered += 1;
+        format!("<p>{body}</p>")
+    }
+}
+
+impl PageVersionVisitor for Renderer {
+    type Output = String;
+
+    fn visit_v0_1_0(&mut self, value: &Page_v0_1_0) -> String {
+        self.render(&value.body)
+    }
+
+    fn visit_v0_2_0(&mut self, value: &Page_v0_2_0) -> String {
+        self.render(&value.body.replace('\n', "<br>"))
+    }
+
+    fn visit_v0_3_0(&mut self, value: &Page_v0_3_0) -> String {
+        format!("<h1>{}</h1>{}", value.title, self.render(&value.body))
+    }
+}
+
+#[test]
+fn visitor() {
+    let mut renderer = Renderer::default();
+    let old = VersionedPage::Page_v0_2_0(Page_v0_2_0 {
+        body: "a\nb".to_owned(),
+    });
+    assert_eq!(old.accept(&mut renderer), "<p>a<br>b</p>");
+
+    let latest = VersionedPage::from(Page {
+        body: "hello".to_owned(),
+        title: "obake".to_owned(),
+    });
+    assert_eq!(latest.accept(&mut renderer), "<h1>obake</h1><p>hello</p>");
+    assert_eq!(renderer.rendered, 2);
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "wasm")]
+
+use serde::{Deserialize, Serialize};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(wasm)]
+#[obake(derive(Serialize, Deserialize), serde(tag = "version"))]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Draft {
+    pub title: String,
+    #[obake(cfg(">=0.2"))]
+    pub words: u32,
+}
+
+impl From<Draft!["0.1.0"]> for Draft!["0.2.0"] {
+    fn from(from: Draft!["0.1.0"]) -> Self {
+        Self {
+            title: from.title,
+            words: 0,
+        }
+    }
+}
+
+#[test]
+fn upgrade_json() {
+    assert_eq!(
+        Draft::upgrade_json(r#"{"version":"0.1.0","title":"notes"}"#).unwrap(),
+        r#"{"version":"0.2.0","title":"notes","words":0}"#
+    );
+    assert_eq!(
+        obake::wasm::upgrade_json::<Draft>(r#"{"version":"0.2.0","title":"notes","words":3}"#)
+            .unwrap(),
+        r#"{"version":"0.2.0","title":"notes","words":3}"#
+    );
+    assert!(obake::wasm::upgrade_json::<Draft>(r#"{"version":"0.3.0"}"#).is_err());
+}
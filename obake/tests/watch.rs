@@ -0,0 +1,121 @@
+#![cfg(feature = "watch")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use obake::watch::{Error, Watcher};
+use serde::Deserialize;
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(derive(Deserialize), serde(tag = "version"))]
+#[derive(Debug, PartialEq, Deserialize)]
+struct Config {
+    #[obake(cfg("0.1.0"))]
+    threads: u32,
+    #[obake(cfg(">=0.2"))]
+    workers: u32,
+}
+
+impl From<Config!["0.1.0"]> for Config!["0.2.0"] {
+    fn from(from: Config!["0.1.0"]) -> Self {
+        Self {
+            workers: from.threads,
+        }
+    }
+}
+
+fn config_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Waits for the next update which isn't the result of reading a partially written file.
+fn next<T>(watcher: &Watcher<T>) -> Result<T, Error> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        match watcher.recv_timeout(timeout).expect("timed out") {
+            Err(Error::Json(err)) if err.is_eof() => continue,
+            update => return update,
+        }
+    }
+}
+
+#[test]
+fn delivers_upgraded_changes() {
+    let path = config_dir("obake-watch-changes").join("config.json");
+    fs::write(&path, r#"{"version":"0.1.0","threads":2}"#).unwrap();
+
+    let watcher = obake::watch::watch::<Config>(&path).unwrap();
+    assert_eq!(next(&watcher).unwrap(), Config { workers: 2 });
+
+    fs::write(&path, r#"{"version":"0.2.0","workers":4}"#).unwrap();
+    assert_eq!(next(&watcher).unwrap(), Config { workers: 4 });
+
+    fs::write(&path, r#"{"version":"0.1.0","threads":8}"#).unwrap();
+    assert_eq!(next(&watcher).unwrap(), Config { workers: 8 });
+}
+
+#[test]
+fn delivers_changes_made_while_loading() {
+    let path = config_dir("obake-watch-loading").join("config.json");
+    fs::write(&path, r#"{"version":"0.1.0","threads":2}"#).unwrap();
+
+    // Rewrites the file while it's first being loaded, which is only delivered if the file was
+    // already being watched
+    let rewritten = AtomicBool::new(false);
+    let file = path.clone();
+    let watcher = obake::watch::watch_with::<Config, _>(&path, move |_| {
+        if !rewritten.swap(true, Ordering::SeqCst) {
+            fs::write(&file, r#"{"version":"0.2.0","workers":4}"#).unwrap();
+        }
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(next(&watcher).unwrap(), Config { workers: 2 });
+    assert_eq!(next(&watcher).unwrap(), Config { workers: 4 });
+}
+
+#[test]
+fn picks_up_created_files() {
+    let path = config_dir("obake-watch-created").join("config.json");
+
+    let watcher = obake::watch::watch::<Config>(&path).unwrap();
+    assert!(watcher.try_recv().is_none());
+
+    fs::write(&path, r#"{"version":"0.2.0","workers":1}"#).unwrap();
+    assert_eq!(next(&watcher).unwrap(), Config { workers: 1 });
+}
+
+#[test]
+fn reports_invalid_configurations() {
+    let path = config_dir("obake-watch-invalid").join("config.json");
+    fs::write(&path, r#"{"version":"0.1.0","threads":0}"#).unwrap();
+
+    let watcher = obake::watch::watch_with::<Config, _>(&path, |config| {
+        if config.workers == 0 {
+            return Err("`workers` must be positive".into());
+        }
+        Ok(())
+    })
+    .unwrap();
+
+    let err = next(&watcher).unwrap_err();
+    assert!(matches!(err, Error::Invalid(_)));
+    assert_eq!(
+        err.to_string(),
+        "configuration failed validation: `workers` must be positive"
+    );
+
+    fs::write(&path, r#"{"version":"0.3.0","workers":1}"#).unwrap();
+    assert!(matches!(next(&watcher).unwrap_err(), Error::Json(_)));
+
+    fs::write(&path, r#"{"version":"0.2.0","workers":3}"#).unwrap();
+    assert_eq!(next(&watcher).unwrap(), Config { workers: 3 });
+}
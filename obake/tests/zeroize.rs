@@ -0,0 +1,96 @@
+#![cfg(feature = "zeroize")]
+#![allow(dead_code)]
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
+#[obake(zeroize, trivial_migrations, partial_migrations)]
+#[derive(Debug, Default, PartialEq)]
+struct Credentials {
+    user: String,
+    #[obake(cfg("<0.3"))]
+    password: String,
+    #[obake(cfg(">=0.3"))]
+    password_hash: Vec<u8>,
+}
+
+impl From<Credentials!["0.2.0"]> for Credentials!["0.3.0"] {
+    fn from(from: Credentials!["0.2.0"]) -> Self {
+        from.migrate_with(|removed| Credentials_v0_3_0_Added {
+            password_hash: removed.password.bytes().rev().collect(),
+        })
+    }
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(zeroize, trivial_migrations)]
+#[derive(Debug, PartialEq)]
+enum Secret {
+    Token(String),
+    Pair { id: u32, key: [u8; 4] },
+}
+
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.2.0"))]
+#[obake(zeroize)]
+#[derive(Debug, Default, PartialEq)]
+struct ApiKey(
+    #[obake(cfg("0.1.0"))] String,
+    #[obake(cfg("0.2.0"), map_from(String::into_bytes))] Vec<u8>,
+);
+
+fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+
+#[test]
+fn migrations_take_secret_fields() {
+    let old = VersionedCredentials::Credentials_v0_1_0(Credentials_v0_1_0 {
+        user: "ghost".to_owned(),
+        password: "abc".to_owned(),
+    });
+    let latest: Credentials = old.into();
+    assert_eq!(latest.user, "ghost");
+    assert_eq!(latest.password_hash, b"cba");
+
+    let secret: Secret = VersionedSecret::Secret_v0_1_0(Secret_v0_1_0::Pair {
+        id: 1,
+        key: [1, 2, 3, 4],
+    })
+    .into();
+    assert_eq!(
+        secret,
+        Secret::Pair {
+            id: 1,
+            key: [1, 2, 3, 4]
+        }
+    );
+
+    let key: ApiKey = VersionedApiKey::ApiKey_v0_1_0(ApiKey_v0_1_0("k".to_owned())).into();
+    assert_eq!(key.0, b"k");
+}
+
+#[test]
+fn zeroizes_every_version() {
+    let mut old = Credentials_v0_2_0 {
+        user: "ghost".to_owned(),
+        password: "abc".to_owned(),
+    };
+    old.zeroize();
+    assert_eq!(old, Credentials_v0_2_0::default());
+
+    let mut versioned = VersionedSecret::from(Secret::Token("token".to_owned()));
+    versioned.zeroize();
+    assert!(
+        matches!(versioned, VersionedSecret::Secret_v0_2_0(Secret::Token(ref token)) if token.is_empty())
+    );
+
+    assert_zeroize_on_drop::<Credentials_v0_1_0>();
+    assert_zeroize_on_drop::<Credentials>();
+    assert_zeroize_on_drop::<VersionedCredentials>();
+    assert_zeroize_on_drop::<ApiKey_v0_1_0>();
+}
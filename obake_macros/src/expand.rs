@@ -1,6 +1,12 @@
+use std::borrow::Borrow;
+use std::fmt::Write;
+
+use syn::spanned::Spanned;
 use syn::Result;
 
-use quote::{format_ident, quote, ToTokens, TokenStreamExt};
+use quote::{format_ident, quote, quote_spanned, ToTokens, TokenStreamExt};
+
+use obake_syntax::version_suffix;
 
 use crate::internal::*;
 
@@ -13,352 +19,5693 @@ macro_rules! try_expand {
     };
 }
 
-trait VersionExt {
-    fn version(&self, version: &Version) -> Self;
+/// A way of constructing a version of an item field-by-field.
+struct Constructor<'a> {
+    path: TokenStream2,
+    fields: ConstructorFields<'a>,
 }
 
-impl VersionExt for syn::Ident {
-    fn version(&self, version: &Version) -> Self {
-        format_ident!(
-            "{}_v{}_{}_{}",
-            self,
-            version.major,
-            version.minor,
-            version.patch
-        )
-    }
+enum ConstructorFields<'a> {
+    Named(Vec<&'a VersionedField>),
+    Unnamed(Vec<&'a VersionedField>),
+    Unit,
 }
 
-impl VersionedField {
-    fn expand_ty_versioned(&self, version: &Version) -> Result<TokenStream2> {
-        if self.attrs.inherits().next().is_none() {
-            let ty = &self.ty;
-            return Ok(quote!(#ty));
-        }
-
-        if let syn::Type::Path(ty_path) = &self.ty {
-            let mut ty_path = ty_path.clone();
+enum FieldRef<'a> {
+    Named(&'a VersionedField),
+    Unnamed(&'a VersionedField),
+}
 
-            if let Some(mut terminator) = ty_path.path.segments.last_mut() {
-                terminator.ident = terminator.ident.version(version);
-                return Ok(quote!(#ty_path));
+impl<'a> Constructor<'a> {
+    fn fields(&self) -> Vec<FieldRef<'a>> {
+        match &self.fields {
+            ConstructorFields::Named(fields) => fields.iter().map(|f| FieldRef::Named(f)).collect(),
+            ConstructorFields::Unnamed(fields) => {
+                fields.iter().map(|f| FieldRef::Unnamed(f)).collect()
             }
+            ConstructorFields::Unit => vec![],
         }
-
-        Err(syn::Error::new(
-            self.attrs.inherits().next().unwrap().span,
-            "`#[obake(inherit)]` can only be applied to fields with `#[obake::versioned]` types",
-        ))
     }
 
-    fn expand_version(&self, version: &Version) -> Result<TokenStream2> {
-        if let Some(derive) = self.attrs.derives().next() {
-            return Err(syn::Error::new(
-                derive.span,
-                "`#[obake(derive(...))]` not valid in this context",
-            ));
+    /// Expands to an expression constructing this version, with (in order) each field initialised
+    /// by `field_expr`.
+    fn expand(&self, mut field_expr: impl FnMut(&FieldRef<'a>) -> TokenStream2) -> TokenStream2 {
+        let path = &self.path;
+        let fields = self.fields();
+        let exprs = fields.iter().map(&mut field_expr);
+        match &self.fields {
+            ConstructorFields::Named(named) => {
+                let cfgs = named.iter().map(|field| field.expand_cfgs());
+                let members = named.iter().map(|field| field.member());
+                quote!(#path { #(#cfgs #members: #exprs,)* })
+            }
+            ConstructorFields::Unnamed(_) => quote!(#path(#(#exprs,)*)),
+            ConstructorFields::Unit => quote!(#path),
         }
+    }
+}
 
-        let mut reqs: Vec<_> = self.attrs.cfgs().map(|attr| attr.req.clone()).collect();
+/// Every declared version of an item, used to document where generated items come from.
+struct Provenance<'a> {
+    item: &'a syn::Ident,
+    versions: &'a [VersionAttr],
+    /// Whether the version being expanded derives `serde` traits, in which case flattened fields
+    /// are marked `#[serde(flatten)]`.
+    derives_serde: bool,
+    /// Whether the version being expanded is exported as a `pyo3` class, in which case `pub`
+    /// fields are exposed as properties.
+    pyclass: bool,
+}
 
-        // If we have no `#[obake(cfg(...))]` attributes, default to `#[obake(cfg("*"))]`
-        if reqs.is_empty() {
-            reqs.push(VersionReq::STAR);
-        }
+impl Provenance<'_> {
+    fn expand_item_doc(&self, attrs: &VersionedAttributes, version: &Version) -> TokenStream2 {
+        expand_doc(
+            attrs,
+            &format!("Generated from version {version} of `{}`.", self.item),
+        )
+    }
 
-        // If we can't find a matching `#[obake(cfg(...))]` attribute, this field is disabled
-        // in this version, so return nothing
-        if !reqs.iter().any(|req| req.matches(version)) {
-            return Ok(quote!());
-        }
+    /// Documents when a field or variant (a `kind`) was added to and removed from the item,
+    /// relative to `version`. Expands to nothing when it is present in every version.
+    fn expand_member_doc(
+        &self,
+        kind: &str,
+        attrs: &VersionedAttributes,
+        version: &Version,
+    ) -> TokenStream2 {
+        let current = self
+            .versions
+            .iter()
+            .position(|attr| &attr.version == version)
+            .unwrap();
+        let added = self.versions[..=current]
+            .iter()
+            .rev()
+            .take_while(|attr| attrs.enabled_in(&attr.version))
+            .last()
+            .filter(|attr| attr.version != self.versions[0].version);
+        let removed = self.versions[current..]
+            .iter()
+            .find(|attr| !attrs.enabled_in(&attr.version));
 
-        let attrs = self.attrs.attrs();
-        let vis = &self.vis;
-        let ident = &self.ident;
-        let colon_token = &self.colon_token;
-        let ty = self.expand_ty_versioned(version)?;
+        let history = match (added, removed) {
+            (None, None) => return quote!(),
+            (Some(added), None) => format!("{kind} added in {}", added.version),
+            (None, Some(removed)) => format!("{kind} removed in {}", removed.version),
+            (Some(added), Some(removed)) => format!(
+                "{kind} added in {}, removed in {}",
+                added.version, removed.version
+            ),
+        };
+        expand_doc(
+            attrs,
+            &format!(
+                "Generated from version {version} of `{}` ({history}).",
+                self.item
+            ),
+        )
+    }
+}
 
-        Ok(quote! {
-            #(#attrs)*
-            #vis #ident #colon_token #ty,
-        })
+/// Appends `doc` to the documentation found in `attrs`, as a separate paragraph.
+fn expand_doc(attrs: &VersionedAttributes, doc: &str) -> TokenStream2 {
+    // Keep the annotation out of the summary line of any existing documentation
+    let separator = if attrs.attrs().any(|attr| attr.path.is_ident("doc")) {
+        quote!(#[doc = ""])
+    } else {
+        quote!()
+    };
+
+    quote! {
+        #separator
+        #[doc = #doc]
     }
 }
 
-impl VersionedFields {
-    fn expand_version(&self, version: &Version) -> Result<TokenStream2> {
-        let fields = self
-            .fields
-            .iter()
-            .map(|field| field.expand_version(version))
-            .collect::<Result<Vec<_>>>()?
-            .into_iter();
+/// Expands to a conversion of `x` from the type `from` to the type `to`, such that a missing
+/// `From` impl is reported at `span`.
+fn expand_conversion(from: &syn::Ident, to: &syn::Ident, span: Span) -> TokenStream2 {
+    let (mut from, mut to) = (from.clone(), to.clone());
+    from.set_span(span);
+    to.set_span(span);
+    // `x` keeps the call-site span, so it still refers to the binding when `span` comes from
+    // another expansion (e.g. a version from `obake::version_set!`)
+    let x = syn::Ident::new("x", Span::call_site());
+    quote_spanned!(span=> <#to as ::core::convert::From<#from>>::from(#x))
+}
 
-        Ok(quote!({
-            #(#fields)*
-        }))
+/// The `#[serde(...)]` options of `attrs`, including those of `#[obake(serde_cfg(...))]`
+/// attributes applying to `version`.
+#[cfg(feature = "serde")]
+fn serde_metas(attrs: &VersionedAttributes, version: &Version) -> Result<Vec<syn::NestedMeta>> {
+    let parse = syn::punctuated::Punctuated::<syn::NestedMeta, syn::Token![,]>::parse_terminated;
+    let mut metas = Vec::new();
+    for attr in attrs.attrs().filter(|attr| attr.path.is_ident("serde")) {
+        metas.extend(attr.parse_args_with(parse)?);
     }
+    for attr in attrs.serde_cfgs().filter(|attr| attr.req.matches(version)) {
+        metas.extend(syn::parse::Parser::parse2(parse, attr.tokens.clone())?);
+    }
+    Ok(metas)
 }
 
-impl VersionedVariantFields {
-    fn expand_version(&self, version: &Version) -> Result<TokenStream2> {
-        Ok(match &self {
-            Self::Unnamed(unnamed) => quote!(#unnamed),
-            Self::Named(named) => {
-                let fields = named.expand_version(version)?;
-                quote!(#fields)
-            }
-            Self::Unit => quote!(),
+/// Whether `attrs` have a `#[serde(flag)]` option in `version`.
+#[cfg(feature = "serde")]
+fn serde_flag(attrs: &VersionedAttributes, version: &Version, flag: &str) -> bool {
+    serde_metas(attrs, version).is_ok_and(|metas| {
+        metas.iter().any(|meta| {
+            matches!(meta, syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident(flag))
         })
-    }
+    })
 }
 
-impl VersionedVariant {
-    fn expand_version(&self, version: &Version) -> Result<TokenStream2> {
-        if let Some(derive) = self.attrs.inherits().next() {
-            return Err(syn::Error::new(
-                derive.span,
-                "`#[obake(inherit)]` not valid in this context",
-            ));
-        }
+/// The value of a `#[serde(option = "...")]` (or `#[serde(option(serialize = "..."))]`) option
+/// of `attrs` in `version`.
+#[cfg(feature = "serde")]
+fn serde_option(
+    attrs: &VersionedAttributes,
+    version: &Version,
+    option: &str,
+) -> Result<Option<String>> {
+    let lit_str = |lit: &syn::Lit| match lit {
+        syn::Lit::Str(lit) => Some(lit.value()),
+        _ => None,
+    };
 
-        if let Some(inherit) = self.attrs.derives().next() {
-            return Err(syn::Error::new(
-                inherit.span,
-                "`#[obake(derive(...))]` not valid in this context",
-            ));
-        }
+    // Later options override earlier ones, as with `serde` itself
+    Ok(serde_metas(attrs, version)?
+        .iter()
+        .rev()
+        .find_map(|meta| match meta {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(meta)) if meta.path.is_ident(option) => {
+                lit_str(&meta.lit)
+            }
+            syn::NestedMeta::Meta(syn::Meta::List(list)) if list.path.is_ident(option) => {
+                list.nested.iter().find_map(|nested| match nested {
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(meta))
+                        if meta.path.is_ident("serialize") =>
+                    {
+                        lit_str(&meta.lit)
+                    }
+                    _ => None,
+                })
+            }
+            _ => None,
+        }))
+}
 
-        let mut reqs: Vec<_> = self.attrs.cfgs().map(|attr| attr.req.clone()).collect();
+/// Whether `attr` is only meaningful to `serde` derives, i.e. `#[serde(...)]` or one of the
+/// `serde_with` attributes (`#[serde_as]` and `#[serde_as(as = "...")]`).
+fn is_serde_attr(attr: &syn::Attribute) -> bool {
+    attr.path.is_ident("serde") || attr.path.is_ident("serde_as")
+}
 
-        // If we have no `#[obake(cfg(...))]` attributes, default to `#[obake(cfg("*"))]`
-        if reqs.is_empty() {
-            reqs.push(VersionReq::STAR);
-        }
+/// Whether `attr` derives any of `traits`, directly or within `#[cfg_attr(...)]`. Derives are
+/// matched by the last segment of their path, so `serde::Serialize` is `Serialize` but
+/// `SerializeDisplay` isn't.
+fn derives_any(attr: &syn::Attribute, traits: &[&str]) -> bool {
+    if attr.path.is_ident("derive") {
+        let parse = syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated;
+        return attr.parse_args_with(parse).is_ok_and(|paths| {
+            paths.iter().any(|path| {
+                path.segments
+                    .last()
+                    .is_some_and(|segment| traits.iter().any(|name| segment.ident == name))
+            })
+        });
+    }
 
-        // If we can't find a matching `#[obake(cfg(...))]` variant, this field is disabled
-        // in this version, so return nothing
-        if !reqs.iter().any(|req| req.matches(version)) {
-            return Ok(quote!());
-        }
+    attr.path.is_ident("cfg_attr")
+        && attr
+            .parse_args::<ConditionalAttrs>()
+            .is_ok_and(|conditional| {
+                conditional
+                    .attrs
+                    .iter()
+                    .any(|attr| derives_any(attr, traits))
+            })
+}
 
-        let attrs = self.attrs.attrs();
-        let ident = &self.ident;
-        let fields = self.fields.expand_version(version)?;
+/// The attributes an `#[obake(latest_only(...))]` applies to the latest version, given its
+/// tokens.
+fn latest_only_attrs(tokens: &TokenStream2) -> Vec<syn::Attribute> {
+    syn::parse::Parser::parse2(syn::Attribute::parse_outer, quote!(#[#tokens])).unwrap_or_default()
+}
 
-        Ok(quote! {
-            #(#attrs)*
-            #ident #fields,
-        })
+/// Renames a `snake_case` field as `#[serde(rename_all = "...")]` does.
+#[cfg(feature = "serde")]
+fn rename_field(rule: &str, field: &str) -> String {
+    let pascal = || {
+        field
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_uppercase().chain(chars).collect()
+                })
+            })
+            .collect::<String>()
+    };
+
+    match rule {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field.to_ascii_uppercase(),
+        "PascalCase" => pascal(),
+        "camelCase" => {
+            let pascal = pascal();
+            let mut chars = pascal.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_lowercase().chain(chars).collect()
+            })
+        }
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.to_ascii_uppercase().replace('_', "-"),
+        _ => field.to_owned(),
     }
 }
 
-impl VersionedVariants {
-    fn expand_version(&self, version: &Version) -> Result<TokenStream2> {
-        let variants = self
-            .variants
-            .iter()
-            .map(|variant| variant.expand_version(version))
-            .collect::<Result<Vec<_>>>()?
-            .into_iter();
+/// Expands to the `#[cfg(...)]` attributes under which items involving both of a `pair` of
+/// versions (e.g. migrations) are generated.
+fn expand_pair_cfg<A: Borrow<VersionAttr>>(pair: &[A]) -> TokenStream2 {
+    let prev = pair[0].borrow().expand_cfg();
+    let next = pair[1].borrow().expand_cfg();
+    quote!(#prev #next)
+}
 
-        Ok(quote!({
-            #(#variants)*
-        }))
+fn unzip3<A, B, C>(items: Vec<(A, B, C)>) -> (Vec<A>, Vec<B>, Vec<C>) {
+    let mut unzipped = (vec![], vec![], vec![]);
+    for (a, b, c) in items {
+        unzipped.0.push(a);
+        unzipped.1.push(b);
+        unzipped.2.push(c);
     }
+    unzipped
 }
 
-impl VersionedItem {
-    fn extract_versions(&self) -> Result<Vec<VersionAttr>> {
-        let mut versions: Vec<_> = self.attrs.versions().cloned().collect();
-        versions.sort();
+/// The 64-bit FNV-1a hash of `bytes`, which is stable across compilers and platforms.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
 
-        // Duplicate version declarations result in an ambiguity in the
-        // choice of migration, so check that we don't have any duplicates.
-        //
-        // As versions are sorted and totally ordered, it's enough to check that
-        // pairwise adjacent versions are unequal.
-        for i in 1..versions.len() {
-            let head = &versions[i];
-            if head == &versions[i - 1] {
-                return Err(syn::Error::new(
-                    head.span,
-                    format!("duplicate definition of version {}", head.version),
-                ));
+/// Converts an `UpperCamelCase` identifier into `snake_case`.
+fn snake_case(ident: &syn::Ident) -> syn::Ident {
+    let mut snake = String::new();
+    for (i, c) in ident.to_string().chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 && !snake.ends_with('_') {
+                snake.push('_');
             }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
         }
+    }
 
-        Ok(versions)
+    syn::Ident::new(&snake, ident.span())
+}
+
+trait VersionExt {
+    fn version(&self, version: &Version) -> Self;
+}
+
+impl VersionExt for syn::Ident {
+    fn version(&self, version: &Version) -> Self {
+        format_ident!("{}_v{}", self, version_suffix(version))
     }
+}
 
-    fn check_preconditions(&self) -> Result<()> {
-        if let Some(inherit) = self.attrs.inherits().next() {
+/// The variant naming `attr` in the `FooVersion` `enum` generated by `#[obake(version_enum)]`
+/// (e.g. `V0_1_0`).
+fn version_enum_variant(attr: &VersionAttr) -> syn::Ident {
+    let suffix = version_suffix(&attr.version).to_uppercase();
+    format_ident!("V{}", suffix, span = attr.span)
+}
+
+impl VersionedAttributes {
+    fn enabled_in(&self, version: &Version) -> bool {
+        let mut reqs: Vec<_> = self.cfgs().map(|attr| attr.req.clone()).collect();
+
+        // If we have no `#[obake(cfg(...))]` attributes, default to `#[obake(cfg("*"))]`
+        if reqs.is_empty() {
+            reqs.push(CfgPredicate::Req(VersionReq::STAR));
+        }
+
+        // If we can't find a matching `#[obake(cfg(...))]` attribute, we're disabled in this
+        // version
+        reqs.iter().any(|req| req.matches(version))
+    }
+
+    /// Expands to the `#[serde(...)]` attributes of the `#[obake(serde_cfg(...))]` attributes
+    /// applying to `version`, if it derives `serde` traits.
+    fn expand_serde_cfgs<'a>(
+        &'a self,
+        version: &'a Version,
+        derives_serde: bool,
+    ) -> impl Iterator<Item = TokenStream2> + 'a {
+        self.serde_cfgs()
+            .filter(move |attr| derives_serde && attr.req.matches(version))
+            .map(|attr| {
+                let tokens = &attr.tokens;
+                quote!(#[serde(#tokens)])
+            })
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn check_no_item_only_attrs(&self) -> Result<()> {
+        if let Some(generate_tests) = self.generate_tests().next() {
             return Err(syn::Error::new(
-                inherit.span,
-                "`#[obake(inherit)]` not valid in this context",
+                generate_tests.span,
+                "`#[obake(generate_tests)]` not valid in this context",
             ));
         }
 
-        if let Some(req) = self.attrs.cfgs().next() {
+        if let Some(downgrade) = self.downgrades().next() {
             return Err(syn::Error::new(
-                req.span,
-                "`#[obake(cfg(...))]` not valid in this context",
+                downgrade.span,
+                "`#[obake(downgrade)]` not valid in this context",
             ));
         }
 
-        if self.attrs.versions().next().is_none() {
+        if let Some(emit_original) = self.emit_originals().next() {
             return Err(syn::Error::new(
-                self.keyword_span(),
-                "`#[obake::versioned]` items require at least one `#[obake(version(...))]` attribute",
+                emit_original.span,
+                "`#[obake(emit_original)]` not valid in this context",
             ));
         }
 
-        Ok(())
-    }
+        if let Some(hide_old_docs) = self.hide_old_docs().next() {
+            return Err(syn::Error::new(
+                hide_old_docs.span,
+                "`#[obake(hide_old_docs)]` not valid in this context",
+            ));
+        }
 
-    fn expand_version(&self, version: &Version) -> Result<TokenStream2> {
-        let attrs = self.attrs.attrs();
-        let vis = &self.vis;
-        let ident = self.ident().version(version);
-        let body = match &self.kind {
-            VersionedItemKind::Struct(inner) => {
-                let struct_token = &inner.struct_token;
-                let fields = inner.fields.expand_version(version)?;
-                quote!(#struct_token #ident #fields)
-            }
-            VersionedItemKind::Enum(inner) => {
-                let enum_token = &inner.enum_token;
-                let variants = inner.variants.expand_version(version)?;
-                quote!(#enum_token #ident #variants)
-            }
-        };
+        if let Some(default) = self.defaults().next() {
+            return Err(syn::Error::new(
+                default.span,
+                "`#[obake(default)]` not valid in this context",
+            ));
+        }
 
-        Ok(quote! {
-            #[doc(hidden)]
-            #[allow(non_camel_case_types)]
-            #(#attrs)*
-            #vis #body
-        })
-    }
+        if let Some(max_versions) = self.max_versions().next() {
+            return Err(syn::Error::new(
+                max_versions.span,
+                "`#[obake(max_versions = ...)]` not valid in this context",
+            ));
+        }
 
-    fn expand_variants(&self) -> impl Iterator<Item = syn::Ident> + '_ {
-        self.attrs
-            .versions()
-            .map(move |attr| self.ident().version(&attr.version))
-    }
+        if let Some(patch) = self.patches().next() {
+            return Err(syn::Error::new(
+                patch.span,
+                "`#[obake(patch(...))]` not valid in this context",
+            ));
+        }
 
-    fn expand(&self) -> TokenStream2 {
-        try_expand!(self.check_preconditions());
+        if let Some(validate) = self.validates().next() {
+            return Err(syn::Error::new(
+                validate.span,
+                "`#[obake(validate = ...)]` not valid in this context",
+            ));
+        }
 
-        let versions = try_expand!(self.extract_versions());
-        let current = versions.last().unwrap();
+        if let Some(graph) = self.graphs().next() {
+            return Err(syn::Error::new(
+                graph.span,
+                "`#[obake(graph)]` not valid in this context",
+            ));
+        }
 
-        let defs = try_expand!(versions
-            .iter()
-            .map(|attr| self.expand_version(&attr.version))
-            .collect::<Result<Vec<_>>>())
-        .into_iter();
+        if let Some(aliases) = self.aliases().next() {
+            return Err(syn::Error::new(
+                aliases.span,
+                "`#[obake(aliases)]` not valid in this context",
+            ));
+        }
 
-        let alias = self.ident().version(&current.version);
-        let alias_decl = {
-            let vis = &self.vis;
-            let ident = self.ident();
-            quote!(#vis type #ident = #alias;)
-        };
+        if let Some(semantic_eq) = self.semantic_eqs().next() {
+            return Err(syn::Error::new(
+                semantic_eq.span,
+                "`#[obake(semantic_eq)]` not valid in this context",
+            ));
+        }
 
-        let enum_ident = format_ident!("Versioned{}", self.ident());
-        let enum_decl = {
-            let vis = &self.vis;
-            let variants = self.expand_variants();
-            let derives = self.attrs.derives().map(|attr| {
-                let tokens = &attr.tokens;
-                quote!(#[derive(#tokens)])
-            });
+        if let Some(visitor) = self.visitors().next() {
+            return Err(syn::Error::new(
+                visitor.span,
+                "`#[obake(visitor)]` not valid in this context",
+            ));
+        }
 
-            quote! {
-                #[doc(hidden)]
-                #(#derives)*
-                #vis enum #enum_ident {
-                    #(
-                        #[allow(non_camel_case_types)]
-                        #variants(#variants),
-                    )*
-                }
-            }
-        };
+        if let Some(version_enum) = self.version_enums().next() {
+            return Err(syn::Error::new(
+                version_enum.span,
+                "`#[obake(version_enum)]` not valid in this context",
+            ));
+        }
 
-        let from_impl = {
-            let ident = self.ident();
-            let migrations =
-                versions
-                    .iter()
-                    .skip(1)
-                    .zip(self.expand_variants())
-                    .map(|(attr, prev)| {
-                        let next = ident.version(&attr.version);
-                        quote!(#enum_ident::#prev(x) => #enum_ident::#next(x.into()),)
-                    });
+        if let Some(assert_layout) = self.assert_layouts().next() {
+            return Err(syn::Error::new(
+                assert_layout.span,
+                "`#[obake(assert_layout(...))]` not valid in this context",
+            ));
+        }
 
+        if let Some(common_fields) = self.common_fields().next() {
+            return Err(syn::Error::new(
+                common_fields.span,
+                "`#[obake(common_fields)]` not valid in this context",
+            ));
+        }
+
+        if let Some(error) = self.errors().next() {
+            return Err(syn::Error::new(
+                error.span,
+                "`#[obake(error)]` not valid in this context",
+            ));
+        }
+
+        if let Some(builder) = self.builders().next() {
+            return Err(syn::Error::new(
+                builder.span,
+                "`#[obake(builder)]` not valid in this context",
+            ));
+        }
+
+        if let Some(use_versions) = self.use_versions().next() {
+            return Err(syn::Error::new(
+                use_versions.span,
+                "`#[obake(use_versions(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(versions_from) = self.versions_froms().next() {
+            return Err(syn::Error::new(
+                versions_from.span,
+                "`#[obake(versions_from = \"...\")]` not valid in this context",
+            ));
+        }
+
+        if let Some(version_set) = self.version_sets().next() {
+            return Err(syn::Error::new(
+                version_set.span,
+                "`#[obake(__version_set(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(renamed_from) = self.renamed_froms().next() {
+            return Err(syn::Error::new(
+                renamed_from.span,
+                "`#[obake(renamed_from(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(split_into) = self.split_intos().next() {
+            return Err(syn::Error::new(
+                split_into.span,
+                "`#[obake(split_into(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(merge_from) = self.merge_froms().next() {
+            return Err(syn::Error::new(
+                merge_from.span,
+                "`#[obake(merge_from(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(coverage) = self.coverages().next() {
+            return Err(syn::Error::new(
+                coverage.span,
+                "`#[obake(coverage(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(latest_only) = self.latest_onlys().next() {
+            return Err(syn::Error::new(
+                latest_only.span,
+                "`#[obake(latest_only(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(trivial_migrations) = self.trivial_migrations().next() {
+            return Err(syn::Error::new(
+                trivial_migrations.span,
+                "`#[obake(trivial_migrations)]` not valid in this context",
+            ));
+        }
+
+        if let Some(partial_migrations) = self.partial_migrations().next() {
+            return Err(syn::Error::new(
+                partial_migrations.span,
+                "`#[obake(partial_migrations)]` not valid in this context",
+            ));
+        }
+
+        if let Some(schema_hash) = self.schema_hashes().next() {
+            return Err(syn::Error::new(
+                schema_hash.span,
+                "`#[obake(schema_hash)]` not valid in this context",
+            ));
+        }
+
+        if let Some(strict) = self.stricts().next() {
+            return Err(syn::Error::new(
+                strict.span,
+                "`#[obake(strict)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "serde")]
+        if let Some(serde) = self.serdes().next() {
+            return Err(syn::Error::new(
+                serde.span,
+                "`#[obake(serde(...))]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "serde")]
+        if let Some(serde_repr) = self.serde_reprs().next() {
+            return Err(syn::Error::new(
+                serde_repr.span,
+                "`#[obake(serde_repr)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "bson")]
+        if let Some(bson) = self.bsons().next() {
+            return Err(syn::Error::new(
+                bson.span,
+                "`#[obake(bson)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "graphql")]
+        if let Some(graphql) = self.graphqls().next() {
+            return Err(syn::Error::new(
+                graphql.span,
+                "`#[obake(graphql)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "json")]
+        if let Some(preserve_unknown) = self.preserve_unknowns().next() {
+            return Err(syn::Error::new(
+                preserve_unknown.span,
+                "`#[obake(preserve_unknown)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "json")]
+        if let Some(unknown_variant) = self.unknown_variants().next() {
+            return Err(syn::Error::new(
+                unknown_variant.span,
+                "`#[obake(unknown_variant)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "validator")]
+        if let Some(validator) = self.validators().next() {
+            return Err(syn::Error::new(
+                validator.span,
+                "`#[obake(validator)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "garde")]
+        if let Some(garde) = self.gardes().next() {
+            return Err(syn::Error::new(
+                garde.span,
+                "`#[obake(garde)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "zeroize")]
+        if let Some(zeroize) = self.zeroizes().next() {
+            return Err(syn::Error::new(
+                zeroize.span,
+                "`#[obake(zeroize)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "wasm")]
+        if let Some(wasm) = self.wasms().next() {
+            return Err(syn::Error::new(
+                wasm.span,
+                "`#[obake(wasm)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "pyo3")]
+        if let Some(pyo3) = self.pyo3s().next() {
+            return Err(syn::Error::new(
+                pyo3.span,
+                "`#[obake(pyo3)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "abi_stable")]
+        if let Some(abi_stable) = self.abi_stables().next() {
+            return Err(syn::Error::new(
+                abi_stable.span,
+                "`#[obake(abi_stable)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "tonic")]
+        if let Some(tonic) = self.tonics().next() {
+            return Err(syn::Error::new(
+                tonic.span,
+                "`#[obake(tonic)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "fixtures")]
+        if let Some(fixtures) = self.fixtures().next() {
+            return Err(syn::Error::new(
+                fixtures.span,
+                "`#[obake(fixtures)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "arbitrary")]
+        if let Some(arbitrary) = self.arbitraries().next() {
+            return Err(syn::Error::new(
+                arbitrary.span,
+                "`#[obake(arbitrary)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "proptest")]
+        if let Some(proptest) = self.proptests().next() {
+            return Err(syn::Error::new(
+                proptest.span,
+                "`#[obake(proptest)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "criterion")]
+        if let Some(generate_benches) = self.generate_benches().next() {
+            return Err(syn::Error::new(
+                generate_benches.span,
+                "`#[obake(generate_benches)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "fuzz")]
+        if let Some(fuzz) = self.fuzzes().next() {
+            return Err(syn::Error::new(
+                fuzz.span,
+                "`#[obake(fuzz)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "dynamic")]
+        if let Some(dynamic) = self.dynamics().next() {
+            return Err(syn::Error::new(
+                dynamic.span,
+                "`#[obake(dynamic)]` not valid in this context",
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::unused_self, clippy::unnecessary_wraps)]
+    fn check_no_field_only_attrs(&self) -> Result<()> {
+        if let Some(flatten) = self.flattens().next() {
+            return Err(syn::Error::new(
+                flatten.span,
+                "`#[obake(flatten)]` not valid in this context",
+            ));
+        }
+
+        if let Some(source) = self.sources().next() {
+            return Err(syn::Error::new(
+                source.span,
+                "`#[obake(source)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "garde")]
+        if let Some(garde_cfg) = self.garde_cfgs().next() {
+            return Err(syn::Error::new(
+                garde_cfg.span,
+                "`#[obake(garde_cfg(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(map_from) = self.map_froms().next() {
+            return Err(syn::Error::new(
+                map_from.span,
+                "`#[obake(map_from(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(retyped) = self.retypeds().next() {
+            return Err(syn::Error::new(
+                retyped.span,
+                "`#[obake(retyped)]` not valid in this context",
+            ));
+        }
+
+        if let Some(computed) = self.computeds().next() {
+            return Err(syn::Error::new(
+                computed.span,
+                "`#[obake(computed = ...)]` not valid in this context",
+            ));
+        }
+
+        if let Some(default_value) = self.default_values().next() {
+            return Err(syn::Error::new(
+                default_value.span,
+                "`#[obake(default = ...)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "proptest")]
+        if let Some(strategy) = self.strategies().next() {
+            return Err(syn::Error::new(
+                strategy.span,
+                "`#[obake(strategy(...))]` not valid in this context",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl VersionedField {
+    fn is_flattened(&self) -> bool {
+        self.attrs.flattens().next().is_some()
+    }
+
+    /// The name of this field, or `0` for the field of a tuple `struct` (which is the only field
+    /// in every version).
+    fn member(&self) -> syn::Member {
+        if self.colon_token.is_some() {
+            syn::Member::Named(self.ident.clone())
+        } else {
+            syn::Member::Unnamed(syn::Index {
+                index: 0,
+                span: self.ident.span(),
+            })
+        }
+    }
+
+    /// The name this field is serialized under in `version` (given the `rename_all` rule of the
+    /// item), or `None` if it isn't serialized under a name of its own.
+    #[cfg(feature = "serde")]
+    fn serde_name(&self, version: &Version, rename_all: Option<&str>) -> Result<Option<String>> {
+        // Tuple `struct`s are serialized as their only field, and skipped or flattened fields
+        // aren't serialized under a name of their own
+        if self.colon_token.is_none()
+            || self.is_flattened()
+            || ["skip", "skip_serializing", "flatten"]
+                .iter()
+                .any(|flag| serde_flag(&self.attrs, version, flag))
+        {
+            return Ok(None);
+        }
+
+        if let Some(name) = serde_option(&self.attrs, version, "rename")? {
+            return Ok(Some(name));
+        }
+        let name = syn::ext::IdentExt::unraw(&self.ident).to_string();
+        Ok(Some(match rename_all {
+            Some(rule) => rename_field(rule, &name),
+            None => name,
+        }))
+    }
+
+    /// Expands to the `#[cfg(...)]` attributes of this field (e.g. `#[cfg(windows)]`), which
+    /// also have to be applied wherever the field is initialised or moved.
+    fn expand_cfgs(&self) -> TokenStream2 {
+        let cfgs = self.attrs.attrs().filter(|attr| attr.path.is_ident("cfg"));
+        quote!(#(#cfgs)*)
+    }
+
+    fn expand_ty_versioned(&self, version: &Version) -> Result<TokenStream2> {
+        if self.attrs.inherits().next().is_none() {
+            let ty = &self.ty;
+            return Ok(quote!(#ty));
+        }
+
+        // Without a mapping declared with `#[obake(inherit(outer = "...", inner = "..."))]`, the
+        // inner version matches the outer version. Otherwise, the mapping for the latest outer
+        // version at or before this one applies
+        let mut mappings: Vec<_> = self
+            .attrs
+            .inherits()
+            .filter_map(|inherit| Some((inherit, inherit.mapping.as_ref()?)))
+            .collect();
+        mappings.sort_by(|(_, a), (_, b)| a.outer.cmp(&b.outer));
+        if let Some(pair) = mappings
+            .windows(2)
+            .find(|pair| pair[0].1.outer == pair[1].1.outer)
+        {
+            return Err(syn::Error::new(
+                pair[1].0.span,
+                format!("duplicate inherit mapping for version {}", pair[1].1.outer),
+            ));
+        }
+
+        let inner = mappings
+            .iter()
+            .rev()
+            .find(|(_, mapping)| mapping.outer <= *version)
+            .map_or(version, |(_, mapping)| &mapping.inner);
+
+        if let syn::Type::Path(ty_path) = &self.ty {
+            let mut ty_path = ty_path.clone();
+
+            if let Some(terminator) = ty_path.path.segments.last_mut() {
+                terminator.ident = terminator.ident.version(inner);
+                return Ok(quote!(#ty_path));
+            }
+        }
+
+        Err(syn::Error::new(
+            self.attrs.inherits().next().unwrap().span,
+            "`#[obake(inherit)]` can only be applied to fields with `#[obake::versioned]` types",
+        ))
+    }
+
+    fn expand_version(&self, version: &Version, provenance: &Provenance) -> Result<TokenStream2> {
+        if let Some(derive) = self.attrs.derives().next() {
+            return Err(syn::Error::new(
+                derive.span,
+                "`#[obake(derive(...))]` not valid in this context",
+            ));
+        }
+
+        self.attrs.check_no_item_only_attrs()?;
+
+        if let Some(renamed_from) = self.attrs.variant_renamed_froms().next() {
+            return Err(syn::Error::new(
+                renamed_from.span,
+                "`#[obake(variant_renamed_from(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(display) = self.attrs.displays().next() {
+            return Err(syn::Error::new(
+                display.span,
+                "`#[obake(display(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(flatten) = self.attrs.flattens().next() {
+            if self.attrs.inherits().next().is_none() {
+                return Err(syn::Error::new(
+                    flatten.span,
+                    "`#[obake(flatten)]` can only be applied to fields with `#[obake(inherit)]`",
+                ));
+            }
+        }
+
+        // If this field is disabled in this version, return nothing
+        if !self.attrs.enabled_in(version) {
+            return Ok(quote!());
+        }
+
+        // `#[serde(...)]` attributes (e.g. `#[serde(flatten)]`) are only kept on versions deriving
+        // `serde` traits, which are the only versions where the attribute exists
+        let attrs: Vec<_> = self
+            .attrs
+            .attrs()
+            .filter(|attr| provenance.derives_serde || !is_serde_attr(attr))
+            .collect();
+        let parse =
+            syn::punctuated::Punctuated::<syn::NestedMeta, syn::Token![,]>::parse_terminated;
+        let is_flatten = |meta: &syn::NestedMeta| match meta {
+            syn::NestedMeta::Meta(syn::Meta::Path(path)) => path.is_ident("flatten"),
+            _ => false,
+        };
+        let serde_flattened = attrs.iter().any(|attr| {
+            attr.path.is_ident("serde")
+                && attr
+                    .parse_args_with(parse)
+                    .is_ok_and(|metas| metas.iter().any(is_flatten))
+        });
+        let flatten = if self.is_flattened() && provenance.derives_serde && !serde_flattened {
+            quote!(#[serde(flatten)])
+        } else {
+            quote!()
+        };
+        let serde_cfgs = self
+            .attrs
+            .expand_serde_cfgs(version, provenance.derives_serde);
+        #[cfg(feature = "garde")]
+        let garde_cfgs: Vec<_> = self
+            .attrs
+            .garde_cfgs()
+            .filter(|attr| attr.req.matches(version))
+            .map(|attr| {
+                let tokens = &attr.tokens;
+                quote!(#[garde(#tokens)])
+            })
+            .collect();
+        #[cfg(not(feature = "garde"))]
+        let garde_cfgs: Vec<TokenStream2> = Vec::new();
+
+        let pyo3_attrs = match &self.vis {
+            syn::Visibility::Public(_) if provenance.pyclass => quote!(#[pyo3(get, set)]),
+            _ => quote!(),
+        };
+
+        let doc = provenance.expand_member_doc("field", &self.attrs, version);
+        let vis = &self.vis;
+        let ident = self.colon_token.map(|_| &self.ident);
+        let colon_token = &self.colon_token;
+        let ty = self.expand_ty_versioned(version)?;
+
+        Ok(quote! {
+            #(#attrs)*
+            #doc
+            #flatten
+            #(#serde_cfgs)*
+            #(#garde_cfgs)*
+            #pyo3_attrs
+            #vis #ident #colon_token #ty,
+        })
+    }
+}
+
+impl VersionedFields {
+    fn enabled_in(&self, version: &Version) -> Vec<&VersionedField> {
+        self.fields
+            .iter()
+            .filter(|field| field.attrs.enabled_in(version))
+            .collect()
+    }
+
+    fn expand_version(&self, version: &Version, provenance: &Provenance) -> Result<TokenStream2> {
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| field.expand_version(version, provenance))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter();
+
+        let Some(paren_token) = self.paren_token else {
+            if let Some(map_from) = self.fields.iter().find_map(|f| f.attrs.map_froms().next()) {
+                return Err(syn::Error::new(
+                    map_from.span,
+                    "`#[obake(map_from(...))]` can only be applied to fields of tuple `struct`s",
+                ));
+            }
+
+            return Ok(quote!({
+                #(#fields)*
+            }));
+        };
+
+        // Versions of tuple `struct`s are newtypes, so that the wrapped type can change between
+        // versions without the positions of other fields shifting
+        let enabled = self.enabled_in(version);
+        if enabled.len() != 1 {
+            let span = enabled
+                .get(1)
+                .map_or(paren_token.span, |field| field.ty.span());
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "tuple `struct`s must have exactly one field in every version, but version \
+                     {version} has {}",
+                    enabled.len()
+                ),
+            ));
+        }
+
+        Ok(quote!((#(#fields)*);))
+    }
+}
+
+impl VersionedVariantFields {
+    fn expand_version(&self, version: &Version, provenance: &Provenance) -> Result<TokenStream2> {
+        Ok(match &self {
+            Self::Unnamed(unnamed) => {
+                // Unnamed fields are matched by position, so they can't come and go between versions
+                if let Some(cfg) = unnamed.fields.iter().find_map(|f| f.attrs.cfgs().next()) {
+                    return Err(syn::Error::new(
+                        cfg.span,
+                        "`#[obake(cfg(...))]` not valid in this context",
+                    ));
+                }
+
+                if let Some(map_from) = unnamed
+                    .fields
+                    .iter()
+                    .find_map(|f| f.attrs.map_froms().next())
+                {
+                    return Err(syn::Error::new(
+                        map_from.span,
+                        "`#[obake(map_from(...))]` can only be applied to fields of tuple `struct`s",
+                    ));
+                }
+
+                let fields = unnamed
+                    .fields
+                    .iter()
+                    .map(|field| field.expand_version(version, provenance))
+                    .collect::<Result<Vec<_>>>()?;
+                quote!((#(#fields)*))
+            }
+            Self::Named(named) => {
+                let fields = named.expand_version(version, provenance)?;
+                quote!(#fields)
+            }
+            Self::Unit => quote!(),
+        })
+    }
+}
+
+impl VersionedVariant {
+    fn expand_version(&self, version: &Version, provenance: &Provenance) -> Result<TokenStream2> {
+        if let Some(derive) = self.attrs.inherits().next() {
+            return Err(syn::Error::new(
+                derive.span,
+                "`#[obake(inherit)]` not valid in this context",
+            ));
+        }
+
+        if let Some(inherit) = self.attrs.derives().next() {
+            return Err(syn::Error::new(
+                inherit.span,
+                "`#[obake(derive(...))]` not valid in this context",
+            ));
+        }
+
+        self.attrs.check_no_item_only_attrs()?;
+        self.attrs.check_no_field_only_attrs()?;
+
+        let latest = &provenance.versions.last().unwrap().version;
+        if let Some(renamed_from) = self
+            .attrs
+            .variant_renamed_froms()
+            .find(|renamed_from| renamed_from.until > *latest)
+        {
+            return Err(syn::Error::new(
+                renamed_from.span,
+                format!(
+                    "the latest version can't be renamed, but `until` is later than version {latest}"
+                ),
+            ));
+        }
+
+        // If this variant is disabled in this version, return nothing
+        if !self.attrs.enabled_in(version) {
+            return Ok(quote!());
+        }
+
+        let attrs = self
+            .attrs
+            .attrs()
+            .filter(|attr| provenance.derives_serde || !is_serde_attr(attr));
+        let serde_cfgs = self
+            .attrs
+            .expand_serde_cfgs(version, provenance.derives_serde);
+        // Versions after a rename still accept the names the variant used to have
+        let aliases = self
+            .attrs
+            .variant_renamed_froms()
+            .filter(|renamed_from| provenance.derives_serde && renamed_from.until <= *version)
+            .map(|renamed_from| {
+                let alias = renamed_from.ident.to_string();
+                quote!(#[serde(alias = #alias)])
+            });
+        let doc = provenance.expand_member_doc("variant", &self.attrs, version);
+        let ident = self.ident_in(version);
+        let fields = self.fields.expand_version(version, provenance)?;
+
+        Ok(quote! {
+            #(#attrs)*
+            #doc
+            #(#serde_cfgs)*
+            #(#aliases)*
+            #ident #fields,
+        })
+    }
+
+    /// The identifier of this variant in `version`, which is named after the earliest
+    /// `#[obake(variant_renamed_from(...))]` still in effect in that version, if any.
+    fn ident_in(&self, version: &Version) -> &syn::Ident {
+        self.attrs
+            .variant_renamed_froms()
+            .filter(|renamed_from| *version < renamed_from.until)
+            .min_by(|a, b| a.until.cmp(&b.until))
+            .map_or(&self.ident, |renamed_from| &renamed_from.ident)
+    }
+}
+
+impl VersionedVariants {
+    fn expand_version(&self, version: &Version, provenance: &Provenance) -> Result<TokenStream2> {
+        let variants = self
+            .variants
+            .iter()
+            .map(|variant| variant.expand_version(version, provenance))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter();
+
+        Ok(quote!({
+            #(#variants)*
+        }))
+    }
+}
+
+impl VersionedItem {
+    /// Whether every version is wiped on drop with `#[obake(zeroize)]`, in which case fields
+    /// can't be moved out of versions, and generated migrations take them instead.
+    #[cfg_attr(not(feature = "zeroize"), allow(clippy::unused_self))]
+    fn zeroizes(&self) -> bool {
+        #[cfg(feature = "zeroize")]
+        let zeroizes = self.attrs.zeroizes().next().is_some();
+        #[cfg(not(feature = "zeroize"))]
+        let zeroizes = false;
+        zeroizes
+    }
+
+    /// Expands to an expression moving the field at `place` out of a version being migrated,
+    /// which is taken (leaving the default value behind) for versions which are wiped on drop.
+    fn expand_move(&self, place: TokenStream2) -> TokenStream2 {
+        if self.zeroizes() {
+            quote!(::core::mem::take(&mut #place))
+        } else {
+            place
+        }
+    }
+
+    fn extract_versions(&self) -> Result<Vec<VersionAttr>> {
+        let mut versions: Vec<_> = self.attrs.versions().cloned().collect();
+        versions.sort();
+
+        // Duplicate version declarations result in an ambiguity in the
+        // choice of migration, so check that we don't have any duplicates.
+        //
+        // As versions are sorted and totally ordered, it's enough to check that
+        // pairwise adjacent versions are unequal.
+        for i in 1..versions.len() {
+            let head = &versions[i];
+            if head == &versions[i - 1] {
+                return Err(syn::Error::new(
+                    head.span,
+                    format!("duplicate definition of version {}", head.version),
+                ));
+            }
+        }
+
+        // Leaving out a version from the middle of the chain would break the chain of
+        // migrations, so only the oldest versions and the latest version can be gated behind
+        // features
+        let Some(oldest) = versions.iter().position(|attr| attr.feature.is_none()) else {
+            let latest = versions.last().unwrap();
+            return Err(syn::Error::new(
+                latest.feature.as_ref().unwrap().span(),
+                "the versions can't all be gated behind features",
+            ));
+        };
+        let newest = versions.len() - 1;
+        if let Some(gated) = versions[oldest..newest]
+            .iter()
+            .find(|attr| attr.feature.is_some())
+        {
+            return Err(syn::Error::new(
+                gated.feature.as_ref().unwrap().span(),
+                format!(
+                    "version {} is newer than version {}, which isn't gated behind a feature, \
+                    but isn't the latest version",
+                    gated.version, versions[oldest].version
+                ),
+            ));
+        }
+
+        if let Some(deprecated) = &versions.last().unwrap().deprecated {
+            return Err(syn::Error::new(
+                deprecated.span(),
+                "the latest version can't be deprecated",
+            ));
+        }
+
+        // Payloads without a tag can only be told apart from tagged payloads, so only one version
+        // (the baseline written before versioning started) can be untagged
+        if let Some(span) = versions[1..].iter().find_map(|attr| attr.untagged_legacy) {
+            return Err(syn::Error::new(
+                span,
+                "only the oldest version can be `untagged_legacy`",
+            ));
+        }
+        if let Some(span) = versions[0].untagged_legacy {
+            if !self.uses_serde() {
+                return Err(syn::Error::new(
+                    span,
+                    "`untagged_legacy` requires `#[obake(serde(...))]`",
+                ));
+            }
+        }
+
+        let latest = &versions.last().unwrap().version;
+        if let Some(renamed_from) = self
+            .attrs
+            .renamed_froms()
+            .find(|renamed_from| renamed_from.until > *latest)
+        {
+            return Err(syn::Error::new(
+                renamed_from.span,
+                format!(
+                    "the latest version can't be renamed, but `until` is later than version {latest}"
+                ),
+            ));
+        }
+
+        Ok(versions)
+    }
+
+    /// With `#[obake(max_versions = ...)]`, checks that no more than that many versions are
+    /// declared, so that support for old versions is dropped deliberately.
+    fn check_max_versions(&self, versions: &[VersionAttr]) -> Result<()> {
+        let Some(attr) = self.attrs.max_versions().next() else {
+            return Ok(());
+        };
+
+        if versions.len() > attr.max {
+            let excess = versions.len() - attr.max;
+            let dropped: Vec<_> = versions[..excess]
+                .iter()
+                .map(|attr| attr.version.to_string())
+                .collect();
+            let dropped = if excess == 1 {
+                format!("version {} (and its migration)", dropped[0])
+            } else {
+                format!("versions {} (and their migrations)", dropped.join(", "))
+            };
+            return Err(syn::Error::new(
+                versions[0].span,
+                format!(
+                    "`{}` declares {} versions, but `#[obake(max_versions = {})]` allows at most \
+                     {}: drop support for {dropped}",
+                    self.ident(),
+                    versions.len(),
+                    attr.max,
+                    attr.max,
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the versions listed by `#[obake(cfg(in(...)))]` attributes are declared.
+    fn check_cfg_versions(&self, versions: &[VersionAttr]) -> Result<()> {
+        let attrs: Vec<&VersionedAttributes> = match &self.kind {
+            VersionedItemKind::Struct(inner) => inner
+                .fields
+                .fields
+                .iter()
+                .map(|field| &field.attrs)
+                .collect(),
+            VersionedItemKind::Enum(inner) => inner
+                .variants
+                .variants
+                .iter()
+                .flat_map(|variant| {
+                    let fields = match &variant.fields {
+                        VersionedVariantFields::Named(fields)
+                        | VersionedVariantFields::Unnamed(fields) => fields.fields.iter().collect(),
+                        VersionedVariantFields::Unit => vec![],
+                    };
+                    std::iter::once(&variant.attrs)
+                        .chain(fields.into_iter().map(|field| &field.attrs))
+                })
+                .collect(),
+        };
+
+        let undeclared = attrs
+            .iter()
+            .flat_map(|attrs| attrs.cfgs())
+            .find_map(|cfg| cfg.req.undeclared_version(versions));
+        if let Some(undeclared) = undeclared {
+            return Err(syn::Error::new(
+                undeclared.span,
+                format!("`{}` has no version {}", self.ident(), undeclared.version),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// With `#[obake(strict)]`, checks that every field (or variant) removed in some version is
+    /// explicitly marked with `#[obake(discard)]`.
+    fn check_strict(&self, versions: &[VersionAttr]) -> Result<()> {
+        if self.attrs.stricts().next().is_none() {
+            return Ok(());
+        }
+
+        let members: Vec<(&syn::Ident, &VersionedAttributes, &str)> = match &self.kind {
+            VersionedItemKind::Struct(inner) => inner
+                .fields
+                .fields
+                .iter()
+                .map(|field| (&field.ident, &field.attrs, "field"))
+                .collect(),
+            VersionedItemKind::Enum(inner) => inner
+                .variants
+                .variants
+                .iter()
+                .map(|variant| (&variant.ident, &variant.attrs, "variant"))
+                .collect(),
+        };
+
+        for (ident, attrs, kind) in members {
+            if attrs.discards().next().is_some() {
+                continue;
+            }
+
+            let removed = versions.windows(2).find(|pair| {
+                attrs.enabled_in(&pair[0].version) && !attrs.enabled_in(&pair[1].version)
+            });
+            if let Some(pair) = removed {
+                let span = attrs.cfgs().next().map_or(ident.span(), |cfg| cfg.span);
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "{kind} `{ident}` is removed in version {}, but `#[obake(strict)]` requires \
+                         removals to be marked with `#[obake(discard)]`",
+                        pair[1].version,
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_preconditions(&self) -> Result<()> {
+        if let Some(inherit) = self.attrs.inherits().next() {
+            return Err(syn::Error::new(
+                inherit.span,
+                "`#[obake(inherit)]` not valid in this context",
+            ));
+        }
+
+        if let Some(req) = self.attrs.cfgs().next() {
+            return Err(syn::Error::new(
+                req.span,
+                "`#[obake(cfg(...))]` not valid in this context",
+            ));
+        }
+
+        self.attrs.check_no_field_only_attrs()?;
+
+        if let Some(renamed_from) = self.attrs.variant_renamed_froms().next() {
+            return Err(syn::Error::new(
+                renamed_from.span,
+                "`#[obake(variant_renamed_from(...))]` not valid in this context",
+            ));
+        }
+
+        if let Some(discard) = self.attrs.discards().next() {
+            return Err(syn::Error::new(
+                discard.span,
+                "`#[obake(discard)]` not valid in this context",
+            ));
+        }
+
+        #[cfg(feature = "bson")]
+        if let Some(bson) = self.attrs.bsons().nth(1) {
+            return Err(syn::Error::new(
+                bson.span,
+                "duplicate `#[obake(bson)]` attribute",
+            ));
+        }
+
+        if self.attrs.versions().next().is_none() {
+            return Err(syn::Error::new(
+                self.keyword_span(),
+                "`#[obake::versioned]` items require at least one `#[obake(version(...))]` attribute",
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn expand_version(&self, attr: &VersionAttr, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let version = &attr.version;
+        let cfg = attr.expand_cfg();
+        let deprecated = attr.expand_deprecated();
+        // Attributes which only the latest version needs (e.g. `derive(Debug)`) are left off
+        // older versions to cut down on generated code
+        let latest_only: Vec<_> = self
+            .attrs
+            .latest_onlys()
+            .filter(|_| &versions.last().unwrap().version == version)
+            .map(|attr| &attr.tokens)
+            .collect();
+        let derives_serde = self
+            .attrs
+            .attrs()
+            .cloned()
+            .chain(
+                latest_only
+                    .iter()
+                    .flat_map(|tokens| latest_only_attrs(tokens)),
+            )
+            .any(|attr| derives_any(&attr, &["Serialize", "Deserialize"]));
+        // `#[serde(...)]` and `#[serde_as]` are dropped from versions which don't derive `serde`
+        // traits (e.g. with `#[obake(latest_only(derive(Serialize)))]`), as they'd be rejected.
+        // Helper attributes go after `latest_only` derives, while attribute macros such as
+        // `#[serde_as]` stay ahead of them, as they must run before the derives
+        let (serde_attrs, attrs): (Vec<_>, Vec<_>) = self
+            .attrs
+            .attrs()
+            .filter(|attr| derives_serde || !is_serde_attr(attr))
+            .partition(|attr| attr.path.is_ident("serde"));
+        let serde_cfgs = self.attrs.expand_serde_cfgs(version, derives_serde);
+        #[cfg(feature = "pyo3")]
+        let pyclass =
+            &versions.last().unwrap().version == version && self.attrs.pyo3s().next().is_some();
+        #[cfg(not(feature = "pyo3"))]
+        let pyclass = false;
+        let provenance = Provenance {
+            item: self.ident(),
+            versions,
+            derives_serde,
+            pyclass,
+        };
+        let doc = provenance.expand_item_doc(&self.attrs, version);
+        let vis = &self.vis;
+        let ident = self.version_ident(version);
+        #[cfg(feature = "graphql")]
+        let graphql_attrs = self.expand_graphql_attrs(&ident)?;
+        #[cfg(not(feature = "graphql"))]
+        let graphql_attrs = quote!();
+        #[cfg(feature = "wasm")]
+        let wasm_attrs = if &versions.last().unwrap().version == version {
+            self.expand_wasm_attrs()?
+        } else {
+            quote!()
+        };
+        #[cfg(not(feature = "wasm"))]
+        let wasm_attrs = quote!();
+        #[cfg(feature = "pyo3")]
+        let pyo3_attrs = if pyclass {
+            self.expand_pyo3_attrs()?
+        } else {
+            quote!()
+        };
+        #[cfg(not(feature = "pyo3"))]
+        let pyo3_attrs = quote!();
+        #[cfg(feature = "abi_stable")]
+        let abi_stable_attrs = self.expand_abi_stable_attrs();
+        #[cfg(not(feature = "abi_stable"))]
+        let abi_stable_attrs = quote!();
+        let body = match &self.kind {
+            VersionedItemKind::Struct(inner) => {
+                let struct_token = &inner.struct_token;
+                let fields = inner.fields.expand_version(version, &provenance)?;
+                quote!(#struct_token #ident #fields)
+            }
+            VersionedItemKind::Enum(inner) => {
+                let enum_token = &inner.enum_token;
+                let variants = inner.variants.expand_version(version, &provenance)?;
+                quote!(#enum_token #ident #variants)
+            }
+        };
+
+        Ok(quote! {
+            #cfg
+            #[doc(hidden)]
+            #[allow(non_camel_case_types)]
+            #(#attrs)*
+            #(#[#latest_only])*
+            #(#serde_attrs)*
+            #(#serde_cfgs)*
+            #graphql_attrs
+            #wasm_attrs
+            #pyo3_attrs
+            #abi_stable_attrs
+            #deprecated
+            #doc
+            #vis #body
+        })
+    }
+
+    /// Expands to the `async-graphql` derive for a version declared with `#[obake(graphql)]`,
+    /// named after the version's identifier so that every version gets a distinct GraphQL type.
+    #[cfg(feature = "graphql")]
+    fn expand_graphql_attrs(&self, ident: &syn::Ident) -> Result<TokenStream2> {
+        let Some(attr) = self.attrs.graphqls().next() else {
+            return Ok(quote!());
+        };
+
+        if let VersionedItemKind::Enum(_) = self.kind {
+            return Err(syn::Error::new(
+                attr.span,
+                "`#[obake(graphql)]` can only be applied to `struct`s",
+            ));
+        }
+
+        let name = ident.to_string();
+        let derive = if attr.input {
+            quote!(InputObject)
+        } else {
+            quote!(SimpleObject)
+        };
+        Ok(quote! {
+            #[derive(::obake::__private::async_graphql::#derive)]
+            #[graphql(crate = "::obake::__private::async_graphql", name = #name)]
+        })
+    }
+
+    /// Whether the generated `enum` derives any of `traits` with `#[obake(derive(...))]`, matched
+    /// as with [`derives_any`].
+    fn enum_derives_any(&self, traits: &[&str]) -> bool {
+        self.attrs.derives().any(|attr| {
+            let tokens = &attr.tokens;
+            syn::parse::Parser::parse2(syn::Attribute::parse_outer, quote!(#[derive(#tokens)]))
+                .is_ok_and(|attrs| attrs.iter().any(|attr| derives_any(attr, traits)))
+        })
+    }
+
+    #[allow(clippy::unused_self)]
+    fn uses_serde(&self) -> bool {
+        #[allow(unused_mut)]
+        let mut uses_serde = false;
+
+        #[cfg(feature = "serde")]
+        {
+            uses_serde |= self.attrs.serdes().next().is_some();
+        }
+
+        #[cfg(feature = "bson")]
+        {
+            uses_serde |= self.attrs.bsons().next().is_some();
+        }
+
+        uses_serde
+    }
+
+    #[allow(clippy::unused_self)]
+    fn expand_serde_attrs(&self) -> TokenStream2 {
+        #[allow(unused_mut)]
+        let mut attrs = quote!();
+
+        #[cfg(feature = "serde")]
+        attrs.append_all(self.attrs.serdes().map(|attr| {
+            let tokens = &attr.tokens;
+            quote!(#[serde(#tokens)])
+        }));
+
+        #[cfg(feature = "bson")]
+        attrs.append_all(self.attrs.bsons().map(|attr| {
+            let field = attr.field_name();
+            quote!(#[serde(tag = #field)])
+        }));
+
+        attrs
+    }
+
+    /// The field naming the version of payloads of the generated `enum`, as set with
+    /// `#[obake(serde(tag = "..."))]` (or `#[obake(bson)]`).
+    #[cfg(feature = "serde")]
+    fn serde_tag(&self) -> Option<String> {
+        let parse =
+            syn::punctuated::Punctuated::<syn::NestedMeta, syn::Token![,]>::parse_terminated;
+        let tag = self
+            .attrs
+            .serdes()
+            .filter_map(|attr| syn::parse::Parser::parse2(parse, attr.tokens.clone()).ok())
+            .flatten()
+            .find_map(|meta| match meta {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Str(tag),
+                    ..
+                })) if path.is_ident("tag") => Some(tag.value()),
+                _ => None,
+            });
+
+        #[cfg(feature = "bson")]
+        let tag = tag.or_else(|| self.attrs.bsons().next().map(BsonAttr::field_name));
+
+        tag
+    }
+
+    #[cfg(feature = "serde")]
+    fn expand_deserialize_version_impl(&self) -> TokenStream2 {
+        if !self.uses_serde() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let variants: Vec<_> = self.expand_variants().collect();
+        let version_strs = self.attrs.versions().map(|attr| attr.version.to_string());
+        let cfgs: Vec<_> = self.expand_variant_cfgs().collect();
+        // Attributes aren't allowed on where-clause predicates, so versions gated behind features
+        // are required to be `Deserialize` whenever they're enabled
+        let bounded = self
+            .attrs
+            .versions()
+            .filter(|attr| attr.feature.is_none())
+            .map(|attr| self.version_ident(&attr.version));
+        let serde = quote!(::obake::__private::serde);
+        let tag = self.serde_tag().map(|tag| {
+            quote!(const TAG: ::core::option::Option<&'static str> = ::core::option::Option::Some(#tag);)
+        });
+        #[cfg(feature = "tracing")]
+        let trace = quote!(::obake::tracing::detected::<Self>(version););
+        #[cfg(not(feature = "tracing"))]
+        let trace = quote!();
+
+        // The bounds mention `'de`, so rather than being rejected as trivially unsatisfiable,
+        // the impl simply doesn't apply when some version isn't `Deserialize`
+        quote! {
+            impl<'de> ::obake::DeserializeVersion<'de> for #ident
+            where
+                #(#bounded: #serde::Deserialize<'de>,)*
+            {
+                #tag
+
+                fn deserialize_version<D>(
+                    version: &str,
+                    deserializer: D,
+                ) -> ::core::result::Result<#enum_ident, D::Error>
+                where
+                    D: #serde::Deserializer<'de>,
+                {
+                    #trace
+                    match version {
+                        #(
+                            #cfgs
+                            #version_strs => #serde::Deserialize::deserialize(deserializer)
+                                .map(#enum_ident::#variants),
+                        )*
+                        _ => ::core::result::Result::Err(
+                            ::obake::__private::unknown_version::<Self, D::Error>(version),
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Implements `obake::SerializeLatest` when the generated `enum` derives `Serialize`, by
+    /// deriving it for a copy of the `enum` borrowing the latest version, with the same `serde`
+    /// attributes. Older versions are kept as skipped variants, so variant indices are unchanged.
+    #[cfg(feature = "serde")]
+    fn expand_serialize_latest_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if !self.enum_derives_any(&["Serialize"]) {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let serde = quote!(::obake::__private::serde);
+        let serde_attrs = self.expand_serde_attrs();
+        let latest = &versions.last().unwrap().version;
+        let latest_variant = self.version_ident(latest);
+        let decls = self.variant_order(versions).into_iter().map(|attr| {
+            let variant = self.version_ident(&attr.version);
+            let cfg = attr.expand_cfg();
+            let decl = if &attr.version != latest {
+                quote!(#[serde(skip)] #variant)
+            } else if attr.untagged_legacy.is_some() {
+                quote!(#[serde(untagged)] #variant(&'a #ident))
+            } else if self.uses_serde() {
+                let version = attr.version.to_string();
+                quote!(#[serde(rename = #version)] #variant(&'a #ident))
+            } else {
+                quote!(#variant(&'a #ident))
+            };
+            quote!(#cfg #[allow(non_camel_case_types)] #decl,)
+        });
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::SerializeLatest for #ident {
+                fn serialize_latest<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    S: #serde::Serializer,
+                {
+                    #[derive(#serde::Serialize)]
+                    #[allow(dead_code)]
+                    #[serde(crate = "::obake::__private::serde")]
+                    #serde_attrs
+                    enum #enum_ident<'a> {
+                        #(#decls)*
+                    }
+
+                    #serde::Serialize::serialize(&#enum_ident::#latest_variant(self), serializer)
+                }
+            }
+        }
+    }
+
+    /// With `#[obake(validate = ...)]`, implements `obake::TryUpgrade`, validating the upgraded
+    /// latest version with the given function.
+    fn expand_try_upgrade_impl(&self) -> TokenStream2 {
+        let Some(attr) = self.attrs.validates().next() else {
+            return quote!();
+        };
+
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let with = &attr.with;
+        quote! {
+            #[automatically_derived]
+            impl ::obake::TryUpgrade for #ident {
+                fn try_upgrade(from: #enum_ident) -> ::core::result::Result<Self, ::obake::Error> {
+                    let x = <Self as ::core::convert::From<#enum_ident>>::from(from);
+                    ::obake::__private::check_upgraded::<Self, _>(#with(&x))?;
+                    ::core::result::Result::Ok(x)
+                }
+            }
+        }
+    }
+
+    /// With `#[obake(validator)]`, implements `obake::validator::ValidatedUpgrade`, validating
+    /// every version passed through while upgrading (or only the latest version, with
+    /// `#[obake(validator(latest))]`).
+    #[cfg(feature = "validator")]
+    fn expand_validator_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let Some(attr) = self.attrs.validators().next() else {
+            return quote!();
+        };
+
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let latest = versions.last().unwrap();
+        let latest_str = latest.version.to_string();
+
+        if attr.latest {
+            return quote! {
+                #[automatically_derived]
+                impl ::obake::validator::ValidatedUpgrade for #ident {
+                    fn upgrade_validated(
+                        from: #enum_ident,
+                    ) -> ::core::result::Result<Self, ::obake::Error> {
+                        let x = Self::from(from);
+                        ::obake::__private::validate_version::<#ident, _>(#latest_str, &x)?;
+                        ::core::result::Result::Ok(x)
+                    }
+                }
+            };
+        }
+
+        let validations = versions.iter().map(|attr| {
+            let cfg = attr.expand_cfg();
+            let variant = self.version_ident(&attr.version);
+            let version = attr.version.to_string();
+            quote! {
+                #cfg
+                #enum_ident::#variant(x) => {
+                    ::obake::__private::validate_version::<#ident, _>(#version, x)?;
+                }
+            }
+        });
+        quote! {
+            #[automatically_derived]
+            impl ::obake::validator::ValidatedUpgrade for #ident {
+                fn upgrade_validated(
+                    mut from: #enum_ident,
+                ) -> ::core::result::Result<Self, ::obake::Error> {
+                    loop {
+                        match &from {
+                            #(#validations)*
+                        }
+                        from = match from.__obake_upgrade_step() {
+                            ::core::ops::ControlFlow::Continue(next) => next,
+                            ::core::ops::ControlFlow::Break(x) => {
+                                return ::core::result::Result::Ok(x);
+                            }
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    /// With `#[obake(garde)]`, expands to a `deserialize_validated` constructor for every version
+    /// and the `VersionedFoo` `enum`, checking the version deserialized against its own `garde`
+    /// rules.
+    #[cfg(feature = "garde")]
+    fn expand_garde_impls(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if self.attrs.gardes().next().is_none() {
+            return quote!();
+        }
+
+        let vis = &self.vis;
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let serde = quote!(::obake::__private::serde);
+        let signature = quote! {
+            #vis fn deserialize_validated<'de, D>(
+                deserializer: D,
+            ) -> ::core::result::Result<Self, ::obake::Error>
+            where
+                Self: #serde::Deserialize<'de>,
+                D: #serde::Deserializer<'de>,
+                D::Error: ::core::convert::Into<::obake::BoxError>,
+        };
+        let deserialize = quote! {
+            <Self as #serde::Deserialize>::deserialize(deserializer)
+                .map_err(::obake::Error::parse::<#ident>)?
+        };
+
+        let (version_impls, arms): (Vec<_>, Vec<_>) = versions
+            .iter()
+            .map(|attr| {
+                let variant = self.version_ident(&attr.version);
+                let version = attr.version.to_string();
+                let cfg = attr.expand_cfg();
+                let validate = quote!(::obake::__private::garde_validate::<#ident, _>);
+                let version_impl = quote! {
+                    #cfg
+                    #[automatically_derived]
+                    impl #variant {
+                        /// Deserializes this version, then validates it against its `garde`
+                        /// rules.
+                        ///
+                        /// # Errors
+                        ///
+                        /// Returns an error if the payload can't be deserialized, or if it fails
+                        /// validation.
+                        #signature
+                        {
+                            let value = #deserialize;
+                            #validate(#version, &value)?;
+                            ::core::result::Result::Ok(value)
+                        }
+                    }
+                };
+                let arm = quote!(#cfg #enum_ident::#variant(value) => #validate(#version, value)?,);
+                (version_impl, arm)
+            })
+            .unzip();
+
+        quote! {
+            #(#version_impls)*
+
+            #[automatically_derived]
+            impl #enum_ident {
+                /// Deserializes any version, then validates it against the `garde` rules of the
+                /// version it was deserialized as.
+                ///
+                /// # Errors
+                ///
+                /// Returns an error if the payload can't be deserialized, or if it fails
+                /// validation.
+                #signature
+                {
+                    let versioned = #deserialize;
+                    match &versioned {
+                        #(#arms)*
+                    }
+                    ::core::result::Result::Ok(versioned)
+                }
+            }
+        }
+    }
+
+    /// With `#[obake(zeroize)]`, implements `Zeroize` and `ZeroizeOnDrop` for every version
+    /// (wiping every field on drop) and for the generated `enum`.
+    #[cfg(feature = "zeroize")]
+    fn expand_zeroize_impls(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if !self.zeroizes() {
+            return quote!();
+        }
+
+        let enum_ident = self.enum_ident();
+        let zeroize = quote!(::obake::__private::zeroize);
+        let (version_impls, arms): (Vec<_>, Vec<_>) = versions
+            .iter()
+            .map(|attr| {
+                let variant = self.version_ident(&attr.version);
+                let cfg = attr.expand_cfg();
+                let arms = self
+                    .constructors(&attr.version, &quote!(#variant))
+                    .into_iter()
+                    .map(|ctor| {
+                        let mut wipes = vec![];
+                        let pattern = ctor.expand(|field| {
+                            let binding = format_ident!("field_{}", wipes.len());
+                            let cfgs = match field {
+                                FieldRef::Named(field) => field.expand_cfgs(),
+                                FieldRef::Unnamed(_) => quote!(),
+                            };
+                            wipes.push(quote!(#cfgs #zeroize::Zeroize::zeroize(#binding);));
+                            binding.into_token_stream()
+                        });
+                        quote!(#pattern => { #(#wipes)* })
+                    });
+                let version_impl = quote! {
+                    #cfg
+                    #[automatically_derived]
+                    impl #zeroize::Zeroize for #variant {
+                        #[allow(non_shorthand_field_patterns)]
+                        fn zeroize(&mut self) {
+                            match self {
+                                #(#arms)*
+                            }
+                        }
+                    }
+
+                    #cfg
+                    #[automatically_derived]
+                    impl ::core::ops::Drop for #variant {
+                        fn drop(&mut self) {
+                            #zeroize::Zeroize::zeroize(self);
+                        }
+                    }
+
+                    #cfg
+                    #[automatically_derived]
+                    impl #zeroize::ZeroizeOnDrop for #variant {}
+                };
+                let arm =
+                    quote!(#cfg #enum_ident::#variant(value) => #zeroize::Zeroize::zeroize(value),);
+                (version_impl, arm)
+            })
+            .unzip();
+
+        quote! {
+            #(#version_impls)*
+
+            #[automatically_derived]
+            impl #zeroize::Zeroize for #enum_ident {
+                fn zeroize(&mut self) {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+
+            // Every version wipes itself on drop
+            #[automatically_derived]
+            impl #zeroize::ZeroizeOnDrop for #enum_ident {}
+        }
+    }
+
+    #[cfg(feature = "bson")]
+    fn expand_bson_impl(&self) -> TokenStream2 {
+        let ident = self.ident();
+        self.attrs
+            .bsons()
+            .map(|attr| {
+                let field = attr.field_name();
+                quote! {
+                    impl ::obake::bson::BsonVersioned for #ident {
+                        const VERSION_FIELD: &'static str = #field;
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn expand_downgrade_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if self.attrs.downgrades().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let migrations = versions.windows(2).rev().map(|pair| {
+            let prev = self.version_ident(&pair[0].version);
+            let next = self.version_ident(&pair[1].version);
+            let version = pair[1].version.to_string();
+            let from = expand_conversion(&next, &prev, self.migration_span(pair));
+            let cfg = expand_pair_cfg(pair);
+            quote! {
+                #cfg
+                #enum_ident::#next(x) if version != #version => #enum_ident::#prev(#from),
+            }
+        });
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::Downgrade for #ident {
+                fn downgrade(self, version: &str) -> ::core::option::Option<#enum_ident> {
+                    if !<Self as ::obake::Versioned>::VERSIONS.contains(&version) {
+                        return ::core::option::Option::None;
+                    }
+
+                    let mut from = #enum_ident::from(self);
+                    loop {
+                        from = match from {
+                            #(#migrations)*
+                            from => return ::core::option::Option::Some(from),
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    /// The names of the fields (or variants) present in `version`.
+    fn members(&self, version: &Version) -> Vec<String> {
+        match &self.kind {
+            VersionedItemKind::Struct(inner) => inner
+                .fields
+                .fields
+                .iter()
+                .filter(|field| field.attrs.enabled_in(version))
+                .map(|field| field.ident.to_string())
+                .collect(),
+            VersionedItemKind::Enum(inner) => inner
+                .variants
+                .variants
+                .iter()
+                .filter(|variant| variant.attrs.enabled_in(version))
+                .map(|variant| variant.ident.to_string())
+                .collect(),
+        }
+    }
+
+    fn expand_graph_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if self.attrs.graphs().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let downgrades = self.attrs.downgrades().next().is_some();
+
+        let mut dot = format!("digraph {ident} {{\n    node [shape=box];\n");
+        let mut mermaid = String::from("graph LR\n");
+        for attr in versions {
+            let node = self.version_ident(&attr.version);
+            let members = self.members(&attr.version);
+            writeln!(
+                dot,
+                "    {node} [label=\"{ident} {}\\n{}\"];",
+                attr.version,
+                members.join("\\n"),
+            )
+            .unwrap();
+            writeln!(
+                mermaid,
+                "    {node}[\"{ident} {}<br/>{}\"]",
+                attr.version,
+                members.join("<br/>"),
+            )
+            .unwrap();
+        }
+
+        for pair in versions.windows(2) {
+            let prev = self.version_ident(&pair[0].version);
+            let next = self.version_ident(&pair[1].version);
+            let (prev_members, next_members) = (
+                self.members(&pair[0].version),
+                self.members(&pair[1].version),
+            );
+            let changes: Vec<_> = next_members
+                .iter()
+                .filter(|member| !prev_members.contains(member))
+                .map(|member| format!("+{member}"))
+                .chain(
+                    prev_members
+                        .iter()
+                        .filter(|member| !next_members.contains(member))
+                        .map(|member| format!("-{member}")),
+                )
+                .collect();
+
+            writeln!(
+                dot,
+                "    {prev} -> {next} [label=\"{}\"];",
+                changes.join("\\n"),
+            )
+            .unwrap();
+            if changes.is_empty() {
+                writeln!(mermaid, "    {prev} --> {next}").unwrap();
+            } else {
+                writeln!(
+                    mermaid,
+                    "    {prev} -->|\"{}\"| {next}",
+                    changes.join("<br/>"),
+                )
+                .unwrap();
+            }
+
+            if downgrades {
+                writeln!(dot, "    {next} -> {prev} [style=dashed];").unwrap();
+                writeln!(mermaid, "    {next} -.-> {prev}").unwrap();
+            }
+        }
+        dot.push('}');
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::MigrationGraph for #ident {
+                const DOT: &'static str = #dot;
+                const MERMAID: &'static str = #mermaid;
+            }
+        }
+    }
+
+    fn expand_coverage_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let traits: Vec<_> = self
+            .attrs
+            .coverages()
+            .flat_map(|attr| attr.traits.iter())
+            .collect();
+        if traits.is_empty() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let probes: Vec<_> = (0..traits.len())
+            .map(|i| format_ident!("Probe{}", i))
+            .collect();
+        let entries = versions.iter().flat_map(|attr| {
+            let version = self.version_ident(&attr.version);
+            let version_str = attr.version.to_string();
+            let cfg = attr.expand_cfg();
+            traits.iter().zip(&probes).map(move |(path, probe)| {
+                let trait_name = path.to_token_stream().to_string().replace(' ', "");
+                quote! {
+                    #cfg
+                    ::obake::TraitCoverage {
+                        version: #version_str,
+                        trait_name: #trait_name,
+                        implemented: #probe::<#version>::IMPLEMENTED,
+                    },
+                }
+            })
+        });
+
+        // Inherent associated constants take precedence over trait associated constants, but only
+        // apply when their bounds hold, so each probe falls back to `false`.
+        quote! {
+            const _: () = {
+                trait Fallback {
+                    const IMPLEMENTED: bool = false;
+                }
+
+                #(
+                    #[allow(dead_code)]
+                    struct #probes<T>(::core::marker::PhantomData<T>);
+
+                    impl<T> Fallback for #probes<T> {}
+
+                    impl<T: #traits> #probes<T> {
+                        const IMPLEMENTED: bool = true;
+                    }
+                )*
+
+                #[automatically_derived]
+                impl ::obake::Coverage for #ident {
+                    const COVERAGE: &'static [::obake::TraitCoverage] = &[#(#entries)*];
+                }
+            };
+        }
+    }
+
+    /// Describes the shape of `version`: the names and types of its fields (or variants), in
+    /// order, with all whitespace removed.
+    fn schema(&self, version: &Version) -> Result<String> {
+        self.schema_with(version, false)
+    }
+
+    /// Like [`Self::schema`], but with the types of fields converted by migrations (see
+    /// [`Self::converts_field`]) as written and variants under their current names, so that
+    /// versions differing only in the versions of those fields (or the names of renamed variants)
+    /// compare equal.
+    fn migration_schema(&self, version: &Version) -> Result<String> {
+        self.schema_with(version, true)
+    }
+
+    fn schema_with(&self, version: &Version, for_migration: bool) -> Result<String> {
+        let ty = |field: &VersionedField| {
+            if for_migration && self.converts_field(field) {
+                Ok(field.ty.to_token_stream())
+            } else {
+                field.expand_ty_versioned(version)
+            }
+        };
+        let fields = |fields: &VersionedFields| {
+            fields
+                .fields
+                .iter()
+                .filter(|field| field.attrs.enabled_in(version))
+                .map(|field| {
+                    Ok(format!(
+                        "{}:{};",
+                        field.member().to_token_stream(),
+                        ty(field)?
+                    ))
+                })
+                .collect::<Result<String>>()
+        };
+
+        let schema = match &self.kind {
+            VersionedItemKind::Struct(inner) => format!("struct{{{}}}", fields(&inner.fields)?),
+            VersionedItemKind::Enum(inner) => {
+                let variants = inner
+                    .variants
+                    .variants
+                    .iter()
+                    .filter(|variant| variant.attrs.enabled_in(version))
+                    .map(|variant| {
+                        let ident = if for_migration {
+                            &variant.ident
+                        } else {
+                            variant.ident_in(version)
+                        };
+                        Ok(match &variant.fields {
+                            VersionedVariantFields::Unnamed(unnamed) => {
+                                let tys = unnamed
+                                    .fields
+                                    .iter()
+                                    .map(|field| Ok(ty(field)?.to_string()))
+                                    .collect::<Result<Vec<_>>>()?;
+                                format!("{}({});", ident, tys.join(","))
+                            }
+                            VersionedVariantFields::Named(named) => {
+                                format!("{}{{{}}};", ident, fields(named)?)
+                            }
+                            VersionedVariantFields::Unit => format!("{ident};"),
+                        })
+                    })
+                    .collect::<Result<String>>()?;
+                format!("enum{{{variants}}}")
+            }
+        };
+
+        Ok(schema.chars().filter(|c| !c.is_whitespace()).collect())
+    }
+
+    /// Whether generated migrations convert `field` with the migrations of its own type, rather
+    /// than moving it as is: flattened fields, and the inherited fields of `enum` variants.
+    fn converts_field(&self, field: &VersionedField) -> bool {
+        field.is_flattened()
+            || matches!(self.kind, VersionedItemKind::Enum(_))
+                && field.attrs.inherits().next().is_some()
+    }
+
+    fn expand_schema_hash_impls(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        if self.attrs.schema_hashes().next().is_none() {
+            return Ok(quote!());
+        }
+
+        let vis = &self.vis;
+        let ident = self.ident();
+        let mut impls = quote!();
+        let mut entries = quote!();
+        for attr in versions {
+            let version = self.version_ident(&attr.version);
+            let version_str = attr.version.to_string();
+            let hash = fnv1a(self.schema(&attr.version)?.as_bytes());
+            let cfg = attr.expand_cfg();
+            impls.append_all(quote! {
+                #cfg
+                impl #version {
+                    /// A fingerprint of the names and types of the fields of this version.
+                    #vis const SCHEMA_HASH: u64 = #hash;
+                }
+            });
+            entries.append_all(quote!(#cfg (#version_str, #version::SCHEMA_HASH),));
+        }
+
+        Ok(quote! {
+            #impls
+
+            #[automatically_derived]
+            impl ::obake::SchemaHash for #ident {
+                const SCHEMA_HASHES: &'static [(&'static str, u64)] = &[#entries];
+            }
+        })
+    }
+
+    /// Expands to `SERDE_FIELDS` and `SERDE_TAG` constants on every version, recording the names
+    /// its fields are serialized under and the tag of its variant in the generated `enum`, for
+    /// `obake::assert_repr!` to check.
+    #[cfg(feature = "serde")]
+    fn expand_serde_repr_impls(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let Some(attr) = self.attrs.serde_reprs().next() else {
+            return Ok(quote!());
+        };
+
+        let VersionedItemKind::Struct(inner) = &self.kind else {
+            return Err(syn::Error::new(
+                attr.span,
+                "`#[obake(serde_repr)]` can only be applied to `struct`s",
+            ));
+        };
+
+        let vis = &self.vis;
+        let mut impls = quote!();
+        for attr in versions {
+            let version = self.version_ident(&attr.version);
+            let rename_all = serde_option(&self.attrs, &attr.version, "rename_all")?;
+            let mut fields = Vec::new();
+            for field in inner.fields.enabled_in(&attr.version) {
+                fields.extend(field.serde_name(&attr.version, rename_all.as_deref())?);
+            }
+
+            let tag = if attr.untagged_legacy.is_some() {
+                String::new()
+            } else if self.uses_serde() {
+                attr.version.to_string()
+            } else {
+                version.to_string()
+            };
+            let cfg = attr.expand_cfg();
+            impls.append_all(quote! {
+                #cfg
+                impl #version {
+                    /// The names the fields of this version are serialized under, in order.
+                    #vis const SERDE_FIELDS: &'static [&'static str] = &[#(#fields),*];
+                    /// The tag of this version in the generated `enum`, which is empty if the
+                    /// version is untagged.
+                    #vis const SERDE_TAG: &'static str = #tag;
+                }
+            });
+        }
+
+        Ok(impls)
+    }
+
+    /// Expands to a `From` impl moving every field of `from` into `to`, for versions with
+    /// identical fields.
+    fn expand_trivial_migration(
+        &self,
+        from: (&Version, &syn::Ident),
+        to: (&Version, &syn::Ident),
+    ) -> TokenStream2 {
+        let (from_version, from_ident) = from;
+        let (to_version, to_ident) = to;
+        let arms = self
+            .constructors(from_version, &quote!(#from_ident))
+            .into_iter()
+            .zip(self.constructors(to_version, &quote!(#to_ident)))
+            .map(|(pattern, ctor)| {
+                let bind = |i: usize, field: &FieldRef| match field {
+                    FieldRef::Named(field) => field.ident.clone(),
+                    FieldRef::Unnamed(_) => format_ident!("field_{}", i),
+                };
+                let (mut i, mut j) = (0, 0);
+                let pattern = pattern.expand(|field| {
+                    i += 1;
+                    bind(i - 1, field).into_token_stream()
+                });
+                // Flattened fields (and inherited fields of variants) may change version, so compose their
+                // migrations
+                let ctor = ctor.expand(|field| {
+                    j += 1;
+                    let binding = bind(j - 1, field);
+                    // Versions which are wiped on drop are matched by reference
+                    let binding = if self.zeroizes() {
+                        quote!(::core::mem::take(#binding))
+                    } else {
+                        binding.into_token_stream()
+                    };
+                    match field {
+                        FieldRef::Named(field) | FieldRef::Unnamed(field)
+                            if self.converts_field(field) =>
+                        {
+                            quote!(::core::convert::From::from(#binding))
+                        }
+                        _ => binding,
+                    }
+                });
+                quote!(#pattern => #ctor,)
+            });
+        let (arg, scrutinee) = if self.zeroizes() {
+            (quote!(mut from), quote!(&mut from))
+        } else {
+            (quote!(from), quote!(from))
+        };
+
+        quote! {
+            #[automatically_derived]
+            impl ::core::convert::From<#from_ident> for #to_ident {
+                #[inline]
+                #[allow(non_shorthand_field_patterns)]
+                fn from(#arg: #from_ident) -> Self {
+                    match #scrutinee {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+    }
+
+    fn expand_trivial_migrations(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        if self.attrs.trivial_migrations().next().is_none() {
+            return Ok(quote!());
+        }
+
+        let downgrades = self.attrs.downgrades().next().is_some();
+        let mut impls = quote!();
+        for pair in versions.windows(2) {
+            let (prev, next) = (&pair[0].version, &pair[1].version);
+            if self.migration_schema(prev)? != self.migration_schema(next)? {
+                continue;
+            }
+
+            let (prev_ident, next_ident) = (self.version_ident(prev), self.version_ident(next));
+            let cfg = expand_pair_cfg(pair);
+            let upgrade = self.expand_trivial_migration((prev, &prev_ident), (next, &next_ident));
+            impls.append_all(quote!(#cfg #upgrade));
+            if downgrades {
+                let downgrade =
+                    self.expand_trivial_migration((next, &next_ident), (prev, &prev_ident));
+                impls.append_all(quote!(#cfg #downgrade));
+            }
+        }
+
+        Ok(impls)
+    }
+
+    /// Expands to the migrations into versions of tuple `struct`s whose field is marked
+    /// `#[obake(map_from(...))]`, which convert the field of the previous version with the given
+    /// function.
+    fn expand_map_from_migrations(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let fields = match &self.kind {
+            VersionedItemKind::Struct(inner) if inner.fields.paren_token.is_some() => &inner.fields,
+            _ => return Ok(quote!()),
+        };
+
+        let mut impls = quote!();
+        for field in &fields.fields {
+            let Some(map_from) = field.attrs.map_froms().next() else {
+                continue;
+            };
+            if field.attrs.enabled_in(&versions[0].version) {
+                return Err(syn::Error::new(
+                    map_from.span,
+                    "`#[obake(map_from(...))]` can't be applied to a field of the first version, \
+                     which has nothing to map from",
+                ));
+            }
+
+            let with = &map_from.with;
+            let arg = if self.zeroizes() {
+                quote!(mut from)
+            } else {
+                quote!(from)
+            };
+            let wrapped = self.expand_move(quote!(from.0));
+            for pair in versions.windows(2) {
+                let (prev, next) = (&pair[0].version, &pair[1].version);
+                if field.attrs.enabled_in(prev) || !field.attrs.enabled_in(next) {
+                    continue;
+                }
+
+                let (prev_ident, next_ident) = (self.version_ident(prev), self.version_ident(next));
+                let cfg = expand_pair_cfg(pair);
+                impls.append_all(quote! {
+                    #cfg
+                    #[automatically_derived]
+                    impl ::core::convert::From<#prev_ident> for #next_ident {
+                        #[inline]
+                        fn from(#arg: #prev_ident) -> Self {
+                            Self(#with(#wrapped))
+                        }
+                    }
+                });
+            }
+        }
+
+        Ok(impls)
+    }
+
+    /// Expands to the migrations into versions of `struct`s where a field marked
+    /// `#[obake(retyped)]` replaces a field of the same name (but another type) in the previous
+    /// version, converting it with `From` (or `TryFrom`, with `retyped(try_from, or = ...)`), or
+    /// where a field marked `#[obake(computed = ...)]` is added, computing it from the previous
+    /// version. Every other field is moved.
+    #[allow(clippy::too_many_lines)]
+    fn expand_retyped_migrations(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let fields = match &self.kind {
+            VersionedItemKind::Struct(inner) => &inner.fields,
+            VersionedItemKind::Enum(inner) => {
+                let fields = inner
+                    .variants
+                    .variants
+                    .iter()
+                    .flat_map(|variant| match &variant.fields {
+                        VersionedVariantFields::Named(fields)
+                        | VersionedVariantFields::Unnamed(fields) => fields.fields.iter().collect(),
+                        VersionedVariantFields::Unit => vec![],
+                    })
+                    .collect::<Vec<_>>();
+                if let Some(retyped) = fields
+                    .iter()
+                    .find_map(|field| field.attrs.retypeds().next())
+                {
+                    return Err(syn::Error::new(
+                        retyped.span,
+                        "`#[obake(retyped)]` can only be applied to fields of `struct`s",
+                    ));
+                }
+                if let Some(computed) = fields
+                    .iter()
+                    .find_map(|field| field.attrs.computeds().next())
+                {
+                    return Err(syn::Error::new(
+                        computed.span,
+                        "`#[obake(computed = ...)]` can only be applied to fields of `struct`s",
+                    ));
+                }
+                return Ok(quote!());
+            }
+        };
+
+        let mut impls = quote!();
+        for pair in versions.windows(2) {
+            let (prev, next) = (&pair[0].version, &pair[1].version);
+            let added = fields
+                .enabled_in(next)
+                .into_iter()
+                .filter(|field| !field.attrs.enabled_in(prev))
+                .collect::<Vec<_>>();
+            let first = added.iter().find_map(|field| {
+                let retyped = field
+                    .attrs
+                    .retypeds()
+                    .next()
+                    .map(|retyped| (retyped.span, "`#[obake(retyped)]`"));
+                let computed = field
+                    .attrs
+                    .computeds()
+                    .next()
+                    .map(|computed| (computed.span, "`#[obake(computed = ...)]`"));
+                retyped.or(computed)
+            });
+            let Some((span, attr)) = first else {
+                continue;
+            };
+
+            let prev_fields = fields.enabled_in(prev);
+            let next_fields = fields.enabled_in(next);
+            let mut computed = vec![];
+            let mut values = vec![];
+            for field in &next_fields {
+                let member = field.member();
+                let cfgs = field.expand_cfgs();
+                if let Some(ComputedAttr { with, .. }) = field
+                    .attrs
+                    .computeds()
+                    .next()
+                    .filter(|_| !field.attrs.enabled_in(prev))
+                {
+                    // Computed before any field is moved out of `from`
+                    let var = format_ident!("__computed_{}", member.to_token_stream().to_string());
+                    computed.push(quote! {
+                        #cfgs
+                        let #var = ::obake::__private::compute(&from, #with);
+                    });
+                    values.push(quote!(#cfgs #member: #var));
+                    continue;
+                }
+
+                let from = prev_fields.iter().find(|from| from.member() == member);
+                let Some(from) = from else {
+                    return Err(syn::Error::new(
+                        span,
+                        format!(
+                            "{attr} requires every field of version {next} to replace a field of \
+                             version {prev} or be computed, but `{}` is new",
+                            member.to_token_stream()
+                        ),
+                    ));
+                };
+                let value = self.expand_move(quote!(from.#member));
+                let retyped = field
+                    .attrs
+                    .retypeds()
+                    .next()
+                    .filter(|_| !field.attrs.enabled_in(prev));
+                values.push(match retyped {
+                    Some(RetypedAttr { fallback: None, .. }) => {
+                        quote!(#cfgs #member: ::core::convert::From::from(#value))
+                    }
+                    Some(RetypedAttr {
+                        fallback: Some(fallback),
+                        ..
+                    }) => quote! {
+                        #cfgs #member: ::core::convert::TryFrom::try_from(#value)
+                            .unwrap_or_else(|_| #fallback)
+                    },
+                    None if field.expand_ty_versioned(next)?.to_string()
+                        == from.expand_ty_versioned(prev)?.to_string() =>
+                    {
+                        quote!(#cfgs #member: #value)
+                    }
+                    // Flattened fields may change version, so compose their migrations
+                    None if self.converts_field(field) => {
+                        quote!(#cfgs #member: ::core::convert::From::from(#value))
+                    }
+                    None => {
+                        return Err(syn::Error::new(
+                            span,
+                            format!(
+                                "{attr} requires the other fields of version {next} to be \
+                                 unchanged from version {prev}, but `{}` changed",
+                                member.to_token_stream()
+                            ),
+                        ))
+                    }
+                });
+            }
+            if let Some(removed) = prev_fields.iter().find(|from| {
+                next_fields
+                    .iter()
+                    .all(|field| field.member() != from.member())
+            }) {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "{attr} requires every field of version {prev} to be carried over to \
+                         version {next}, but `{}` was removed",
+                        removed.member().to_token_stream()
+                    ),
+                ));
+            }
+
+            let (prev_ident, next_ident) = (self.version_ident(prev), self.version_ident(next));
+            let arg = if self.zeroizes() {
+                quote!(mut from)
+            } else {
+                quote!(from)
+            };
+            let cfg = expand_pair_cfg(pair);
+            impls.append_all(quote! {
+                #cfg
+                #[automatically_derived]
+                impl ::core::convert::From<#prev_ident> for #next_ident {
+                    #[inline]
+                    fn from(#arg: #prev_ident) -> Self {
+                        #(#computed)*
+                        Self { #(#values,)* }
+                    }
+                }
+            });
+        }
+
+        Ok(impls)
+    }
+
+    /// Expands to the migrations out of versions with `#[obake(patch(...))]`, which build the
+    /// next version by moving, defaulting and deleting fields as the patch script describes, and
+    /// keep the fields it doesn't mention.
+    #[allow(clippy::too_many_lines)]
+    fn expand_patch_migrations(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let mut impls = quote!();
+        let mut patched = vec![];
+        for attr in self.attrs.patches() {
+            let fields =
+                match &self.kind {
+                    VersionedItemKind::Struct(inner) if inner.fields.paren_token.is_none() => {
+                        &inner.fields
+                    }
+                    _ => return Err(syn::Error::new(
+                        attr.span,
+                        "`#[obake(patch(...))]` can only be applied to `struct`s with named fields",
+                    )),
+                };
+
+            let prev = &attr.from;
+            let Some(i) = versions.iter().position(|version| version.version == *prev) else {
+                return Err(syn::Error::new(
+                    attr.span,
+                    format!("`{}` has no version {prev}", self.ident()),
+                ));
+            };
+            let Some(next) = versions.get(i + 1).map(|version| &version.version) else {
+                return Err(syn::Error::new(
+                    attr.span,
+                    format!(
+                        "version {prev} is the latest version, so there's nothing to patch it into"
+                    ),
+                ));
+            };
+            if patched.contains(&prev) {
+                return Err(syn::Error::new(
+                    attr.span,
+                    format!("duplicate patch of version {prev}"),
+                ));
+            }
+            patched.push(prev);
+
+            let prev_fields = fields.enabled_in(prev);
+            let next_fields = fields.enabled_in(next);
+            let in_prev =
+                |ident: &syn::Ident| prev_fields.iter().any(|field| field.ident == *ident);
+            let in_next =
+                |ident: &syn::Ident| next_fields.iter().any(|field| field.ident == *ident);
+            let display = |path: &[syn::Ident]| {
+                path.iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(".")
+            };
+
+            // The values the script sets, by their path in the next version, and the fields of
+            // the previous version it moves or deletes
+            let mut set: Vec<(&[syn::Ident], TokenStream2)> = vec![];
+            let mut consumed = vec![];
+            for op in &attr.ops {
+                let (span, to, value) = match op {
+                    PatchOp::Move { span, from, to } => {
+                        if !in_prev(&from[0]) {
+                            return Err(syn::Error::new(
+                                *span,
+                                format!("`{}` isn't a field of version {prev}", from[0]),
+                            ));
+                        }
+                        consumed.push(&from[0]);
+                        (span, to, self.expand_move(quote!(from.#(#from).*)))
+                    }
+                    PatchOp::Default { span, to, value } => {
+                        let value = value.as_ref().map_or_else(
+                            || quote!(::core::default::Default::default()),
+                            ToTokens::to_token_stream,
+                        );
+                        (span, to, value)
+                    }
+                    PatchOp::Delete { span, field } => {
+                        if !in_prev(field) {
+                            return Err(syn::Error::new(
+                                *span,
+                                format!("`{field}` isn't a field of version {prev}"),
+                            ));
+                        }
+                        consumed.push(field);
+                        continue;
+                    }
+                };
+
+                if !in_next(&to[0]) {
+                    return Err(syn::Error::new(
+                        *span,
+                        format!("`{}` isn't a field of version {next}", to[0]),
+                    ));
+                }
+                if to.len() > 2 {
+                    return Err(syn::Error::new(
+                        *span,
+                        format!(
+                            "`{}` is nested too deeply, as only fields of version {next} and \
+                             their fields can be set",
+                            display(to)
+                        ),
+                    ));
+                }
+                // A field is set either as a whole or field by field, but only once
+                if set
+                    .iter()
+                    .any(|(path, _)| path.starts_with(to) || to.starts_with(path))
+                {
+                    return Err(syn::Error::new(
+                        *span,
+                        format!(
+                            "`{}` is set more than once by the patch of version {prev}",
+                            display(to)
+                        ),
+                    ));
+                }
+                set.push((to, value));
+            }
+
+            let mut kept = vec![];
+            let mut values = vec![];
+            for field in &next_fields {
+                let member = &field.ident;
+                let cfgs = field.expand_cfgs();
+                if let Some((_, value)) = set
+                    .iter()
+                    .find(|(path, _)| path.len() == 1 && path[0] == *member)
+                {
+                    values.push(quote!(#cfgs #member: #value));
+                    continue;
+                }
+
+                let nested: Vec<_> = set
+                    .iter()
+                    .filter(|(path, _)| path[0] == *member)
+                    .map(|(path, value)| {
+                        let inner = &path[1];
+                        quote!(#inner: #value)
+                    })
+                    .collect();
+                if !nested.is_empty() {
+                    let ty = field.expand_ty_versioned(next)?;
+                    values.push(quote!(#cfgs #member: #ty { #(#nested),* }));
+                    continue;
+                }
+
+                let from = prev_fields
+                    .iter()
+                    .find(|from| from.ident == *member && !consumed.contains(&member));
+                let Some(from) = from else {
+                    return Err(syn::Error::new(
+                        attr.span,
+                        format!(
+                            "`{member}` of version {next} isn't set by the patch of version \
+                             {prev} (e.g. with `default({member})`)"
+                        ),
+                    ));
+                };
+                kept.push(member);
+                let value = self.expand_move(quote!(from.#member));
+                if field.expand_ty_versioned(next)?.to_string()
+                    == from.expand_ty_versioned(prev)?.to_string()
+                {
+                    values.push(quote!(#cfgs #member: #value));
+                } else if self.converts_field(field) {
+                    // Flattened fields may change version, so compose their migrations
+                    values.push(quote!(#cfgs #member: ::core::convert::From::from(#value)));
+                } else {
+                    return Err(syn::Error::new(
+                        attr.span,
+                        format!(
+                            "`{member}` changed type between versions {prev} and {next}, so has \
+                             to be set by the patch of version {prev}"
+                        ),
+                    ));
+                }
+            }
+
+            if let Some(dropped) = prev_fields
+                .iter()
+                .map(|field| &field.ident)
+                .find(|field| !consumed.contains(field) && !kept.contains(field))
+            {
+                return Err(syn::Error::new(
+                    attr.span,
+                    format!(
+                        "`{dropped}` of version {prev} isn't carried over to version {next}, so \
+                         has to be removed by the patch with `delete({dropped})`"
+                    ),
+                ));
+            }
+
+            let (prev_ident, next_ident) = (self.version_ident(prev), self.version_ident(next));
+            let arg = if self.zeroizes() {
+                quote!(mut from)
+            } else {
+                quote!(from)
+            };
+            // Patches read from a file are recompiled whenever it changes
+            let include = attr.path.as_ref().map(|path| {
+                let path = path.to_string_lossy();
+                quote_spanned!(attr.span => const _: &[u8] = ::core::include_bytes!(#path);)
+            });
+            let cfg = expand_pair_cfg(&versions[i..=i + 1]);
+            impls.append_all(quote! {
+                #include
+                #cfg
+                #[automatically_derived]
+                impl ::core::convert::From<#prev_ident> for #next_ident {
+                    #[inline]
+                    #[allow(unused_mut, unused_variables)]
+                    fn from(#arg: #prev_ident) -> Self {
+                        Self { #(#values,)* }
+                    }
+                }
+            });
+        }
+
+        Ok(impls)
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn expand_partial_migrations(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let Some(attr) = self.attrs.partial_migrations().next() else {
+            return Ok(quote!());
+        };
+
+        let fields = match &self.kind {
+            VersionedItemKind::Struct(inner) => &inner.fields,
+            VersionedItemKind::Enum(_) => {
+                return Err(syn::Error::new(
+                    attr.span,
+                    "`#[obake(partial_migrations)]` can only be applied to `struct`s",
+                ))
+            }
+        };
+
+        let vis = &self.vis;
+        let ident = self.ident();
+        let mut impls = quote!();
+        for pair in versions.windows(2) {
+            let (prev, next) = (&pair[0].version, &pair[1].version);
+            let (prev_ident, next_ident) = (self.version_ident(prev), self.version_ident(next));
+            let removed_ident = format_ident!("{}_Removed", prev_ident);
+            let added_ident = format_ident!("{}_Added", next_ident);
+
+            // A field is kept if it has the same name and type in both versions, and flattened
+            // fields in both versions are converted with the migrations of their own type
+            let mut kept = vec![];
+            let mut converted = vec![];
+            let mut removed = vec![];
+            let mut added = vec![];
+            let (mut removed_members, mut added_members) = (vec![], vec![]);
+            for field in &fields.fields {
+                let (in_prev, in_next) =
+                    (field.attrs.enabled_in(prev), field.attrs.enabled_in(next));
+                let cfgs = field.expand_cfgs();
+                if in_prev && in_next && field.is_flattened() {
+                    converted.push((cfgs, field.member()));
+                    continue;
+                }
+                let same_ty = in_prev
+                    && in_next
+                    && field.expand_ty_versioned(prev)?.to_string()
+                        == field.expand_ty_versioned(next)?.to_string();
+                if same_ty {
+                    kept.push((cfgs, field.member()));
+                    continue;
+                }
+                if in_prev {
+                    let ty = field.expand_ty_versioned(prev)?;
+                    removed.push((cfgs.clone(), &field.ident, ty));
+                    removed_members.push(field.member());
+                }
+                if in_next {
+                    let ty = field.expand_ty_versioned(next)?;
+                    added.push((cfgs, &field.ident, ty));
+                    added_members.push(field.member());
+                }
+            }
+
+            let (kept_cfgs, kept): (Vec<_>, Vec<_>) = kept.into_iter().unzip();
+            let kept_values: Vec<_> = kept
+                .iter()
+                .map(|member| self.expand_move(quote!(self.#member)))
+                .collect();
+            let (converted_cfgs, converted): (Vec<_>, Vec<_>) = converted.into_iter().unzip();
+            let converted_values: Vec<_> = converted
+                .iter()
+                .map(|member| self.expand_move(quote!(self.#member)))
+                .collect();
+            let removed_values: Vec<_> = removed_members
+                .iter()
+                .map(|member| self.expand_move(quote!(self.#member)))
+                .collect();
+            let receiver = if self.zeroizes() {
+                quote!(mut self)
+            } else {
+                quote!(self)
+            };
+            let (removed_cfgs, removed_idents, removed_tys) = unzip3(removed);
+            let (added_cfgs, added_idents, added_tys) = unzip3(added);
+            let removed_doc = format!(
+                "The fields of version {prev} of `{ident}` which aren't carried over to version \
+                 {next} unchanged."
+            );
+            let added_doc =
+                format!("The fields of version {next} of `{ident}` which aren't carried over from version {prev} unchanged.");
+            let migrate_doc = format!(
+                "Migrates to version {next}, moving every field with the same name and type in \
+                 both versions (converting flattened fields), and computing the other fields of \
+                 version {next} from the remaining fields with `f`."
+            );
+
+            // Every helper here is for migrating out of an older version
+            let hidden = self.expand_hidden_if_old(prev, versions);
+            let cfg = expand_pair_cfg(pair);
+            impls.append_all(quote! {
+                #cfg
+                #[doc = #removed_doc]
+                #hidden
+                #[allow(non_camel_case_types)]
+                #vis struct #removed_ident {
+                    #(#removed_cfgs #vis #removed_idents: #removed_tys,)*
+                }
+
+                #cfg
+                #[doc = #added_doc]
+                #hidden
+                #[allow(non_camel_case_types)]
+                #vis struct #added_ident {
+                    #(#added_cfgs #vis #added_idents: #added_tys,)*
+                }
+
+                #cfg
+                impl #prev_ident {
+                    #[doc = #migrate_doc]
+                    #hidden
+                    #[allow(dead_code)]
+                    #vis fn migrate_with<F>(#receiver, f: F) -> #next_ident
+                    where
+                        F: ::core::ops::FnOnce(#removed_ident) -> #added_ident,
+                    {
+                        let #added_ident { #(#added_cfgs #added_idents,)* } = f(#removed_ident {
+                            #(#removed_cfgs #removed_idents: #removed_values,)*
+                        });
+
+                        #next_ident {
+                            #(#kept_cfgs #kept: #kept_values,)*
+                            #(
+                                #converted_cfgs
+                                #converted: ::core::convert::From::from(#converted_values),
+                            )*
+                            #(#added_cfgs #added_members: #added_idents,)*
+                        }
+                    }
+                }
+            });
+        }
+
+        Ok(impls)
+    }
+
+    fn expand_tests(&self) -> TokenStream2 {
+        if self.attrs.generate_tests().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let mod_ident = format_ident!("{}_obake_tests", ident);
+        let variants: Vec<_> = self.expand_variants().collect();
+        let upgrade_tests =
+            variants
+                .iter()
+                .zip(self.expand_variant_cfgs())
+                .map(|(variant, cfg)| {
+                    let test_ident = format_ident!("upgrade_from_{}", variant);
+                    quote! {
+                        #cfg
+                        #[test]
+                        fn #test_ident() {
+                            let from = #enum_ident::#variant(::core::default::Default::default());
+                            let _: #ident = from.into();
+                        }
+                    }
+                });
+
+        #[cfg(feature = "json")]
+        let round_trip_tests = self.expand_round_trip_tests();
+        #[cfg(not(feature = "json"))]
+        let round_trip_tests = quote!();
+
+        #[cfg(feature = "proptest")]
+        let downgrade_tests = self.expand_downgrade_tests();
+        #[cfg(not(feature = "proptest"))]
+        let downgrade_tests = quote!();
+
+        quote! {
+            #[cfg(test)]
+            #[allow(non_snake_case)]
+            mod #mod_ident {
+                use super::*;
+
+                #(#upgrade_tests)*
+                #round_trip_tests
+                #downgrade_tests
+            }
+        }
+    }
+
+    /// Expands to property tests checking that downgrading and then upgrading a value preserves
+    /// the fields shared by both versions.
+    #[cfg(feature = "proptest")]
+    fn expand_downgrade_tests(&self) -> TokenStream2 {
+        let fields = match &self.kind {
+            VersionedItemKind::Struct(inner) => &inner.fields,
+            VersionedItemKind::Enum(_) => return quote!(),
+        };
+
+        if self.attrs.downgrades().next().is_none() || self.attrs.proptests().next().is_none() {
+            return quote!();
+        }
+
+        let mut versions: Vec<_> = self.attrs.versions().collect();
+        versions.sort();
+
+        let proptest = quote!(::obake::__private::proptest);
+        let tests = versions.windows(2).map(|pair| {
+            let prev = self.version_ident(&pair[0].version);
+            let next = self.version_ident(&pair[1].version);
+            let test_ident = format_ident!("downgrade_round_trip_{}", prev);
+            let cfg = expand_pair_cfg(pair);
+            let shared = fields
+                .fields
+                .iter()
+                .filter(|field| {
+                    field.attrs.enabled_in(&pair[0].version)
+                        && field.attrs.enabled_in(&pair[1].version)
+                })
+                .map(|field| {
+                    let cfgs = field.expand_cfgs();
+                    let member = field.member();
+                    quote!(#cfgs #proptest::prop_assert_eq!(&to.#member, &from.#member);)
+                });
+
+            quote! {
+                #cfg
+                #[test]
+                fn #test_ident(from in #proptest::arbitrary::any::<#next>()) {
+                    let to: #next = #prev::from(::core::clone::Clone::clone(&from)).into();
+                    #(#shared)*
+                }
+            }
+        });
+
+        quote! {
+            #proptest::proptest! {
+                #(#tests)*
+            }
+        }
+    }
+
+    #[cfg(feature = "json")]
+    fn expand_round_trip_tests(&self) -> TokenStream2 {
+        if !self.uses_serde() {
+            return quote!();
+        }
+
+        let enum_ident = self.enum_ident();
+        let serde_json = quote!(::obake::__private::serde_json);
+        self.expand_variants()
+            .zip(self.expand_variant_cfgs())
+            .map(|(variant, cfg)| {
+                let test_ident = format_ident!("round_trip_{}", variant);
+                quote! {
+                    #cfg
+                    #[test]
+                    fn #test_ident() {
+                        let from = #enum_ident::#variant(::core::default::Default::default());
+                        let json = #serde_json::to_value(&from).unwrap();
+                        let to: #enum_ident = #serde_json::from_value(json.clone()).unwrap();
+                        assert!(
+                            ::core::matches!(to, #enum_ident::#variant(_)),
+                            "deserialized as the wrong version",
+                        );
+                        assert_eq!(#serde_json::to_value(&to).unwrap(), json);
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// The versions among `versions` in the order their variants are declared in the generated
+    /// `enum` (i.e. as written rather than sorted, with an `untagged_legacy` version last), which
+    /// is the order of their indices in encodings such as `bincode`.
+    fn variant_order<'a>(&'a self, versions: &'a [VersionAttr]) -> Vec<&'a VersionAttr> {
+        let (legacy, tagged): (Vec<_>, Vec<_>) = self
+            .attrs
+            .versions()
+            .filter(|attr| versions.contains(attr))
+            .partition(|attr| attr.untagged_legacy.is_some());
+        tagged.into_iter().chain(legacy).collect()
+    }
+
+    fn expand_variants(&self) -> impl Iterator<Item = syn::Ident> + '_ {
+        self.attrs
+            .versions()
+            .map(move |attr| self.version_ident(&attr.version))
+    }
+
+    /// The `#[cfg(...)]` attributes of each of [`Self::expand_variants`].
+    fn expand_variant_cfgs(&self) -> impl Iterator<Item = TokenStream2> + '_ {
+        self.attrs.versions().map(VersionAttr::expand_cfg)
+    }
+
+    /// The ways of constructing `version`, named by `ty`: the `struct` itself, or each of the
+    /// enabled variants of an `enum`.
+    fn constructors(&self, version: &Version, ty: &TokenStream2) -> Vec<Constructor<'_>> {
+        match &self.kind {
+            VersionedItemKind::Struct(inner) => vec![Constructor {
+                path: quote!(#ty),
+                fields: ConstructorFields::Named(inner.fields.enabled_in(version)),
+            }],
+            VersionedItemKind::Enum(inner) => inner
+                .variants
+                .variants
+                .iter()
+                .filter(|variant| variant.attrs.enabled_in(version))
+                .map(|variant| {
+                    let ident = variant.ident_in(version);
+                    Constructor {
+                        path: quote!(#ty::#ident),
+                        fields: match &variant.fields {
+                            VersionedVariantFields::Named(fields) => {
+                                ConstructorFields::Named(fields.enabled_in(version))
+                            }
+                            VersionedVariantFields::Unnamed(fields) => {
+                                ConstructorFields::Unnamed(fields.fields.iter().collect())
+                            }
+                            VersionedVariantFields::Unit => ConstructorFields::Unit,
+                        },
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Expands to the `wasm-bindgen` attribute exporting the latest version of an item declared
+    /// with `#[obake(wasm)]` as a class named after the item.
+    #[cfg(feature = "wasm")]
+    fn expand_wasm_attrs(&self) -> Result<TokenStream2> {
+        let Some(attr) = self.attrs.wasms().next() else {
+            return Ok(quote!());
+        };
+
+        if let VersionedItemKind::Enum(_) = self.kind {
+            return Err(syn::Error::new(
+                attr.span,
+                "`#[obake(wasm)]` can only be applied to `struct`s",
+            ));
+        }
+
+        let name = self.ident().to_string();
+        Ok(quote! {
+            #[::obake::__private::wasm_bindgen::prelude::wasm_bindgen(
+                wasm_bindgen = ::obake::__private::wasm_bindgen,
+                js_name = #name,
+                getter_with_clone,
+            )]
+        })
+    }
+
+    /// Expands to the `pyo3` attribute exporting the latest version of an item declared with
+    /// `#[obake(pyo3)]` as a class named after the item.
+    #[cfg(feature = "pyo3")]
+    fn expand_pyo3_attrs(&self) -> Result<TokenStream2> {
+        let Some(attr) = self.attrs.pyo3s().next() else {
+            return Ok(quote!());
+        };
+
+        if let VersionedItemKind::Enum(_) = self.kind {
+            return Err(syn::Error::new(
+                attr.span,
+                "`#[obake(pyo3)]` can only be applied to `struct`s",
+            ));
+        }
+
+        let name = self.ident().to_string();
+        Ok(quote! {
+            #[::obake::__private::pyo3::pyclass(
+                crate = "::obake::__private::pyo3",
+                name = #name,
+                skip_from_py_object,
+            )]
+        })
+    }
+
+    /// Expands to the `abi_stable` derive for every version of an item declared with
+    /// `#[obake(abi_stable)]`, which are `#[repr(C)]` unless they declare their own `repr`.
+    #[cfg(feature = "abi_stable")]
+    fn expand_abi_stable_attrs(&self) -> TokenStream2 {
+        if self.attrs.abi_stables().next().is_none() {
+            return quote!();
+        }
+
+        let repr = if self.attrs.attrs().any(|attr| attr.path.is_ident("repr")) {
+            quote!()
+        } else {
+            quote!(#[repr(C)])
+        };
+        quote! {
+            #[derive(::abi_stable::StableAbi)]
+            #repr
+        }
+    }
+
+    #[cfg(feature = "wasm")]
+    fn expand_wasm_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if self.attrs.wasms().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let latest = self.version_ident(&versions.last().unwrap().version);
+        let name = ident.to_string();
+        let doc = format!(
+            "Upgrades a version-tagged JSON document to the latest version of `{ident}`, as JSON."
+        );
+        quote! {
+            #[::obake::__private::wasm_bindgen::prelude::wasm_bindgen(
+                wasm_bindgen = ::obake::__private::wasm_bindgen,
+                js_class = #name,
+            )]
+            impl #latest {
+                #[doc = #doc]
+                #[wasm_bindgen(js_name = "upgradeJson")]
+                pub fn upgrade_json(
+                    json: &str,
+                ) -> ::core::result::Result<
+                    ::std::string::String,
+                    ::obake::__private::wasm_bindgen::JsError,
+                > {
+                    ::obake::wasm::upgrade_json::<Self>(json).map_err(|err| {
+                        ::obake::__private::wasm_bindgen::JsError::new(&::std::string::ToString::to_string(&err))
+                    })
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "tonic")]
+    fn expand_tonic_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if self.attrs.tonics().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let decodes = versions.iter().map(|attr| {
+            let version = self.version_ident(&attr.version);
+            let version_str = attr.version.to_string();
+            let cfg = attr.expand_cfg();
+            quote! {
+                #cfg
+                #version_str => ::core::option::Option::Some(
+                    <#version as ::obake::__private::prost::Message>::decode(payload)
+                        .map(#enum_ident::#version),
+                ),
+            }
+        });
+        let encodes = versions.iter().map(|attr| {
+            let version = self.version_ident(&attr.version);
+            let version_str = attr.version.to_string();
+            let cfg = attr.expand_cfg();
+            quote! {
+                #cfg
+                #enum_ident::#version(x) => (
+                    #version_str,
+                    ::obake::__private::prost::Message::encode_to_vec(x),
+                ),
+            }
+        });
+
+        quote! {
+            #[automatically_derived]
+            impl ::obake::tonic::VersionedMessage for #ident {
+                fn decode_as(
+                    version: &str,
+                    payload: &[u8],
+                ) -> ::core::option::Option<
+                    ::core::result::Result<#enum_ident, ::obake::__private::prost::DecodeError>,
+                > {
+                    match version {
+                        #(#decodes)*
+                        _ => ::core::option::Option::None,
+                    }
+                }
+
+                fn encode_versioned(
+                    versioned: &#enum_ident,
+                ) -> (&'static str, ::std::vec::Vec<u8>) {
+                    match versioned {
+                        #(#encodes)*
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "fixtures")]
+    fn expand_fixtures_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if self.attrs.fixtures().next().is_none() {
+            return quote!();
+        }
+
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let writes = versions.iter().map(|attr| {
+            let version = self.version_ident(&attr.version);
+            let version_str = attr.version.to_string();
+            let cfg = attr.expand_cfg();
+            quote! {
+                #cfg
+                ::obake::fixtures::write(
+                    dir,
+                    <Self as ::obake::Versioned>::NAME,
+                    #version_str,
+                    &#enum_ident::#version(::core::default::Default::default()),
+                )?;
+            }
+        });
+
+        quote! {
+            #[cfg(test)]
+            impl #ident {
+                /// Writes a sample payload for every version of this data-structure into `dir`.
+                #[allow(dead_code)]
+                pub fn write_fixtures<P>(dir: P) -> ::std::io::Result<()>
+                where
+                    P: ::core::convert::AsRef<::std::path::Path>,
+                {
+                    let dir = dir.as_ref();
+                    #(#writes)*
+                    ::core::result::Result::Ok(())
+                }
+            }
+        }
+    }
+
+    /// Expands to a module of `criterion` benchmarks deserializing and upgrading payloads in every
+    /// version.
+    #[cfg(feature = "criterion")]
+    fn expand_benches(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let Some(attr) = self.attrs.generate_benches().next() else {
+            return quote!();
+        };
+
+        let vis = &self.vis;
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let module = format_ident!("{}_benches", snake_case(ident));
+        let benches = versions.iter().map(|attr| {
+            let version = self.version_ident(&attr.version);
+            let version_str = attr.version.to_string();
+            let cfg = attr.expand_cfg();
+            quote! {
+                #cfg
+                ::obake::criterion::bench_version::<super::#version, super::#ident>(
+                    &mut group,
+                    #version_str,
+                    |from| super::#enum_ident::#version(from).into(),
+                );
+            }
+        });
+
+        let cfg = attr
+            .feature
+            .as_ref()
+            .map(|feature| quote!(#[cfg(feature = #feature)]));
+        let module_doc = format!("`criterion` benchmarks for every version of [`{ident}`].");
+        let doc = format!(
+            "Benchmarks deserializing and upgrading payloads in every version of \
+             [`{ident}`](super::{ident})."
+        );
+
+        quote! {
+            #cfg
+            #[doc = #module_doc]
+            #vis mod #module {
+                #[doc = #doc]
+                pub fn migrations(c: &mut ::obake::__private::criterion::Criterion) {
+                    let mut group = ::obake::criterion::group::<super::#ident>(c);
+                    #(#benches)*
+                    group.finish();
+                }
+            }
+        }
+    }
+
+    /// Expands to `fuzz_decode_upgrade`, decoding a payload in any version and upgrading it to the
+    /// latest version.
+    #[cfg(feature = "fuzz")]
+    fn expand_fuzz_impl(&self) -> TokenStream2 {
+        let Some(attr) = self.attrs.fuzzes().next() else {
+            return quote!();
+        };
+
+        let vis = &self.vis;
+        let ident = self.ident();
+        let format = attr
+            .format
+            .as_ref()
+            .map_or_else(|| quote!(::obake::fuzz::Json), |format| quote!(#format));
+
+        quote! {
+            impl #ident {
+                /// Decodes `bytes` as any version of this data-structure and upgrades it to the
+                /// latest version, for use as the body of a `cargo fuzz` target. Payloads which
+                /// can't be decoded are ignored.
+                #[allow(dead_code)]
+                #vis fn fuzz_decode_upgrade(bytes: &[u8]) {
+                    ::obake::fuzz::decode_upgrade::<Self, #format>(bytes);
+                }
+            }
+        }
+    }
+
+    /// Expands to an impl of `obake::dynamic::Dynamic`, describing how the serialized fields of
+    /// each version differ from those of the previous version.
+    #[cfg(feature = "dynamic")]
+    fn expand_dynamic_impl(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let Some(attr) = self.attrs.dynamics().next() else {
+            return Ok(quote!());
+        };
+
+        let VersionedItemKind::Struct(inner) = &self.kind else {
+            return Err(syn::Error::new(
+                attr.span,
+                "`#[obake(dynamic)]` can only be applied to `struct`s",
+            ));
+        };
+
+        let dynamic = quote!(::obake::dynamic);
+        let mut steps = vec![];
+        for pair in versions.windows(2) {
+            let (prev, next) = (&pair[0].version, &pair[1].version);
+            let prev_rename_all = serde_option(&self.attrs, prev, "rename_all")?;
+            let next_rename_all = serde_option(&self.attrs, next, "rename_all")?;
+
+            let mut changes = vec![];
+            for field in &inner.fields.fields {
+                let cfgs = field.expand_cfgs();
+                let prev_name = if field.attrs.enabled_in(prev) {
+                    field.serde_name(prev, prev_rename_all.as_deref())?
+                } else {
+                    None
+                };
+                let next_name = if field.attrs.enabled_in(next) {
+                    field.serde_name(next, next_rename_all.as_deref())?
+                } else {
+                    None
+                };
+
+                changes.push(match (prev_name, next_name) {
+                    (Some(from), Some(to)) if from != to => {
+                        quote!(#cfgs #dynamic::Change::Rename { from: #from, to: #to })
+                    }
+                    (Some(name), None) => quote!(#cfgs #dynamic::Change::Remove { field: #name }),
+                    (None, Some(name)) => {
+                        // Values computed by a typed migration can't be filled in by default
+                        let default = if field.attrs.retypeds().next().is_some()
+                            || field.attrs.computeds().next().is_some()
+                        {
+                            quote!(::core::option::Option::None)
+                        } else {
+                            let ty = field.expand_ty_versioned(next)?;
+                            quote! {{
+                                use ::obake::__private::{NoDynamicDefault as _, ViaDefault as _};
+                                let default = ::obake::__private::DynamicDefault::<#ty>(::core::marker::PhantomData);
+                                (&&default).dynamic_default()
+                            }}
+                        };
+                        quote! {
+                            #cfgs #dynamic::Change::Add { field: #name, default: || #default }
+                        }
+                    }
+                    _ => continue,
+                });
+            }
+
+            let cfg = expand_pair_cfg(pair);
+            let (prev, next) = (prev.to_string(), next.to_string());
+            steps.push(quote! {
+                #cfg #dynamic::Step { from: #prev, to: #next, changes: &[#(#changes),*] }
+            });
+        }
+
+        let ident = self.ident();
+        let tag = self.serde_tag().map_or_else(
+            || quote!(::core::option::Option::None),
+            |tag| quote!(::core::option::Option::Some(#tag)),
+        );
+        Ok(quote! {
+            impl #dynamic::Dynamic for #ident {
+                const TAG: ::core::option::Option<&'static str> = #tag;
+                const STEPS: &'static [#dynamic::Step] = &[#(#steps),*];
+            }
+        })
+    }
+
+    #[cfg(feature = "arbitrary")]
+    fn expand_arbitrary_impls(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if self.attrs.arbitraries().next().is_none() {
+            return quote!();
+        }
+
+        let arbitrary = quote!(::obake::__private::arbitrary);
+        let choose = |ctors: Vec<TokenStream2>| {
+            if ctors.is_empty() {
+                return quote!(::core::result::Result::Err(#arbitrary::Error::EmptyChoose));
+            }
+
+            let len = ctors.len();
+            let indices = 0..len;
+            quote! {
+                ::core::result::Result::Ok(match u.choose_index(#len)? {
+                    #(#indices => #ctors,)*
+                    _ => ::core::unreachable!(),
+                })
+            }
+        };
+
+        let version_impls = versions.iter().map(|attr| {
+            let ident = self.version_ident(&attr.version);
+            let body = choose(
+                self.constructors(&attr.version, &quote!(Self))
+                    .iter()
+                    .map(|ctor| ctor.expand(|_| quote!(#arbitrary::Arbitrary::arbitrary(u)?)))
+                    .collect(),
+            );
+
+            let cfg = attr.expand_cfg();
+            quote! {
+                #cfg
+                #[automatically_derived]
+                impl<'a> #arbitrary::Arbitrary<'a> for #ident {
+                    fn arbitrary(u: &mut #arbitrary::Unstructured<'a>) -> #arbitrary::Result<Self> {
+                        #body
+                    }
+                }
+            }
+        });
+
+        let enum_ident = self.enum_ident();
+        let variants = self.expand_variants();
+        let cfgs = self.expand_variant_cfgs();
+
+        quote! {
+            #(#version_impls)*
+
+            #[automatically_derived]
+            impl<'a> #arbitrary::Arbitrary<'a> for #enum_ident {
+                fn arbitrary(u: &mut #arbitrary::Unstructured<'a>) -> #arbitrary::Result<Self> {
+                    // Versions can be gated behind features, so choose between a list of
+                    // constructors rather than matching on an index
+                    let choices: &[fn(
+                        &mut #arbitrary::Unstructured<'a>,
+                    ) -> #arbitrary::Result<Self>] = &[#(
+                        #cfgs
+                        |u| ::core::result::Result::Ok(
+                            Self::#variants(#arbitrary::Arbitrary::arbitrary(u)?),
+                        ),
+                    )*];
+                    (u.choose(choices)?)(u)
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "proptest")]
+    fn expand_proptest_impls(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        if self.attrs.proptests().next().is_none() {
+            return Ok(quote!());
+        }
+
+        let proptest = quote!(::obake::__private::proptest);
+        let strategy = quote!(#proptest::strategy::Strategy);
+        let union = |strategies: Vec<TokenStream2>| {
+            quote! {
+                #strategy::boxed(#proptest::strategy::Union::new(::std::vec![
+                    #(#strategy::boxed(#strategies),)*
+                ]))
+            }
+        };
+
+        let mut version_impls = vec![];
+        for attr in versions {
+            let ident = self.version_ident(&attr.version);
+
+            let mut ctors = vec![];
+            for ctor in self.constructors(&attr.version, &quote!(Self)) {
+                // Generate values for each field as nested pairs, `(f_0, (f_1, ... ()))`, so that
+                // we aren't limited by the arities `Strategy` is implemented for
+                let mut strategies = quote!(#proptest::strategy::Just(()));
+                let mut pattern = quote!(());
+                for (i, field) in ctor.fields().iter().enumerate().rev() {
+                    let field_strategy = match field {
+                        FieldRef::Named(field) => {
+                            if let Some(attr) = field.attrs.strategies().next() {
+                                attr.expr.to_token_stream()
+                            } else {
+                                let ty = field.expand_ty_versioned(&attr.version)?;
+                                quote!(#proptest::arbitrary::any::<#ty>())
+                            }
+                        }
+                        FieldRef::Unnamed(field) => {
+                            let ty = field.expand_ty_versioned(&attr.version)?;
+                            quote!(#proptest::arbitrary::any::<#ty>())
+                        }
+                    };
+                    let binding = format_ident!("field_{}", i);
+                    strategies = quote!((#field_strategy, #strategies));
+                    pattern = quote!((#binding, #pattern));
+                }
+
+                let mut i = 0_usize;
+                let expr = ctor.expand(|_| {
+                    let binding = format_ident!("field_{}", i);
+                    i += 1;
+                    quote!(#binding)
+                });
+                ctors.push(quote!(#strategy::prop_map(#strategies, |#pattern| #expr)));
+            }
+
+            let body = if ctors.len() == 1 {
+                let ctor = &ctors[0];
+                quote!(#strategy::boxed(#ctor))
+            } else {
+                union(ctors)
+            };
+
+            let cfg = attr.expand_cfg();
+            version_impls.push(quote! {
+                #cfg
+                #[automatically_derived]
+                impl #proptest::arbitrary::Arbitrary for #ident {
+                    type Parameters = ();
+                    type Strategy = #proptest::strategy::BoxedStrategy<Self>;
+
+                    fn arbitrary_with((): ()) -> Self::Strategy {
+                        #body
+                    }
+                }
+            });
+        }
+
+        let enum_ident = self.enum_ident();
+        let variants = self.expand_variants();
+        let cfgs = self.expand_variant_cfgs();
+        let enum_body = quote! {
+            #strategy::boxed(#proptest::strategy::Union::new(::std::vec![#(
+                #cfgs
+                #strategy::boxed(#strategy::prop_map(
+                    #proptest::arbitrary::any::<#variants>(),
+                    #enum_ident::#variants,
+                )),
+            )*]))
+        };
+
+        Ok(quote! {
+            #(#version_impls)*
+
+            #[automatically_derived]
+            impl #proptest::arbitrary::Arbitrary for #enum_ident {
+                type Parameters = ();
+                type Strategy = #proptest::strategy::BoxedStrategy<Self>;
+
+                fn arbitrary_with((): ()) -> Self::Strategy {
+                    #enum_body
+                }
+            }
+        })
+    }
+
+    /// The identifier of the generated `struct` (or `enum`) for `version`, which is named after
+    /// the earliest `#[obake(renamed_from(...))]` still in effect in that version, if any.
+    fn version_ident(&self, version: &Version) -> syn::Ident {
+        self.attrs
+            .renamed_froms()
+            .filter(|renamed_from| *version < renamed_from.until)
+            .min_by(|a, b| a.until.cmp(&b.until))
+            .map_or_else(|| self.ident(), |renamed_from| &renamed_from.ident)
+            .version(version)
+    }
+
+    /// Expands to aliases named after the current identifier for every version generated with
+    /// an old identifier, so that `#[obake(inherit)]` can still find them.
+    fn expand_renamed_aliases(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let vis = &self.vis;
+        let aliases = versions.iter().filter_map(|attr| {
+            let renamed = self.version_ident(&attr.version);
+            let alias = self.ident().version(&attr.version);
+            if renamed == alias {
+                return None;
+            }
+
+            let cfg = attr.expand_cfg();
+            let deprecated = attr.expand_deprecated();
+            Some(quote! {
+                #cfg
+                #[doc(hidden)]
+                #[allow(non_camel_case_types)]
+                #deprecated
+                #vis type #alias = #renamed;
+            })
+        });
+
+        quote!(#(#aliases)*)
+    }
+
+    fn enum_ident(&self) -> syn::Ident {
+        format_ident!("Versioned{}", self.ident())
+    }
+
+    fn expand_enum_decl(&self) -> TokenStream2 {
+        let vis = &self.vis;
+        let enum_ident = self.enum_ident();
+        let variants = self.expand_variants();
+        let cfgs = self.expand_variant_cfgs();
+        let derives = self.attrs.derives().map(|attr| {
+            let tokens = &attr.tokens;
+            quote!(#[derive(#tokens)])
+        });
+        let serde_attrs = self.expand_serde_attrs();
+        // The generated `enum` is tagged with a `u8`, so it has a stable layout for `abi_stable`
+        #[cfg(feature = "abi_stable")]
+        let abi_stable_attrs = if self.attrs.abi_stables().next().is_some() {
+            quote! {
+                #[derive(::abi_stable::StableAbi)]
+                #[repr(C, u8)]
+            }
+        } else {
+            quote!()
+        };
+        #[cfg(not(feature = "abi_stable"))]
+        let abi_stable_attrs = quote!();
+        #[cfg(feature = "graphql")]
+        let graphql_attrs = self.expand_graphql_enum_attrs();
+        #[cfg(not(feature = "graphql"))]
+        let graphql_attrs = quote!();
+        // One-of input objects have a field per version, named after the version (e.g.
+        // `v0_1_0`) rather than the variant
+        #[cfg(feature = "graphql")]
+        let graphql_input = self.attrs.graphqls().any(|attr| attr.input);
+        #[cfg(not(feature = "graphql"))]
+        let graphql_input = false;
+        let graphql_renames = self.attrs.versions().map(|attr| {
+            if graphql_input {
+                let version = &attr.version;
+                let name = format!("v{}", version_suffix(version));
+                quote!(#[graphql(name = #name)])
+            } else {
+                quote!()
+            }
+        });
+        let docs = self.attrs.versions().map(|attr| {
+            let doc = format!("Version {} of [`{}`].", attr.version, self.ident());
+            quote!(#[doc = #doc])
+        });
+        let enum_doc = format!(
+            "Any version of [`{}`], generated by `#[obake::versioned]`.",
+            self.ident()
+        );
+        let deprecations = self.attrs.versions().map(VersionAttr::expand_deprecated);
+        let renames = self.attrs.versions().map(|attr| {
+            if attr.untagged_legacy.is_some() {
+                quote!(#[serde(untagged)])
+            } else if self.uses_serde() {
+                let version = attr.version.to_string();
+                quote!(#[serde(rename = #version)])
+            } else {
+                quote!()
+            }
+        });
+        let decls = variants
+            .zip(cfgs)
+            .zip(docs)
+            .zip(deprecations)
+            .zip(renames)
+            .zip(graphql_renames)
+            .map(
+                |(((((variant, cfg), doc), deprecation), rename), graphql_rename)| {
+                    quote! {
+                        #cfg
+                        #[allow(non_camel_case_types)]
+                        #doc
+                        #deprecation
+                        #rename
+                        #graphql_rename
+                        #variant(#variant),
+                    }
+                },
+            );
+        // `serde` only tries untagged variants once every tagged variant has failed, and requires
+        // them to be declared last
+        let (legacy, decls): (Vec<_>, Vec<_>) = self
+            .attrs
+            .versions()
+            .zip(decls)
+            .partition(|(attr, _)| attr.untagged_legacy.is_some());
+        let decls = decls.into_iter().chain(legacy).map(|(_, decl)| decl);
+
+        quote! {
+            #[doc(hidden)]
+            #[doc = #enum_doc]
+            #(#derives)*
+            #serde_attrs
+            #abi_stable_attrs
+            #graphql_attrs
+            #vis enum #enum_ident {
+                #(#decls)*
+            }
+        }
+    }
+
+    /// Expands to the `async-graphql` derive making the generated `enum` a union (or, for input
+    /// objects, a one-of input object) over every version.
+    #[cfg(feature = "graphql")]
+    fn expand_graphql_enum_attrs(&self) -> TokenStream2 {
+        let Some(attr) = self.attrs.graphqls().next() else {
+            return quote!();
+        };
+
+        let name = self.enum_ident().to_string();
+        let derive = if attr.input {
+            quote!(OneofObject)
+        } else {
+            quote!(Union)
+        };
+        quote! {
+            #[derive(::obake::__private::async_graphql::#derive)]
+            #[graphql(crate = "::obake::__private::async_graphql", name = #name)]
+        }
+    }
+
+    /// Finds the span of the first change between a `pair` of adjacent versions: the
+    /// `#[obake(cfg(...))]` attribute of the first field or variant added or removed, or the
+    /// `#[obake(version(...))]` attribute of the later version.
+    fn migration_span(&self, pair: &[VersionAttr]) -> Span {
+        let changed = |attrs: &VersionedAttributes| {
+            attrs.enabled_in(&pair[0].version) != attrs.enabled_in(&pair[1].version)
+        };
+        let attrs: Vec<_> = match &self.kind {
+            VersionedItemKind::Struct(inner) => inner
+                .fields
+                .fields
+                .iter()
+                .map(|field| &field.attrs)
+                .collect(),
+            VersionedItemKind::Enum(inner) => inner
+                .variants
+                .variants
+                .iter()
+                .map(|variant| &variant.attrs)
+                .collect(),
+        };
+
+        attrs
+            .into_iter()
+            .find(|attrs| changed(attrs))
+            .and_then(|attrs| attrs.cfgs().next())
+            .map_or(pair[1].span, |cfg| cfg.span)
+    }
+
+    /// Expands to the match arms of the upgrade step, each migrating a version (bound to `x`) to
+    /// the next.
+    fn expand_upgrade_arms(&self, versions: &[VersionAttr]) -> Vec<TokenStream2> {
+        let enum_ident = self.enum_ident();
+        #[cfg(feature = "json")]
+        let preserve_unknown = self.attrs.preserve_unknowns().next().is_some();
+        #[cfg(not(feature = "json"))]
+        let preserve_unknown = false;
+        versions
+            .windows(2)
+            .map(|pair| {
+                let prev = self.version_ident(&pair[0].version);
+                let next = self.version_ident(&pair[1].version);
+                let from = expand_conversion(&prev, &next, self.migration_span(pair));
+                let cfg = expand_pair_cfg(pair);
+                let check = quote!();
+                #[cfg(feature = "metrics")]
+                let check = {
+                    let name = self.ident().to_string();
+                    let (from, to) = (pair[0].version.to_string(), pair[1].version.to_string());
+                    quote! {
+                        #check
+                        ::obake::__private::record_migration(#name, #from, #to);
+                    }
+                };
+                // Each migration is traced until the next version has been constructed
+                #[cfg(feature = "tracing")]
+                let check = {
+                    let ident = self.ident();
+                    let (from, to) = (pair[0].version.to_string(), pair[1].version.to_string());
+                    quote! {
+                        #check
+                        let _hop = ::obake::tracing::hop::<#ident>(#from, #to);
+                    }
+                };
+                if !preserve_unknown {
+                    return quote! {
+                        #cfg
+                        #enum_ident::#prev(x) => {
+                            #check
+                            ::core::ops::ControlFlow::Continue(#enum_ident::#next(#from))
+                        }
+                    };
+                }
+
+                // Unknown fields are carried over whether or not the migration moves them
+                quote! {
+                    #cfg
+                    #enum_ident::#prev(mut x) => {
+                        #check
+                        let unknown_fields = ::core::mem::take(&mut x.unknown_fields);
+                        let mut next: #next = #from;
+                        next.unknown_fields.extend(unknown_fields);
+                        ::core::ops::ControlFlow::Continue(#enum_ident::#next(next))
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn expand_from_impl(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let alias = self.version_ident(&versions.last().unwrap().version);
+        let migrations = self.expand_upgrade_arms(versions);
+        #[cfg(feature = "tracing")]
+        let trace = quote! {
+            let _upgrade = ::obake::tracing::upgrade::<Self>(
+                <Self as ::obake::Versioned>::version_of(&from),
+            );
+        };
+        #[cfg(not(feature = "tracing"))]
+        let trace = quote!();
+
+        let from_latest = quote! {
+            #[automatically_derived]
+            impl From<#ident> for #enum_ident {
+                #[inline]
+                fn from(from: #ident) -> Self {
+                    Self::#alias(from)
+                }
+            }
+        };
+        // `async-graphql` unions already convert from each of their variants
+        #[cfg(feature = "graphql")]
+        let from_latest = if self.attrs.graphqls().any(|attr| !attr.input) {
+            quote!()
+        } else {
+            from_latest
+        };
+
+        quote! {
+            #[automatically_derived]
+            impl #enum_ident {
+                /// Migrates a value to the next version, or returns it if it's in the latest
+                /// version. Every migration is generated once, here, and shared by each way of
+                /// upgrading (e.g. `From` and, with `#[obake(validator)]`, `ValidatedUpgrade`).
+                fn __obake_upgrade_step(self) -> ::core::ops::ControlFlow<#ident, Self> {
+                    match self {
+                        #(#migrations)*
+                        #enum_ident::#alias(x) => ::core::ops::ControlFlow::Break(x),
+                    }
+                }
+            }
+
+            // Not `#[inline]`, so that the upgrade path is only generated once, however many
+            // versions there are
+            #[automatically_derived]
+            impl From<#enum_ident> for #ident {
+                fn from(mut from: #enum_ident) -> Self {
+                    #trace
+                    loop {
+                        from = match from.__obake_upgrade_step() {
+                            ::core::ops::ControlFlow::Continue(next) => next,
+                            ::core::ops::ControlFlow::Break(x) => return x,
+                        };
+                    }
+                }
+            }
+
+            #from_latest
+        }
+    }
+
+    /// Expands to a module of readable aliases (e.g. `foo::V0_1_0`) for every version, whose
+    /// names are spanned to their `#[obake(version(...))]` attributes, so that completions have
+    /// docs and go-to-definition lands on the version being declared.
+    fn expand_aliases(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if self.attrs.aliases().next().is_none() {
+            return quote!();
+        }
+
+        let vis = &self.vis;
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let module = snake_case(ident);
+        let aliases = versions.iter().map(|attr| {
+            let version = &attr.version;
+            let alias = format_ident!(
+                "V{}",
+                version_suffix(version).to_uppercase(),
+                span = attr.span,
+            );
+            let target = self.version_ident(version);
+            let doc = format!("Version {version} of [`{ident}`](super::{ident}).");
+            let cfg = attr.expand_cfg();
+            let deprecated = attr.expand_deprecated();
+            let hidden = self.expand_hidden_if_old(version, versions);
+            quote! {
+                #cfg
+                #[doc = #doc]
+                #hidden
+                #[allow(non_camel_case_types)]
+                #deprecated
+                pub type #alias = super::#target;
+            }
+        });
+
+        let module_doc = format!("Readable aliases for every version of [`{ident}`].");
+        let latest_doc = format!("The latest version of [`{ident}`](super::{ident}).");
+        let any_doc = format!("Any version of [`{ident}`](super::{ident}).");
+        let latest = self.version_ident(&versions.last().unwrap().version);
+
+        quote! {
+            #[doc = #module_doc]
+            #vis mod #module {
+                #(#aliases)*
+
+                #[doc = #latest_doc]
+                pub type Latest = super::#latest;
+
+                #[doc = #any_doc]
+                pub type Any = super::#enum_ident;
+            }
+        }
+    }
+
+    /// Expands to `semantically_eq`, comparing any two versions once both are upgraded to the
+    /// latest version, and comparisons between the latest version and each older version.
+    fn expand_semantic_eq_impls(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if self.attrs.semantic_eqs().next().is_none() {
+            return quote!();
+        }
+
+        let vis = &self.vis;
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let latest = self.version_ident(&versions.last().unwrap().version);
+        // Each version is cloned rather than the `enum`, which only derives `Clone` if asked to
+        let clones = versions.iter().map(|attr| {
+            let version = self.version_ident(&attr.version);
+            let cfg = attr.expand_cfg();
+            quote! {
+                #cfg
+                #enum_ident::#version(x) => #enum_ident::#version(::core::clone::Clone::clone(x)),
+            }
+        });
+        let cmps = versions[..versions.len() - 1].iter().map(|attr| {
+            let version = self.version_ident(&attr.version);
+            let cfg = attr.expand_cfg();
+            quote! {
+                #cfg
+                #[automatically_derived]
+                impl ::core::cmp::PartialEq<#version> for #latest {
+                    fn eq(&self, other: &#version) -> bool {
+                        *self == #ident::from(#enum_ident::#version(::core::clone::Clone::clone(other)))
+                    }
+                }
+
+                #cfg
+                #[automatically_derived]
+                impl ::core::cmp::PartialEq<#latest> for #version {
+                    #[inline]
+                    fn eq(&self, other: &#latest) -> bool {
+                        other == self
+                    }
+                }
+            }
+        });
+        let doc = format!(
+            "Whether `a` and `b` are equal once both are upgraded to the latest version of \
+             [`{ident}`], whichever versions they are."
+        );
+
+        quote! {
+            #[automatically_derived]
+            impl #enum_ident {
+                #[doc = #doc]
+                #[must_use]
+                #vis fn semantically_eq(a: &Self, b: &Self) -> bool {
+                    let upgrade = |versioned: &Self| #ident::from(match versioned {
+                        #(#clones)*
+                    });
+                    upgrade(a) == upgrade(b)
+                }
+            }
+
+            #(#cmps)*
+        }
+    }
+
+    /// Expands to a fieldless `FooVersion` `enum` with a variant for every version, declared in
+    /// order so that it's ordered by semantic version, along with conversions from the generated
+    /// `enum` and to version strings (and, with the `semver` feature, `semver::Version`s).
+    fn expand_version_enum(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let Some(attr) = self.attrs.version_enums().next() else {
+            return quote!();
+        };
+
+        let vis = &self.vis;
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let version_enum = format_ident!("{}Version", ident);
+        let variants: Vec<_> = versions.iter().map(version_enum_variant).collect();
+        let cfgs: Vec<_> = versions.iter().map(VersionAttr::expand_cfg).collect();
+        let strs: Vec<_> = versions
+            .iter()
+            .map(|attr| attr.version.to_string())
+            .collect();
+        let derives = attr.derives.iter().map(|tokens| quote!(#[derive(#tokens)]));
+        let decls = versions.iter().zip(&variants).map(|(attr, variant)| {
+            let cfg = attr.expand_cfg();
+            let doc = format!("Version {} of [`{ident}`].", attr.version);
+            let deprecated = attr.expand_deprecated();
+            quote! {
+                #cfg
+                #[doc = #doc]
+                #deprecated
+                #variant,
+            }
+        });
+        let version_idents = versions
+            .iter()
+            .map(|attr| self.version_ident(&attr.version));
+        let latest = variants.last().unwrap();
+        let doc = format!(
+            "The versions of [`{ident}`], without any data, generated by \
+             `#[obake(version_enum)]`."
+        );
+
+        let str_impls = self.expand_version_enum_str_impls(versions);
+        #[cfg(feature = "semver")]
+        let semver_impls = self.expand_version_enum_semver_impls();
+        #[cfg(not(feature = "semver"))]
+        let semver_impls = quote!();
+
+        quote! {
+            #[doc = #doc]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            #(#derives)*
+            #[allow(non_camel_case_types)]
+            #vis enum #version_enum {
+                #(#decls)*
+            }
+
+            #[automatically_derived]
+            impl #version_enum {
+                /// The latest version.
+                #vis const LATEST: Self = Self::#latest;
+
+                /// Every version, from oldest to latest.
+                #vis const ALL: &'static [Self] = &[#(#cfgs Self::#variants,)*];
+
+                /// Iterates over every version, from oldest to latest.
+                #vis fn iter() -> ::core::iter::Copied<::core::slice::Iter<'static, Self>> {
+                    Self::ALL.iter().copied()
+                }
+
+                /// The version string of this version, one of `obake::Versioned::VERSIONS`.
+                #[must_use]
+                #vis const fn as_str(self) -> &'static str {
+                    match self {
+                        #(#cfgs Self::#variants => #strs,)*
+                    }
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::convert::From<&#enum_ident> for #version_enum {
+                fn from(versioned: &#enum_ident) -> Self {
+                    match versioned {
+                        #(#cfgs #enum_ident::#version_idents(_) => Self::#variants,)*
+                    }
+                }
+            }
+
+            #str_impls
+            #semver_impls
+
+            #[automatically_derived]
+            impl #enum_ident {
+                /// The version of this value.
+                #[must_use]
+                #vis fn version(&self) -> #version_enum {
+                    #version_enum::from(self)
+                }
+
+                /// Orders `self` and `other` by the version they're in, oldest first (by semantic
+                /// version rather than the order versions are declared in), whatever their
+                /// contents. Mixed versions can be sorted by age with
+                /// `values.sort_by(Self::cmp_version)`.
+                ///
+                /// This isn't an `Ord` impl, as values in the same version would be ordered as
+                /// equal without being equal; the version itself is `Ord`, so
+                /// `values.sort_by_key(Self::version)` sorts them too.
+                #[must_use]
+                #vis fn cmp_version(&self, other: &Self) -> ::core::cmp::Ordering {
+                    ::core::cmp::Ord::cmp(&self.version(), &other.version())
+                }
+            }
+        }
+    }
+
+    /// Expands to conversions between the `FooVersion` `enum` and version strings, which are
+    /// parsed (e.g. from command-line arguments) with `FromStr` given the `std` feature.
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    fn expand_version_enum_str_impls(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let ident = self.ident();
+        let version_enum = format_ident!("{}Version", ident);
+        #[cfg(feature = "std")]
+        let from_str_impl = {
+            let variants = versions.iter().map(version_enum_variant);
+            let cfgs = versions.iter().map(VersionAttr::expand_cfg);
+            let strs = versions.iter().map(|attr| attr.version.to_string());
+            quote! {
+                #[automatically_derived]
+                impl ::core::str::FromStr for #version_enum {
+                    type Err = ::obake::UnknownVersionError;
+
+                    fn from_str(version: &str) -> ::core::result::Result<Self, Self::Err> {
+                        match version {
+                            #(#cfgs #strs => ::core::result::Result::Ok(Self::#variants),)*
+                            found => ::core::result::Result::Err(
+                                ::obake::UnknownVersionError::new::<#ident>(found),
+                            ),
+                        }
+                    }
+                }
+            }
+        };
+        #[cfg(not(feature = "std"))]
+        let from_str_impl = quote!();
+
+        quote! {
+            #[automatically_derived]
+            impl ::core::convert::From<#version_enum> for &'static str {
+                fn from(version: #version_enum) -> Self {
+                    version.as_str()
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::convert::AsRef<str> for #version_enum {
+                fn as_ref(&self) -> &str {
+                    self.as_str()
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::fmt::Display for #version_enum {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.write_str(self.as_str())
+                }
+            }
+
+            #from_str_impl
+        }
+    }
+
+    /// Expands to conversions between the `FooVersion` `enum` and `semver::Version`s.
+    #[cfg(feature = "semver")]
+    fn expand_version_enum_semver_impls(&self) -> TokenStream2 {
+        let version_enum = format_ident!("{}Version", self.ident());
+        quote! {
+            #[automatically_derived]
+            impl ::core::convert::From<#version_enum> for ::obake::semver::Version {
+                fn from(version: #version_enum) -> Self {
+                    ::obake::semver::Version::parse(version.as_str())
+                        .expect("versions are checked by `#[obake::versioned]`")
+                }
+            }
+
+            #[automatically_derived]
+            impl ::core::convert::TryFrom<&::obake::semver::Version> for #version_enum {
+                type Error = ::obake::UnknownVersionError;
+
+                fn try_from(version: &::obake::semver::Version) -> ::core::result::Result<Self, Self::Error> {
+                    ::core::str::FromStr::from_str(&::std::string::ToString::to_string(version))
+                }
+            }
+        }
+    }
+
+    /// Expands to a `FooVersionVisitor` trait with a method handling each version, and an
+    /// `accept` method on the generated `enum` calling the method for its version.
+    fn expand_visitor(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        if self.attrs.visitors().next().is_none() {
+            return quote!();
+        }
+
+        let vis = &self.vis;
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let trait_ident = format_ident!("{}VersionVisitor", ident);
+        let (methods, arms): (Vec<_>, Vec<_>) = versions
+            .iter()
+            .map(|attr| {
+                let version = &attr.version;
+                let variant = self.version_ident(version);
+                let method = format_ident!("visit_v{}", version_suffix(version));
+                let cfg = attr.expand_cfg();
+                let doc = format!("Handles version {version} of [`{ident}`].");
+                let method_decl = quote! {
+                    #cfg
+                    #[doc = #doc]
+                    fn #method(&mut self, value: &#variant) -> Self::Output;
+                };
+                let arm = quote!(#cfg #enum_ident::#variant(value) => visitor.#method(value),);
+                (method_decl, arm)
+            })
+            .unzip();
+        let trait_doc = format!(
+            "Handles every version of [`{ident}`], one method per version, for use with \
+             [`{enum_ident}::accept`]."
+        );
+
+        quote! {
+            #[doc = #trait_doc]
+            #vis trait #trait_ident {
+                /// The result of visiting a version.
+                type Output;
+
+                #(#methods)*
+            }
+
+            #[automatically_derived]
+            impl #enum_ident {
+                /// Calls the method of `visitor` handling this version.
+                #vis fn accept<V: #trait_ident>(&self, visitor: &mut V) -> V::Output {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+    }
+
+    /// Expands to compile-time assertions of the size and alignment of the versions named by
+    /// `#[obake(assert_layout(...))]`, for versions whose layout is relied upon (e.g. across an
+    /// FFI boundary).
+    fn expand_layout_assertions(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let name = self.ident().to_string();
+        let mut assertions = quote!();
+        for attr in self.attrs.assert_layouts() {
+            let version = &attr.version.version;
+            let Some(declared) = versions.iter().find(|other| &other.version == version) else {
+                return Err(syn::Error::new(
+                    attr.version.span,
+                    format!("`{name}` has no version {version}"),
+                ));
+            };
+
+            let ident = self.version_ident(version);
+            let cfg = declared.expand_cfg();
+            let checks = [("size", &attr.size), ("alignment", &attr.align)];
+            let checks = checks.iter().filter_map(|(property, expected)| {
+                let expected = expected.as_ref()?;
+                let f = if *property == "size" {
+                    quote!(size_of)
+                } else {
+                    quote!(align_of)
+                };
+                let message =
+                    format!("the {property} of version {version} of `{name}` isn't {expected}");
+                Some(quote_spanned! {expected.span()=>
+                    ::core::assert!(::core::mem::#f::<#ident>() == #expected, #message);
+                })
+            });
+            assertions.append_all(quote! {
+                #cfg
+                const _: () = {
+                    #(#checks)*
+                };
+            });
+        }
+
+        Ok(assertions)
+    }
+
+    /// The `#[obake(display(...))]` of each constructor of `version` of an item declared with
+    /// `#[obake(error)]`, which every `struct` and every `enum` variant must have.
+    fn error_displays<'a>(
+        &'a self,
+        version: &Version,
+        attr: &ErrorAttr,
+    ) -> Result<Vec<(Constructor<'a>, &'a DisplayAttr)>> {
+        let displays = match &self.kind {
+            VersionedItemKind::Struct(_) => {
+                let display = self.attrs.displays().next().ok_or_else(|| {
+                    syn::Error::new(
+                        attr.span,
+                        "`#[obake(error)]` requires `#[obake(display(...))]` on the `struct`",
+                    )
+                })?;
+                vec![display]
+            }
+            VersionedItemKind::Enum(inner) => {
+                if let Some(display) = self.attrs.displays().next() {
+                    return Err(syn::Error::new(
+                        display.span,
+                        "`#[obake(display(...))]` not valid in this context",
+                    ));
+                }
+
+                inner
+                    .variants
+                    .variants
+                    .iter()
+                    .filter(|variant| variant.attrs.enabled_in(version))
+                    .map(|variant| {
+                        variant.attrs.displays().next().ok_or_else(|| {
+                            syn::Error::new(
+                                variant.ident.span(),
+                                "`#[obake(error)]` requires `#[obake(display(...))]` on every \
+                                 variant",
+                            )
+                        })
+                    })
+                    .collect::<Result<_>>()?
+            }
+        };
+
+        Ok(self
+            .constructors(version, &quote!(Self))
+            .into_iter()
+            .zip(displays)
+            .collect())
+    }
+
+    /// Expands to `Display` and `Error` impls for every version of an item declared with
+    /// `#[obake(error)]` and for the generated `enum`, which delegates to the version it holds.
+    #[allow(clippy::too_many_lines)]
+    fn expand_error_impls(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let Some(attr) = self.attrs.errors().next() else {
+            let display = self.attrs.displays().next().or_else(|| match &self.kind {
+                VersionedItemKind::Struct(_) => None,
+                VersionedItemKind::Enum(inner) => inner
+                    .variants
+                    .variants
+                    .iter()
+                    .find_map(|variant| variant.attrs.displays().next()),
+            });
+            if let Some(display) = display {
+                return Err(syn::Error::new(
+                    display.span,
+                    "`#[obake(display(...))]` requires `#[obake(error)]`",
+                ));
+            }
+            return Ok(quote!());
+        };
+
+        let mut impls = quote!();
+        for version_attr in versions {
+            let (mut display_arms, mut source_arms) = (vec![], vec![]);
+            for (ctor, display) in self.error_displays(&version_attr.version, attr)? {
+                let fields = ctor.fields();
+                let bindings: Vec<_> = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| match field {
+                        FieldRef::Named(field) if field.colon_token.is_some() => {
+                            field.ident.clone()
+                        }
+                        _ => format_ident!("_{}", i),
+                    })
+                    .collect();
+                let mut bound = bindings.iter();
+                let pattern = ctor.expand(|_| bound.next().unwrap().into_token_stream());
+                let source = fields
+                    .iter()
+                    .position(|field| match field {
+                        FieldRef::Named(field) | FieldRef::Unnamed(field) => {
+                            field.attrs.sources().next().is_some()
+                        }
+                    })
+                    .map_or_else(
+                        || quote!(::core::option::Option::None),
+                        |i| {
+                            let binding = &bindings[i];
+                            quote!(::core::option::Option::Some(#binding.as_dyn_error()))
+                        },
+                    );
+                let tokens = &display.tokens;
+                display_arms.push(quote!(#pattern => ::core::write!(f, #tokens),));
+                source_arms.push(quote!(#pattern => #source,));
+            }
+
+            let cfg = version_attr.expand_cfg();
+            let ident = self.version_ident(&version_attr.version);
+            impls.append_all(quote! {
+                #cfg
+                #[automatically_derived]
+                impl ::core::fmt::Display for #ident {
+                    #[allow(unused_variables, non_shorthand_field_patterns)]
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        match self {
+                            #(#display_arms)*
+                        }
+                    }
+                }
+
+                #cfg
+                #[automatically_derived]
+                impl ::core::error::Error for #ident {
+                    #[allow(unused_variables, non_shorthand_field_patterns)]
+                    fn source(&self) -> ::core::option::Option<&(dyn ::core::error::Error + 'static)> {
+                        use ::obake::__private::AsDynError as _;
+                        match self {
+                            #(#source_arms)*
+                        }
+                    }
+                }
+            });
+        }
+
+        let enum_ident = self.enum_ident();
+        let variants: Vec<_> = versions
+            .iter()
+            .map(|attr| self.version_ident(&attr.version))
+            .collect();
+        let cfgs: Vec<_> = versions.iter().map(VersionAttr::expand_cfg).collect();
+        impls.append_all(quote! {
+            #[automatically_derived]
+            impl ::core::fmt::Display for #enum_ident {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match self {
+                        #(#cfgs Self::#variants(version) => ::core::fmt::Display::fmt(version, f),)*
+                    }
+                }
+            }
+        });
+
+        // `Error` requires `Debug`, which the generated `enum` only has if it derives it
+        if !self.enum_derives_any(&["Debug"]) {
+            return Ok(impls);
+        }
+
+        impls.append_all(quote! {
+            #[automatically_derived]
+            impl ::core::error::Error for #enum_ident {
+                fn source(&self) -> ::core::option::Option<&(dyn ::core::error::Error + 'static)> {
+                    match self {
+                        #(#cfgs Self::#variants(version) => ::core::error::Error::source(version),)*
+                    }
+                }
+            }
+        });
+
+        Ok(impls)
+    }
+
+    /// Expands to a `FooCommon` trait with a getter for each field declared once and present in
+    /// every version, implemented by every version and by the `VersionedFoo` `enum`, so that these
+    /// fields can be read without upgrading.
+    fn expand_common_fields(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let Some(attr) = self.attrs.common_fields().next() else {
+            return Ok(quote!());
+        };
+
+        let fields = match &self.kind {
+            VersionedItemKind::Struct(inner) if inner.fields.paren_token.is_none() => &inner.fields,
+            _ => {
+                return Err(syn::Error::new(
+                    attr.span,
+                    "`#[obake(common_fields)]` can only be applied to `struct`s with named fields",
+                ))
+            }
+        };
+
+        // Fields with `#[obake(inherit)]` have a different type in each version, so aren't common
+        let common: Vec<_> = fields
+            .fields
+            .iter()
+            .filter(|field| field.attrs.inherits().next().is_none())
+            .filter(|field| {
+                versions
+                    .iter()
+                    .all(|attr| field.attrs.enabled_in(&attr.version))
+            })
+            .collect();
+
+        let vis = &self.vis;
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let trait_ident = format_ident!("{}Common", ident);
+        let cfgs: Vec<_> = common.iter().map(|field| field.expand_cfgs()).collect();
+        let idents: Vec<_> = common.iter().map(|field| &field.ident).collect();
+        let tys: Vec<_> = common.iter().map(|field| &field.ty).collect();
+        let docs = idents
+            .iter()
+            .map(|field| format!("The `{field}` field, present in every version."));
+        let trait_doc = format!(
+            "The fields present in every version of [`{ident}`], readable from any version (or \
+             [`{enum_ident}`]) without upgrading it."
+        );
+
+        let variants: Vec<_> = versions
+            .iter()
+            .map(|attr| self.version_ident(&attr.version))
+            .collect();
+        let variant_cfgs: Vec<_> = versions.iter().map(VersionAttr::expand_cfg).collect();
+        let variant_impls = variants.iter().zip(&variant_cfgs).map(|(variant, cfg)| {
             quote! {
+                #cfg
                 #[automatically_derived]
-                impl From<#enum_ident> for #ident {
-                    #[inline]
-                    fn from(mut from: #enum_ident) -> Self {
-                        #![allow(unreachable_code)]
-                        loop {
-                            from = match from {
-                                #(#migrations)*
-                                #enum_ident::#alias(x) => return x,
-                            };
+                impl #trait_ident for #variant {
+                    #(
+                        #cfgs
+                        fn #idents(&self) -> &#tys {
+                            &self.#idents
+                        }
+                    )*
+                }
+            }
+        });
+        let match_arms: Vec<_> = idents
+            .iter()
+            .map(|field| {
+                let arms = variants.iter().zip(&variant_cfgs).map(
+                    |(variant, cfg)| quote!(#cfg #enum_ident::#variant(value) => &value.#field,),
+                );
+                quote!(#(#arms)*)
+            })
+            .collect();
+
+        Ok(quote! {
+            #[doc = #trait_doc]
+            #vis trait #trait_ident {
+                #(
+                    #cfgs
+                    #[doc = #docs]
+                    fn #idents(&self) -> &#tys;
+                )*
+            }
+
+            #(#variant_impls)*
+
+            #[automatically_derived]
+            impl #trait_ident for #enum_ident {
+                #(
+                    #cfgs
+                    fn #idents(&self) -> &#tys {
+                        match self {
+                            #match_arms
                         }
                     }
+                )*
+            }
+        })
+    }
+
+    /// With `#[obake(hide_old_docs)]`, expands to `#[doc(hidden)]` for items generated for
+    /// `version` if it isn't the latest version, so rustdoc only documents the latest version.
+    fn expand_hidden_if_old(&self, version: &Version, versions: &[VersionAttr]) -> TokenStream2 {
+        let latest = &versions.last().unwrap().version;
+        if self.attrs.hide_old_docs().next().is_some() && version != latest {
+            quote!(#[doc(hidden)])
+        } else {
+            quote!()
+        }
+    }
+
+    /// Expands to a `Default` impl for every version of a `struct` declared with
+    /// `#[obake(default)]`, initialising each field enabled in that version with its
+    /// `#[obake(default = ...)]` value, or else with the `Default` value of its type.
+    fn expand_default_impls(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let attr = self.attrs.defaults().next();
+        let fields = match &self.kind {
+            VersionedItemKind::Struct(inner) => &inner.fields.fields,
+            VersionedItemKind::Enum(_) => {
+                return match attr {
+                    Some(attr) => Err(syn::Error::new(
+                        attr.span,
+                        "`#[obake(default)]` can only be applied to `struct`s",
+                    )),
+                    None => Ok(quote!()),
                 }
+            }
+        };
+
+        for field in fields {
+            let mut values = field.attrs.default_values();
+            let Some(value) = values.next() else {
+                continue;
+            };
+            if attr.is_none() {
+                return Err(syn::Error::new(
+                    value.span,
+                    "`#[obake(default = ...)]` requires `#[obake(default)]` on the item",
+                ));
+            }
+            if let Some(duplicate) = values.next() {
+                return Err(syn::Error::new(
+                    duplicate.span,
+                    "duplicate `#[obake(default = ...)]`",
+                ));
+            }
+        }
+
+        if attr.is_none() {
+            return Ok(quote!());
+        }
 
+        let impls = versions.iter().map(|attr| {
+            let ident = self.version_ident(&attr.version);
+            let cfg = attr.expand_cfg();
+            let ctor = self.constructors(&attr.version, &quote!(Self)).remove(0);
+            let expr = ctor.expand(|field| {
+                let (FieldRef::Named(field) | FieldRef::Unnamed(field)) = field;
+                field.attrs.default_values().next().map_or_else(
+                    || quote!(::core::default::Default::default()),
+                    |value| value.expr.to_token_stream(),
+                )
+            });
+            quote! {
+                #cfg
                 #[automatically_derived]
-                impl From<#ident> for #enum_ident {
-                    #[inline]
-                    fn from(from: #ident) -> Self {
-                        Self::#alias(from)
+                impl ::core::default::Default for #ident {
+                    fn default() -> Self {
+                        #expr
                     }
                 }
             }
+        });
+        Ok(quote!(#(#impls)*))
+    }
+
+    /// Expands to a builder for every version (e.g. `Foo_v0_1_0_Builder`, created with
+    /// `Foo_v0_1_0::builder()`), with a setter for each field.
+    fn expand_builders(&self, versions: &[VersionAttr]) -> Result<TokenStream2> {
+        let Some(attr) = self.attrs.builders().next() else {
+            return Ok(quote!());
+        };
+
+        let fields = match &self.kind {
+            VersionedItemKind::Struct(inner) if inner.fields.paren_token.is_none() => &inner.fields,
+            _ => {
+                return Err(syn::Error::new(
+                    attr.span,
+                    "`#[obake(builder)]` can only be applied to `struct`s with named fields",
+                ))
+            }
         };
 
-        let versioned_impl = {
-            let ident = self.ident();
+        let vis = &self.vis;
+        let name = self.ident().to_string();
+        let mut builders = quote!();
+        for attr in versions {
+            let version = &attr.version;
+            let version_str = version.to_string();
+            let ident = self.version_ident(version);
+            let builder_ident = format_ident!("{}_Builder", ident);
+            let cfg = attr.expand_cfg();
+            let deprecated = attr.expand_deprecated();
+            let hidden = self.expand_hidden_if_old(version, versions);
+
+            let fields = fields.enabled_in(version);
+            let cfgs: Vec<_> = fields.iter().map(|field| field.expand_cfgs()).collect();
+            let idents: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+            let field_strs = idents.iter().map(ToString::to_string);
+            let tys = fields
+                .iter()
+                .map(|field| field.expand_ty_versioned(version))
+                .collect::<Result<Vec<_>>>()?;
+            let setter_docs = idents.iter().map(|ident| format!("Sets `{ident}`."));
+            let builder_doc = format!("Builds version {version} of `{name}` field-by-field.");
+
+            builders.append_all(quote! {
+                #cfg
+                #[doc = #builder_doc]
+                #hidden
+                #[allow(non_camel_case_types)]
+                #[derive(Default)]
+                #[must_use]
+                #deprecated
+                #vis struct #builder_ident {
+                    #(#cfgs #idents: ::core::option::Option<#tys>,)*
+                }
+
+                #cfg
+                #[automatically_derived]
+                impl #ident {
+                    /// Starts building this version, with every field unset.
+                    #vis fn builder() -> #builder_ident {
+                        ::core::default::Default::default()
+                    }
+                }
+
+                #cfg
+                #[automatically_derived]
+                impl #builder_ident {
+                    #(
+                        #cfgs
+                        #[doc = #setter_docs]
+                        #vis fn #idents(mut self, value: #tys) -> Self {
+                            self.#idents = ::core::option::Option::Some(value);
+                            self
+                        }
+                    )*
+
+                    /// Builds the version from the fields set.
+                    ///
+                    /// # Errors
+                    ///
+                    /// Returns an error naming the first field which wasn't set.
+                    #vis fn build(self) -> ::core::result::Result<#ident, ::obake::BuildError> {
+                        ::core::result::Result::Ok(#ident {
+                            #(
+                                #cfgs
+                                #idents: self.#idents.ok_or(::obake::BuildError {
+                                    type_name: #name,
+                                    version: #version_str,
+                                    field: #field_strs,
+                                })?,
+                            )*
+                        })
+                    }
+                }
+            });
+        }
+
+        Ok(builders)
+    }
+
+    /// Expands to conversions for `#[obake(split_into(...))]`, from any version into the latest
+    /// versions of each of the types it was split into, and `#[obake(merge_from(...))]`, from
+    /// any versions of each of the types merged into the latest version.
+    fn expand_split_merge_impls(&self) -> TokenStream2 {
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let splits = self.attrs.split_intos().map(|attr| {
+            let types = &attr.types;
+            let with = &attr.with;
             quote! {
-                impl ::obake::Versioned for #ident {
-                    type Versioned = #enum_ident;
+                #[automatically_derived]
+                impl From<#enum_ident> for (#(#types,)*) {
+                    fn from(from: #enum_ident) -> Self {
+                        #with(<#ident as From<#enum_ident>>::from(from))
+                    }
+                }
+            }
+        });
+
+        let merges = self.attrs.merge_froms().map(|attr| {
+            let types = &attr.types;
+            let with = &attr.with;
+            let args: Vec<_> = (0..types.len()).map(|i| format_ident!("from_{}", i)).collect();
+            quote! {
+                #[automatically_derived]
+                impl From<(#(<#types as ::obake::Versioned>::Versioned,)*)> for #ident {
+                    fn from((#(#args,)*): (#(<#types as ::obake::Versioned>::Versioned,)*)) -> Self {
+                        #with(#(<#types as From<_>>::from(#args)),*)
+                    }
+                }
+            }
+        });
+
+        quote!(#(#splits)* #(#merges)*)
+    }
+
+    /// Expands to an `include_bytes!` of every manifest read by `#[obake(versions_from = "...")]`,
+    /// so the crate is rebuilt when a manifest changes.
+    fn expand_versions_from(&self) -> TokenStream2 {
+        let includes = self.attrs.versions_froms().map(|attr| {
+            let path = attr.path.to_string_lossy();
+            quote_spanned!(attr.span => const _: &[u8] = ::core::include_bytes!(#path);)
+        });
+
+        quote!(#(#includes)*)
+    }
+
+    fn expand_macro_rules(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let ident = self.ident();
+        let enum_ident = self.enum_ident();
+        let rules = self
+            .attrs
+            .versions()
+            .zip(self.expand_variants())
+            .map(|(attr, variant)| {
+                let version = attr.version.to_string();
+                quote!([#version] => { #variant };)
+            });
+        // Patterns matching each variant of the generated `enum`, for `obake::match_versions!`
+        let patterns = versions.iter().map(|attr| {
+            let version = attr.version.to_string();
+            let variant = self.version_ident(&attr.version);
+            quote!([@pattern #version, $($binding:tt)*] => { #enum_ident::#variant($($binding)*) };)
+        });
+        let latest = self.version_ident(&versions.last().unwrap().version);
+
+        quote! {
+            #[allow(unused_macros)]
+            macro_rules! #ident {
+                #(#rules)*
+                #(#patterns)*
+                [@pattern latest, $($binding:tt)*] => { #enum_ident::#latest($($binding)*) };
+            }
+        }
+    }
+
+    /// With `#[obake(emit_original)]`, expands to the item as written (with every field and
+    /// variant of every version, and without its `#[obake(...)]` attributes), for derive macros
+    /// which inspect it.
+    fn expand_original(&self) -> Result<TokenStream2> {
+        if self.attrs.emit_originals().next().is_none() {
+            return Ok(quote!());
+        }
+
+        let field = |field: &VersionedField| {
+            let attrs = field.attrs.attrs();
+            let vis = &field.vis;
+            let ty = &field.ty;
+            if field.colon_token.is_some() {
+                let ident = &field.ident;
+                quote!(#(#attrs)* #vis #ident: #ty)
+            } else {
+                quote!(#(#attrs)* #vis #ty)
+            }
+        };
+        let check_unique = |idents: Vec<&syn::Ident>, kind: &str| {
+            for (i, ident) in idents.iter().enumerate() {
+                if idents[..i].contains(ident) {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!(
+                            "`#[obake(emit_original)]` requires the names of {kind}s to be unique \
+                             across versions, but `{ident}` is declared more than once"
+                        ),
+                    ));
                 }
             }
+            Ok(())
         };
 
-        let macro_rules = {
-            let ident = self.ident();
-            let rules = self
-                .attrs
-                .versions()
-                .zip(self.expand_variants())
-                .map(|(attr, variant)| {
-                    let version = attr.version.to_string();
-                    quote!([#version] => { #variant };)
+        let attrs = self.attrs.attrs();
+        let vis = &self.vis;
+        let ident = self.ident();
+        match &self.kind {
+            VersionedItemKind::Struct(inner) => {
+                let fields = inner.fields.fields.iter().map(field);
+                if inner.fields.paren_token.is_some() {
+                    return Ok(quote!(#(#attrs)* #vis struct #ident(#(#fields),*);));
+                }
+                check_unique(
+                    inner
+                        .fields
+                        .fields
+                        .iter()
+                        .map(|field| &field.ident)
+                        .collect(),
+                    "field",
+                )?;
+                Ok(quote!(#(#attrs)* #vis struct #ident { #(#fields,)* }))
+            }
+            VersionedItemKind::Enum(inner) => {
+                check_unique(
+                    inner
+                        .variants
+                        .variants
+                        .iter()
+                        .map(|variant| &variant.ident)
+                        .collect(),
+                    "variant",
+                )?;
+                let variants = inner.variants.variants.iter().map(|variant| {
+                    let attrs = variant.attrs.attrs();
+                    let ident = &variant.ident;
+                    let fields = match &variant.fields {
+                        VersionedVariantFields::Named(fields) => {
+                            let fields = fields.fields.iter().map(field);
+                            quote!({ #(#fields,)* })
+                        }
+                        VersionedVariantFields::Unnamed(fields) => {
+                            let fields = fields.fields.iter().map(field);
+                            quote!((#(#fields),*))
+                        }
+                        VersionedVariantFields::Unit => quote!(),
+                    };
+                    quote!(#(#attrs)* #ident #fields)
                 });
+                Ok(quote!(#(#attrs)* #vis enum #ident { #(#variants,)* }))
+            }
+        }
+    }
+
+    fn expand(&self) -> TokenStream2 {
+        // Emitted as written, before any fields or variants are added for the generated versions
+        let original = try_expand!(self.expand_original());
+
+        #[cfg(feature = "json")]
+        if let Some(item) = try_expand!(self.with_unknown_fields()) {
+            let expanded = item.expand_item();
+            return quote!(#original #expanded);
+        }
+
+        #[cfg(feature = "json")]
+        if let Some(item) = try_expand!(self.with_unknown_variant()) {
+            let expanded = item.expand_item();
+            return quote!(#original #expanded);
+        }
+
+        let expanded = self.expand_item();
+        quote!(#original #expanded)
+    }
+
+    /// With `#[obake(preserve_unknown)]`, returns this item with a hidden `unknown_fields` field
+    /// added to every version, which captures any fields of a document that aren't declared in
+    /// its version (e.g. those written by a newer version).
+    #[cfg(feature = "json")]
+    fn with_unknown_fields(&self) -> Result<Option<Self>> {
+        let Some(attr) = self.attrs.preserve_unknowns().next() else {
+            return Ok(None);
+        };
+
+        let derives_serde = self
+            .attrs
+            .attrs()
+            .any(|attr| derives_any(attr, &["Serialize", "Deserialize"]));
+        if !derives_serde {
+            return Err(syn::Error::new(
+                attr.span,
+                "`#[obake(preserve_unknown)]` requires every version to derive `Serialize` or \
+                 `Deserialize`",
+            ));
+        }
+
+        let mut item = self.clone();
+        let fields = match &mut item.kind {
+            VersionedItemKind::Struct(inner) if inner.fields.paren_token.is_none() => {
+                &mut inner.fields
+            }
+            _ => return Err(syn::Error::new(
+                attr.span,
+                "`#[obake(preserve_unknown)]` can only be applied to `struct`s with named fields",
+            )),
+        };
+        let vis = &self.vis;
+        fields.fields.push(syn::parse_quote! {
+            #[doc(hidden)]
+            #[serde(flatten)]
+            #vis unknown_fields: ::obake::UnknownFields
+        });
+
+        Ok(Some(item))
+    }
+
+    /// With `#[obake(unknown_variant)]`, returns this item with an `Unknown` variant added to
+    /// every version (or only the latest with `unknown_variant(latest_only)`), which captures any
+    /// variant of a document that isn't declared in its version (e.g. one written by a newer
+    /// version) as it was read.
+    #[cfg(feature = "json")]
+    fn with_unknown_variant(&self) -> Result<Option<Self>> {
+        let Some(attr) = self.attrs.unknown_variants().next() else {
+            return Ok(None);
+        };
+
+        let derives_serde = self
+            .attrs
+            .attrs()
+            .cloned()
+            .chain(
+                self.attrs
+                    .latest_onlys()
+                    .flat_map(|attr| latest_only_attrs(&attr.tokens)),
+            )
+            .any(|attr| derives_any(&attr, &["Deserialize"]));
+        if !derives_serde {
+            return Err(syn::Error::new(
+                attr.span,
+                "`#[obake(unknown_variant)]` requires the latest version to derive `Deserialize`",
+            ));
+        }
+
+        let mut item = self.clone();
+        let VersionedItemKind::Enum(inner) = &mut item.kind else {
+            return Err(syn::Error::new(
+                attr.span,
+                "`#[obake(unknown_variant)]` can only be applied to `enum`s",
+            ));
+        };
+        if let Some(variant) = inner
+            .variants
+            .variants
+            .iter()
+            .find(|variant| variant.ident == "Unknown")
+        {
+            return Err(syn::Error::new(
+                variant.ident.span(),
+                "`#[obake(unknown_variant)]` adds a variant named `Unknown`, which is already \
+                 declared",
+            ));
+        }
+
+        let cfg = if attr.latest_only {
+            let versions = self.extract_versions()?;
+            let req = format!(">={}", versions.last().unwrap().version);
+            quote!(#[obake(cfg(#req))])
+        } else {
+            quote!()
+        };
+        // Untagged variants are only tried once every other variant fails to match, so this
+        // captures the whole of an unrecognized variant
+        inner.variants.variants.push(syn::parse_quote! {
+            #cfg
+            #[serde(untagged)]
+            Unknown(::obake::__private::serde_json::Value)
+        });
+
+        Ok(Some(item))
+    }
+
+    fn expand_item(&self) -> TokenStream2 {
+        try_expand!(self.check_preconditions());
+
+        let versions = try_expand!(self.extract_versions());
+        try_expand!(self.check_max_versions(&versions));
+        try_expand!(self.check_cfg_versions(&versions));
+        try_expand!(self.check_strict(&versions));
+
+        // A version newer than every ungated version (e.g. an experimental next format) only
+        // exists when its feature is enabled, so the latest version depends on the feature:
+        // everything is expanded both with and without it
+        let Some(experimental) = versions.last().unwrap().feature.as_ref() else {
+            return self.expand_versions(&versions);
+        };
+        let cfg = quote!(feature = #experimental);
+        let with = gate(self.expand_versions(&versions), &cfg);
+        let without = gate(
+            self.expand_versions(&versions[..versions.len() - 1]),
+            &quote!(not(#cfg)),
+        );
+        quote!(#with #without)
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn expand_versions(&self, versions: &[VersionAttr]) -> TokenStream2 {
+        let current = versions.last().unwrap();
 
+        let defs = try_expand!(versions
+            .iter()
+            .map(|attr| self.expand_version(attr, versions))
+            .collect::<Result<Vec<_>>>())
+        .into_iter();
+
+        let alias = self.version_ident(&current.version);
+        let alias_decl = {
+            let vis = &self.vis;
+            let ident = self.ident();
+            let docs = self.attrs.attrs().filter(|attr| attr.path.is_ident("doc"));
+            let doc = expand_doc(
+                &self.attrs,
+                &format!(
+                    "Aliases the latest version of `{ident}` ({}).",
+                    current.version
+                ),
+            );
+            quote! {
+                #(#docs)*
+                #doc
+                #vis type #ident = #alias;
+            }
+        };
+
+        let enum_decl = self.expand_enum_decl();
+        let from_impl = self.expand_from_impl(versions);
+
+        let versioned_impl = {
+            let ident = self.ident();
+            let enum_ident = self.enum_ident();
+            let name = ident.to_string();
+            let version_strs = versions.iter().map(|attr| attr.version.to_string());
+            let cfgs = versions.iter().map(VersionAttr::expand_cfg);
+            let variant_order = self.variant_order(versions);
+            let variant_strs = variant_order.iter().map(|attr| attr.version.to_string());
+            let variant_cfgs = variant_order.iter().map(|attr| attr.expand_cfg());
+            let version_ofs = versions.iter().map(|attr| {
+                let version = self.version_ident(&attr.version);
+                let version_str = attr.version.to_string();
+                let cfg = attr.expand_cfg();
+                quote!(#cfg #enum_ident::#version(_) => #version_str,)
+            });
             quote! {
-                macro_rules! #ident {
-                    #(#rules)*
+                impl ::obake::Versioned for #ident {
+                    type Versioned = #enum_ident;
+                    const NAME: &'static str = #name;
+                    const VERSIONS: &'static [&'static str] = &[#(#cfgs #version_strs),*];
+                    #[doc(hidden)]
+                    const VARIANT_VERSIONS: &'static [&'static str] =
+                        &[#(#variant_cfgs #variant_strs),*];
+
+                    fn version_of(versioned: &#enum_ident) -> &'static str {
+                        match versioned {
+                            #(#version_ofs)*
+                        }
+                    }
                 }
             }
         };
 
-        quote! {
+        #[cfg(feature = "serde")]
+        let deserialize_version_impl = self.expand_deserialize_version_impl();
+        #[cfg(not(feature = "serde"))]
+        let deserialize_version_impl = quote!();
+
+        #[cfg(feature = "serde")]
+        let serialize_latest_impl = self.expand_serialize_latest_impl(versions);
+        #[cfg(not(feature = "serde"))]
+        let serialize_latest_impl = quote!();
+
+        #[cfg(feature = "bson")]
+        let bson_impl = self.expand_bson_impl();
+        #[cfg(not(feature = "bson"))]
+        let bson_impl = quote!();
+
+        #[cfg(feature = "wasm")]
+        let wasm_impl = self.expand_wasm_impl(versions);
+        #[cfg(not(feature = "wasm"))]
+        let wasm_impl = quote!();
+
+        #[cfg(feature = "tonic")]
+        let tonic_impl = self.expand_tonic_impl(versions);
+        #[cfg(not(feature = "tonic"))]
+        let tonic_impl = quote!();
+
+        #[cfg(feature = "validator")]
+        let validator_impl = self.expand_validator_impl(versions);
+        #[cfg(not(feature = "validator"))]
+        let validator_impl = quote!();
+
+        #[cfg(feature = "garde")]
+        let garde_impls = self.expand_garde_impls(versions);
+        #[cfg(not(feature = "garde"))]
+        let garde_impls = quote!();
+
+        #[cfg(feature = "zeroize")]
+        let zeroize_impls = self.expand_zeroize_impls(versions);
+        #[cfg(not(feature = "zeroize"))]
+        let zeroize_impls = quote!();
+
+        #[cfg(feature = "fixtures")]
+        let fixtures_impl = self.expand_fixtures_impl(versions);
+        #[cfg(not(feature = "fixtures"))]
+        let fixtures_impl = quote!();
+
+        #[cfg(feature = "arbitrary")]
+        let arbitrary_impls = self.expand_arbitrary_impls(versions);
+        #[cfg(not(feature = "arbitrary"))]
+        let arbitrary_impls = quote!();
+
+        #[cfg(feature = "proptest")]
+        let proptest_impls = try_expand!(self.expand_proptest_impls(versions));
+        #[cfg(not(feature = "proptest"))]
+        let proptest_impls = quote!();
+
+        #[cfg(feature = "criterion")]
+        let benches = self.expand_benches(versions);
+        #[cfg(not(feature = "criterion"))]
+        let benches = quote!();
+
+        #[cfg(feature = "fuzz")]
+        let fuzz_impl = self.expand_fuzz_impl();
+        #[cfg(not(feature = "fuzz"))]
+        let fuzz_impl = quote!();
+
+        #[cfg(feature = "dynamic")]
+        let dynamic_impl = try_expand!(self.expand_dynamic_impl(versions));
+        #[cfg(not(feature = "dynamic"))]
+        let dynamic_impl = quote!();
+
+        let downgrade_impl = self.expand_downgrade_impl(versions);
+        let try_upgrade_impl = self.expand_try_upgrade_impl();
+        let graph_impl = self.expand_graph_impl(versions);
+        let aliases = self.expand_aliases(versions);
+        let renamed_aliases = self.expand_renamed_aliases(versions);
+        let semantic_eq_impls = self.expand_semantic_eq_impls(versions);
+        let visitor = self.expand_visitor(versions);
+        let version_enum = self.expand_version_enum(versions);
+        let builders = try_expand!(self.expand_builders(versions));
+        let default_impls = try_expand!(self.expand_default_impls(versions));
+        let common_fields = try_expand!(self.expand_common_fields(versions));
+        let error_impls = try_expand!(self.expand_error_impls(versions));
+        let layout_assertions = try_expand!(self.expand_layout_assertions(versions));
+        let split_merge_impls = self.expand_split_merge_impls();
+        let coverage_impl = self.expand_coverage_impl(versions);
+        let schema_hash_impls = try_expand!(self.expand_schema_hash_impls(versions));
+        #[cfg(feature = "serde")]
+        let serde_repr_impls = try_expand!(self.expand_serde_repr_impls(versions));
+        #[cfg(not(feature = "serde"))]
+        let serde_repr_impls = quote!();
+        let trivial_migrations = try_expand!(self.expand_trivial_migrations(versions));
+        let partial_migrations = try_expand!(self.expand_partial_migrations(versions));
+        let map_from_migrations = try_expand!(self.expand_map_from_migrations(versions));
+        let retyped_migrations = try_expand!(self.expand_retyped_migrations(versions));
+        let patch_migrations = try_expand!(self.expand_patch_migrations(versions));
+        let macro_rules = self.expand_macro_rules(versions);
+        let versions_from = self.expand_versions_from();
+        let tests = self.expand_tests();
+
+        let expanded = quote! {
             #(#defs)*
-            #alias_decl
             #enum_decl
             #from_impl
             #versioned_impl
+            #downgrade_impl
+            #try_upgrade_impl
+            #graph_impl
+            #aliases
+            #renamed_aliases
+            #semantic_eq_impls
+            #visitor
+            #version_enum
+            #builders
+            #default_impls
+            #common_fields
+            #error_impls
+            #layout_assertions
+            #split_merge_impls
+            #coverage_impl
+            #schema_hash_impls
+            #serde_repr_impls
+            #trivial_migrations
+            #partial_migrations
+            #map_from_migrations
+            #retyped_migrations
+            #patch_migrations
+            #deserialize_version_impl
+            #serialize_latest_impl
+            #bson_impl
+            #tonic_impl
+            #wasm_impl
+            #validator_impl
+            #garde_impls
+            #zeroize_impls
+            #fixtures_impl
+            #arbitrary_impls
+            #proptest_impls
+            #benches
+            #fuzz_impl
+            #dynamic_impl
             #macro_rules
+            #versions_from
+            #tests
+        };
+
+        // The item itself is emitted as written, so the latest version is referred to by name
+        let expanded = if self.attrs.emit_originals().next().is_some() {
+            retarget(expanded, self.ident(), &alias)
+        } else {
+            quote!(#alias_decl #expanded)
+        };
+
+        if versions.iter().any(|attr| attr.deprecated.is_some()) {
+            allow_deprecated(expanded)
+        } else {
+            expanded
+        }
+    }
+}
+
+/// Allows the `deprecated` lint on every generated item, so that deprecated versions can still
+/// be referred to by the generated migrations and conversions (and only warn when referred to by
+/// hand).
+fn allow_deprecated(expanded: TokenStream2) -> TokenStream2 {
+    let attr: syn::Attribute = syn::parse_quote!(#[allow(deprecated)]);
+    // Lint attributes on macro invocations are ignored
+    map_items(expanded, |item| {
+        if !matches!(item, syn::Item::Macro(_)) {
+            item_attrs(item).push(attr.clone());
+        }
+    })
+}
+
+/// Applies a `#[cfg(...)]` attribute to every generated item.
+fn gate(expanded: TokenStream2, cfg: &TokenStream2) -> TokenStream2 {
+    let attr: syn::Attribute = syn::parse_quote!(#[cfg(#cfg)]);
+    map_items(expanded, |item| item_attrs(item).push(attr.clone()))
+}
+
+/// Replaces every `from` identifier in `tokens` with `to`, other than the names of macros (so
+/// that the latest version is referred to by name when the item itself is emitted with
+/// `#[obake(emit_original)]`, while `Foo!` still names the versions of `Foo`).
+fn retarget(tokens: TokenStream2, from: &syn::Ident, to: &syn::Ident) -> TokenStream2 {
+    let mut macro_name = false;
+    let mut retargeted = TokenStream2::new();
+    let mut prev_macro_rules = false;
+    for token in tokens {
+        let token = match token {
+            proc_macro2::TokenTree::Ident(ident) if ident == *from && !macro_name => {
+                proc_macro2::TokenTree::Ident(syn::Ident::new(&to.to_string(), ident.span()))
+            }
+            proc_macro2::TokenTree::Group(group) => {
+                let mut retargeted =
+                    proc_macro2::Group::new(group.delimiter(), retarget(group.stream(), from, to));
+                retargeted.set_span(group.span());
+                proc_macro2::TokenTree::Group(retargeted)
+            }
+            token => token,
+        };
+        macro_name = prev_macro_rules
+            && matches!(&token, proc_macro2::TokenTree::Punct(punct) if punct.as_char() == '!');
+        prev_macro_rules =
+            matches!(&token, proc_macro2::TokenTree::Ident(ident) if ident == "macro_rules");
+        retargeted.append(token);
+    }
+    retargeted
+}
+
+fn map_items(expanded: TokenStream2, f: impl FnMut(&mut syn::Item)) -> TokenStream2 {
+    // Leave anything which doesn't parse for the compiler to report
+    let Ok(mut file) = syn::parse2::<syn::File>(expanded.clone()) else {
+        return expanded;
+    };
+
+    file.items.iter_mut().for_each(f);
+    quote!(#file)
+}
+
+fn item_attrs(item: &mut syn::Item) -> &mut Vec<syn::Attribute> {
+    match item {
+        syn::Item::Const(item) => &mut item.attrs,
+        syn::Item::Enum(item) => &mut item.attrs,
+        syn::Item::ExternCrate(item) => &mut item.attrs,
+        syn::Item::Fn(item) => &mut item.attrs,
+        syn::Item::ForeignMod(item) => &mut item.attrs,
+        syn::Item::Impl(item) => &mut item.attrs,
+        syn::Item::Macro(item) => &mut item.attrs,
+        syn::Item::Macro2(item) => &mut item.attrs,
+        syn::Item::Mod(item) => &mut item.attrs,
+        syn::Item::Static(item) => &mut item.attrs,
+        syn::Item::Struct(item) => &mut item.attrs,
+        syn::Item::Trait(item) => &mut item.attrs,
+        syn::Item::TraitAlias(item) => &mut item.attrs,
+        syn::Item::Type(item) => &mut item.attrs,
+        syn::Item::Union(item) => &mut item.attrs,
+        syn::Item::Use(item) => &mut item.attrs,
+        _ => unreachable!("generated items are never verbatim"),
+    }
+}
+
+/// Splits an item (given as `item`, without the `#[obake::versioned]` attribute) with `obake`
+/// helper attributes wrapped in `#[cfg_attr(...)]` into a copy for either outcome of the first
+/// such predicate, each gated by it and declared with `#[obake::versioned]` again (which splits
+/// on any other predicates). Only the compiler can evaluate the predicate, so otherwise the
+/// wrapped helpers would be left on the generated items rather than applied. Returns `None` if
+/// the item has no wrapped helpers.
+pub fn expand_cfg_attrs(item: &TokenStream2) -> Option<TokenStream2> {
+    // Leave anything which doesn't parse for `VersionedItem` to report
+    let mut enabled = syn::parse2::<syn::DeriveInput>(item.clone()).ok()?;
+    let mut predicate = None;
+    for_each_attrs(&mut enabled, |attrs| {
+        predicate = predicate.take().or_else(|| {
+            attrs
+                .iter()
+                .find_map(crate::parse::conditional_obake_attrs)
+                .map(|conditional| conditional.predicate)
+        });
+    });
+    let predicate = predicate?;
+
+    // Every `cfg_attr` with the same predicate is resolved at once, so gating several helpers
+    // behind one feature only doubles the item
+    let key = predicate.to_token_stream().to_string();
+    let resolve = |attrs: &mut Vec<syn::Attribute>, enabled: bool| {
+        *attrs = attrs
+            .drain(..)
+            .flat_map(|attr| match crate::parse::conditional_obake_attrs(&attr) {
+                Some(conditional) if conditional.predicate.to_token_stream().to_string() == key => {
+                    if enabled {
+                        conditional.attrs
+                    } else {
+                        Vec::new()
+                    }
+                }
+                _ => vec![attr],
+            })
+            .collect();
+    };
+    let mut disabled = enabled.clone();
+    for_each_attrs(&mut enabled, |attrs| resolve(attrs, true));
+    for_each_attrs(&mut disabled, |attrs| resolve(attrs, false));
+
+    Some(quote! {
+        #[cfg(#predicate)]
+        #[::obake::versioned]
+        #enabled
+
+        #[cfg(not(#predicate))]
+        #[::obake::versioned]
+        #disabled
+    })
+}
+
+/// Calls `f` with the attributes of `input` and of each of its variants and fields.
+fn for_each_attrs(input: &mut syn::DeriveInput, mut f: impl FnMut(&mut Vec<syn::Attribute>)) {
+    f(&mut input.attrs);
+    match &mut input.data {
+        syn::Data::Struct(data) => data.fields.iter_mut().for_each(|field| f(&mut field.attrs)),
+        syn::Data::Enum(data) => {
+            for variant in &mut data.variants {
+                f(&mut variant.attrs);
+                variant
+                    .fields
+                    .iter_mut()
+                    .for_each(|field| f(&mut field.attrs));
+            }
+        }
+        syn::Data::Union(data) => data
+            .fields
+            .named
+            .iter_mut()
+            .for_each(|field| f(&mut field.attrs)),
+    }
+}
+
+impl VersionedItem {
+    /// Expands an item declared with `#[obake(use_versions(...))]` (given as `item`, without the
+    /// `#[obake::versioned]` attribute) to an invocation of the macro generated by
+    /// `obake::version_set!`, which applies `#[obake::versioned]` again along with the versions
+    /// in the set. Returns `None` if the item doesn't use a version set, or it has already been
+    /// resolved.
+    pub fn expand_use_versions(&self, item: &TokenStream2) -> Option<TokenStream2> {
+        let mut use_versions = self.attrs.use_versions();
+        let first = use_versions.next()?;
+        if self.attrs.version_sets().next().is_some() {
+            return None;
+        }
+
+        if let Some(duplicate) = use_versions.next() {
+            return Some(
+                syn::Error::new(duplicate.span, "types can only use a single version set")
+                    .into_compile_error(),
+            );
         }
+
+        if let Some(version) = self.attrs.versions().next() {
+            return Some(
+                syn::Error::new(
+                    version.span,
+                    "versions cannot be declared alongside `#[obake(use_versions(...))]`",
+                )
+                .into_compile_error(),
+            );
+        }
+
+        let path = &first.path;
+        Some(quote_spanned!(first.span => #path! { #item }))
+    }
+}
+
+/// Expands an `obake::version_set!` to the macro which applies its versions to an item.
+pub fn expand_version_set(set: &VersionSet) -> TokenStream2 {
+    let attrs = &set.attrs;
+    let vis = &set.vis;
+    let ident = &set.ident;
+    let versions = &set.versions;
+    let hidden = format_ident!("__obake_version_set_{}", ident);
+    quote! {
+        #(#attrs)*
+        #[doc(hidden)]
+        #[allow(unused_macros)]
+        macro_rules! #hidden {
+            ($($item:tt)*) => {
+                #[::obake::versioned]
+                #[obake(__version_set(#(#versions),*))]
+                $($item)*
+            };
+        }
+
+        #[allow(unused_imports)]
+        #vis use #hidden as #ident;
     }
 }
 
@@ -367,3 +5714,40 @@ impl ToTokens for VersionedItem {
         tokens.append_all(self.expand());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(item: &str) -> String {
+        syn::parse_str::<VersionedItem>(item)
+            .unwrap()
+            .expand()
+            .to_string()
+    }
+
+    #[test]
+    fn provenance_docs() {
+        let expanded = expand(
+            r#"
+            #[obake(version("0.1.0"))]
+            #[obake(version("0.2.0"))]
+            struct Foo {
+                #[obake(cfg("0.1.0"))]
+                foo: u32,
+                #[obake(cfg(">=0.2"))]
+                bar: u32,
+            }
+            "#,
+        );
+        for doc in [
+            "Generated from version 0.1.0 of `Foo`.",
+            "Generated from version 0.2.0 of `Foo`.",
+            "Generated from version 0.1.0 of `Foo` (field removed in 0.2.0).",
+            "Generated from version 0.2.0 of `Foo` (field added in 0.2.0).",
+        ] {
+            let doc = format!("{doc:?}");
+            assert!(expanded.contains(&doc), "missing {}", doc);
+        }
+    }
+}
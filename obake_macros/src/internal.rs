@@ -1,45 +1,58 @@
 use syn::Token;
 
-pub use proc_macro::TokenStream;
 pub use proc_macro2::{Span, TokenStream as TokenStream2};
 
+pub use obake_syntax::{CfgPredicate, InheritMapping, VersionAttr, VersionSet};
 pub use semver::{Version, VersionReq};
 
 #[derive(Clone)]
-pub struct VersionAttr {
-    pub version: Version,
+pub struct CfgAttr {
+    pub req: CfgPredicate,
     pub span: Span,
 }
 
-impl PartialEq for VersionAttr {
-    fn eq(&self, other: &Self) -> bool {
-        self.version == other.version
-    }
+/// The arguments of a `#[cfg_attr(predicate, attrs...)]` attribute.
+#[derive(Clone)]
+pub struct ConditionalAttrs {
+    pub predicate: syn::NestedMeta,
+    pub attrs: Vec<syn::Attribute>,
 }
 
-impl Eq for VersionAttr {}
+#[derive(Clone)]
+pub struct FlattenAttr {
+    pub span: Span,
+}
 
-impl PartialOrd for VersionAttr {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.version.partial_cmp(&other.version)
-    }
+#[derive(Clone)]
+pub struct SerdeCfgAttr {
+    pub req: CfgPredicate,
+    pub tokens: TokenStream2,
 }
 
-impl Ord for VersionAttr {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.version.cmp(&other.version)
-    }
+#[derive(Clone)]
+pub struct MapFromAttr {
+    pub span: Span,
+    pub with: syn::Path,
 }
 
 #[derive(Clone)]
-pub struct CfgAttr {
-    pub req: VersionReq,
+pub struct RetypedAttr {
+    pub span: Span,
+    /// Set with `retyped(try_from, or = ...)`, for conversions which can fail.
+    pub fallback: Option<syn::Expr>,
+}
+
+#[derive(Clone)]
+pub struct ComputedAttr {
     pub span: Span,
+    /// Called with a reference to the previous version, as in `computed = |old| ...`.
+    pub with: syn::Expr,
 }
 
 #[derive(Clone)]
 pub struct InheritAttr {
     pub span: Span,
+    pub mapping: Option<InheritMapping>,
 }
 
 #[derive(Clone)]
@@ -48,20 +61,460 @@ pub struct DeriveAttr {
     pub tokens: TokenStream2,
 }
 
+#[derive(Clone)]
+pub struct GenerateTestsAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct DowngradeAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct MaxVersionsAttr {
+    pub span: Span,
+    pub max: usize,
+}
+
+#[derive(Clone)]
+pub struct PatchAttr {
+    pub span: Span,
+    pub from: Version,
+    pub ops: Vec<PatchOp>,
+    pub path: Option<std::path::PathBuf>,
+}
+
+/// A step of the script of `#[obake(patch(...))]`, naming fields by their path (e.g.
+/// `server.host`).
+#[derive(Clone)]
+pub enum PatchOp {
+    /// `move(a.b -> c.d)`, or `rename(a -> b)` for fields of the versions themselves.
+    Move {
+        span: Span,
+        from: Vec<syn::Ident>,
+        to: Vec<syn::Ident>,
+    },
+    /// `default(a)`, or `default(a = expr)`.
+    Default {
+        span: Span,
+        to: Vec<syn::Ident>,
+        value: Option<Box<syn::Expr>>,
+    },
+    /// `delete(a)`.
+    Delete { span: Span, field: syn::Ident },
+}
+
+#[derive(Clone)]
+pub struct HideOldDocsAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct DefaultAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct DefaultValueAttr {
+    pub span: Span,
+    /// The value of the field in the generated `Default` impls, as in `default = ...`.
+    pub expr: syn::Expr,
+}
+
+#[derive(Clone)]
+pub struct EmitOriginalAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct ValidateAttr {
+    pub span: Span,
+    /// Called with a reference to the upgraded latest version.
+    pub with: syn::Path,
+}
+
+#[derive(Clone)]
+pub struct GraphAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct RenamedFromAttr {
+    pub span: Span,
+    pub ident: syn::Ident,
+    pub until: Version,
+}
+
+#[derive(Clone)]
+pub struct SplitIntoAttr {
+    pub span: Span,
+    pub types: Vec<syn::Path>,
+    pub with: syn::Path,
+}
+
+#[derive(Clone)]
+pub struct MergeFromAttr {
+    pub span: Span,
+    pub types: Vec<syn::Path>,
+    pub with: syn::Path,
+}
+
+#[derive(Clone)]
+pub struct UseVersionsAttr {
+    pub span: Span,
+    pub path: syn::Path,
+}
+
+#[derive(Clone)]
+pub struct VersionsFromAttr {
+    pub span: Span,
+    pub path: std::path::PathBuf,
+}
+
+#[derive(Clone)]
+pub struct VersionSetAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct AliasesAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct SemanticEqAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct VisitorAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct VersionEnumAttr {
+    pub span: Span,
+    /// Set with `version_enum(derive(...))`, for derives applied to the generated `enum`.
+    pub derives: Option<TokenStream2>,
+}
+
+#[derive(Clone)]
+pub struct BuilderAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct CommonFieldsAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct ErrorAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct DisplayAttr {
+    pub span: Span,
+    pub tokens: TokenStream2,
+}
+
+#[derive(Clone)]
+pub struct SourceAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct AssertLayoutAttr {
+    pub span: Span,
+    pub version: VersionAttr,
+    pub size: Option<syn::LitInt>,
+    pub align: Option<syn::LitInt>,
+}
+
+#[derive(Clone)]
+pub struct CoverageAttr {
+    pub span: Span,
+    pub traits: syn::punctuated::Punctuated<syn::Path, Token![,]>,
+}
+
+#[derive(Clone)]
+pub struct LatestOnlyAttr {
+    pub span: Span,
+    pub tokens: TokenStream2,
+}
+
+#[derive(Clone)]
+pub struct TrivialMigrationsAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct PartialMigrationsAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct SchemaHashAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct StrictAttr {
+    pub span: Span,
+}
+
+#[derive(Clone)]
+pub struct DiscardAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone)]
+pub struct SerdeAttr {
+    pub span: Span,
+    pub tokens: TokenStream2,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone)]
+pub struct SerdeReprAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "bson")]
+#[derive(Clone)]
+pub struct BsonAttr {
+    pub span: Span,
+    pub field: Option<syn::LitStr>,
+}
+
+#[cfg(feature = "bson")]
+impl BsonAttr {
+    pub const DEFAULT_FIELD: &'static str = "_schema_version";
+
+    pub fn field_name(&self) -> String {
+        self.field
+            .as_ref()
+            .map_or_else(|| Self::DEFAULT_FIELD.to_owned(), syn::LitStr::value)
+    }
+}
+
+#[cfg(feature = "graphql")]
+#[derive(Clone)]
+pub struct GraphqlAttr {
+    pub span: Span,
+    pub input: bool,
+}
+
+#[cfg(feature = "json")]
+#[derive(Clone)]
+pub struct PreserveUnknownAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "json")]
+#[derive(Clone)]
+pub struct UnknownVariantAttr {
+    pub span: Span,
+    pub latest_only: bool,
+}
+
+#[cfg(feature = "wasm")]
+#[derive(Clone)]
+pub struct WasmAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "pyo3")]
+#[derive(Clone)]
+pub struct Pyo3Attr {
+    pub span: Span,
+}
+
+#[cfg(feature = "abi_stable")]
+#[derive(Clone)]
+pub struct AbiStableAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "tonic")]
+#[derive(Clone)]
+pub struct TonicAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "validator")]
+#[derive(Clone)]
+pub struct ValidatorAttr {
+    pub span: Span,
+    /// Whether only the latest version is validated, rather than every version passed through.
+    pub latest: bool,
+}
+
+#[cfg(feature = "garde")]
+#[derive(Clone)]
+pub struct GardeAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "garde")]
+#[derive(Clone)]
+pub struct GardeCfgAttr {
+    pub span: Span,
+    pub req: CfgPredicate,
+    pub tokens: TokenStream2,
+}
+
+#[cfg(feature = "zeroize")]
+#[derive(Clone)]
+pub struct ZeroizeAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "fixtures")]
+#[derive(Clone)]
+pub struct FixturesAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "arbitrary")]
+#[derive(Clone)]
+pub struct ArbitraryAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "proptest")]
+#[derive(Clone)]
+pub struct ProptestAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "proptest")]
+#[derive(Clone)]
+pub struct StrategyAttr {
+    pub span: Span,
+    pub expr: Box<syn::Expr>,
+}
+
+#[cfg(feature = "fuzz")]
+#[derive(Clone)]
+pub struct FuzzAttr {
+    pub span: Span,
+    pub format: Option<syn::Path>,
+}
+
+#[cfg(feature = "dynamic")]
+#[derive(Clone)]
+pub struct DynamicAttr {
+    pub span: Span,
+}
+
+#[cfg(feature = "criterion")]
+#[derive(Clone)]
+pub struct GenerateBenchesAttr {
+    pub span: Span,
+    pub feature: Option<syn::LitStr>,
+}
+
 #[derive(Clone)]
 pub enum ObakeAttribute {
     Version(VersionAttr),
     Cfg(CfgAttr),
     Inherit(InheritAttr),
+    Flatten(FlattenAttr),
+    SerdeCfg(SerdeCfgAttr),
+    MapFrom(MapFromAttr),
+    Retyped(RetypedAttr),
+    Computed(ComputedAttr),
     Derive(DeriveAttr),
+    GenerateTests(GenerateTestsAttr),
+    Downgrade(DowngradeAttr),
+    Validate(ValidateAttr),
+    EmitOriginal(EmitOriginalAttr),
+    HideOldDocs(HideOldDocsAttr),
+    Default(DefaultAttr),
+    DefaultValue(DefaultValueAttr),
+    MaxVersions(MaxVersionsAttr),
+    Patch(PatchAttr),
+    Graph(GraphAttr),
+    Aliases(AliasesAttr),
+    SemanticEq(SemanticEqAttr),
+    Visitor(VisitorAttr),
+    VersionEnum(VersionEnumAttr),
+    Builder(BuilderAttr),
+    CommonFields(CommonFieldsAttr),
+    AssertLayout(AssertLayoutAttr),
+    Error(ErrorAttr),
+    Display(DisplayAttr),
+    Source(SourceAttr),
+    UseVersions(UseVersionsAttr),
+    VersionSet(VersionSetAttr),
+    VersionsFrom(VersionsFromAttr),
+    RenamedFrom(RenamedFromAttr),
+    VariantRenamedFrom(RenamedFromAttr),
+    SplitInto(SplitIntoAttr),
+    MergeFrom(MergeFromAttr),
+    Coverage(CoverageAttr),
+    LatestOnly(LatestOnlyAttr),
+    SchemaHash(SchemaHashAttr),
+    TrivialMigrations(TrivialMigrationsAttr),
+    PartialMigrations(PartialMigrationsAttr),
+    Strict(StrictAttr),
+    Discard(DiscardAttr),
+    #[cfg(feature = "serde")]
+    Serde(SerdeAttr),
+    #[cfg(feature = "serde")]
+    SerdeRepr(SerdeReprAttr),
+    #[cfg(feature = "bson")]
+    Bson(BsonAttr),
+    #[cfg(feature = "graphql")]
+    Graphql(GraphqlAttr),
+    #[cfg(feature = "tonic")]
+    Tonic(TonicAttr),
+    #[cfg(feature = "json")]
+    PreserveUnknown(PreserveUnknownAttr),
+    #[cfg(feature = "json")]
+    UnknownVariant(UnknownVariantAttr),
+    #[cfg(feature = "wasm")]
+    Wasm(WasmAttr),
+    #[cfg(feature = "pyo3")]
+    Pyo3(Pyo3Attr),
+    #[cfg(feature = "abi_stable")]
+    AbiStable(AbiStableAttr),
+    #[cfg(feature = "validator")]
+    Validator(ValidatorAttr),
+    #[cfg(feature = "garde")]
+    Garde(GardeAttr),
+    #[cfg(feature = "garde")]
+    GardeCfg(GardeCfgAttr),
+    #[cfg(feature = "zeroize")]
+    Zeroize(ZeroizeAttr),
+    #[cfg(feature = "fixtures")]
+    Fixtures(FixturesAttr),
+    #[cfg(feature = "arbitrary")]
+    Arbitrary(ArbitraryAttr),
+    #[cfg(feature = "proptest")]
+    Proptest(ProptestAttr),
+    #[cfg(feature = "proptest")]
+    Strategy(StrategyAttr),
+    #[cfg(feature = "criterion")]
+    GenerateBenches(GenerateBenchesAttr),
+    #[cfg(feature = "fuzz")]
+    Fuzz(FuzzAttr),
+    #[cfg(feature = "dynamic")]
+    Dynamic(DynamicAttr),
 }
 
 #[derive(Clone)]
 pub struct VersionedField {
     pub attrs: VersionedAttributes,
     pub vis: syn::Visibility,
+    /// Named `field_0`, `field_1`, etc. after their position for fields of tuple `struct`s,
+    /// which have no `colon_token`.
     pub ident: syn::Ident,
-    pub colon_token: Token![:],
+    pub colon_token: Option<Token![:]>,
     pub ty: syn::Type,
 }
 
@@ -108,28 +561,529 @@ impl ObakeAttribute {
             _ => None,
         }
     }
-}
 
-impl VersionedAttribute {
-    pub fn obake(&self) -> Option<&ObakeAttribute> {
+    pub fn generate_tests(&self) -> Option<&GenerateTestsAttr> {
         #![allow(clippy::match_wildcard_for_single_variants)]
         match &self {
-            VersionedAttribute::Obake(obake) => Some(obake),
+            ObakeAttribute::GenerateTests(generate_tests) => Some(generate_tests),
             _ => None,
         }
     }
 
-    pub fn attr(&self) -> Option<&syn::Attribute> {
+    pub fn downgrade(&self) -> Option<&DowngradeAttr> {
         #![allow(clippy::match_wildcard_for_single_variants)]
-        match self {
-            VersionedAttribute::Attribute(attr) => Some(attr),
+        match &self {
+            ObakeAttribute::Downgrade(downgrade) => Some(downgrade),
             _ => None,
         }
     }
-}
 
-impl VersionedAttributes {
-    pub fn obake(&self) -> impl Iterator<Item = &ObakeAttribute> + '_ {
+    pub fn max_versions(&self) -> Option<&MaxVersionsAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::MaxVersions(max_versions) => Some(max_versions),
+            _ => None,
+        }
+    }
+
+    pub fn patch(&self) -> Option<&PatchAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Patch(patch) => Some(patch),
+            _ => None,
+        }
+    }
+
+    pub fn hide_old_docs(&self) -> Option<&HideOldDocsAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::HideOldDocs(hide_old_docs) => Some(hide_old_docs),
+            _ => None,
+        }
+    }
+
+    pub fn default(&self) -> Option<&DefaultAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Default(default) => Some(default),
+            _ => None,
+        }
+    }
+
+    pub fn default_value(&self) -> Option<&DefaultValueAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::DefaultValue(default_value) => Some(default_value),
+            _ => None,
+        }
+    }
+
+    pub fn emit_original(&self) -> Option<&EmitOriginalAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::EmitOriginal(emit_original) => Some(emit_original),
+            _ => None,
+        }
+    }
+
+    pub fn validate(&self) -> Option<&ValidateAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Validate(validate) => Some(validate),
+            _ => None,
+        }
+    }
+
+    pub fn graph(&self) -> Option<&GraphAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Graph(graph) => Some(graph),
+            _ => None,
+        }
+    }
+
+    pub fn aliases(&self) -> Option<&AliasesAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Aliases(aliases) => Some(aliases),
+            _ => None,
+        }
+    }
+
+    pub fn semantic_eq(&self) -> Option<&SemanticEqAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::SemanticEq(semantic_eq) => Some(semantic_eq),
+            _ => None,
+        }
+    }
+
+    pub fn visitor(&self) -> Option<&VisitorAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Visitor(visitor) => Some(visitor),
+            _ => None,
+        }
+    }
+
+    pub fn version_enum(&self) -> Option<&VersionEnumAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::VersionEnum(version_enum) => Some(version_enum),
+            _ => None,
+        }
+    }
+
+    pub fn builder(&self) -> Option<&BuilderAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Builder(builder) => Some(builder),
+            _ => None,
+        }
+    }
+
+    pub fn common_fields(&self) -> Option<&CommonFieldsAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::CommonFields(common_fields) => Some(common_fields),
+            _ => None,
+        }
+    }
+
+    pub fn error(&self) -> Option<&ErrorAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Error(error) => Some(error),
+            _ => None,
+        }
+    }
+
+    pub fn display(&self) -> Option<&DisplayAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Display(display) => Some(display),
+            _ => None,
+        }
+    }
+
+    pub fn source(&self) -> Option<&SourceAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Source(source) => Some(source),
+            _ => None,
+        }
+    }
+
+    pub fn assert_layout(&self) -> Option<&AssertLayoutAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::AssertLayout(assert_layout) => Some(assert_layout),
+            _ => None,
+        }
+    }
+
+    pub fn use_versions(&self) -> Option<&UseVersionsAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::UseVersions(use_versions) => Some(use_versions),
+            _ => None,
+        }
+    }
+
+    pub fn version_set(&self) -> Option<&VersionSetAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::VersionSet(version_set) => Some(version_set),
+            _ => None,
+        }
+    }
+
+    pub fn versions_from(&self) -> Option<&VersionsFromAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::VersionsFrom(versions_from) => Some(versions_from),
+            _ => None,
+        }
+    }
+
+    pub fn renamed_from(&self) -> Option<&RenamedFromAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::RenamedFrom(renamed_from) => Some(renamed_from),
+            _ => None,
+        }
+    }
+
+    pub fn variant_renamed_from(&self) -> Option<&RenamedFromAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::VariantRenamedFrom(renamed_from) => Some(renamed_from),
+            _ => None,
+        }
+    }
+
+    pub fn split_into(&self) -> Option<&SplitIntoAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::SplitInto(split_into) => Some(split_into),
+            _ => None,
+        }
+    }
+
+    pub fn merge_from(&self) -> Option<&MergeFromAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::MergeFrom(merge_from) => Some(merge_from),
+            _ => None,
+        }
+    }
+
+    pub fn flatten(&self) -> Option<&FlattenAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Flatten(flatten) => Some(flatten),
+            _ => None,
+        }
+    }
+
+    pub fn serde_cfg(&self) -> Option<&SerdeCfgAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::SerdeCfg(serde_cfg) => Some(serde_cfg),
+            _ => None,
+        }
+    }
+
+    pub fn map_from(&self) -> Option<&MapFromAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::MapFrom(map_from) => Some(map_from),
+            _ => None,
+        }
+    }
+
+    pub fn retyped(&self) -> Option<&RetypedAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Retyped(retyped) => Some(retyped),
+            _ => None,
+        }
+    }
+
+    pub fn computed(&self) -> Option<&ComputedAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Computed(computed) => Some(computed),
+            _ => None,
+        }
+    }
+
+    pub fn coverage(&self) -> Option<&CoverageAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Coverage(coverage) => Some(coverage),
+            _ => None,
+        }
+    }
+
+    pub fn latest_only(&self) -> Option<&LatestOnlyAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::LatestOnly(latest_only) => Some(latest_only),
+            _ => None,
+        }
+    }
+
+    pub fn trivial_migrations(&self) -> Option<&TrivialMigrationsAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::TrivialMigrations(trivial_migrations) => Some(trivial_migrations),
+            _ => None,
+        }
+    }
+
+    pub fn partial_migrations(&self) -> Option<&PartialMigrationsAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::PartialMigrations(partial_migrations) => Some(partial_migrations),
+            _ => None,
+        }
+    }
+
+    pub fn schema_hash(&self) -> Option<&SchemaHashAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::SchemaHash(schema_hash) => Some(schema_hash),
+            _ => None,
+        }
+    }
+
+    pub fn strict(&self) -> Option<&StrictAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Strict(strict) => Some(strict),
+            _ => None,
+        }
+    }
+
+    pub fn discard(&self) -> Option<&DiscardAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Discard(discard) => Some(discard),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn serde(&self) -> Option<&SerdeAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Serde(serde) => Some(serde),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn serde_repr(&self) -> Option<&SerdeReprAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::SerdeRepr(serde_repr) => Some(serde_repr),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "bson")]
+    pub fn bson(&self) -> Option<&BsonAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Bson(bson) => Some(bson),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "graphql")]
+    pub fn graphql(&self) -> Option<&GraphqlAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Graphql(graphql) => Some(graphql),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "json")]
+    pub fn preserve_unknown(&self) -> Option<&PreserveUnknownAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::PreserveUnknown(preserve_unknown) => Some(preserve_unknown),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "json")]
+    pub fn unknown_variant(&self) -> Option<&UnknownVariantAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::UnknownVariant(unknown_variant) => Some(unknown_variant),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "validator")]
+    pub fn validator(&self) -> Option<&ValidatorAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Validator(validator) => Some(validator),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "garde")]
+    pub fn garde(&self) -> Option<&GardeAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Garde(garde) => Some(garde),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "garde")]
+    pub fn garde_cfg(&self) -> Option<&GardeCfgAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::GardeCfg(garde_cfg) => Some(garde_cfg),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "zeroize")]
+    pub fn zeroize(&self) -> Option<&ZeroizeAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Zeroize(zeroize) => Some(zeroize),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "wasm")]
+    pub fn wasm(&self) -> Option<&WasmAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Wasm(wasm) => Some(wasm),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "pyo3")]
+    pub fn pyo3(&self) -> Option<&Pyo3Attr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Pyo3(pyo3) => Some(pyo3),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "abi_stable")]
+    pub fn abi_stable(&self) -> Option<&AbiStableAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::AbiStable(abi_stable) => Some(abi_stable),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "tonic")]
+    pub fn tonic(&self) -> Option<&TonicAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Tonic(tonic) => Some(tonic),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "fixtures")]
+    pub fn fixtures(&self) -> Option<&FixturesAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Fixtures(fixtures) => Some(fixtures),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    pub fn arbitrary(&self) -> Option<&ArbitraryAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Arbitrary(arbitrary) => Some(arbitrary),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "proptest")]
+    pub fn proptest(&self) -> Option<&ProptestAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Proptest(proptest) => Some(proptest),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "proptest")]
+    pub fn strategy(&self) -> Option<&StrategyAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Strategy(strategy) => Some(strategy),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "criterion")]
+    pub fn generate_benches(&self) -> Option<&GenerateBenchesAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::GenerateBenches(generate_benches) => Some(generate_benches),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "fuzz")]
+    pub fn fuzz(&self) -> Option<&FuzzAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Fuzz(fuzz) => Some(fuzz),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "dynamic")]
+    pub fn dynamic(&self) -> Option<&DynamicAttr> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            ObakeAttribute::Dynamic(dynamic) => Some(dynamic),
+            _ => None,
+        }
+    }
+}
+
+impl VersionedAttribute {
+    pub fn obake(&self) -> Option<&ObakeAttribute> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match &self {
+            VersionedAttribute::Obake(obake) => Some(obake),
+            _ => None,
+        }
+    }
+
+    pub fn attr(&self) -> Option<&syn::Attribute> {
+        #![allow(clippy::match_wildcard_for_single_variants)]
+        match self {
+            VersionedAttribute::Attribute(attr) => Some(attr),
+            _ => None,
+        }
+    }
+}
+
+impl VersionedAttributes {
+    pub fn obake(&self) -> impl Iterator<Item = &ObakeAttribute> + '_ {
         self.attrs.iter().filter_map(VersionedAttribute::obake)
     }
 
@@ -149,6 +1103,268 @@ impl VersionedAttributes {
         self.obake().filter_map(ObakeAttribute::derive)
     }
 
+    pub fn generate_tests(&self) -> impl Iterator<Item = &GenerateTestsAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::generate_tests)
+    }
+
+    pub fn downgrades(&self) -> impl Iterator<Item = &DowngradeAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::downgrade)
+    }
+
+    pub fn max_versions(&self) -> impl Iterator<Item = &MaxVersionsAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::max_versions)
+    }
+
+    pub fn patches(&self) -> impl Iterator<Item = &PatchAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::patch)
+    }
+
+    pub fn hide_old_docs(&self) -> impl Iterator<Item = &HideOldDocsAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::hide_old_docs)
+    }
+
+    pub fn defaults(&self) -> impl Iterator<Item = &DefaultAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::default)
+    }
+
+    pub fn default_values(&self) -> impl Iterator<Item = &DefaultValueAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::default_value)
+    }
+
+    pub fn emit_originals(&self) -> impl Iterator<Item = &EmitOriginalAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::emit_original)
+    }
+
+    pub fn validates(&self) -> impl Iterator<Item = &ValidateAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::validate)
+    }
+
+    pub fn graphs(&self) -> impl Iterator<Item = &GraphAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::graph)
+    }
+
+    pub fn aliases(&self) -> impl Iterator<Item = &AliasesAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::aliases)
+    }
+
+    pub fn semantic_eqs(&self) -> impl Iterator<Item = &SemanticEqAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::semantic_eq)
+    }
+
+    pub fn visitors(&self) -> impl Iterator<Item = &VisitorAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::visitor)
+    }
+
+    pub fn version_enums(&self) -> impl Iterator<Item = &VersionEnumAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::version_enum)
+    }
+
+    pub fn builders(&self) -> impl Iterator<Item = &BuilderAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::builder)
+    }
+
+    pub fn common_fields(&self) -> impl Iterator<Item = &CommonFieldsAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::common_fields)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ErrorAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::error)
+    }
+
+    pub fn displays(&self) -> impl Iterator<Item = &DisplayAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::display)
+    }
+
+    pub fn sources(&self) -> impl Iterator<Item = &SourceAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::source)
+    }
+
+    pub fn assert_layouts(&self) -> impl Iterator<Item = &AssertLayoutAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::assert_layout)
+    }
+
+    pub fn use_versions(&self) -> impl Iterator<Item = &UseVersionsAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::use_versions)
+    }
+
+    pub fn version_sets(&self) -> impl Iterator<Item = &VersionSetAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::version_set)
+    }
+
+    pub fn versions_froms(&self) -> impl Iterator<Item = &VersionsFromAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::versions_from)
+    }
+
+    pub fn renamed_froms(&self) -> impl Iterator<Item = &RenamedFromAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::renamed_from)
+    }
+
+    pub fn variant_renamed_froms(&self) -> impl Iterator<Item = &RenamedFromAttr> + '_ {
+        self.obake()
+            .filter_map(ObakeAttribute::variant_renamed_from)
+    }
+
+    pub fn split_intos(&self) -> impl Iterator<Item = &SplitIntoAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::split_into)
+    }
+
+    pub fn merge_froms(&self) -> impl Iterator<Item = &MergeFromAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::merge_from)
+    }
+
+    pub fn flattens(&self) -> impl Iterator<Item = &FlattenAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::flatten)
+    }
+
+    pub fn serde_cfgs(&self) -> impl Iterator<Item = &SerdeCfgAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::serde_cfg)
+    }
+
+    pub fn map_froms(&self) -> impl Iterator<Item = &MapFromAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::map_from)
+    }
+
+    pub fn retypeds(&self) -> impl Iterator<Item = &RetypedAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::retyped)
+    }
+
+    pub fn computeds(&self) -> impl Iterator<Item = &ComputedAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::computed)
+    }
+
+    pub fn coverages(&self) -> impl Iterator<Item = &CoverageAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::coverage)
+    }
+
+    pub fn latest_onlys(&self) -> impl Iterator<Item = &LatestOnlyAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::latest_only)
+    }
+
+    pub fn trivial_migrations(&self) -> impl Iterator<Item = &TrivialMigrationsAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::trivial_migrations)
+    }
+
+    pub fn partial_migrations(&self) -> impl Iterator<Item = &PartialMigrationsAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::partial_migrations)
+    }
+
+    pub fn schema_hashes(&self) -> impl Iterator<Item = &SchemaHashAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::schema_hash)
+    }
+
+    pub fn stricts(&self) -> impl Iterator<Item = &StrictAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::strict)
+    }
+
+    pub fn discards(&self) -> impl Iterator<Item = &DiscardAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::discard)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn serdes(&self) -> impl Iterator<Item = &SerdeAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::serde)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn serde_reprs(&self) -> impl Iterator<Item = &SerdeReprAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::serde_repr)
+    }
+
+    #[cfg(feature = "bson")]
+    pub fn bsons(&self) -> impl Iterator<Item = &BsonAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::bson)
+    }
+
+    #[cfg(feature = "graphql")]
+    pub fn graphqls(&self) -> impl Iterator<Item = &GraphqlAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::graphql)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn preserve_unknowns(&self) -> impl Iterator<Item = &PreserveUnknownAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::preserve_unknown)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn unknown_variants(&self) -> impl Iterator<Item = &UnknownVariantAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::unknown_variant)
+    }
+
+    #[cfg(feature = "validator")]
+    pub fn validators(&self) -> impl Iterator<Item = &ValidatorAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::validator)
+    }
+
+    #[cfg(feature = "garde")]
+    pub fn gardes(&self) -> impl Iterator<Item = &GardeAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::garde)
+    }
+
+    #[cfg(feature = "garde")]
+    pub fn garde_cfgs(&self) -> impl Iterator<Item = &GardeCfgAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::garde_cfg)
+    }
+
+    #[cfg(feature = "zeroize")]
+    pub fn zeroizes(&self) -> impl Iterator<Item = &ZeroizeAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::zeroize)
+    }
+
+    #[cfg(feature = "wasm")]
+    pub fn wasms(&self) -> impl Iterator<Item = &WasmAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::wasm)
+    }
+
+    #[cfg(feature = "pyo3")]
+    pub fn pyo3s(&self) -> impl Iterator<Item = &Pyo3Attr> + '_ {
+        self.obake().filter_map(ObakeAttribute::pyo3)
+    }
+
+    #[cfg(feature = "abi_stable")]
+    pub fn abi_stables(&self) -> impl Iterator<Item = &AbiStableAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::abi_stable)
+    }
+
+    #[cfg(feature = "tonic")]
+    pub fn tonics(&self) -> impl Iterator<Item = &TonicAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::tonic)
+    }
+
+    #[cfg(feature = "fixtures")]
+    pub fn fixtures(&self) -> impl Iterator<Item = &FixturesAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::fixtures)
+    }
+
+    #[cfg(feature = "arbitrary")]
+    pub fn arbitraries(&self) -> impl Iterator<Item = &ArbitraryAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::arbitrary)
+    }
+
+    #[cfg(feature = "proptest")]
+    pub fn proptests(&self) -> impl Iterator<Item = &ProptestAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::proptest)
+    }
+
+    #[cfg(feature = "proptest")]
+    pub fn strategies(&self) -> impl Iterator<Item = &StrategyAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::strategy)
+    }
+
+    #[cfg(feature = "criterion")]
+    pub fn generate_benches(&self) -> impl Iterator<Item = &GenerateBenchesAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::generate_benches)
+    }
+
+    #[cfg(feature = "fuzz")]
+    pub fn fuzzes(&self) -> impl Iterator<Item = &FuzzAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::fuzz)
+    }
+
+    #[cfg(feature = "dynamic")]
+    pub fn dynamics(&self) -> impl Iterator<Item = &DynamicAttr> + '_ {
+        self.obake().filter_map(ObakeAttribute::dynamic)
+    }
+
     pub fn attrs(&self) -> impl Iterator<Item = &syn::Attribute> + '_ {
         self.attrs.iter().filter_map(VersionedAttribute::attr)
     }
@@ -156,13 +1372,17 @@ impl VersionedAttributes {
 
 #[derive(Clone)]
 pub struct VersionedFields {
-    pub brace_token: syn::token::Brace,
+    /// Set for the fields of tuple `struct`s, which have a single field in every version (and of
+    /// `enum` variants with unnamed fields).
+    pub paren_token: Option<syn::token::Paren>,
     pub fields: syn::punctuated::Punctuated<VersionedField, Token![,]>,
 }
 
 #[derive(Clone)]
 pub enum VersionedVariantFields {
-    Unnamed(syn::FieldsUnnamed),
+    /// Named `field_0`, `field_1`, etc. after their position, as with the fields of tuple
+    /// `struct`s.
+    Unnamed(VersionedFields),
     Named(VersionedFields),
     Unit,
 }
@@ -176,7 +1396,6 @@ pub struct VersionedVariant {
 
 #[derive(Clone)]
 pub struct VersionedVariants {
-    pub brace_token: syn::token::Brace,
     pub variants: syn::punctuated::Punctuated<VersionedVariant, Token![,]>,
 }
 
@@ -15,7 +15,20 @@ mod parse;
 #[proc_macro_attribute]
 pub fn versioned(args: TokenStream, input: TokenStream) -> TokenStream {
     let _ = parse_macro_input!(args as Nothing);
+    let item = proc_macro2::TokenStream::from(input.clone());
+    if let Some(expanded) = expand::expand_cfg_attrs(&item) {
+        return TokenStream::from(expanded);
+    }
+
     let input = parse_macro_input!(input as internal::VersionedItem);
-    let expanded = quote!(#input);
+    let expanded = input
+        .expand_use_versions(&item)
+        .unwrap_or_else(|| quote!(#input));
     TokenStream::from(expanded)
 }
+
+#[proc_macro]
+pub fn version_set(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as internal::VersionSet);
+    TokenStream::from(expand::expand_version_set(&input))
+}
@@ -1,50 +1,307 @@
-use std::convert::{TryFrom, TryInto};
-
+use quote::format_ident;
 use syn::parse::{Parse, ParseStream, Result};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
 use syn::{braced, parenthesized, Token};
 
+use obake_syntax::{parse_version, VersionsHelper};
+
 use crate::internal::*;
 
 const OBAKE: &str = "obake";
 
-impl Parse for VersionAttr {
-    fn parse(input: ParseStream) -> Result<Self> {
-        let version_str = input.parse::<syn::LitStr>()?;
-        let span = version_str.span();
-        let version = Version::parse(&version_str.value())
-            .map_err(|err| syn::Error::new(version_str.span(), err))?;
+/// Every helper attribute, along with the feature of `obake` it requires (if any).
+const HELPERS: &[(&str, Option<&str>)] = &[
+    ("version", None),
+    ("versions", None),
+    ("versions_from", None),
+    ("cfg", None),
+    ("inherit", None),
+    ("flatten", None),
+    ("serde_cfg", None),
+    ("map_from", None),
+    ("retyped", None),
+    ("computed", None),
+    ("derive", None),
+    ("generate_tests", None),
+    ("coverage", None),
+    ("latest_only", None),
+    ("trivial_migrations", None),
+    ("partial_migrations", None),
+    ("schema_hash", None),
+    ("strict", None),
+    ("discard", None),
+    ("graph", None),
+    ("aliases", None),
+    ("semantic_eq", None),
+    ("visitor", None),
+    ("version_enum", None),
+    ("builder", None),
+    ("common_fields", None),
+    ("assert_layout", None),
+    ("error", None),
+    ("display", None),
+    ("source", None),
+    ("use_versions", None),
+    ("renamed_from", None),
+    ("variant_renamed_from", None),
+    ("split_into", None),
+    ("merge_from", None),
+    ("downgrade", None),
+    ("validate", None),
+    ("emit_original", None),
+    ("hide_old_docs", None),
+    ("default", None),
+    ("max_versions", None),
+    ("patch", None),
+    ("serde", Some("serde")),
+    ("serde_repr", Some("serde")),
+    ("bson", Some("bson")),
+    ("graphql", Some("graphql")),
+    ("tonic", Some("tonic")),
+    ("preserve_unknown", Some("json")),
+    ("unknown_variant", Some("json")),
+    ("wasm", Some("wasm")),
+    ("pyo3", Some("pyo3")),
+    ("abi_stable", Some("abi_stable")),
+    ("validator", Some("validator")),
+    ("garde", Some("garde")),
+    ("garde_cfg", Some("garde")),
+    ("zeroize", Some("zeroize")),
+    ("fixtures", Some("fixtures")),
+    ("arbitrary", Some("arbitrary")),
+    ("proptest", Some("proptest")),
+    ("strategy", Some("proptest")),
+    ("generate_benches", Some("criterion")),
+    ("fuzz", Some("fuzz")),
+    ("dynamic", Some("dynamic")),
+];
+
+/// The number of single-character insertions, deletions and substitutions needed to turn `a`
+/// into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<_> = b.chars().collect();
+    let mut row: Vec<_> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != *b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
 
-        Ok(Self { version, span })
+fn unrecognised_helper(ident: &syn::Ident) -> syn::Error {
+    let name = ident.to_string();
+    if let Some((_, Some(feature))) = HELPERS.iter().find(|(helper, _)| *helper == name) {
+        return syn::Error::new(
+            ident.span(),
+            format!("`{name}` requires the `{feature}` feature of `obake`"),
+        );
+    }
+
+    let suggestion = HELPERS
+        .iter()
+        .map(|(helper, _)| (edit_distance(&name, helper), helper))
+        .min()
+        .filter(|(distance, _)| *distance <= (name.len() / 3).max(1))
+        .map_or_else(String::new, |(_, helper)| {
+            format!(", did you mean `{helper}`?")
+        });
+    let helpers: Vec<_> = HELPERS
+        .iter()
+        .map(|(helper, _)| format!("`{helper}`"))
+        .collect();
+    let message = format!(
+        "unrecognised `obake` helper attribute{suggestion}\n\nexpected one of {}",
+        helpers.join(", "),
+    );
+    syn::Error::new(ident.span(), message)
+}
+
+/// Parses a list of types followed by the function converting to or from them, as in
+/// `split_into(A, B, with = split)`.
+fn parse_types_with(input: ParseStream) -> Result<(Vec<syn::Path>, syn::Path)> {
+    let mut types = Vec::new();
+    loop {
+        if input.peek(syn::Ident) && input.peek2(Token![=]) {
+            let key = input.parse::<syn::Ident>()?;
+            if key != "with" {
+                return Err(syn::Error::new(key.span(), "expected `with`"));
+            }
+            input.parse::<Token![=]>()?;
+            let with = input.parse()?;
+            if types.is_empty() {
+                return Err(syn::Error::new_spanned(key, "expected at least one type"));
+            }
+
+            return Ok((types, with));
+        }
+
+        types.push(input.parse()?);
+        if input.is_empty() {
+            return Err(syn::Error::new(input.span(), "expected `with = ...`"));
+        }
+        input.parse::<Token![,]>()?;
     }
 }
 
 impl Parse for CfgAttr {
     fn parse(input: ParseStream) -> Result<Self> {
-        let req_str = input.parse::<syn::LitStr>()?;
-        let span = req_str.span();
-        let req = VersionReq::parse(&req_str.value())
-            .map_err(|err| syn::Error::new(req_str.span(), err))?;
+        let span = input.span();
+        let req = input.parse()?;
 
         Ok(Self { req, span })
     }
 }
 
+impl Parse for ConditionalAttrs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let predicate = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        // The attributes are only split on top-level commas, as they can hold arbitrary tokens
+        // (e.g. `obake(computed = ...)`)
+        let mut attrs = Vec::new();
+        while !input.is_empty() {
+            let mut tokens = TokenStream2::new();
+            while !input.is_empty() && !input.peek(Token![,]) {
+                tokens.extend(Some(input.parse::<proc_macro2::TokenTree>()?));
+            }
+            if !tokens.is_empty() {
+                let attr = quote::quote!(#[#tokens]);
+                attrs.extend(syn::parse::Parser::parse2(
+                    syn::Attribute::parse_outer,
+                    attr,
+                )?);
+            }
+            input.parse::<Option<Token![,]>>()?;
+        }
+
+        Ok(Self { predicate, attrs })
+    }
+}
+
+/// Parses `attr` if it's a `#[cfg_attr(...)]` wrapping `obake` helper attributes (possibly
+/// within further `cfg_attr`s).
+pub fn conditional_obake_attrs(attr: &syn::Attribute) -> Option<ConditionalAttrs> {
+    if !attr.path.is_ident("cfg_attr") {
+        return None;
+    }
+
+    let conditional = attr.parse_args::<ConditionalAttrs>().ok()?;
+    conditional
+        .attrs
+        .iter()
+        .any(|attr| attr.path.is_ident(OBAKE) || conditional_obake_attrs(attr).is_some())
+        .then_some(conditional)
+}
+
+impl AssertLayoutAttr {
+    fn parse(span: proc_macro2::Span, input: ParseStream) -> Result<Self> {
+        let mut attr = Self {
+            span,
+            version: parse_version(input)?,
+            size: None,
+            align: None,
+        };
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key = input.parse::<syn::Ident>()?;
+            input.parse::<Token![=]>()?;
+            match key {
+                _ if key == "size" => attr.size = Some(input.parse()?),
+                _ if key == "align" => attr.align = Some(input.parse()?),
+                _ => return Err(syn::Error::new(key.span(), "expected `size` or `align`")),
+            }
+        }
+
+        if attr.size.is_none() && attr.align.is_none() {
+            return Err(syn::Error::new(
+                span,
+                "expected `size = ...` or `align = ...`",
+            ));
+        }
+
+        Ok(attr)
+    }
+}
+
 impl Parse for ObakeAttribute {
+    #[allow(clippy::too_many_lines)]
     fn parse(input: ParseStream) -> Result<Self> {
         let ident = input.parse::<syn::Ident>()?;
 
         Ok(match ident {
-            _ if ident == "version" => {
+            _ if ident == "cfg" => {
                 let content;
                 parenthesized!(content in input);
-                Self::Version(content.parse()?)
+                Self::Cfg(content.parse()?)
             }
-            _ if ident == "cfg" => {
+            _ if ident == "inherit" => Self::Inherit(InheritAttr {
+                span: ident.span(),
+                mapping: if input.peek(syn::token::Paren) {
+                    let content;
+                    parenthesized!(content in input);
+                    Some(content.parse()?)
+                } else {
+                    None
+                },
+            }),
+            _ if ident == "flatten" => Self::Flatten(FlattenAttr { span: ident.span() }),
+            _ if ident == "serde_cfg" => {
                 let content;
                 parenthesized!(content in input);
-                Self::Cfg(content.parse()?)
+                let CfgAttr { req, .. } = content.parse()?;
+                content.parse::<Token![,]>()?;
+                Self::SerdeCfg(SerdeCfgAttr {
+                    req,
+                    tokens: content.parse()?,
+                })
+            }
+            _ if ident == "map_from" => {
+                let content;
+                parenthesized!(content in input);
+                Self::MapFrom(MapFromAttr {
+                    span: ident.span(),
+                    with: content.parse()?,
+                })
+            }
+            _ if ident == "retyped" => Self::Retyped(RetypedAttr {
+                span: ident.span(),
+                fallback: if input.peek(syn::token::Paren) {
+                    let content;
+                    parenthesized!(content in input);
+                    let key = content.parse::<syn::Ident>()?;
+                    if key != "try_from" {
+                        return Err(syn::Error::new(key.span(), "expected `try_from`"));
+                    }
+                    content.parse::<Token![,]>()?;
+                    let key = content.parse::<syn::Ident>()?;
+                    if key != "or" {
+                        return Err(syn::Error::new(key.span(), "expected `or`"));
+                    }
+                    content.parse::<Token![=]>()?;
+                    Some(content.parse()?)
+                } else {
+                    None
+                },
+            }),
+            _ if ident == "computed" => {
+                input.parse::<Token![=]>()?;
+                Self::Computed(ComputedAttr {
+                    span: ident.span(),
+                    with: input.parse()?,
+                })
             }
-            _ if ident == "inherit" => Self::Inherit(InheritAttr { span: ident.span() }),
             _ if ident == "derive" => {
                 let content;
                 parenthesized!(content in input);
@@ -53,48 +310,522 @@ impl Parse for ObakeAttribute {
                     tokens: content.parse()?,
                 })
             }
-            _ => {
-                return Err(syn::Error::new(
-                    ident.span(),
-                    "unrecognised `obake` helper attribute",
-                ))
+            _ if ident == "generate_tests" => {
+                Self::GenerateTests(GenerateTestsAttr { span: ident.span() })
+            }
+            _ if ident == "coverage" => {
+                let content;
+                parenthesized!(content in input);
+                Self::Coverage(CoverageAttr {
+                    span: ident.span(),
+                    traits: content.parse_terminated(syn::Path::parse)?,
+                })
+            }
+            _ if ident == "latest_only" => {
+                let content;
+                parenthesized!(content in input);
+                Self::LatestOnly(LatestOnlyAttr {
+                    span: ident.span(),
+                    tokens: content.parse()?,
+                })
+            }
+            _ if ident == "trivial_migrations" => {
+                Self::TrivialMigrations(TrivialMigrationsAttr { span: ident.span() })
+            }
+            _ if ident == "partial_migrations" => {
+                Self::PartialMigrations(PartialMigrationsAttr { span: ident.span() })
+            }
+            _ if ident == "schema_hash" => Self::SchemaHash(SchemaHashAttr { span: ident.span() }),
+            _ if ident == "strict" => Self::Strict(StrictAttr { span: ident.span() }),
+            _ if ident == "discard" => Self::Discard(DiscardAttr { span: ident.span() }),
+            _ if ident == "graph" => Self::Graph(GraphAttr { span: ident.span() }),
+            _ if ident == "aliases" => Self::Aliases(AliasesAttr { span: ident.span() }),
+            _ if ident == "semantic_eq" => Self::SemanticEq(SemanticEqAttr { span: ident.span() }),
+            _ if ident == "visitor" => Self::Visitor(VisitorAttr { span: ident.span() }),
+            _ if ident == "version_enum" => {
+                let derives = if input.peek(syn::token::Paren) {
+                    let content;
+                    parenthesized!(content in input);
+                    let key = content.parse::<syn::Ident>()?;
+                    if key != "derive" {
+                        return Err(syn::Error::new(key.span(), "expected `derive(...)`"));
+                    }
+                    let derives;
+                    parenthesized!(derives in content);
+                    Some(derives.parse()?)
+                } else {
+                    None
+                };
+                Self::VersionEnum(VersionEnumAttr {
+                    span: ident.span(),
+                    derives,
+                })
+            }
+            _ if ident == "builder" => Self::Builder(BuilderAttr { span: ident.span() }),
+            _ if ident == "assert_layout" => {
+                let content;
+                parenthesized!(content in input);
+                Self::AssertLayout(AssertLayoutAttr::parse(ident.span(), &content)?)
             }
+            _ if ident == "common_fields" => {
+                Self::CommonFields(CommonFieldsAttr { span: ident.span() })
+            }
+            _ if ident == "error" => Self::Error(ErrorAttr { span: ident.span() }),
+            _ if ident == "display" => {
+                let content;
+                parenthesized!(content in input);
+                Self::Display(DisplayAttr {
+                    span: ident.span(),
+                    tokens: content.parse()?,
+                })
+            }
+            _ if ident == "source" => Self::Source(SourceAttr { span: ident.span() }),
+            _ if ident == "use_versions" => {
+                let content;
+                parenthesized!(content in input);
+                Self::UseVersions(UseVersionsAttr {
+                    span: ident.span(),
+                    path: content.parse()?,
+                })
+            }
+            _ if ident == "split_into" => {
+                let content;
+                parenthesized!(content in input);
+                let (types, with) = parse_types_with(&content)?;
+                Self::SplitInto(SplitIntoAttr {
+                    span: ident.span(),
+                    types,
+                    with,
+                })
+            }
+            _ if ident == "merge_from" => {
+                let content;
+                parenthesized!(content in input);
+                let (types, with) = parse_types_with(&content)?;
+                Self::MergeFrom(MergeFromAttr {
+                    span: ident.span(),
+                    types,
+                    with,
+                })
+            }
+            _ if ident == "renamed_from" || ident == "variant_renamed_from" => {
+                let content;
+                parenthesized!(content in input);
+                let name = content.parse::<syn::LitStr>()?;
+                content.parse::<Token![,]>()?;
+                let key = content.parse::<syn::Ident>()?;
+                if key != "until" {
+                    return Err(syn::Error::new(key.span(), "expected `until`"));
+                }
+                content.parse::<Token![=]>()?;
+                let renamed_from = RenamedFromAttr {
+                    span: ident.span(),
+                    ident: name.parse()?,
+                    until: parse_version(&content)?.version,
+                };
+                if ident == "renamed_from" {
+                    Self::RenamedFrom(renamed_from)
+                } else {
+                    Self::VariantRenamedFrom(renamed_from)
+                }
+            }
+            _ if ident == "downgrade" => Self::Downgrade(DowngradeAttr { span: ident.span() }),
+            _ if ident == "hide_old_docs" => {
+                Self::HideOldDocs(HideOldDocsAttr { span: ident.span() })
+            }
+            // `default` on the item derives `Default` for each version, and `default = ...`
+            // on a field sets its value there
+            _ if ident == "default" && input.peek(Token![=]) => {
+                input.parse::<Token![=]>()?;
+                Self::DefaultValue(DefaultValueAttr {
+                    span: ident.span(),
+                    expr: input.parse()?,
+                })
+            }
+            _ if ident == "default" => Self::Default(DefaultAttr { span: ident.span() }),
+            _ if ident == "emit_original" => {
+                Self::EmitOriginal(EmitOriginalAttr { span: ident.span() })
+            }
+            _ if ident == "max_versions" => {
+                input.parse::<Token![=]>()?;
+                let max = input.parse::<syn::LitInt>()?;
+                Self::MaxVersions(MaxVersionsAttr {
+                    span: ident.span(),
+                    max: max.base10_parse()?,
+                })
+            }
+            _ if ident == "patch" => {
+                let content;
+                parenthesized!(content in input);
+                let from = parse_version(&content)?.version;
+                content.parse::<Token![,]>()?;
+
+                // The script is either written in place, or read from a file, as in
+                // `#[obake(patch("0.1.0", file = "patches/0.1.0.patch"))]`
+                let (ops, path) = if content.peek(syn::Ident)
+                    && content.peek2(Token![=])
+                    && content.fork().parse::<syn::Ident>()? == "file"
+                {
+                    content.parse::<syn::Ident>()?;
+                    content.parse::<Token![=]>()?;
+                    let (ops, path) = parse_patch_file(&content.parse()?)?;
+                    (ops, Some(path))
+                } else {
+                    (parse_patch_ops(&content)?, None)
+                };
+                Self::Patch(PatchAttr {
+                    span: ident.span(),
+                    from,
+                    ops,
+                    path,
+                })
+            }
+            _ if ident == "validate" => {
+                input.parse::<Token![=]>()?;
+                Self::Validate(ValidateAttr {
+                    span: ident.span(),
+                    with: input.parse()?,
+                })
+            }
+            #[cfg(feature = "serde")]
+            _ if ident == "serde" => {
+                let content;
+                parenthesized!(content in input);
+                Self::Serde(SerdeAttr {
+                    span: ident.span(),
+                    tokens: content.parse()?,
+                })
+            }
+            #[cfg(feature = "serde")]
+            _ if ident == "serde_repr" => Self::SerdeRepr(SerdeReprAttr { span: ident.span() }),
+            #[cfg(feature = "bson")]
+            _ if ident == "bson" => {
+                let field = if input.is_empty() {
+                    None
+                } else {
+                    let content;
+                    parenthesized!(content in input);
+                    let key = content.parse::<syn::Ident>()?;
+                    if key != "field" {
+                        return Err(syn::Error::new(key.span(), "expected `field`"));
+                    }
+                    content.parse::<Token![=]>()?;
+                    Some(content.parse()?)
+                };
+
+                Self::Bson(BsonAttr {
+                    span: ident.span(),
+                    field,
+                })
+            }
+            #[cfg(feature = "graphql")]
+            _ if ident == "graphql" => {
+                let input = if input.peek(syn::token::Paren) {
+                    let content;
+                    parenthesized!(content in input);
+                    let kind = content.parse::<syn::Ident>()?;
+                    if kind != "input" {
+                        return Err(syn::Error::new(kind.span(), "expected `input`"));
+                    }
+                    true
+                } else {
+                    false
+                };
+
+                Self::Graphql(GraphqlAttr {
+                    span: ident.span(),
+                    input,
+                })
+            }
+            #[cfg(feature = "json")]
+            _ if ident == "preserve_unknown" => {
+                Self::PreserveUnknown(PreserveUnknownAttr { span: ident.span() })
+            }
+            #[cfg(feature = "json")]
+            _ if ident == "unknown_variant" => Self::UnknownVariant(UnknownVariantAttr {
+                span: ident.span(),
+                latest_only: if input.peek(syn::token::Paren) {
+                    let content;
+                    parenthesized!(content in input);
+                    let key = content.parse::<syn::Ident>()?;
+                    if key != "latest_only" {
+                        return Err(syn::Error::new(key.span(), "expected `latest_only`"));
+                    }
+                    true
+                } else {
+                    false
+                },
+            }),
+            #[cfg(feature = "wasm")]
+            _ if ident == "wasm" => Self::Wasm(WasmAttr { span: ident.span() }),
+            #[cfg(feature = "pyo3")]
+            _ if ident == "pyo3" => Self::Pyo3(Pyo3Attr { span: ident.span() }),
+            #[cfg(feature = "abi_stable")]
+            _ if ident == "abi_stable" => Self::AbiStable(AbiStableAttr { span: ident.span() }),
+            #[cfg(feature = "tonic")]
+            _ if ident == "tonic" => Self::Tonic(TonicAttr { span: ident.span() }),
+            #[cfg(feature = "validator")]
+            _ if ident == "validator" => {
+                let latest = if input.peek(syn::token::Paren) {
+                    let content;
+                    parenthesized!(content in input);
+                    let mode = content.parse::<syn::Ident>()?;
+                    if mode != "latest" {
+                        return Err(syn::Error::new(mode.span(), "expected `latest`"));
+                    }
+                    true
+                } else {
+                    false
+                };
+                Self::Validator(ValidatorAttr {
+                    span: ident.span(),
+                    latest,
+                })
+            }
+            #[cfg(feature = "garde")]
+            _ if ident == "garde" => Self::Garde(GardeAttr { span: ident.span() }),
+            #[cfg(feature = "garde")]
+            _ if ident == "garde_cfg" => {
+                let content;
+                parenthesized!(content in input);
+                let CfgAttr { req, .. } = content.parse()?;
+                content.parse::<Token![,]>()?;
+                Self::GardeCfg(GardeCfgAttr {
+                    span: ident.span(),
+                    req,
+                    tokens: content.parse()?,
+                })
+            }
+            #[cfg(feature = "zeroize")]
+            _ if ident == "zeroize" => Self::Zeroize(ZeroizeAttr { span: ident.span() }),
+            #[cfg(feature = "fixtures")]
+            _ if ident == "fixtures" => Self::Fixtures(FixturesAttr { span: ident.span() }),
+            #[cfg(feature = "arbitrary")]
+            _ if ident == "arbitrary" => Self::Arbitrary(ArbitraryAttr { span: ident.span() }),
+            #[cfg(feature = "proptest")]
+            _ if ident == "proptest" => Self::Proptest(ProptestAttr { span: ident.span() }),
+            #[cfg(feature = "proptest")]
+            _ if ident == "strategy" => {
+                let content;
+                parenthesized!(content in input);
+                Self::Strategy(StrategyAttr {
+                    span: ident.span(),
+                    expr: content.parse()?,
+                })
+            }
+            #[cfg(feature = "criterion")]
+            _ if ident == "generate_benches" => {
+                // The benchmarks can be gated behind a feature of the crate declaring them, as in
+                // `#[obake(generate_benches(feature = "bench"))]`
+                let feature = if input.peek(syn::token::Paren) {
+                    let content;
+                    parenthesized!(content in input);
+                    let key: syn::Ident = content.parse()?;
+                    if key != "feature" {
+                        return Err(syn::Error::new(key.span(), "expected `feature`"));
+                    }
+                    content.parse::<Token![=]>()?;
+                    Some(content.parse()?)
+                } else {
+                    None
+                };
+                Self::GenerateBenches(GenerateBenchesAttr {
+                    span: ident.span(),
+                    feature,
+                })
+            }
+            #[cfg(feature = "fuzz")]
+            _ if ident == "fuzz" => {
+                // Payloads are decoded as JSON, unless another format is given, as in
+                // `#[obake(fuzz(format = obake::fuzz::Bincode))]`
+                let format = if input.peek(syn::token::Paren) {
+                    let content;
+                    parenthesized!(content in input);
+                    let key: syn::Ident = content.parse()?;
+                    if key != "format" {
+                        return Err(syn::Error::new(key.span(), "expected `format`"));
+                    }
+                    content.parse::<Token![=]>()?;
+                    Some(content.parse()?)
+                } else {
+                    None
+                };
+                Self::Fuzz(FuzzAttr {
+                    span: ident.span(),
+                    format,
+                })
+            }
+            #[cfg(feature = "dynamic")]
+            _ if ident == "dynamic" => Self::Dynamic(DynamicAttr { span: ident.span() }),
+            _ => return Err(unrecognised_helper(&ident)),
         })
     }
 }
 
-impl TryFrom<syn::Attribute> for ObakeAttribute {
-    type Error = syn::Error;
+/// Parses the steps of a patch script, which may be separated by commas.
+fn parse_patch_ops(input: ParseStream) -> Result<Vec<PatchOp>> {
+    let path = |input: ParseStream| -> Result<Vec<syn::Ident>> {
+        let path = Punctuated::<syn::Ident, Token![.]>::parse_separated_nonempty(input)?;
+        Ok(path.into_iter().collect())
+    };
+
+    let mut ops = Vec::new();
+    while !input.is_empty() {
+        let content;
+        if input.peek(Token![move]) {
+            let span = input.parse::<Token![move]>()?.span;
+            parenthesized!(content in input);
+            let from = path(&content)?;
+            content.parse::<Token![->]>()?;
+            ops.push(PatchOp::Move {
+                span,
+                from,
+                to: path(&content)?,
+            });
+        } else {
+            let ident = input.parse::<syn::Ident>()?;
+            let span = ident.span();
+            if ident == "rename" {
+                parenthesized!(content in input);
+                let from = content.parse()?;
+                content.parse::<Token![->]>()?;
+                ops.push(PatchOp::Move {
+                    span,
+                    from: vec![from],
+                    to: vec![content.parse()?],
+                });
+            } else if ident == "default" {
+                parenthesized!(content in input);
+                let to = path(&content)?;
+                let value = if content.is_empty() {
+                    None
+                } else {
+                    content.parse::<Token![=]>()?;
+                    Some(content.parse()?)
+                };
+                ops.push(PatchOp::Default { span, to, value });
+            } else if ident == "delete" {
+                parenthesized!(content in input);
+                ops.push(PatchOp::Delete {
+                    span,
+                    field: content.parse()?,
+                });
+            } else {
+                return Err(syn::Error::new(
+                    span,
+                    "expected `rename`, `move`, `default` or `delete`",
+                ));
+            }
+        }
+        content.parse::<syn::parse::Nothing>()?;
 
-    fn try_from(attr: syn::Attribute) -> Result<Self> {
-        attr.parse_args()
+        if !input.is_empty() {
+            input.parse::<Option<Token![,]>>()?;
+        }
     }
+
+    Ok(ops)
 }
 
-impl TryFrom<syn::Attribute> for VersionedAttribute {
-    type Error = syn::Error;
+/// Reads a patch script from a file, relative to the directory of the crate's `Cargo.toml`,
+/// returning its steps along with the file's full path. Steps are written as in
+/// `#[obake(patch(...))]`, one after another, as in
+///
+/// ```text
+/// rename(login -> name)
+/// move(port -> server.port)
+/// default(tags)
+/// delete(legacy_id)
+/// ```
+fn parse_patch_file(file: &syn::LitStr) -> Result<(Vec<PatchOp>, std::path::PathBuf)> {
+    let error = |message: String| syn::Error::new(file.span(), message);
 
-    fn try_from(attr: syn::Attribute) -> Result<Self> {
-        attr.path.get_ident().map_or_else(
-            || Ok(Self::Attribute(attr.clone())),
-            |ident| {
-                if ident == OBAKE {
-                    Ok(Self::Obake(attr.clone().try_into()?))
-                } else {
-                    Ok(Self::Attribute(attr.clone()))
+    let dir = std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let name = file.value();
+    let path = std::path::Path::new(&dir).join(&name);
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|err| error(format!("failed to read `{name}`: {err}")))?;
+    let mut ops = syn::parse::Parser::parse_str(parse_patch_ops, &contents)
+        .map_err(|err| error(format!("failed to parse `{name}`: {err}")))?;
+
+    // Errors in the steps are reported at the name of the file
+    for op in &mut ops {
+        let (PatchOp::Move { span, .. }
+        | PatchOp::Default { span, .. }
+        | PatchOp::Delete { span, .. }) = op;
+        *span = file.span();
+    }
+
+    Ok((ops, path))
+}
+
+/// Parses the comma-separated helpers in an `obake` attribute, expanding `versions(...)` into a
+/// `version(...)` for each of the versions listed.
+fn parse_helpers(input: ParseStream) -> Result<Vec<ObakeAttribute>> {
+    let mut helpers = Vec::new();
+    while !input.is_empty() {
+        if VersionsHelper::peek(input) {
+            match input.parse()? {
+                VersionsHelper::Version(attr) => helpers.push(ObakeAttribute::Version(attr)),
+                VersionsHelper::Versions(versions) => {
+                    helpers.extend(versions.into_iter().map(ObakeAttribute::Version));
+                }
+                VersionsHelper::VersionsFrom {
+                    span,
+                    versions,
+                    path,
+                } => {
+                    helpers.extend(versions.into_iter().map(ObakeAttribute::Version));
+                    helpers.push(ObakeAttribute::VersionsFrom(VersionsFromAttr {
+                        span,
+                        path,
+                    }));
+                }
+                VersionsHelper::VersionSet { span, versions } => {
+                    helpers.extend(versions.into_iter().map(ObakeAttribute::Version));
+                    helpers.push(ObakeAttribute::VersionSet(VersionSetAttr { span }));
                 }
-            },
-        )
+                VersionsHelper::Feature { span, feature } => {
+                    let previous = match helpers.last_mut() {
+                        Some(ObakeAttribute::Version(attr)) => Some(attr),
+                        _ => None,
+                    };
+                    VersionsHelper::gate(previous, span, feature)?;
+                }
+            }
+        } else {
+            helpers.push(input.parse()?);
+        }
+
+        if input.is_empty() {
+            break;
+        }
+        input.parse::<Token![,]>()?;
     }
+
+    Ok(helpers)
 }
 
 impl Parse for VersionedAttributes {
     fn parse(input: ParseStream) -> Result<VersionedAttributes> {
-        let attrs = input
-            .call(syn::Attribute::parse_outer)?
-            .into_iter()
-            .map(TryInto::try_into)
-            .collect::<Result<Vec<_>>>()?;
+        let mut attrs = Vec::new();
+        for attr in input.call(syn::Attribute::parse_outer)? {
+            if !attr.path.is_ident(OBAKE) {
+                attrs.push(VersionedAttribute::Attribute(attr));
+                continue;
+            }
+
+            // Several helpers can share an attribute, e.g. `#[obake(version("0.1.0"), strict)]`
+            let helpers = attr.parse_args_with(parse_helpers)?;
+            if helpers.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "expected at least one `obake` helper attribute",
+                ));
+            }
+
+            attrs.extend(helpers.into_iter().map(VersionedAttribute::Obake));
+        }
 
         Ok(Self { attrs })
     }
@@ -106,7 +837,7 @@ impl Parse for VersionedField {
             attrs: input.parse()?,
             vis: input.parse()?,
             ident: input.parse()?,
-            colon_token: input.parse()?,
+            colon_token: Some(input.parse()?),
             ty: input.parse()?,
         })
     }
@@ -115,15 +846,43 @@ impl Parse for VersionedField {
 impl Parse for VersionedFields {
     fn parse(input: ParseStream) -> Result<Self> {
         let content;
-        let brace_token = braced!(content in input);
+        braced!(content in input);
 
         Ok(Self {
-            brace_token,
+            paren_token: None,
             fields: content.parse_terminated(VersionedField::parse)?,
         })
     }
 }
 
+/// Parses the fields of a tuple `struct` (or `enum` variant), naming them after their position.
+fn parse_unnamed_fields(input: ParseStream) -> Result<VersionedFields> {
+    let content;
+    let paren_token = parenthesized!(content in input);
+    let mut fields = Punctuated::new();
+    while !content.is_empty() {
+        let attrs = content.parse()?;
+        let vis = content.parse()?;
+        let ty: syn::Type = content.parse()?;
+        fields.push_value(VersionedField {
+            attrs,
+            vis,
+            ident: format_ident!("field_{}", fields.len(), span = ty.span()),
+            colon_token: None,
+            ty,
+        });
+        if content.is_empty() {
+            break;
+        }
+        fields.push_punct(content.parse()?);
+    }
+
+    Ok(VersionedFields {
+        paren_token: Some(paren_token),
+        fields,
+    })
+}
+
 impl Parse for VersionedVariantFields {
     fn parse(input: ParseStream) -> Result<Self> {
         if input.is_empty() {
@@ -132,7 +891,7 @@ impl Parse for VersionedVariantFields {
 
         let lookahead = input.lookahead1();
         Ok(if lookahead.peek(syn::token::Paren) {
-            Self::Unnamed(input.parse()?)
+            Self::Unnamed(parse_unnamed_fields(input)?)
         } else if lookahead.peek(syn::token::Brace) {
             Self::Named(input.parse()?)
         } else {
@@ -154,10 +913,9 @@ impl Parse for VersionedVariant {
 impl Parse for VersionedVariants {
     fn parse(input: ParseStream) -> Result<Self> {
         let content;
-        let brace_token = braced!(content in input);
+        braced!(content in input);
 
         Ok(Self {
-            brace_token,
             variants: content.parse_terminated(VersionedVariant::parse)?,
         })
     }
@@ -165,10 +923,20 @@ impl Parse for VersionedVariants {
 
 impl Parse for VersionedStruct {
     fn parse(input: ParseStream) -> Result<Self> {
+        let struct_token = input.parse()?;
+        let ident = input.parse()?;
+        let fields = if input.peek(syn::token::Paren) {
+            let fields = parse_unnamed_fields(input)?;
+            input.parse::<Token![;]>()?;
+            fields
+        } else {
+            input.parse()?
+        };
+
         Ok(Self {
-            struct_token: input.parse()?,
-            ident: input.parse()?,
-            fields: input.parse()?,
+            struct_token,
+            ident,
+            fields,
         })
     }
 }
@@ -0,0 +1,571 @@
+//! The grammar of the versions declared by `#[obake::versioned]` and the requirements selecting
+//! them, shared by the macro and by `obake::cbindgen` (which reads source files itself, so has to
+//! understand them the same way).
+
+#![deny(clippy::all, clippy::pedantic)]
+
+use std::path::PathBuf;
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, Token};
+
+pub use semver::{Version, VersionReq};
+
+/// A declared version, as in `#[obake(version("0.2.0", feature = "v2"))]`.
+#[derive(Clone)]
+pub struct VersionAttr {
+    pub version: Version,
+    pub span: Span,
+    pub feature: Option<syn::LitStr>,
+    pub deprecated: Option<syn::LitStr>,
+    pub untagged_legacy: Option<Span>,
+}
+
+impl VersionAttr {
+    /// Expands to the `#[cfg(...)]` attribute under which this version is generated, if any.
+    #[must_use]
+    pub fn expand_cfg(&self) -> TokenStream {
+        self.feature
+            .as_ref()
+            .map_or_else(|| quote!(), |feature| quote!(#[cfg(feature = #feature)]))
+    }
+
+    /// Expands to the `#[deprecated]` attribute of a version declared with a `deprecated` note.
+    #[must_use]
+    pub fn expand_deprecated(&self) -> TokenStream {
+        self.deprecated
+            .as_ref()
+            .map_or_else(|| quote!(), |note| quote!(#[deprecated = #note]))
+    }
+}
+
+impl PartialEq for VersionAttr {
+    fn eq(&self, other: &Self) -> bool {
+        self.version == other.version
+    }
+}
+
+impl Eq for VersionAttr {}
+
+impl PartialOrd for VersionAttr {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for VersionAttr {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.version.cmp(&other.version)
+    }
+}
+
+/// The versions an `#[obake(cfg(...))]` attribute applies to.
+#[derive(Clone)]
+pub enum CfgPredicate {
+    /// A version requirement, as in `cfg(">=0.2, <0.4")`.
+    Req(VersionReq),
+    /// Matches the versions matched by any of its predicates, as in `cfg(any(...))`.
+    Any(Vec<CfgPredicate>),
+    /// Matches the versions matched by all of its predicates, as in `cfg(all(...))`.
+    All(Vec<CfgPredicate>),
+    /// Matches the versions its predicate doesn't match, as in `cfg(not(...))`.
+    Not(Box<CfgPredicate>),
+    /// Matches exactly the listed versions, each of which must be declared, as in
+    /// `cfg(in("0.1.0", "0.3.0"))`.
+    In(Vec<VersionAttr>),
+}
+
+impl CfgPredicate {
+    /// Whether `version` matches the predicate.
+    #[must_use]
+    pub fn matches(&self, version: &Version) -> bool {
+        match self {
+            Self::Req(req) => {
+                // Pre-releases (e.g. `0.9.0-experimental`) are matched as their release, so they
+                // pick up the fields of the version they preview
+                let release = Version::new(version.major, version.minor, version.patch);
+                req.matches(version) || req.matches(&release)
+            }
+            Self::Any(reqs) => reqs.iter().any(|req| req.matches(version)),
+            Self::All(reqs) => reqs.iter().all(|req| req.matches(version)),
+            Self::Not(req) => !req.matches(version),
+            Self::In(versions) => versions.iter().any(|attr| attr.version == *version),
+        }
+    }
+
+    /// The first version listed by a `cfg(in(...))` in the predicate which isn't one of
+    /// `versions`.
+    #[must_use]
+    pub fn undeclared_version<'a>(&'a self, versions: &[VersionAttr]) -> Option<&'a VersionAttr> {
+        match self {
+            Self::Req(_) => None,
+            Self::Any(reqs) | Self::All(reqs) => {
+                reqs.iter().find_map(|req| req.undeclared_version(versions))
+            }
+            Self::Not(req) => req.undeclared_version(versions),
+            Self::In(listed) => listed.iter().find(|attr| !versions.contains(attr)),
+        }
+    }
+}
+
+/// The versions of the outer and inner types an `#[obake(inherit(...))]` maps between.
+#[derive(Clone)]
+pub struct InheritMapping {
+    pub outer: Version,
+    pub inner: Version,
+}
+
+/// A set of versions declared once with `obake::version_set!` and shared between types with
+/// `#[obake(use_versions(...))]`.
+#[derive(Clone)]
+pub struct VersionSet {
+    pub attrs: Vec<syn::Attribute>,
+    pub vis: syn::Visibility,
+    pub ident: syn::Ident,
+    pub versions: Vec<syn::LitStr>,
+}
+
+/// A helper declaring versions, or gating the version declared just before it.
+pub enum VersionsHelper {
+    /// `version("...", ...)`.
+    Version(VersionAttr),
+    /// `versions("...", ...)`, declaring each of the versions listed.
+    Versions(Vec<VersionAttr>),
+    /// `versions_from = "..."`, declaring the versions listed in a manifest.
+    VersionsFrom {
+        span: Span,
+        versions: Vec<VersionAttr>,
+        path: PathBuf,
+    },
+    /// `__version_set(...)`, emitted by the macros generated by `obake::version_set!` in place of
+    /// the `use_versions(...)` they resolve.
+    VersionSet {
+        span: Span,
+        versions: Vec<VersionAttr>,
+    },
+    /// A trailing `feature = "..."`, as in
+    /// `#[obake(version("0.9.0-experimental"), feature = "unstable-format")]`.
+    Feature { span: Span, feature: syn::LitStr },
+}
+
+impl VersionsHelper {
+    /// Whether the next helper in `input` is one of these.
+    #[must_use]
+    pub fn peek(input: ParseStream) -> bool {
+        let Ok(ident) = input.fork().parse::<syn::Ident>() else {
+            return false;
+        };
+
+        if ident == "feature" {
+            input.peek2(Token![=])
+        } else {
+            ident == "version"
+                || ident == "versions"
+                || ident == "versions_from"
+                || ident == "__version_set"
+        }
+    }
+
+    /// Gates `previous` (the version declared by the helper just before this one, if any) behind
+    /// the feature of a `feature = "..."` helper.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there's no such version, or if it's already gated.
+    pub fn gate(
+        previous: Option<&mut VersionAttr>,
+        span: Span,
+        feature: syn::LitStr,
+    ) -> Result<()> {
+        match previous {
+            Some(attr) if attr.feature.is_none() => {
+                attr.feature = Some(feature);
+                Ok(())
+            }
+            _ => Err(syn::Error::new(
+                span,
+                "`feature` must directly follow an ungated `version(...)`",
+            )),
+        }
+    }
+}
+
+impl Parse for VersionsHelper {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident = input.parse::<syn::Ident>()?;
+        if ident == "feature" || ident == "versions_from" {
+            input.parse::<Token![=]>()?;
+            let value = input.parse::<syn::LitStr>()?;
+            return Ok(if ident == "feature" {
+                Self::Feature {
+                    span: ident.span(),
+                    feature: value,
+                }
+            } else {
+                let (versions, path) = parse_versions_from(&value)?;
+                Self::VersionsFrom {
+                    span: ident.span(),
+                    versions,
+                    path,
+                }
+            });
+        }
+
+        let content;
+        parenthesized!(content in input);
+        if ident == "version" {
+            return Ok(Self::Version(content.parse()?));
+        }
+
+        let versions = Punctuated::<_, Token![,]>::parse_terminated_with(&content, parse_version)?;
+        let versions: Vec<_> = versions.into_iter().collect();
+        match ident {
+            _ if ident == "versions" && versions.is_empty() => Err(syn::Error::new(
+                ident.span(),
+                "expected at least one version",
+            )),
+            _ if ident == "versions" => Ok(Self::Versions(versions)),
+            _ if ident == "__version_set" => Ok(Self::VersionSet {
+                span: ident.span(),
+                versions,
+            }),
+            _ => Err(syn::Error::new(ident.span(), "expected a version helper")),
+        }
+    }
+}
+
+/// The suffix of the identifiers generated for `version`, e.g. `0_1_0` (or, for pre-releases,
+/// `0_9_0_experimental`).
+#[must_use]
+pub fn version_suffix(version: &Version) -> String {
+    let mut suffix = format!("{}_{}_{}", version.major, version.minor, version.patch);
+    if !version.pre.is_empty() {
+        suffix.push('_');
+        suffix.extend(
+            version
+                .pre
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }),
+        );
+    }
+    suffix
+}
+
+impl Parse for VersionSet {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let content;
+        syn::bracketed!(content in input);
+        let versions = Punctuated::<syn::LitStr, Token![,]>::parse_terminated(&content)?;
+        for version in &versions {
+            Version::parse(&version.value()).map_err(|err| invalid_version(version, &err))?;
+        }
+
+        if versions.is_empty() {
+            return Err(syn::Error::new(
+                content.span(),
+                "expected at least one version",
+            ));
+        }
+
+        input.parse::<Option<Token![;]>>()?;
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            versions: versions.into_iter().collect(),
+        })
+    }
+}
+
+/// Finds the byte offset of the first character of `version` that doesn't fit the semver grammar,
+/// or `None` if the problem isn't with a particular character (e.g. a number being too large).
+fn invalid_offset(version: &str) -> Option<usize> {
+    let bytes = version.as_bytes();
+    let mut i = 0;
+    let numeric = |i: &mut usize| {
+        let start = *i;
+        while bytes.get(*i).is_some_and(u8::is_ascii_digit) {
+            *i += 1;
+        }
+
+        if *i == start {
+            Some(*i)
+        } else if *i - start > 1 && bytes[start] == b'0' {
+            Some(start)
+        } else {
+            None
+        }
+    };
+
+    for separator in [Some(b'.'), Some(b'.'), None] {
+        if let Some(offset) = numeric(&mut i) {
+            return Some(offset);
+        }
+
+        if let Some(separator) = separator {
+            if bytes.get(i) != Some(&separator) {
+                return Some(i);
+            }
+            i += 1;
+        }
+    }
+
+    for (prefix, numbers) in [(b'-', true), (b'+', false)] {
+        if bytes.get(i) != Some(&prefix) {
+            continue;
+        }
+
+        loop {
+            i += 1;
+            let start = i;
+            while bytes
+                .get(i)
+                .is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'-')
+            {
+                i += 1;
+            }
+
+            let identifier = &bytes[start..i];
+            if identifier.is_empty() {
+                return Some(i);
+            }
+            if numbers
+                && identifier.len() > 1
+                && identifier[0] == b'0'
+                && identifier.iter().all(u8::is_ascii_digit)
+            {
+                return Some(start);
+            }
+            if bytes.get(i) != Some(&b'.') {
+                break;
+            }
+        }
+    }
+
+    (i < bytes.len()).then_some(i)
+}
+
+/// Reports a semver error at the offending character of `lit`, where possible. Literal subspans
+/// are only available with a nightly compiler, so otherwise the character is pointed out in the
+/// message instead.
+fn invalid_version(lit: &syn::LitStr, err: &semver::Error) -> syn::Error {
+    let value = lit.value();
+    let token = lit.token();
+    let offset = invalid_offset(&value)
+        .filter(|offset| *offset < value.len() && token.to_string() == format!("{value:?}"));
+    let Some(offset) = offset else {
+        return syn::Error::new(lit.span(), err);
+    };
+
+    if let Some(span) = token.subspan(1 + offset..=1 + offset) {
+        return syn::Error::new(span, err);
+    }
+
+    syn::Error::new(
+        lit.span(),
+        format!("{err}\n\n{value}\n{:>width$}", "^", width = offset + 1),
+    )
+}
+
+/// Parses a version string, without any options.
+///
+/// # Errors
+///
+/// Returns an error if the version is invalid.
+pub fn parse_version(input: ParseStream) -> Result<VersionAttr> {
+    let version_str = input.parse::<syn::LitStr>()?;
+    let span = version_str.span();
+    let version =
+        Version::parse(&version_str.value()).map_err(|err| invalid_version(&version_str, &err))?;
+
+    Ok(VersionAttr {
+        version,
+        span,
+        feature: None,
+        deprecated: None,
+        untagged_legacy: None,
+    })
+}
+
+impl Parse for VersionAttr {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut attr = parse_version(input)?;
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key = input.parse::<syn::Ident>()?;
+            match key {
+                _ if key == "feature" => {
+                    input.parse::<Token![=]>()?;
+                    attr.feature = Some(input.parse()?);
+                }
+                _ if key == "deprecated" => {
+                    input.parse::<Token![=]>()?;
+                    attr.deprecated = Some(input.parse()?);
+                }
+                _ if key == "untagged_legacy" => attr.untagged_legacy = Some(key.span()),
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "expected `feature`, `deprecated` or `untagged_legacy`",
+                    ))
+                }
+            }
+        }
+
+        Ok(attr)
+    }
+}
+
+impl Parse for InheritMapping {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut outer = None;
+        let mut inner = None;
+        while !input.is_empty() {
+            let key = input.parse::<syn::Ident>()?;
+            let slot = match key {
+                _ if key == "outer" => &mut outer,
+                _ if key == "inner" => &mut inner,
+                _ => return Err(syn::Error::new(key.span(), "expected `outer` or `inner`")),
+            };
+            input.parse::<Token![=]>()?;
+            *slot = Some(parse_version(input)?.version);
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        match (outer, inner) {
+            (Some(outer), Some(inner)) => Ok(Self { outer, inner }),
+            _ => Err(syn::Error::new(
+                input.span(),
+                "expected both `outer = \"...\"` and `inner = \"...\"`",
+            )),
+        }
+    }
+}
+
+impl Parse for CfgPredicate {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(syn::LitStr) {
+            let req_str = input.parse::<syn::LitStr>()?;
+            let req = VersionReq::parse(&req_str.value())
+                .map_err(|err| syn::Error::new(req_str.span(), err))?;
+            return Ok(Self::Req(req));
+        }
+
+        if input.peek(Token![in]) {
+            input.parse::<Token![in]>()?;
+            let content;
+            parenthesized!(content in input);
+            let versions = Punctuated::<VersionAttr, Token![,]>::parse_terminated_with(
+                &content,
+                parse_version,
+            )?;
+            return Ok(Self::In(versions.into_iter().collect()));
+        }
+
+        let expected =
+            "expected a version requirement, `any(...)`, `all(...)`, `not(...)` or `in(...)`";
+        let ident = input
+            .parse::<syn::Ident>()
+            .map_err(|err| syn::Error::new(err.span(), expected))?;
+        if ident != "any" && ident != "all" && ident != "not" {
+            return Err(syn::Error::new(ident.span(), expected));
+        }
+        let content;
+        parenthesized!(content in input);
+        let predicates = || -> Result<Vec<Self>> {
+            Ok(Punctuated::<Self, Token![,]>::parse_terminated(&content)?
+                .into_iter()
+                .collect())
+        };
+        match ident {
+            _ if ident == "any" => Ok(Self::Any(predicates()?)),
+            _ if ident == "all" => Ok(Self::All(predicates()?)),
+            _ => {
+                let predicate = content.parse()?;
+                content.parse::<Option<Token![,]>>()?;
+                Ok(Self::Not(Box::new(predicate)))
+            }
+        }
+    }
+}
+
+/// Reads the versions listed in a manifest, relative to the directory of the crate's
+/// `Cargo.toml`, returning them along with the manifest's full path. The manifest lists each
+/// version as either a string or a table with a `version` and an optional `feature`, as in
+///
+/// ```toml
+/// versions = [
+///     { version = "0.1.0", feature = "legacy" },
+///     "0.2.0",
+/// ]
+/// ```
+fn parse_versions_from(manifest: &syn::LitStr) -> Result<(Vec<VersionAttr>, PathBuf)> {
+    let error = |message: String| syn::Error::new(manifest.span(), message);
+
+    let dir = std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let name = manifest.value();
+    let path = std::path::Path::new(&dir).join(&name);
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|err| error(format!("failed to read `{name}`: {err}")))?;
+    let table = contents
+        .parse::<toml::Table>()
+        .map_err(|err| error(format!("failed to parse `{name}`: {err}")))?;
+    let entries = table
+        .get("versions")
+        .and_then(toml::Value::as_array)
+        .ok_or_else(|| error(format!("expected a `versions` array in `{name}`")))?;
+
+    let mut versions = Vec::new();
+    for entry in entries {
+        let (version, feature) = match entry {
+            toml::Value::String(version) => (version.as_str(), None),
+            toml::Value::Table(table) => {
+                let version = table.get("version").and_then(toml::Value::as_str);
+                let feature = table.get("feature").map(toml::Value::as_str);
+                match (version, feature) {
+                    (Some(version), None | Some(Some(_))) => (version, feature.flatten()),
+                    _ => {
+                        return Err(error(format!(
+                            "expected `version` (and optionally `feature`) strings in `{entry}`"
+                        )))
+                    }
+                }
+            }
+            _ => {
+                return Err(error(format!(
+                    "expected a version string or table, found `{entry}`"
+                )))
+            }
+        };
+
+        versions.push(VersionAttr {
+            version: Version::parse(version)
+                .map_err(|err| error(format!("invalid version `{version}`: {err}")))?,
+            span: manifest.span(),
+            feature: feature.map(|feature| syn::LitStr::new(feature, manifest.span())),
+            deprecated: None,
+            untagged_legacy: None,
+        });
+    }
+
+    if versions.is_empty() {
+        return Err(error(format!("expected at least one version in `{name}`")));
+    }
+
+    Ok((versions, path))
+}